@@ -43,7 +43,8 @@ async fn main() {
     // FrameworkOptions contains all of poise's configuration option in one struct
     // Every option can be omitted to use its default value
     let options = poise::FrameworkOptions {
-        commands: vec![commands::help(), commands::vote(), commands::getvotes()],
+        // Collects every command in commands.rs, so new commands don't need to be listed here
+        commands: poise::commands_from_module!(commands, "examples/basic_structure/commands.rs"),
         prefix_options: poise::PrefixFrameworkOptions {
             prefix: Some("~".into()),
             edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
@@ -69,6 +70,17 @@ async fn main() {
                 println!("Executed command {}!", ctx.command().qualified_name);
             })
         },
+        // This code is run when a command invocation is denied by a check, permissions etc.
+        on_invocation_denied: |ctx, reason| {
+            Box::pin(async move {
+                println!(
+                    "{} was denied running {}: {:?}",
+                    ctx.author().name,
+                    ctx.command().qualified_name,
+                    reason
+                );
+            })
+        },
         // Every command invocation must pass this check to continue execution
         command_check: Some(|ctx| {
             Box::pin(async move {
@@ -55,18 +55,17 @@ async fn event_handler(
         serenity::FullEvent::Ready { data_about_bot, .. } => {
             println!("Logged in as {}", data_about_bot.user.name);
         }
-        serenity::FullEvent::Message { new_message } => {
+        serenity::FullEvent::Message { new_message }
             if new_message.content.to_lowercase().contains("poise")
-                && new_message.author.id != ctx.cache.current_user().id
-            {
-                let old_mentions = data.poise_mentions.fetch_add(1, Ordering::SeqCst);
-                new_message
-                    .reply(
-                        ctx,
-                        format!("Poise has been mentioned {} times", old_mentions + 1),
-                    )
-                    .await?;
-            }
+                && new_message.author.id != ctx.cache.current_user().id =>
+        {
+            let old_mentions = data.poise_mentions.fetch_add(1, Ordering::SeqCst);
+            new_message
+                .reply(
+                    ctx,
+                    format!("Poise has been mentioned {} times", old_mentions + 1),
+                )
+                .await?;
         }
         _ => {}
     }
@@ -2,10 +2,14 @@ use crate::{Context, Error};
 use poise::serenity_prelude as serenity;
 
 /// View the difference between two file sizes
+///
+/// As a prefix command, you can also reply to a message with a file instead of attaching one
 #[poise::command(prefix_command, slash_command)]
 pub async fn file_details(
     ctx: Context<'_>,
-    #[description = "File to examine"] file: serenity::Attachment,
+    #[description = "File to examine"]
+    #[from_reply]
+    file: serenity::Attachment,
     #[description = "Second file to examine"] file_2: Option<serenity::Attachment>,
 ) -> Result<(), Error> {
     ctx.say(format!(
@@ -17,10 +21,11 @@ pub async fn file_details(
     Ok(())
 }
 
+/// Sum up the sizes of all attached files
 #[poise::command(prefix_command)]
 pub async fn totalsize(
     ctx: Context<'_>,
-    #[description = "File to rename"] files: Vec<serenity::Attachment>,
+    #[description = "Files to sum up"] files: Vec<serenity::Attachment>,
 ) -> Result<(), Error> {
     let total = files.iter().map(|f| f.size as u64).sum::<u64>();
 
@@ -0,0 +1,23 @@
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Repeats your message in uppercase
+///
+/// With `auto_reply`, the return value is sent as the response, no `ctx.say()` needed.
+#[poise::command(slash_command, prefix_command, auto_reply)]
+pub async fn shout(
+    _ctx: Context<'_>,
+    #[description = "What to shout"]
+    #[rest]
+    text: String,
+) -> Result<String, Error> {
+    Ok(text.to_uppercase())
+}
+
+/// Shows your avatar in an embed
+#[poise::command(slash_command, prefix_command, auto_reply)]
+pub async fn avatar(ctx: Context<'_>) -> Result<serenity::CreateEmbed, Error> {
+    Ok(serenity::CreateEmbed::new()
+        .title(&ctx.author().name)
+        .image(ctx.author().face()))
+}
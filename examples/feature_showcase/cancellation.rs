@@ -0,0 +1,26 @@
+use crate::{Context, Error};
+
+/// Counts down from the given number, one second at a time
+///
+/// Stops early if the bot shuts down, using the invocation's cancellation token.
+#[poise::command(slash_command, prefix_command)]
+pub async fn countdown(
+    ctx: Context<'_>,
+    #[description = "Seconds to count down from"]
+    #[max = 60]
+    seconds: u32,
+) -> Result<(), Error> {
+    let token = ctx.cancellation_token().await;
+    for remaining in (1..=seconds).rev() {
+        ctx.say(remaining.to_string()).await?;
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
+            _ = token.cancelled() => {
+                ctx.say("Countdown aborted, the bot is shutting down").await?;
+                return Ok(());
+            }
+        }
+    }
+    ctx.say("Liftoff!").await?;
+    Ok(())
+}
@@ -12,10 +12,38 @@ use poise::serenity_prelude as serenity;
 
 #[poise::command(prefix_command, owners_only, hide_in_help)]
 pub async fn shutdown(ctx: Context<'_>) -> Result<(), Error> {
+    // Lets running commands wrap up, see the `countdown` command
+    ctx.framework().options().shutdown_token.cancel();
     ctx.framework().shard_manager().shutdown_all().await;
     Ok(())
 }
 
+/// Runs a command as if the given user had invoked it, including their checks and cooldowns
+#[poise::command(prefix_command, owners_only, hide_in_help)]
+pub async fn runas(
+    ctx: Context<'_>,
+    user: serenity::User,
+    command: String,
+    #[rest] args: Option<String>,
+) -> Result<(), Error> {
+    let found = ctx
+        .framework()
+        .invoke_command(
+            ctx.serenity_context(),
+            ctx.channel_id(),
+            &command,
+            args.as_deref().unwrap_or(""),
+            Some(&user),
+            true,
+        )
+        .await;
+    if !found {
+        ctx.say(format!("No prefix command named `{}`", command))
+            .await?;
+    }
+    Ok(())
+}
+
 /// A moderator-only command, using required_permissions
 #[poise::command(
     prefix_command,
@@ -92,12 +120,23 @@ pub async fn ferrisparty(ctx: Context<'_>) -> Result<(), Error> {
     guild_cooldown = 2,
     channel_cooldown = 2,
     member_cooldown = 3,
+    // Invocations less than 2 seconds too early are delayed instead of rejected, at most one per
+    // user, guild and channel at a time
+    cooldown_wait = 2,
+    cooldown_max_waiting = 1,
 )]
 pub async fn cooldowns(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say("You successfully called the command").await?;
     Ok(())
 }
 
+/// A command limited by the `expensive` rate limit bucket, registered in `FrameworkOptions::buckets`
+#[poise::command(prefix_command, slash_command, bucket = "expensive")]
+pub async fn ratelimited(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("You successfully called the command").await?;
+    Ok(())
+}
+
 #[poise::command(prefix_command, slash_command)]
 pub async fn minmax(
     ctx: Context<'_>,
@@ -121,6 +160,35 @@ pub async fn get_guild_name(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Has a shorter cooldown for supporters, see [`tiered_cooldowns`]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    user_cooldown = 60,
+    rename = "tiered_cooldowns"
+)]
+async fn tiered_cooldowns_command(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("You successfully invoked the command!").await?;
+    Ok(())
+}
+
+/// Users with the supporter role only have to wait 10 seconds instead of a minute
+pub fn tiered_cooldowns() -> poise::Command<crate::Data, Error> {
+    let command = tiered_cooldowns_command();
+    let supporter_role = serenity::RoleId::new(123456789);
+    let supporter_cooldowns = poise::RoleCooldowns {
+        user: Some(std::time::Duration::from_secs(10)),
+        ..Default::default()
+    };
+    command
+        .cooldown_config
+        .write()
+        .unwrap()
+        .role_tiers
+        .push((supporter_role, supporter_cooldowns));
+    command
+}
+
 /// A dm-only command
 #[poise::command(prefix_command, slash_command, dm_only)]
 pub async fn only_in_dms(ctx: Context<'_>) -> Result<(), Error> {
@@ -130,13 +198,24 @@ pub async fn only_in_dms(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Only runs on NSFW channels
-#[poise::command(prefix_command, slash_command, nsfw_only)]
+///
+/// The slash command is additionally registered as age-restricted, which poise doesn't model
+#[poise::command(
+    prefix_command,
+    slash_command,
+    nsfw_only,
+    on_create_slash = "age_restricted"
+)]
 pub async fn lennyface(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say("( ͡° ͜ʖ ͡°)").await?;
 
     Ok(())
 }
 
+fn age_restricted(builder: serenity::CreateCommand) -> serenity::CreateCommand {
+    builder.nsfw(true)
+}
+
 /// Utilizes the permissions v2 `default_member_permissions` field
 #[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
 pub async fn permissions_v2(ctx: Context<'_>) -> Result<(), Error> {
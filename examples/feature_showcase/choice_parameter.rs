@@ -21,6 +21,28 @@ pub async fn choice(
     Ok(())
 }
 
+#[derive(Debug, poise::ChoiceParameter)]
+pub enum Theme {
+    // Explicit values stay the same if the variants are reordered, so they're safe to store
+    #[value = "dark"]
+    Dark,
+    #[value = "light"]
+    Light,
+}
+
+/// Dummy command to test choice parameters with explicit values
+#[poise::command(prefix_command, slash_command)]
+pub async fn theme(
+    ctx: Context<'_>,
+    #[description = "The theme to use"] theme: Theme,
+) -> Result<(), Error> {
+    use poise::ChoiceParameter as _;
+
+    ctx.say(format!("Storing theme `{}`", theme.to_value()))
+        .await?;
+    Ok(())
+}
+
 // For simple choices, you can also declare the options inline
 //
 // Features: supports duplicate options and theoretically any type that implements Display
@@ -34,6 +56,15 @@ pub async fn inline_choice(
     ctx: Context<'_>,
     #[description = "Which continent are you from"]
     #[choices("Europe", "Asia", "Africa", "America", "Australia", "Antarctica")]
+    #[choices_localized(
+        "de",
+        "Europa",
+        "Asien",
+        "Afrika",
+        "Amerika",
+        "Australien",
+        "Antarktis"
+    )]
     continent: &'static str,
 ) -> Result<(), Error> {
     ctx.say(format!("{} is a great continent!", continent))
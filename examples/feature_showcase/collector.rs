@@ -18,16 +18,11 @@ pub async fn boop(ctx: Context<'_>) -> Result<(), Error> {
             .components(components)
     };
 
-    ctx.send(reply).await?;
+    let handle = ctx.send(reply).await?;
 
     let mut boop_count = 0;
-    while let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
-        .author_id(ctx.author().id)
-        .channel_id(ctx.channel_id())
-        .timeout(std::time::Duration::from_secs(120))
-        .filter(move |mci| mci.data.custom_id == uuid_boop.to_string())
-        .await
-    {
+    let timeout = std::time::Duration::from_secs(120);
+    while let Some(mci) = handle.await_component_interaction(ctx, timeout).await? {
         boop_count += 1;
 
         let mut msg = mci.message.clone();
@@ -43,3 +38,59 @@ pub async fn boop(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Doubles a number, with a button to double the result again
+///
+/// Demonstrates `ctx.rerun_with()`
+#[poise::command(prefix_command, slash_command)]
+pub async fn double(
+    ctx: Context<'_>,
+    #[description = "Number to double"] n: u32,
+) -> Result<(), Error> {
+    let result = n.saturating_mul(2);
+    let components = vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(ctx.id().to_string()).label("Double again"),
+    ])];
+    let reply = CreateReply::default()
+        .content(format!("{n} doubled is {result}"))
+        .components(components);
+    let handle = ctx.send(reply).await?;
+
+    let timeout = std::time::Duration::from_secs(60);
+    if let Some(mci) = handle.await_component_interaction(ctx, timeout).await? {
+        mci.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+            .await?;
+
+        let prefix_args = result.to_string();
+        ctx.rerun_with(poise::RerunArgs {
+            prefix_args: Some(&prefix_args),
+            slash_options: vec![("n", serenity::ResolvedValue::Integer(result.into()))],
+            ..Default::default()
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Asks for roles via a role select menu
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn pick_roles(ctx: Context<'_>) -> Result<(), Error> {
+    let roles = poise::components::SelectMenuPrompt::roles()
+        .content("Which roles do you want?")
+        .max_values(3)
+        .run(ctx)
+        .await?;
+
+    match roles {
+        Some(roles) => {
+            let mentions = roles.iter().map(|role| format!("<@&{}>", role));
+            let mentions = mentions.collect::<Vec<_>>().join(", ");
+            ctx.say(format!("You picked {}", mentions)).await?;
+        }
+        None => {
+            ctx.say("You didn't pick any roles in time").await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Manage the notes of a user
+///
+/// Every variant of this enum becomes a subcommand, and all of them are handled by `notes` below.
+#[derive(poise::CommandGroup)]
+#[command_group(
+    handler = "notes",
+    context = "Context<'_>",
+    prefix_command,
+    slash_command
+)]
+pub enum Notes {
+    /// Add a note to a user
+    Add {
+        #[description = "User to add the note to"]
+        user: serenity::User,
+        #[description = "Content of the note"]
+        #[rest]
+        note: String,
+    },
+    /// Remove all notes of a user
+    Clear {
+        #[description = "User whose notes to remove"]
+        user: serenity::User,
+    },
+    /// Show how many users have notes
+    #[name = "count"]
+    CountUsers,
+}
+
+async fn notes(ctx: Context<'_>, action: Notes) -> Result<(), Error> {
+    let response = match action {
+        Notes::Add { user, note } => format!("Added note to {}: {}", user.name, note),
+        Notes::Clear { user } => format!("Removed all notes of {}", user.name),
+        Notes::CountUsers => "No user has any notes, this is just an example".to_string(),
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
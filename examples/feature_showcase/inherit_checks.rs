@@ -13,6 +13,20 @@ async fn parent_check(_ctx: Context<'_>) -> Result<bool, Error> {
     Ok(true)
 }
 
+/// Doesn't run the checks of `child1` and `parent_checks`
+#[poise::command(slash_command, prefix_command, skip_parent_checks)]
+async fn sibling(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say(ctx.invocation_string()).await?;
+    Ok(())
+}
+
+/// Doesn't run the checks of `child1` and `parent_checks`, but still their restrictions
+#[poise::command(slash_command, prefix_command, inherit_checks = false)]
+async fn independent(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say(ctx.invocation_string()).await?;
+    Ok(())
+}
+
 #[poise::command(slash_command, prefix_command, check = "child2_check")]
 async fn child2(ctx: Context<'_>, _b: bool, _s: String, _i: u32) -> Result<(), Error> {
     ctx.say(ctx.invocation_string()).await?;
@@ -21,7 +35,7 @@ async fn child2(ctx: Context<'_>, _b: bool, _s: String, _i: u32) -> Result<(), E
 #[poise::command(
     slash_command,
     prefix_command,
-    subcommands("child2"),
+    subcommands("child2", "sibling", "independent"),
     check = "child1_check"
 )]
 async fn child1(_ctx: Context<'_>) -> Result<(), Error> {
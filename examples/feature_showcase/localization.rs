@@ -63,3 +63,18 @@ pub async fn welcome(
     ctx.say(format!("<@{}> {}", user.id, message)).await?;
     Ok(())
 }
+
+/// Say goodbye to a user
+///
+/// The localizations of this command and its parameters are read from a file at compile time
+#[poise::command(
+    slash_command,
+    localizations = "examples/feature_showcase/localizations.toml"
+)]
+pub async fn farewell(
+    ctx: Context<'_>,
+    #[description = "The user to say goodbye to"] user: serenity::User,
+) -> Result<(), Error> {
+    ctx.say(format!("Goodbye, <@{}>!", user.id)).await?;
+    Ok(())
+}
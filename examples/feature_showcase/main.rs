@@ -1,11 +1,14 @@
 mod attachment_parameter;
+mod auto_reply;
 mod autocomplete;
 mod bool_parameter;
 mod builtins;
+mod cancellation;
 mod checks;
 mod choice_parameter;
 mod code_block_parameter;
 mod collector;
+mod command_group;
 mod context_menu;
 mod inherit_checks;
 mod localization;
@@ -36,42 +39,58 @@ async fn main() {
                 attachment_parameter::file_details(),
                 attachment_parameter::totalsize(),
                 autocomplete::greet(),
+                auto_reply::shout(),
+                auto_reply::avatar(),
                 bool_parameter::oracle(),
+                cancellation::countdown(),
                 #[cfg(feature = "cache")]
                 builtins::servers(),
                 builtins::help(),
                 builtins::pretty_help(),
                 checks::shutdown(),
+                checks::runas(),
                 checks::modonly(),
                 checks::delete(),
                 checks::ferrisparty(),
                 checks::cooldowns(),
+                checks::tiered_cooldowns(),
+                checks::ratelimited(),
                 checks::minmax(),
                 checks::get_guild_name(),
                 checks::only_in_dms(),
                 checks::lennyface(),
                 checks::permissions_v2(),
                 choice_parameter::choice(),
+                choice_parameter::theme(),
                 choice_parameter::inline_choice(),
                 choice_parameter::inline_choice_int(),
                 code_block_parameter::code(),
                 collector::boop(),
+                collector::double(),
+                collector::pick_roles(),
+                command_group::Notes::command(),
                 context_menu::user_info(),
                 context_menu::echo(),
                 inherit_checks::parent_checks(),
                 localization::welcome(),
+                localization::farewell(),
                 modal::modal(),
                 modal::component_modal(),
+                modal::validated_modal(),
                 paginate::paginate(),
+                paginate::squares(),
                 panic_handler::div(),
                 parameter_attributes::addmultiple(),
+                parameter_attributes::rate(),
                 parameter_attributes::voiceinfo(),
                 parameter_attributes::say(),
                 parameter_attributes::punish(),
                 parameter_attributes::stringlen(),
                 raw_identifiers::r#move(),
                 response_with_reply::reply(),
+                response_with_reply::quote(),
                 subcommands::parent(),
+                subcommands::config(),
                 subcommand_required::parent_subcommand_required(),
                 track_edits::test_reuse_response(),
                 track_edits::add(),
@@ -86,6 +105,15 @@ async fn main() {
                 }),
                 ..Default::default()
             },
+            // Three uses per minute per user, with one invocation allowed to wait for the limit
+            buckets: [(
+                "expensive".to_owned(),
+                poise::CooldownBucket::new()
+                    .limit(3)
+                    .time_span(std::time::Duration::from_secs(60))
+                    .await_ratelimits(1),
+            )]
+            .into(),
             on_error: |error| {
                 Box::pin(async move {
                     println!("what the hell");
@@ -48,3 +48,24 @@ pub async fn component_modal(ctx: crate::Context<'_>) -> Result<(), Error> {
     }
     Ok(())
 }
+
+#[derive(Debug, poise::Modal)]
+#[name = "Sign up"]
+#[allow(dead_code)] // fields only used for Debug print
+struct SignupModal {
+    #[regex = "[a-z0-9_]{3,16}"]
+    username: String,
+    #[min_value = 13]
+    #[max_value = 150]
+    age: String,
+}
+
+/// Tests modal validation. Invalid input shows an error with a button to try again
+#[poise::command(slash_command)]
+pub async fn validated_modal(ctx: poise::ApplicationContext<'_, Data, Error>) -> Result<(), Error> {
+    let data =
+        poise::execute_validated_modal::<_, _, SignupModal>(ctx, None, Default::default()).await?;
+    println!("Got data: {:?}", data);
+
+    Ok(())
+}
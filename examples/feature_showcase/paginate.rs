@@ -1,4 +1,5 @@
 use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
 
 #[poise::command(slash_command, prefix_command)]
 pub async fn paginate(ctx: Context<'_>) -> Result<(), Error> {
@@ -13,3 +14,17 @@ pub async fn paginate(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Shows the squares of the first thousand numbers, one per page
+#[poise::command(slash_command, prefix_command)]
+pub async fn squares(ctx: Context<'_>) -> Result<(), Error> {
+    poise::builtins::Paginator::from_fn(1000, |page| {
+        serenity::CreateEmbed::new()
+            .title(format!("Page {}", page + 1))
+            .description(format!("{} squared is {}", page, page * page))
+    })
+    .run(ctx)
+    .await?;
+
+    Ok(())
+}
@@ -3,8 +3,13 @@ use poise::serenity_prelude as serenity;
 
 /// Adds multiple numbers
 ///
-/// Demonstrates `#[min]` and `#[max]`
-#[poise::command(prefix_command, slash_command)]
+/// Demonstrates `#[min]` and `#[max]`, and example invocations shown in help and parse errors
+#[poise::command(
+    prefix_command,
+    slash_command,
+    example = "~addmultiple 1 2 1234567890123456",
+    example = "~addmultiple -5 10 1234567890987654"
+)]
 pub async fn addmultiple(
     ctx: Context<'_>,
     #[description = "An operand"] a: i8,
@@ -20,6 +25,24 @@ pub async fn addmultiple(
     Ok(())
 }
 
+/// Rates something from one to five stars
+///
+/// Demonstrates `#[option_modifier]`
+#[poise::command(slash_command)]
+pub async fn rate(
+    ctx: Context<'_>,
+    #[description = "Number of stars"]
+    #[option_modifier = "one_to_five"]
+    stars: u8,
+) -> Result<(), Error> {
+    ctx.say("⭐".repeat(stars.into())).await?;
+    Ok(())
+}
+
+fn one_to_five(option: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+    option.min_int_value(1).max_int_value(5)
+}
+
 /// Demonstrates `#[channel_types]`
 #[poise::command(slash_command)]
 pub async fn voiceinfo(
@@ -56,7 +79,9 @@ pub async fn say(
     #[description = "Text to say"]
     msg: String,
 ) -> Result<(), Error> {
-    ctx.say(msg).await?;
+    // Echoed text shouldn't be able to ping anyone
+    ctx.say_with_mentions(msg, serenity::CreateAllowedMentions::new())
+        .await?;
     Ok(())
 }
 
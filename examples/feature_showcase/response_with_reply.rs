@@ -1,7 +1,18 @@
 use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
 
 #[poise::command(slash_command, prefix_command)]
 pub async fn reply(ctx: Context<'_>) -> Result<(), Error> {
     ctx.reply(format!("Hello {}!", ctx.author().name)).await?;
     Ok(())
 }
+
+/// Quotes a message in this channel by replying to it
+#[poise::command(prefix_command)]
+pub async fn quote(ctx: Context<'_>, message: serenity::Message) -> Result<(), Error> {
+    let reply = poise::CreateReply::default()
+        .content(format!("{} once said this", message.author.name))
+        .reply_to(message.id);
+    ctx.send(reply).await?;
+    Ok(())
+}
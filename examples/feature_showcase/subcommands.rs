@@ -23,3 +23,42 @@ pub async fn child2(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say("You invoked the second child command!").await?;
     Ok(())
 }
+
+/// A three-level command tree: `/config logging enable` and `/config logging disable`
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("logging"),
+    subcommand_required
+)]
+pub async fn config(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Configure logging
+///
+/// `subcommand_group` registers this as a subcommand group on Discord and lists it separately in
+/// help
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("enable", "disable"),
+    subcommand_group
+)]
+pub async fn logging(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Enable logging
+#[poise::command(prefix_command, slash_command)]
+pub async fn enable(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Logging enabled").await?;
+    Ok(())
+}
+
+/// Disable logging
+#[poise::command(prefix_command, slash_command)]
+pub async fn disable(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Logging disabled").await?;
+    Ok(())
+}
@@ -1,10 +1,14 @@
-mod translation;
-
+use poise::localization::fluent::Translations;
 use poise::serenity_prelude as serenity;
-use translation::tr;
+use poise::tr;
 
 pub struct Data {
-    translations: translation::Translations,
+    translations: Translations,
+}
+impl AsRef<Translations> for Data {
+    fn as_ref(&self) -> &Translations {
+        &self.translations
+    }
 }
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
@@ -61,8 +65,9 @@ async fn main() {
     env_logger::init();
 
     let mut commands = vec![welcome(), info(), register()];
-    let translations = translation::read_ftl().expect("failed to read translation files");
-    translation::apply_translations(&translations, &mut commands);
+    let translations =
+        Translations::load_dir("translations", "en-US").expect("failed to read translation files");
+    translations.apply_to_commands(&mut commands);
 
     let token = std::env::var("TOKEN").unwrap();
     let intents = serenity::GatewayIntents::non_privileged();
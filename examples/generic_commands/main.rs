@@ -1,7 +1,55 @@
 //! If you need it, poise-annotated command functions can also be generic over the user data type
 //! or error type
 //!
-//! The original use case for this feature was to have the same command in two different bots
+//! The original use case for this feature was to have the same command in two different bots. This
+//! example shows how a command library can be written, which bots then instantiate with their own
+//! Data and Error types
+
+/// A reusable set of commands, e.g. published as its own crate
+mod counter_commands {
+    use poise::serenity_prelude as serenity;
+
+    /// State the library needs from the bot's user data
+    pub struct Counter(pub std::sync::atomic::AtomicU64);
+
+    /// Requirements of the library on the bot's user data and error types.
+    ///
+    /// Bundling them in a trait avoids repeating them on every command. Bots implement it on a
+    /// marker type and pick the commands via `commands::<MyBot>()`.
+    pub trait Bot: Send + Sync + 'static {
+        type Data: AsRef<Counter> + Send + Sync + 'static;
+        type Error: From<serenity::Error> + Send + Sync + 'static;
+    }
+
+    type Context<'a, B> = poise::Context<'a, <B as Bot>::Data, <B as Bot>::Error>;
+
+    /// Increments the counter
+    #[poise::command(slash_command, prefix_command)]
+    pub async fn increment<U, E>(ctx: poise::Context<'_, U, E>) -> Result<(), E>
+    where
+        U: AsRef<Counter> + Send + Sync + 'static,
+        E: From<serenity::Error>,
+    {
+        let counter: &Counter = ctx.data().as_ref();
+        let value = counter.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        ctx.say(format!("The counter is now {}", value)).await?;
+        Ok(())
+    }
+
+    /// Shows the counter
+    #[poise::command(slash_command, prefix_command)]
+    pub async fn count<B: Bot>(ctx: Context<'_, B>) -> Result<(), B::Error> {
+        let counter: &Counter = ctx.data().as_ref();
+        let value = counter.0.load(std::sync::atomic::Ordering::SeqCst);
+        ctx.say(format!("The counter is at {}", value)).await?;
+        Ok(())
+    }
+
+    /// All commands of this library, for the bot to add to its `FrameworkOptions::commands`
+    pub fn commands<B: Bot>() -> Vec<poise::Command<B::Data, B::Error>> {
+        vec![increment(), count::<B>()]
+    }
+}
 
 #[poise::command(slash_command)]
 pub async fn example<U: Sync, E>(ctx: poise::Context<'_, U, E>) -> Result<(), E> {
@@ -16,8 +64,32 @@ pub async fn example<U: Sync, E>(ctx: poise::Context<'_, U, E>) -> Result<(), E>
     Ok(())
 }
 
+struct Data {
+    counter: counter_commands::Counter,
+}
+impl AsRef<counter_commands::Counter> for Data {
+    fn as_ref(&self) -> &counter_commands::Counter {
+        &self.counter
+    }
+}
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Connects this bot's types to the command library
+struct MyBot;
+impl counter_commands::Bot for MyBot {
+    type Data = Data;
+    type Error = Error;
+}
+
 #[tokio::main]
 async fn main() {
     let _example1 = example::<(), ()>();
     let _example2 = example::<String, Box<dyn std::error::Error>>();
+
+    let mut commands = counter_commands::commands::<MyBot>();
+    commands.push(example());
+    let _options = poise::FrameworkOptions::<Data, Error> {
+        commands,
+        ..Default::default()
+    };
 }
@@ -9,11 +9,17 @@ use poise::serenity_prelude as serenity;
 type Error = &'static str;
 type Context<'a> = poise::Context<'a, (), Error>;
 
+/// Invocation data is keyed by type, so the command check can store its own data without
+/// overwriting the string set in the global check
+struct CheckedAt(std::time::Instant);
+
 async fn my_check(ctx: Context<'_>) -> Result<bool, Error> {
     println!(
         "In command specific check: {:?}",
         ctx.invocation_data::<&str>().await.as_deref()
     );
+    ctx.set_invocation_data(CheckedAt(std::time::Instant::now()))
+        .await;
 
     Ok(true)
 }
@@ -39,6 +45,9 @@ pub async fn invocation_data_test(
         "In command: {:?}",
         ctx.invocation_data::<&str>().await.as_deref()
     );
+    if let Some(checked_at) = ctx.invocation_data::<CheckedAt>().await {
+        println!("Command check ran {:?} ago", checked_at.0.elapsed());
+    }
 
     if should_succeed > 0 {
         Ok(())
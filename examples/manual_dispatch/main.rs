@@ -23,12 +23,14 @@ impl serenity::EventHandler for Handler {
     async fn message(&self, ctx: serenity::Context, new_message: serenity::Message) {
         // FrameworkContext contains all data that poise::Framework usually manages
         let shard_manager = (*self.shard_manager.lock().unwrap()).clone().unwrap();
-        let framework_data = poise::FrameworkContext {
-            bot_id: serenity::UserId::new(846453852164587620),
-            options: &self.options,
-            user_data: &(),
-            shard_manager: &shard_manager,
-        };
+        let commands = self.options.active_commands();
+        let framework_data = poise::FrameworkContext::new(
+            serenity::UserId::new(846453852164587620),
+            &self.options,
+            &commands,
+            &(),
+            &shard_manager,
+        );
 
         let event = serenity::FullEvent::Message { new_message };
         poise::dispatch_event(framework_data, &ctx, event).await;
@@ -38,6 +40,7 @@ impl serenity::EventHandler for Handler {
 }
 
 #[tokio::main]
+#[allow(clippy::result_large_err)]
 async fn main() -> Result<(), Error> {
     let token = std::env::var("DISCORD_TOKEN").expect("missing DISCORD_TOKEN");
     let intents = serenity::GatewayIntents::non_privileged();
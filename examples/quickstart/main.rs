@@ -26,12 +26,8 @@ async fn main() {
             commands: vec![age()],
             ..Default::default()
         })
-        .setup(|ctx, _ready, framework| {
-            Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                Ok(Data {})
-            })
-        })
+        .auto_register(poise::builtins::RegisterTarget::Global)
+        .setup(|_ctx, _ready, _framework| Box::pin(async move { Ok(Data {}) }))
         .build();
 
     let client = serenity::ClientBuilder::new(token, intents)
@@ -12,6 +12,21 @@ struct VariantAttribute {
     name: Vec<String>,
     #[darling(multiple)]
     name_localized: Vec<crate::util::Tuple2<String>>,
+    value: Option<syn::Lit>,
+}
+
+/// Converts an explicit choice value into a `poise::ChoiceValue` expression
+fn choice_value(lit: &syn::Lit) -> Result<proc_macro2::TokenStream, darling::Error> {
+    match lit {
+        syn::Lit::Int(int) => {
+            let int = int.base10_parse::<i32>()?;
+            Ok(quote::quote! { poise::ChoiceValue::Integer(#int) })
+        }
+        syn::Lit::Str(string) => {
+            Ok(quote::quote! { poise::ChoiceValue::String(String::from(#string)) })
+        }
+        _ => Err(syn::Error::new(lit.span(), "choice values must be integers or strings").into()),
+    }
 }
 
 pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
@@ -31,6 +46,7 @@ pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling:
     let mut alternative_names = Vec::new();
     let mut locales: Vec<Vec<String>> = Vec::new();
     let mut localized_names: Vec<Vec<String>> = Vec::new();
+    let mut values: Vec<Option<syn::Lit>> = Vec::new();
 
     for variant in enum_.variants {
         if !matches!(&variant.fields, syn::Fields::Unit) {
@@ -48,6 +64,17 @@ pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling:
             .collect();
         let mut attrs = <VariantAttribute as darling::FromMeta>::from_list(&attrs)?;
 
+        // An explicit discriminant like `Variant = 42` doubles as the choice value
+        let discriminant = match variant.discriminant {
+            Some((_, syn::Expr::Lit(syn::ExprLit { lit, .. }))) => Some(lit),
+            Some((_, expr)) => {
+                let err_msg = "choice parameter discriminants must be integer literals";
+                return Err(syn::Error::new(expr.span(), err_msg).into());
+            }
+            None => None,
+        };
+        values.push(attrs.value.take().or(discriminant));
+
         let main_name = if attrs.name.is_empty() {
             variant.ident.to_string()
         } else {
@@ -65,6 +92,57 @@ pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling:
 
     let enum_ident = &input.ident;
     let indices = 0..variant_idents.len();
+
+    // Either all or none of the variants have explicit values, and all values are of one kind
+    let value_methods = if values.iter().all(Option::is_none) {
+        quote::quote! {}
+    } else {
+        let values = values
+            .iter()
+            .zip(&variant_idents)
+            .map(|(value, ident)| match value {
+                Some(value) => Ok(value),
+                None => Err(syn::Error::new(
+                    ident.span(),
+                    "either all or no variants must have a value",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if values
+            .windows(2)
+            .any(|pair| std::mem::discriminant(pair[0]) != std::mem::discriminant(pair[1]))
+        {
+            let err_msg = "choice values must be either all integers or all strings";
+            return Err(syn::Error::new(input.ident.span(), err_msg).into());
+        }
+        let values = values
+            .into_iter()
+            .map(choice_value)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        quote::quote! {
+            fn from_value(value: &poise::ChoiceValue) -> Option<Self> {
+                #( if *value == #values {
+                    return Some(Self::#variant_idents);
+                } )*
+                None
+            }
+
+            fn to_value(&self) -> poise::ChoiceValue {
+                match self {
+                    #( Self::#variant_idents => #values, )*
+                }
+            }
+        }
+    };
+    let list_values = match value_methods.is_empty() {
+        true => vec![quote::quote! { None }; variant_idents.len()],
+        false => values
+            .iter()
+            .flatten()
+            .map(|value| choice_value(value).map(|value| quote::quote! { Some(#value) }))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
     Ok(quote::quote! {
         impl poise::ChoiceParameter for #enum_ident {
             fn list() -> Vec<poise::CommandParameterChoice> {
@@ -74,9 +152,12 @@ pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling:
                     localizations: std::collections::HashMap::from([
                         #( (#locales.to_string(), #localized_names.to_string()) ),*
                     ]),
+                    value: #list_values,
                 }, )* ]
             }
 
+            #value_methods
+
             fn from_index(index: usize) -> Option<Self> {
                 match index {
                     #( #indices => Some(Self::#variant_idents), )*
@@ -0,0 +1,117 @@
+//! Reads the file given in `#[poise::command(localizations = "...")]` at compile time
+
+use super::{CommandArgs, CommandParameter};
+use crate::util::Tuple2;
+
+fn error(message: impl std::fmt::Display) -> syn::Error {
+    syn::Error::new(proc_macro2::Span::call_site(), message)
+}
+
+/// Converts a `{ locale = "text", ... }` table into localization tuples
+fn read_localizations(
+    value: Option<&toml::Value>,
+    key: &str,
+) -> Result<Vec<Tuple2<String>>, syn::Error> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let table = value
+        .as_table()
+        .ok_or_else(|| error(format_args!("`{}` must be a table of locales", key)))?;
+    table
+        .iter()
+        .map(|(locale, text)| match text.as_str() {
+            Some(text) => Ok(Tuple2(locale.clone(), text.to_owned())),
+            None => Err(error(format_args!("`{}.{}` must be a string", key, locale))),
+        })
+        .collect()
+}
+
+/// Adds localizations to `existing`, unless a localization for the same locale was already given
+/// via attribute
+fn merge(existing: &mut Vec<Tuple2<String>>, new: Vec<Tuple2<String>>) {
+    for localization in new {
+        if !existing.iter().any(|x| x.0 == localization.0) {
+            existing.push(localization);
+        }
+    }
+}
+
+/// Fills in the name and description localizations of the command and its parameters from the
+/// localization file. Returns the absolute path of the file, so that the generated code can
+/// `include_str!` it and recompile when it changes.
+///
+/// The file is looked up relative to the crate root and contains one table per command, keyed by
+/// the command's identifying name:
+///
+/// ```toml
+/// [ping]
+/// name = { de = "ping" }
+/// description = { de = "Antwortet mit Pong" }
+/// help_text = { de = "Misst die Antwortzeit des Bots" }
+///
+/// [ping.parameters.target]
+/// name = { de = "ziel" }
+/// description = { de = "Wen angepingt werden soll" }
+/// ```
+pub fn apply_localization_file(
+    path: &str,
+    identifying_name: &str,
+    args: &mut CommandArgs,
+    parameters: &mut [CommandParameter],
+) -> Result<String, syn::Error> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = std::path::Path::new(&manifest_dir).join(path);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| error(format_args!("couldn't read {}: {}", path.display(), e)))?;
+    let file = content
+        .parse::<toml::Table>()
+        .map_err(|e| error(format_args!("couldn't parse {}: {}", path.display(), e)))?;
+
+    if let Some(command) = file.get(identifying_name) {
+        let command = command
+            .as_table()
+            .ok_or_else(|| error(format_args!("`{}` must be a table", identifying_name)))?;
+
+        merge(
+            &mut args.name_localized,
+            read_localizations(command.get("name"), "name")?,
+        );
+        merge(
+            &mut args.description_localized,
+            read_localizations(command.get("description"), "description")?,
+        );
+        merge(
+            &mut args.help_text_localized,
+            read_localizations(command.get("help_text"), "help_text")?,
+        );
+
+        if let Some(file_parameters) = command.get("parameters") {
+            let file_parameters = file_parameters
+                .as_table()
+                .ok_or_else(|| error("`parameters` must be a table"))?;
+            for (name, localizations) in file_parameters {
+                let parameter =
+                    parameters
+                        .iter_mut()
+                        .find(|p| &p.name == name)
+                        .ok_or_else(|| {
+                            error(format_args!(
+                                "`{}` has no parameter named `{}`",
+                                identifying_name, name
+                            ))
+                        })?;
+                merge(
+                    &mut parameter.args.name_localized,
+                    read_localizations(localizations.get("name"), "name")?,
+                );
+                merge(
+                    &mut parameter.args.description_localized,
+                    read_localizations(localizations.get("description"), "description")?,
+                );
+            }
+        }
+    }
+
+    Ok(path.to_string_lossy().into_owned())
+}
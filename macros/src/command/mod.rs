@@ -1,8 +1,10 @@
+mod localizations;
 mod prefix;
 mod slash;
 
 use crate::util::{
-    iter_tuple_2_to_hash_map, wrap_option, wrap_option_and_map, wrap_option_to_string,
+    iter_tuple_2_to_hash_map, tuple_2_iter_deref, wrap_option, wrap_option_and_map,
+    wrap_option_to_string,
 };
 use proc_macro::TokenStream;
 use syn::spanned::Spanned as _;
@@ -14,13 +16,17 @@ pub struct CommandArgs {
     prefix_command: bool,
     slash_command: bool,
     context_menu_command: Option<String>,
+    entry_point_command: bool,
 
     // When changing these, document it in parent file!
     // TODO: decide why darling(multiple) feels wrong here but not in e.g. localizations (because
     //  if it's actually irrational, the inconsistency should be fixed)
-    subcommands: crate::util::List<syn::Path>,
-    aliases: crate::util::List<String>,
+    subcommands: crate::util::Subcommands,
+    aliases: crate::util::Aliases,
     subcommand_required: bool,
+    subcommand_group: bool,
+    skip_parent_checks: bool,
+    inherit_checks: Option<bool>,
     invoke_on_edit: bool,
     reuse_response: bool,
     track_deletion: bool,
@@ -28,16 +34,28 @@ pub struct CommandArgs {
     broadcast_typing: bool,
     help_text_fn: Option<syn::Path>,
     #[darling(multiple)]
+    help_text_localized: Vec<crate::util::Tuple2<String>>,
+    help_text_localized_fn: Option<syn::Path>,
+    #[darling(multiple)]
+    example: Vec<String>,
+    #[darling(multiple)]
     check: Vec<syn::Path>,
+    #[darling(multiple)]
+    final_check: Vec<syn::Path>,
+    #[darling(multiple)]
+    middleware: Vec<syn::Path>,
     on_error: Option<syn::Path>,
+    invocation_logger: Option<syn::Path>,
     rename: Option<String>,
     #[darling(multiple)]
     name_localized: Vec<crate::util::Tuple2<String>>,
     #[darling(multiple)]
     description_localized: Vec<crate::util::Tuple2<String>>,
+    localizations: Option<String>,
     discard_spare_arguments: bool,
     hide_in_help: bool,
     ephemeral: bool,
+    auto_defer: bool,
     default_member_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_bot_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
@@ -45,9 +63,13 @@ pub struct CommandArgs {
     guild_only: bool,
     dm_only: bool,
     nsfw_only: bool,
+    install_context: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
+    interaction_context: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     identifying_name: Option<String>,
     category: Option<String>,
     custom_data: Option<syn::Expr>,
+    auto_reply: bool,
+    on_create_slash: Option<syn::Path>,
 
     // In seconds
     global_cooldown: Option<u64>,
@@ -55,6 +77,17 @@ pub struct CommandArgs {
     guild_cooldown: Option<u64>,
     channel_cooldown: Option<u64>,
     member_cooldown: Option<u64>,
+    cooldown_wait: Option<u64>,
+    cooldown_max_waiting: Option<u32>,
+    bucket: Option<String>,
+    max_concurrent_invocations: Option<usize>,
+}
+
+impl CommandArgs {
+    /// Paths of all subcommands given in `subcommands(...)`
+    pub fn subcommand_paths(&self) -> impl Iterator<Item = &syn::Path> {
+        self.subcommands.0.iter().map(|subcommand| &subcommand.path)
+    }
 }
 
 /// Representation of the function parameter attribute arguments
@@ -69,15 +102,21 @@ struct ParamArgs {
     #[darling(multiple)]
     description_localized: Vec<crate::util::Tuple2<String>>,
     autocomplete: Option<syn::Path>,
-    channel_types: Option<crate::util::List<syn::Ident>>,
+    channel_types: Option<crate::util::ChannelTypes>,
     choices: Option<crate::util::List<syn::Lit>>,
-    min: Option<syn::Lit>,
-    max: Option<syn::Lit>,
-    min_length: Option<syn::Lit>,
-    max_length: Option<syn::Lit>,
+    #[darling(multiple)]
+    choices_localized: Vec<crate::util::List<String>>,
+    min: Option<syn::Expr>,
+    max: Option<syn::Expr>,
+    min_length: Option<syn::Expr>,
+    max_length: Option<syn::Expr>,
+    option_modifier: Option<syn::Path>,
+    custom_parser: Option<syn::Path>,
+    required: Option<bool>,
     lazy: bool,
     flag: bool,
     rest: bool,
+    from_reply: bool,
 }
 
 /// Part of the Invocation struct. Represents a single parameter of a Discord command.
@@ -85,9 +124,150 @@ struct CommandParameter {
     name: String,
     type_: syn::Type,
     args: ParamArgs,
+    /// Predicates of the `#[cfg(...)]` attributes on this parameter
+    cfg: Vec<proc_macro2::TokenStream>,
     span: proc_macro2::Span,
 }
 
+impl Invocation {
+    /// Path to the inner command function, with explicit generic arguments. Type parameters that
+    /// don't appear in the Context type (e.g. `B` in `Context<'_, B::Data, B::Error>`) can't be
+    /// inferred at the call site otherwise
+    fn inner_function(&self) -> proc_macro2::TokenStream {
+        let generic_args = self
+            .function
+            .sig
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                syn::GenericParam::Type(param) => Some(&param.ident),
+                syn::GenericParam::Const(param) => Some(&param.ident),
+                syn::GenericParam::Lifetime(_) => None,
+            })
+            .collect::<Vec<_>>();
+        match generic_args.is_empty() {
+            true => quote::quote! { inner },
+            false => quote::quote! { inner::<#( #generic_args ),*> },
+        }
+    }
+
+    /// Calls the inner command function with the given arguments and converts its result into
+    /// `Result<(), FrameworkError>`. With `auto_reply`, the returned value is sent as the response
+    fn call_inner(&self, args: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let inner = self.inner_function();
+        let call = quote::quote! {
+            #inner(ctx.into(), #args)
+                .await
+                .map_err(|error| poise::FrameworkError::new_command(ctx.into(), error))
+        };
+        match self.args.auto_reply {
+            false => call,
+            true => quote::quote! { {
+                let reply = #call?;
+                poise::send_reply(ctx.into(), ::std::convert::Into::into(reply))
+                    .await
+                    .map(|_| ())
+                    .map_err(|error| poise::FrameworkError::new_command(
+                        ctx.into(),
+                        ::std::convert::Into::into(error),
+                    ))
+            } },
+        }
+    }
+}
+
+impl CommandParameter {
+    /// If the parameter is an `Option` or a `Vec`, which of the two, and the type inside.
+    ///
+    /// Detected from the spelling of the type. With `#[required = false]` on a type that isn't
+    /// recognizably an `Option<T>`, like a type alias, `T` is named through
+    /// `poise::OptionalArgument`
+    fn optional_type(&self) -> Option<(syn::Ident, syn::Type)> {
+        for wrapper in ["Option", "Vec"] {
+            if let Some(inner) = crate::util::extract_type_parameter(wrapper, &self.type_) {
+                return Some((syn::Ident::new(wrapper, self.span), inner.clone()));
+            }
+        }
+        match self.args.required {
+            Some(false) => {
+                let type_ = &self.type_;
+                Some((
+                    syn::Ident::new("Option", self.span),
+                    syn::parse_quote! { <#type_ as ::poise::OptionalArgument>::Inner },
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the slash command option is required: if the type isn't an `Option` or `Vec`,
+    /// unless overridden with `#[required = ...]`
+    fn required(&self) -> bool {
+        // Don't require user to input a value for flags - use false as default value
+        if self.args.flag {
+            return false;
+        }
+        self.args
+            .required
+            .unwrap_or_else(|| self.optional_type().is_none())
+    }
+
+    /// The parameter type as given to the argument parsing macros, which recognize `Option` and
+    /// `Vec` only if spelled exactly like that
+    fn parse_type(&self) -> syn::Type {
+        match self.optional_type() {
+            Some((wrapper, inner)) => syn::parse_quote! { #wrapper<#inner> },
+            None => self.type_.clone(),
+        }
+    }
+
+    /// The `#[cfg(...)]` attributes of this parameter, for code generated from it
+    fn cfg_attrs(&self) -> proc_macro2::TokenStream {
+        let predicates = &self.cfg;
+        quote::quote! { #( #[cfg(#predicates)] )* }
+    }
+}
+
+/// Maximum number of cfg-gated parameters per command, see [`cfg_variants`]
+const MAX_CFG_GATED_PARAMETERS: usize = 4;
+
+/// Enumerates every combination of cfg-gated parameters being compiled in or out, for code that
+/// can't carry cfg attributes per parameter (like the argument parsing macros).
+///
+/// Returns a cfg attribute selecting the combination, and the indices of the parameters present in
+/// it. Without cfg-gated parameters, this is a single unconditional variant with all parameters.
+///
+/// The number of variants grows exponentially, so the caller must limit the number of cfg-gated
+/// parameters to [`MAX_CFG_GATED_PARAMETERS`].
+fn cfg_variants(parameters: &[CommandParameter]) -> Vec<(proc_macro2::TokenStream, Vec<usize>)> {
+    let gated = (0..parameters.len())
+        .filter(|&i| !parameters[i].cfg.is_empty())
+        .collect::<Vec<_>>();
+    if gated.is_empty() {
+        return vec![(quote::quote! {}, (0..parameters.len()).collect())];
+    }
+
+    (0..1_usize << gated.len())
+        .map(|mask| {
+            let is_included = |i: usize| match gated.iter().position(|&g| g == i) {
+                Some(bit) => mask & (1 << bit) != 0,
+                None => true,
+            };
+            let conditions = gated.iter().map(|&i| {
+                let predicates = &parameters[i].cfg;
+                match is_included(i) {
+                    true => quote::quote! { all(#( #predicates ),*) },
+                    false => quote::quote! { not(all(#( #predicates ),*)) },
+                }
+            });
+            let cfg = quote::quote! { #[cfg(all(#( #conditions ),*))] };
+            let included = (0..parameters.len()).filter(|&i| is_included(i)).collect();
+            (cfg, included)
+        })
+        .collect()
+}
+
 /// Passed to prefix and slash command spec generators; contains info to be included in command spec
 pub struct Invocation {
     parameters: Vec<CommandParameter>,
@@ -97,6 +277,8 @@ pub struct Invocation {
     default_member_permissions: syn::Expr,
     required_permissions: syn::Expr,
     required_bot_permissions: syn::Expr,
+    /// Absolute path of the `localizations` file, if any
+    localization_file: Option<String>,
     args: CommandArgs,
 }
 
@@ -129,7 +311,7 @@ fn extract_help_from_doc_comments(attrs: &[syn::Attribute]) -> (Option<String>,
 }
 
 pub fn command(
-    args: CommandArgs,
+    mut args: CommandArgs,
     mut function: syn::ItemFn,
 ) -> Result<TokenStream, darling::Error> {
     // Verify some things about the function. Not strictly needed, but avoids confusion
@@ -145,9 +327,19 @@ pub fn command(
     }
 
     // Verify that at least one command type was enabled
-    if !args.prefix_command && !args.slash_command && args.context_menu_command.is_none() {
-        let err_msg = "you must enable at least one of `prefix_command`, `slash_command` or \
-            `context_menu_command`";
+    if !args.prefix_command
+        && !args.slash_command
+        && args.context_menu_command.is_none()
+        && !args.entry_point_command
+    {
+        let err_msg = "you must enable at least one of `prefix_command`, `slash_command`, \
+            `context_menu_command` or `entry_point_command`";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+
+    // Entry point commands are launched without any options
+    if args.entry_point_command && function.sig.inputs.len() > 1 {
+        let err_msg = "entry_point_command is set, but the command has arguments";
         return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
     }
 
@@ -163,6 +355,13 @@ pub fn command(
         return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
     }
 
+    // A subcommand group only holds subcommands, so it must have some and can't take arguments
+    if args.subcommand_group && (function.sig.inputs.len() > 1 || args.subcommands.0.is_empty()) {
+        let err_msg = "subcommand_group is set to true, but the command has arguments or no \
+            subcommands";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+
     // Collect argument names/types/attributes to insert into generated function
     let mut parameters = Vec::new();
     for command_param in function.sig.inputs.iter_mut().skip(1) {
@@ -175,12 +374,38 @@ pub fn command(
             }
         };
 
-        let attrs: Vec<_> = pattern
+        // cfg attributes stay on the parameter of the inner function, all others are ours
+        let (cfg_attrs, attrs): (Vec<_>, Vec<_>) = pattern
             .attrs
             .drain(..)
+            .partition(|attr| attr.path().is_ident("cfg"));
+        let cfg = cfg_attrs
+            .iter()
+            .map(|attr| attr.meta.require_list().map(|list| list.tokens.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        pattern.attrs = cfg_attrs;
+
+        let attrs: Vec<_> = attrs
+            .into_iter()
             .map(|attr| darling::ast::NestedMeta::Meta(attr.meta))
             .collect();
         let attrs = <ParamArgs as darling::FromMeta>::from_list(&attrs)?;
+        if attrs.custom_parser.is_some()
+            && (attrs.choices.is_some()
+                || attrs.required.is_some()
+                || attrs.flag
+                || attrs.lazy
+                || attrs.rest
+                || attrs.from_reply)
+        {
+            let message = "#[custom_parser] cannot be used together with #[choices], #[required], \
+                #[flag], #[lazy], #[rest] or #[from_reply]";
+            return Err(syn::Error::new(span, message).into());
+        }
+        if attrs.flag && attrs.required.is_some() {
+            let message = "#[flag] parameters are never required, #[required] has no effect";
+            return Err(syn::Error::new(span, message).into());
+        }
 
         let name = if let Some(rename) = &attrs.rename {
             rename.clone()
@@ -194,9 +419,42 @@ pub fn command(
             name,
             type_: (*pattern.ty).clone(),
             args: attrs,
+            cfg,
             span,
         });
     }
+    if let Some(param) = parameters
+        .iter()
+        .filter(|param| !param.cfg.is_empty())
+        .nth(MAX_CFG_GATED_PARAMETERS)
+    {
+        let message = format!(
+            "at most {} parameters can have #[cfg(...)] attributes, because argument parsing is \
+            generated for every combination of them",
+            MAX_CFG_GATED_PARAMETERS
+        );
+        return Err(syn::Error::new(param.span, message).into());
+    }
+
+    let localization_file = match &args.localizations {
+        Some(path) => {
+            let identifying_name = args.identifying_name.clone().unwrap_or_else(|| {
+                function
+                    .sig
+                    .ident
+                    .to_string()
+                    .trim_start_matches("r#")
+                    .to_string()
+            });
+            Some(localizations::apply_localization_file(
+                &path.clone(),
+                &identifying_name,
+                &mut args,
+                &mut parameters,
+            )?)
+        }
+        None => None,
+    };
 
     // Extract the command descriptions from the function doc comments
     let (description, help_text) = extract_help_from_doc_comments(&function.attrs);
@@ -225,6 +483,7 @@ pub fn command(
         default_member_permissions,
         required_permissions,
         required_bot_permissions,
+        localization_file,
     };
 
     Ok(TokenStream::from(generate_command(inv)?))
@@ -251,6 +510,10 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         true => Some(slash::generate_slash_action(&inv)?),
         false => None,
     });
+    let entry_point_action = wrap_option(match inv.args.entry_point_command {
+        true => Some(slash::generate_slash_action(&inv)?),
+        false => None,
+    });
     let context_menu_action = wrap_option(match &inv.args.context_menu_command {
         Some(_) => Some(slash::generate_context_menu_action(&inv)?),
         None => None,
@@ -285,11 +548,29 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let default_member_permissions = &inv.default_member_permissions;
     let required_permissions = &inv.required_permissions;
     let required_bot_permissions = &inv.required_bot_permissions;
-    let subcommand_required = inv.args.subcommand_required;
+    // Subcommand groups can't be invoked on their own
+    let subcommand_required = inv.args.subcommand_required || inv.args.subcommand_group;
+    let subcommand_group = inv.args.subcommand_group;
+    let skip_parent_checks = inv.args.skip_parent_checks;
+    let inherit_checks = inv.args.inherit_checks.unwrap_or(true);
     let owners_only = inv.args.owners_only;
     let guild_only = inv.args.guild_only;
     let dm_only = inv.args.dm_only;
     let nsfw_only = inv.args.nsfw_only;
+    let install_context = match &inv.args.install_context {
+        Some(contexts) => {
+            let contexts = contexts.iter();
+            quote::quote! { Some(vec![ #( poise::serenity_prelude::InstallationContext::#contexts ),* ]) }
+        }
+        None => quote::quote! { None },
+    };
+    let interaction_context = match &inv.args.interaction_context {
+        Some(contexts) => {
+            let contexts = contexts.iter();
+            quote::quote! { Some(vec![ #( poise::serenity_prelude::InteractionContext::#contexts ),* ]) }
+        }
+        None => quote::quote! { None },
+    };
 
     let help_text = match &inv.args.help_text_fn {
         Some(help_text_fn) => quote::quote! { Some(#help_text_fn()) },
@@ -299,22 +580,63 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         },
     };
 
+    let help_text_localizations = {
+        let from_attributes =
+            iter_tuple_2_to_hash_map(tuple_2_iter_deref(&inv.args.help_text_localized));
+        match &inv.args.help_text_localized_fn {
+            // Explicit attributes take precedence over the function output
+            Some(help_text_localized_fn) => quote::quote! { {
+                let mut localizations: std::collections::HashMap<String, String> =
+                    #help_text_localized_fn();
+                localizations.extend(#from_attributes);
+                localizations
+            } },
+            None => from_attributes,
+        }
+    };
+
+    let examples = &inv.args.example;
+    let bucket = wrap_option_to_string(inv.args.bucket.as_ref());
+    let max_concurrent_invocations = wrap_option(inv.args.max_concurrent_invocations);
     let checks = &inv.args.check;
+    let final_checks = &inv.args.final_check;
+    let middleware = &inv.args.middleware;
     // Box::pin the callback in order to store it in a struct
     let on_error = match &inv.args.on_error {
         Some(on_error) => quote::quote! { Some(|err| Box::pin(#on_error(err))) },
         None => quote::quote! { None },
     };
+    let invocation_logger = match &inv.args.invocation_logger {
+        Some(logger) => quote::quote! { Some(|record| Box::pin(#logger(record))) },
+        None => quote::quote! { None },
+    };
 
     let invoke_on_edit = inv.args.invoke_on_edit || inv.args.track_edits;
     let reuse_response = inv.args.reuse_response || inv.args.track_edits;
     let track_deletion = inv.args.track_deletion || inv.args.track_edits;
     let broadcast_typing = inv.args.broadcast_typing;
-    let aliases = &inv.args.aliases.0;
-    let subcommands = &inv.args.subcommands.0;
+    let aliases = match &inv.args.aliases {
+        crate::util::Aliases::List(aliases) => quote::quote! {
+            vec![ #( #aliases.to_string(), )* ]
+        },
+        crate::util::Aliases::Expr(aliases) => quote::quote! {
+            ::std::iter::IntoIterator::into_iter(#aliases)
+                .map(|alias| ::std::string::ToString::to_string(&alias))
+                .collect()
+        },
+    };
+    let subcommands = inv.args.subcommands.0.iter().map(|subcommand| {
+        let path = &subcommand.path;
+        match &subcommand.cfg {
+            Some(predicate) => quote::quote! { #[cfg(#predicate)] #path() },
+            None => quote::quote! { #path() },
+        }
+    });
 
     let parameters = slash::generate_parameters(&inv)?;
     let ephemeral = inv.args.ephemeral;
+    let auto_defer = inv.args.auto_defer;
+    let on_create_slash = wrap_option(inv.args.on_create_slash.as_ref());
     let custom_data = match &inv.args.custom_data {
         Some(custom_data) => quote::quote! { Box::new(#custom_data) },
         None => quote::quote! { Box::new(()) },
@@ -324,9 +646,17 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let description_localizations =
         iter_tuple_2_to_hash_map(inv.args.description_localized.into_iter());
 
+    // Makes rustc recompile the command when the localization file changes
+    let localization_file = inv
+        .localization_file
+        .iter()
+        .map(|path| quote::quote! { const _: &str = include_str!(#path); });
+
     let function_ident =
         std::mem::replace(&mut inv.function.sig.ident, syn::parse_quote! { inner });
     let function_generics = &inv.function.sig.generics;
+    // Generics' ToTokens impl leaves out the where clause
+    let function_where_clause = &inv.function.sig.generics.where_clause;
     let function_visibility = &inv.function.vis;
     let function = &inv.function;
     Ok(quote::quote! {
@@ -334,16 +664,21 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         #function_visibility fn #function_ident #function_generics() -> ::poise::Command<
             <#ctx_type_with_static as poise::_GetGenerics>::U,
             <#ctx_type_with_static as poise::_GetGenerics>::E,
-        > {
+        > #function_where_clause {
             #function
+            #( #localization_file )*
 
             ::poise::Command {
                 prefix_action: #prefix_action,
                 slash_action: #slash_action,
                 context_menu_action: #context_menu_action,
+                entry_point_action: #entry_point_action,
 
-                subcommands: vec![ #( #subcommands() ),* ],
+                subcommands: vec![ #( #subcommands ),* ],
                 subcommand_required: #subcommand_required,
+                subcommand_group: #subcommand_group,
+                skip_parent_checks: #skip_parent_checks,
+                inherit_checks: #inherit_checks,
                 name: #command_name.to_string(),
                 name_localizations: #name_localizations,
                 qualified_name: String::from(#command_name), // properly filled in later by Framework
@@ -353,9 +688,14 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 description: #description,
                 description_localizations: #description_localizations,
                 help_text: #help_text,
+                help_text_localizations: #help_text_localizations,
+                examples: vec![ #( #examples.to_string(), )* ],
                 hide_in_help: #hide_in_help,
                 cooldowns: std::sync::Mutex::new(::poise::Cooldowns::new()),
                 cooldown_config: #cooldown_config,
+                bucket: #bucket,
+                max_concurrent_invocations: #max_concurrent_invocations,
+                active_invocations: std::sync::atomic::AtomicUsize::new(0),
                 reuse_response: #reuse_response,
                 default_member_permissions: #default_member_permissions,
                 required_permissions: #required_permissions,
@@ -364,19 +704,32 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 guild_only: #guild_only,
                 dm_only: #dm_only,
                 nsfw_only: #nsfw_only,
+                install_context: #install_context,
+                interaction_context: #interaction_context,
                 checks: vec![ #( |ctx| Box::pin(#checks(ctx)) ),* ],
+                final_checks: vec![ #( |ctx| Box::pin(#final_checks(ctx)) ),* ],
+                middleware: vec![ #( Box::new(#middleware) ),* ],
                 on_error: #on_error,
-                parameters: vec![ #( #parameters ),* ],
+                invocation_logger: #invocation_logger,
+                parameters: {
+                    let mut parameters: Vec<::poise::CommandParameter<_, _>> = vec![ #( #parameters ),* ];
+                    // Optional parameters come last - Discord requires this order
+                    parameters.sort_by_key(|parameter| !parameter.required);
+                    parameters
+                },
                 custom_data: #custom_data,
 
-                aliases: vec![ #( #aliases.to_string(), )* ],
+                aliases: #aliases,
                 invoke_on_edit: #invoke_on_edit,
                 track_deletion: #track_deletion,
                 broadcast_typing: #broadcast_typing,
 
                 context_menu_name: #context_menu_name,
                 ephemeral: #ephemeral,
+                auto_defer: #auto_defer,
+                on_create_slash: #on_create_slash,
 
+                __adapter: None,
                 __non_exhaustive: (),
             }
         }
@@ -403,6 +756,11 @@ fn generate_cooldown_config(args: &CommandArgs) -> proc_macro2::TokenStream {
     let guild_cooldown = wrap_option_and_map(args.guild_cooldown, &to_seconds_path);
     let channel_cooldown = wrap_option_and_map(args.channel_cooldown, &to_seconds_path);
     let member_cooldown = wrap_option_and_map(args.member_cooldown, &to_seconds_path);
+    let wait_threshold = wrap_option_and_map(args.cooldown_wait, &to_seconds_path);
+    let max_waiting = match args.cooldown_max_waiting {
+        Some(max_waiting) => quote::quote! { Some(#max_waiting) },
+        None => quote::quote! { None },
+    };
 
     quote::quote!(
         std::sync::RwLock::new(::poise::CooldownConfig {
@@ -411,6 +769,9 @@ fn generate_cooldown_config(args: &CommandArgs) -> proc_macro2::TokenStream {
             guild: #guild_cooldown,
             channel: #channel_cooldown,
             member: #member_cooldown,
+            wait_threshold: #wait_threshold,
+            max_waiting: #max_waiting,
+            role_tiers: Vec::new(),
             __non_exhaustive: ()
         })
     )
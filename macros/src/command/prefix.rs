@@ -8,18 +8,24 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
         Lazy,
         Flag,
         Rest,
+        FromReply,
     }
-    let modifier = match (p.args.lazy, p.args.rest, p.args.flag) {
-        (false, false, false) => Modifier::None,
-        (true, false, false) => Modifier::Lazy,
-        (false, true, false) => Modifier::Rest,
-        (false, false, true) => Modifier::Flag,
+    let modifier = match (p.args.lazy, p.args.rest, p.args.flag, p.args.from_reply) {
+        (false, false, false, false) => Modifier::None,
+        (true, false, false, false) => Modifier::Lazy,
+        (false, true, false, false) => Modifier::Rest,
+        (false, false, true, false) => Modifier::Flag,
+        (false, false, false, true) => Modifier::FromReply,
         _ => {
             let message = "modifiers like #[lazy] or #[rest] currently cannot be used together";
             return Err(syn::Error::new(p.span, message));
         }
     };
-    let type_ = &p.type_;
+    let type_ = &p.parse_type();
+    if let Some(parser) = &p.args.custom_parser {
+        // Combinations with modifiers are rejected when parsing the parameter attributes
+        return Ok(quote::quote! { #[custom] (#parser => #type_) });
+    }
     Ok(match modifier {
         Modifier::Flag => {
             if p.type_ != syn::parse_quote! { bool } {
@@ -33,10 +39,35 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
         }
         Modifier::Lazy => quote::quote! { #[lazy] (#type_) },
         Modifier::Rest => quote::quote! { #[rest] (#type_) },
+        Modifier::FromReply => quote::quote! { #[from_reply] (#type_) },
         Modifier::None => quote::quote! { (#type_) },
     })
 }
 
+/// Whether the type is `Attachment`, `Option<Attachment>` or `Vec<Attachment>`, judging by the last
+/// path segment because the macro can't resolve types
+fn is_attachment_type(type_: &syn::Type) -> bool {
+    let syn::Type::Path(path) = type_ else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Attachment" {
+        return true;
+    }
+    if segment.ident != "Option" && segment.ident != "Vec" {
+        return false;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(inner)) => is_attachment_type(inner),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStream, syn::Error> {
     let param_idents = (0..inv.parameters.len())
         .map(|i| format_ident!("poise_param_{i}"))
@@ -51,28 +82,134 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
         false => None,
     };
 
+    // The parse macro can't skip cfg-gated parameters, so it's invoked once per combination
+    let parse_statements = super::cfg_variants(&inv.parameters).into_iter().map(
+        |(cfg, indices)| {
+            let param_idents = indices.iter().map(|&i| &param_idents[i]);
+            let param_specs = indices.iter().map(|&i| &param_specs[i]);
+            // Command::parameters is sorted differently, so the parameter is found by name
+            let param_names = indices.iter().map(|&i| &inv.parameters[i].name);
+            quote::quote! {
+                #cfg
+                let ( #( #param_idents, )* .. ) = ::poise::_parse_prefix_args_indexed!(
+                    ctx.serenity_context, ctx.msg, ctx.args, 0 =>
+                    #( #param_specs, )*
+                    #wildcard_arg
+                ).await.map_err(|(error, input, index)| {
+                    let error = poise::FrameworkError::new_argument_parse(ctx.into(), input, error);
+                    match index.and_then(|index| [ #( #param_names, )* ].get(index).copied()) {
+                        Some(name) => error.with_parameter_name(name),
+                        None => error,
+                    }
+                })?;
+            }
+        },
+    );
+    let param_args = inv
+        .parameters
+        .iter()
+        .zip(&param_idents)
+        .map(|(param, ident)| {
+            let cfg_attrs = param.cfg_attrs();
+            quote::quote! { #cfg_attrs #ident }
+        });
+
+    // Messages with more attachments than the attachment parameters take are rejected, so that
+    // attachments aren't silently ignored. `discard_spare_arguments` opts out of this too
+    let attachment_counts = inv
+        .parameters
+        .iter()
+        .zip(&param_idents)
+        .filter(|(param, _)| {
+            !param.args.flag
+                && param.args.custom_parser.is_none()
+                && is_attachment_type(&param.type_)
+        })
+        .map(|(param, ident)| {
+            let cfg_attrs = param.cfg_attrs();
+            quote::quote! {
+                #cfg_attrs
+                { used_attachments += ::poise::AttachmentCount::attachment_count(&#ident); }
+            }
+        })
+        .collect::<Vec<_>>();
+    let check_attachments = !attachment_counts.is_empty() && !inv.args.discard_spare_arguments;
+    let attachment_check = check_attachments.then(|| {
+        quote::quote! {
+            let mut used_attachments = 0;
+            #( #attachment_counts )*
+            let available = ctx.msg.attachments.len();
+            if used_attachments < available {
+                return Err(poise::FrameworkError::new_argument_parse(
+                    ctx.into(),
+                    None,
+                    Box::new(::poise::TooManyAttachments {
+                        used: used_attachments,
+                        available,
+                        __non_exhaustive: (),
+                    }),
+                ));
+            }
+        }
+    });
+
+    // Discord only restricts the channel picker of slash commands, so check prefix arguments here
+    let channel_type_checks = inv
+        .parameters
+        .iter()
+        .zip(&param_idents)
+        .filter(|(param, _)| param.args.channel_types.is_some())
+        .map(|(param, ident)| {
+            let cfg_attrs = param.cfg_attrs();
+            let name = &param.name;
+            quote::quote! {
+                #cfg_attrs
+                {
+                    use ::poise::ChannelTypesHack as _;
+                    let channel_types = (&&::poise::ChannelTypesOf(&#ident)).channel_types();
+                    ::poise::check_channel_types(ctx.into(), #name, channel_types)?;
+                }
+            }
+        });
+
+    let constraint_checks = inv
+        .parameters
+        .iter()
+        .zip(&param_idents)
+        .filter(|(param, _)| {
+            let args = &param.args;
+            args.min.is_some()
+                || args.max.is_some()
+                || args.min_length.is_some()
+                || args.max_length.is_some()
+        })
+        .map(|(param, ident)| {
+            let cfg_attrs = param.cfg_attrs();
+            let name = &param.name;
+            quote::quote! {
+                #cfg_attrs
+                {
+                    use ::poise::MeasuresHack as _;
+                    let measures = (&&::poise::MeasuresOf(&#ident)).measures();
+                    ::poise::check_parameter_constraints(ctx.into(), #name, measures)?;
+                }
+            }
+        });
+
+    let call_inner = inv.call_inner(quote::quote! { #( #param_args, )* });
+
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
-            let ( #( #param_idents, )* .. ) = ::poise::parse_prefix_args!(
-                ctx.serenity_context, ctx.msg, ctx.args, 0 =>
-                #( #param_specs, )*
-                #wildcard_arg
-            ).await.map_err(|(error, input)| poise::FrameworkError::new_argument_parse(
-                ctx.into(),
-                input,
-                error,
-            ))?;
+            #( #parse_statements )*
+            #attachment_check
+            #( #channel_type_checks )*
+            #( #constraint_checks )*
 
             if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+                ::poise::start_cooldown(ctx.into()).await;
             }
 
-            inner(ctx.into(), #( #param_idents, )* )
-                .await
-                .map_err(|error| poise::FrameworkError::new_command(
-                    ctx.into(),
-                    error,
-                ))
+            #call_inner
         })
     })
 }
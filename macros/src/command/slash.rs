@@ -1,10 +1,73 @@
 use super::Invocation;
 use crate::util::{
-    extract_type_parameter, iter_tuple_2_to_hash_map, tuple_2_iter_deref, wrap_option_to_string,
+    iter_tuple_2_to_hash_map, tuple_2_iter_deref, wrap_option, wrap_option_to_string,
 };
 use quote::format_ident;
 use syn::spanned::Spanned as _;
 
+/// Generates the localization maps of the inline choices of `param`, one per choice, from the
+/// `#[choices_localized("locale", "a", "b")]` attributes
+fn choice_localizations(
+    param: &super::CommandParameter,
+    num_choices: usize,
+) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
+    let mut localizations = (0..num_choices).map(|_| Vec::new()).collect::<Vec<_>>();
+    for list in &param.args.choices_localized {
+        let Some((locale, names)) = list.0.split_first() else {
+            return Err(syn::Error::new(
+                param.span,
+                "missing locale in choices_localized",
+            ));
+        };
+        if names.len() != num_choices {
+            return Err(syn::Error::new(
+                param.span,
+                format!(
+                    "choices_localized for `{}` has {} names, expected one per choice ({})",
+                    locale,
+                    names.len(),
+                    num_choices
+                ),
+            ));
+        }
+        for (choice_localizations, name) in localizations.iter_mut().zip(names) {
+            choice_localizations.push(crate::util::Tuple2(locale, name));
+        }
+    }
+    Ok(localizations
+        .into_iter()
+        .map(|choice_localizations| iter_tuple_2_to_hash_map(choice_localizations.into_iter()))
+        .collect())
+}
+
+/// Generates the value of a `#[min_length]` or `#[max_length]` attribute. Discord takes lengths
+/// as u16, so out-of-range values are rejected instead of wrapping around: integer literals at
+/// compile time, other expressions when the command is created
+fn length_limit(
+    attribute: &str,
+    value: Option<&syn::Expr>,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let Some(value) = value else {
+        return Ok(quote::quote! { None });
+    };
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Int(lit),
+        ..
+    }) = value
+    {
+        let value = lit.base10_parse::<u16>().map_err(|_| {
+            let message = format!("{} must be between 0 and {}", attribute, u16::MAX);
+            syn::Error::new(lit.span(), message)
+        })?;
+        return Ok(quote::quote! { Some(#value) });
+    }
+
+    let message = format!("{} must be between 0 and {}", attribute, u16::MAX);
+    Ok(quote::quote! {
+        Some(<u16 as ::std::convert::TryFrom<_>>::try_from(#value).expect(#message))
+    })
+}
+
 pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
     let mut parameter_structs = Vec::new();
     for param in &inv.parameters {
@@ -12,17 +75,23 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         // supply descriptions later (e.g. via translation framework like fluent)
         let description = wrap_option_to_string(param.args.description.as_ref());
 
-        let (mut required, type_) = match extract_type_parameter("Option", &param.type_)
-            .or_else(|| extract_type_parameter("Vec", &param.type_))
-        {
-            Some(t) => (false, t),
-            None => (true, &param.type_),
+        let type_ = match param.optional_type() {
+            Some((_, t)) => t,
+            None => param.type_.clone(),
         };
 
-        // Don't require user to input a value for flags - use false as default value (see below)
-        if param.args.flag {
-            required = false;
-        }
+        // Custom parsers bypass the type detection and decide everything themselves
+        let custom_parser = param.args.custom_parser.as_ref().map(|parser| {
+            let type_ = &param.type_;
+            quote::quote! { <#parser as ::poise::CustomParameterParser<#type_>> }
+        });
+        let required = match &custom_parser {
+            Some(custom_parser) => quote::quote! { #custom_parser::required() },
+            None => {
+                let required = param.required();
+                quote::quote! { #required }
+            }
+        };
 
         let param_name = &param.name;
         let name_localizations =
@@ -56,34 +125,24 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         };
 
         // We can just cast to f64 here because Discord only uses f64 precision anyways
-        // TODO: move this to poise::CommandParameter::{min, max} fields
-        let min_value_setter = match &param.args.min {
-            Some(x) => quote::quote! { .min_number_value(#x as f64) },
-            None => quote::quote! {},
-        };
-        let max_value_setter = match &param.args.max {
-            Some(x) => quote::quote! { .max_number_value(#x as f64) },
-            None => quote::quote! {},
-        };
-        // TODO: move this to poise::CommandParameter::{min_length, max_length} fields
-        let min_length_setter = match &param.args.min_length {
-            Some(x) => quote::quote! { .min_length(#x) },
-            None => quote::quote! {},
+        let min = match &param.args.min {
+            Some(x) => quote::quote! { Some((#x) as f64) },
+            None => quote::quote! { None },
         };
-        let max_length_setter = match &param.args.max_length {
-            Some(x) => quote::quote! { .max_length(#x) },
-            None => quote::quote! {},
+        let max = match &param.args.max {
+            Some(x) => quote::quote! { Some((#x) as f64) },
+            None => quote::quote! { None },
         };
+        let min_length = length_limit("min_length", param.args.min_length.as_ref())?;
+        let max_length = length_limit("max_length", param.args.max_length.as_ref())?;
         let type_setter = match inv.args.slash_command {
             true => {
                 if let Some(_choices) = &param.args.choices {
                     quote::quote! { Some(|o| o.kind(::poise::serenity_prelude::CommandOptionType::Integer)) }
+                } else if let Some(custom_parser) = &custom_parser {
+                    quote::quote! { Some(|o| #custom_parser::create(o)) }
                 } else {
-                    quote::quote! { Some(|o| {
-                        poise::create_slash_argument!(#type_, o)
-                        #min_value_setter #max_value_setter
-                        #min_length_setter #max_length_setter
-                    }) }
+                    quote::quote! { Some(|o| poise::create_slash_argument!(#type_, o)) }
                 }
             }
             false => quote::quote! { None },
@@ -94,11 +153,15 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
             true => {
                 if let Some(choices) = &param.args.choices {
                     let choices = &choices.0;
+                    let localizations = choice_localizations(param, choices.len())?;
                     quote::quote! { vec![#( ::poise::CommandParameterChoice {
                         name: ToString::to_string(&#choices),
-                        localizations: Default::default(),
+                        localizations: #localizations,
+                        value: None,
                         __non_exhaustive: (),
                     } ),*] }
+                } else if let Some(custom_parser) = &custom_parser {
+                    quote::quote! { #custom_parser::choices() }
                 } else {
                     quote::quote! { poise::slash_argument_choices!(#type_) }
                 }
@@ -107,36 +170,40 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         };
 
         let channel_types = match &param.args.channel_types {
-            Some(crate::util::List(channel_types)) => quote::quote! { Some(
-                vec![ #( poise::serenity_prelude::ChannelType::#channel_types ),* ]
+            Some(crate::util::ChannelTypes::List(channel_types)) => quote::quote! { Some(
+                vec![ #( #channel_types ),* ]
+            ) },
+            Some(crate::util::ChannelTypes::Slice(channel_types)) => quote::quote! { Some(
+                ::std::borrow::ToOwned::to_owned(&#channel_types[..])
             ) },
             None => quote::quote! { None },
         };
 
-        parameter_structs.push((
-            quote::quote! {
-                ::poise::CommandParameter {
-                    name: #param_name.to_string(),
-                    name_localizations: #name_localizations,
-                    description: #description,
-                    description_localizations: #desc_localizations,
-                    required: #required,
-                    channel_types: #channel_types,
-                    type_setter: #type_setter,
-                    choices: #choices,
-                    autocomplete_callback: #autocomplete_callback,
-                    __non_exhaustive: (),
-                }
-            },
-            required,
-        ));
+        let option_modifier = wrap_option(param.args.option_modifier.as_ref());
+
+        let cfg_attrs = param.cfg_attrs();
+        parameter_structs.push(quote::quote! {
+            #cfg_attrs
+            ::poise::CommandParameter {
+                name: #param_name.to_string(),
+                name_localizations: #name_localizations,
+                description: #description,
+                description_localizations: #desc_localizations,
+                required: #required,
+                channel_types: #channel_types,
+                type_setter: #type_setter,
+                choices: #choices,
+                min: #min,
+                max: #max,
+                min_length: #min_length,
+                max_length: #max_length,
+                autocomplete_callback: #autocomplete_callback,
+                option_modifier: #option_modifier,
+                __non_exhaustive: (),
+            }
+        });
     }
-    // Sort the parameters so that optional parameters come last - Discord requires this order
-    parameter_structs.sort_by_key(|(_, required)| !required);
-    Ok(parameter_structs
-        .into_iter()
-        .map(|(builder, _)| builder)
-        .collect::<Vec<_>>())
+    Ok(parameter_structs)
 }
 
 pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStream, syn::Error> {
@@ -164,37 +231,60 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
             let t = &p.type_;
             if p.args.flag {
                 quote::quote! { FLAG }
+            } else if let Some(parser) = &p.args.custom_parser {
+                quote::quote! { CUSTOM #parser => #t }
             } else if let Some(choices) = &p.args.choices {
                 let choice_indices = (0..choices.0.len()).map(syn::Index::from);
                 let choice_vals = &choices.0;
                 quote::quote! { INLINE_CHOICE #t [#(#choice_indices: #choice_vals),*] }
             } else {
+                let t = p.parse_type();
                 quote::quote! { #t }
             }
         })
         .collect::<Vec<_>>();
 
+    // The parse macro can't skip cfg-gated parameters, so it's invoked once per combination
+    let parse_statements =
+        super::cfg_variants(&inv.parameters)
+            .into_iter()
+            .map(|(cfg, indices)| {
+                let param_identifiers = indices.iter().map(|&i| &param_identifiers[i]);
+                let param_names = indices.iter().map(|&i| param_names[i]);
+                let param_types = indices.iter().map(|&i| &param_types[i]);
+                quote::quote! {
+                    // idk why this can't be put in the macro itself (where the lint is triggered) and
+                    // why clippy doesn't turn off this lint inside macros in the first place
+                    #[allow(clippy::needless_question_mark)]
+                    #cfg
+                    let ( #( #param_identifiers, )* ) = ::poise::_parse_slash_args_named!(
+                        ctx.serenity_context, ctx.interaction, ctx.args =>
+                        #( (#param_names: #param_types), )*
+                    ).await.map_err(|(error, name)| {
+                        error.to_framework_error(ctx).with_parameter_name(name)
+                    })?;
+                }
+            });
+    let param_args = inv
+        .parameters
+        .iter()
+        .zip(&param_identifiers)
+        .map(|(param, ident)| {
+            let cfg_attrs = param.cfg_attrs();
+            quote::quote! { #cfg_attrs #ident }
+        });
+
+    let call_inner = inv.call_inner(quote::quote! { #( #param_args, )* });
+
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
-            // idk why this can't be put in the macro itself (where the lint is triggered) and
-            // why clippy doesn't turn off this lint inside macros in the first place
-            #[allow(clippy::needless_question_mark)]
-
-            let ( #( #param_identifiers, )* ) = ::poise::parse_slash_args!(
-                ctx.serenity_context, ctx.interaction, ctx.args =>
-                #( (#param_names: #param_types), )*
-            ).await.map_err(|error| error.to_framework_error(ctx))?;
+            #( #parse_statements )*
 
             if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+                ::poise::start_cooldown(ctx.into()).await;
             }
 
-            inner(ctx.into(), #( #param_identifiers, )*)
-                .await
-                .map_err(|error| poise::FrameworkError::new_command(
-                    ctx.into(),
-                    error,
-                ))
+            #call_inner
         })
     })
 }
@@ -212,19 +302,16 @@ pub fn generate_context_menu_action(
         }
     };
 
+    let call_inner = inv.call_inner(quote::quote! { value });
+
     Ok(quote::quote! {
         <#param_type as ::poise::ContextMenuParameter<_, _>>::to_action(|ctx, value| {
             Box::pin(async move {
                 if !ctx.framework.options.manual_cooldowns {
-                    ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
+                    ::poise::start_cooldown(ctx.into()).await;
                 }
 
-                inner(ctx.into(), value)
-                    .await
-                    .map_err(|error| poise::FrameworkError::new_command(
-                        ctx.into(),
-                        error,
-                    ))
+                #call_inner
             })
         })
     })
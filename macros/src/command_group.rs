@@ -0,0 +1,170 @@
+//! Implements the #[derive(CommandGroup)] derive macro
+
+use proc_macro::TokenStream;
+use syn::spanned::Spanned as _;
+
+/// Representation of the `#[command_group(...)]` attribute arguments on the enum
+#[derive(Debug, darling::FromMeta)]
+struct GroupArgs {
+    // Note to self: when adding an attribute here, add it to #[proc_macro_derive]!
+    handler: syn::Path,
+    context: syn::Type,
+    rename: Option<String>,
+    #[darling(default)]
+    prefix_command: bool,
+    #[darling(default)]
+    slash_command: bool,
+}
+
+/// Representation of the variant attribute arguments
+#[derive(Debug, Default, darling::FromMeta)]
+#[darling(allow_unknown_fields, default)]
+struct VariantAttribute {
+    name: Option<String>,
+}
+
+/// Converts `AddRole` to `add_role`
+fn to_snake_case(ident: &syn::Ident) -> String {
+    let mut name = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+pub fn command_group(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
+    let enum_ = match &input.data {
+        syn::Data::Enum(x) => x,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Only enums can be used for command groups",
+            )
+            .into())
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "Command group enums cannot be generic",
+        )
+        .into());
+    }
+
+    let group_attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("command_group"))
+        .ok_or_else(|| {
+            let message = "missing `#[command_group(handler = \"...\", context = \"...\")]`";
+            syn::Error::new(input.ident.span(), message)
+        })?;
+    let args = <GroupArgs as darling::FromMeta>::from_meta(&group_attr.meta)?;
+    if !args.prefix_command && !args.slash_command {
+        let message = "you must enable at least one of `prefix_command` or `slash_command`";
+        return Err(syn::Error::new(group_attr.span(), message).into());
+    }
+
+    let enum_ident = &input.ident;
+    let handler = &args.handler;
+    let context = &args.context;
+    // Same trick as in the command macro to get U and E without a concrete lifetime
+    let context_with_static =
+        syn::fold::fold_type(&mut crate::util::AllLifetimesToStatic, context.clone());
+    let error = quote::quote! { <#context_with_static as ::poise::_GetGenerics>::E };
+    let command_types = [
+        args.prefix_command
+            .then(|| quote::quote! { prefix_command }),
+        args.slash_command.then(|| quote::quote! { slash_command }),
+    ];
+    let command_types = command_types.iter().flatten().collect::<Vec<_>>();
+
+    let mut subcommand_idents = Vec::new();
+    let mut subcommands = Vec::new();
+    for variant in &enum_.variants {
+        let fields = match &variant.fields {
+            syn::Fields::Named(fields) => fields.named.iter().collect(),
+            syn::Fields::Unit => Vec::new(),
+            syn::Fields::Unnamed(_) => {
+                return Err(syn::Error::new(
+                    variant.fields.span(),
+                    "Command group variants must have named fields or no fields",
+                )
+                .into())
+            }
+        };
+
+        let attrs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("name"))
+            .map(|attr| darling::ast::NestedMeta::Meta(attr.meta.clone()))
+            .collect::<Vec<_>>();
+        let attrs = <VariantAttribute as darling::FromMeta>::from_list(&attrs)?;
+        let name = attrs.name.unwrap_or_else(|| to_snake_case(&variant.ident));
+        let docs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+
+        // Prefixed, so the generated functions can't shadow the handler
+        let function_ident =
+            quote::format_ident!("__poise_group_{}", to_snake_case(&variant.ident));
+        let variant_ident = &variant.ident;
+        let field_idents = fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
+        let params = fields.iter().map(|field| {
+            let attrs = &field.attrs;
+            let ident = &field.ident;
+            let type_ = &field.ty;
+            quote::quote! { #( #attrs )* #ident: #type_ }
+        });
+
+        subcommands.push(quote::quote! {
+            #( #docs )*
+            #[::poise::command( #( #command_types, )* rename = #name, identifying_name = #name )]
+            async fn #function_ident(
+                ctx: #context,
+                #( #params, )*
+            ) -> ::std::result::Result<(), #error> {
+                #handler(ctx, #enum_ident::#variant_ident { #( #field_idents ),* }).await
+            }
+        });
+        subcommand_idents.push(function_ident);
+    }
+
+    let group_name = args.rename.unwrap_or_else(|| to_snake_case(enum_ident));
+    let docs = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"));
+    let subcommand_names = subcommand_idents.iter().map(|ident| ident.to_string());
+    Ok(quote::quote! {
+        impl #enum_ident {
+            /// Creates a command with one subcommand per variant of this enum
+            pub fn command() -> ::poise::Command<
+                <#context_with_static as ::poise::_GetGenerics>::U,
+                #error,
+            > {
+                #( #subcommands )*
+
+                #( #docs )*
+                #[::poise::command(
+                    #( #command_types, )*
+                    rename = #group_name,
+                    identifying_name = #group_name,
+                    subcommands( #( #subcommand_names ),* ),
+                    subcommand_required
+                )]
+                async fn __poise_group(ctx: #context) -> ::std::result::Result<(), #error> {
+                    Ok(())
+                }
+
+                __poise_group()
+            }
+        }
+    }
+    .into())
+}
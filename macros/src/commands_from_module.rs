@@ -0,0 +1,117 @@
+//! Implements the commands_from_module! macro
+
+use proc_macro::TokenStream;
+use syn::spanned::Spanned as _;
+
+/// Input of the macro: `module::path` or `module::path, "path/to/module.rs"`
+struct Input {
+    module: syn::Path,
+    file: Option<syn::LitStr>,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+        let module = input.parse()?;
+        let file = match input.parse::<Option<syn::Token![,]>>()? {
+            Some(_) if !input.is_empty() => Some(input.parse()?),
+            _ => None,
+        };
+        Ok(Self { module, file })
+    }
+}
+
+/// Guesses the source file of a module from its path, like rustc does for `mod` declarations in a
+/// crate rooted at `src/`
+fn module_file(manifest_dir: &std::path::Path, module: &syn::Path) -> Option<std::path::PathBuf> {
+    let mut dir = manifest_dir.join("src");
+    for segment in &module.segments {
+        if segment.ident != "crate" {
+            dir.push(segment.ident.to_string());
+        }
+    }
+    let file = dir.with_extension("rs");
+    if file.is_file() {
+        return Some(file);
+    }
+    let file = dir.join("mod.rs");
+    match file.is_file() {
+        true => Some(file),
+        false => None,
+    }
+}
+
+/// Returns the parsed `#[poise::command(...)]` arguments if the function is a command
+fn command_args(function: &syn::ItemFn) -> Option<crate::command::CommandArgs> {
+    let attr = function.attrs.iter().find(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "command")
+    })?;
+    let args = match &attr.meta {
+        syn::Meta::List(list) => darling::ast::NestedMeta::parse_meta_list(list.tokens.clone()),
+        _ => Ok(Vec::new()),
+    };
+    // Invalid arguments are reported by the command macro itself
+    <crate::command::CommandArgs as darling::FromMeta>::from_list(&args.ok()?).ok()
+}
+
+pub fn commands_from_module(input: TokenStream) -> Result<TokenStream, syn::Error> {
+    let Input { module, file } = syn::parse(input)?;
+
+    let manifest_dir =
+        std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let path = match &file {
+        Some(file) => manifest_dir.join(file.value()),
+        None => module_file(&manifest_dir, &module).ok_or_else(|| {
+            let message = "couldn't find the module's source file; pass its path explicitly: \
+                `commands_from_module!(module, \"path/to/module.rs\")`";
+            syn::Error::new(module.span(), message)
+        })?,
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        let message = format!("couldn't read {}: {}", path.display(), e);
+        syn::Error::new(module.span(), message)
+    })?;
+    let file = syn::parse_file(&content).map_err(|e| {
+        let message = format!("couldn't parse {}: {}", path.display(), e);
+        syn::Error::new(module.span(), message)
+    })?;
+
+    let commands = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(function) => Some((function, command_args(function)?)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Subcommands are reachable via their parent command and must not be registered on their own
+    let subcommands = commands
+        .iter()
+        .flat_map(|(_, args)| args.subcommand_paths())
+        .filter_map(|path| path.segments.last())
+        .map(|segment| segment.ident.to_string())
+        .collect::<std::collections::HashSet<_>>();
+
+    let commands = commands
+        .iter()
+        .filter(|(function, _)| !subcommands.contains(&function.sig.ident.to_string()))
+        .map(|(function, _)| {
+            let ident = &function.sig.ident;
+            let cfg_attrs = function
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"));
+            quote::quote! { #( #cfg_attrs )* #module::#ident() }
+        });
+
+    // include_str makes rustc recompile when the module changes, e.g. when commands are added
+    let path = path.to_string_lossy();
+    Ok(quote::quote! { {
+        const _: &str = include_str!(#path);
+        vec![ #( #commands ),* ]
+    } }
+    .into())
+}
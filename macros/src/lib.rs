@@ -4,6 +4,8 @@ Procedural macros used in poise, like [`macro@command`]
 
 mod choice_parameter;
 mod command;
+mod command_group;
+mod commands_from_module;
 mod modal;
 mod util;
 
@@ -25,15 +27,22 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `prefix_command`: Generate a prefix command
 - `slash_command`: Generate a slash command
 - `context_menu_command`: Generate a context menu command
+    - The parameter type decides where the entry appears: `serenity::User` for users, `serenity::Message` for messages, or `poise::ContextMenuTarget` for both
+- `entry_point_command`: Generate the primary entry point command of an app with Activities, which runs when the user launches the app. The command can't have parameters
 
 ## Meta properties
 
 - `subcommands`: List of subcommands `subcommands("foo", "bar", "baz")`
+    - Subcommands can be conditionally included with `cfg(predicate, ...)`, e.g. `subcommands("foo", cfg(feature = "admin", "bar", "baz"))`
 - `name_localized`: Adds localized name of the parameter `name_localized("locale", "new_name")` (slash-only)
 - `description_localized`: Adds localized description of the parameter `description_localized("locale", "Description")` (slash-only)
+- `localizations`: Path to a TOML file, relative to the crate root, which is read at compile time to fill in localized names, descriptions and help texts of the command and its parameters (slash-only)
+    - The file has one table per command, keyed by the function name (or `identifying_name`). See the "Localization file" section for the format
+    - Localizations given explicitly via `name_localized` or `description_localized` take precedence
 - `rename`: Choose an alternative command name instead of the function name
     - Useful if your command name is a Rust keyword, like `move`
 - `aliases`: Command name aliases (only applies to prefix commands)
+    - Either a list of strings `aliases("foo", "bar")`, or an expression evaluating to an iterator of strings when the command is constructed, e.g. `aliases = SHARED_ALIASES` with `const SHARED_ALIASES: &[&str] = &["foo", "bar"]`
 - `category`: Category of this command which affects placement in the help command
 - `custom_data`: Arbitrary expression that will be boxed and stored in `Command::custom_data`
 - `identifying_name`: Optionally, a unique identifier for this command for your personal usage
@@ -44,20 +53,32 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `required_bot_permissions`: Permissions which the bot is known to need
 - `default_member_permissions`: Like `required_permissions`, but checked server-side (slash only)
     - Due to being checked server-side, users without the required permissions are prevented from executing the command in the first place, which is a better experience
-    - However, `default_member_permissions` has no effect on subcommands, which always inherit their permissions from the top-level command
+    - Discord ignores `default_member_permissions` on subcommands, so poise checks it when a subcommand is invoked, and registers the top-level command with the permissions that all its subcommands have in common
     - Also, guild owners can freely change the required permissions for any bot command for their guild
 - `owners_only`: Restricts command callers to a configurable list of owners (see FrameworkOptions)
 - `guild_only`: Restricts command callers to only run on a guild
 - `dm_only`: Restricts command callers to only run on a DM
 - `nsfw_only`: Restricts command callers to only run on a NSFW channel
+- `install_context`: Installation contexts in which this command is available, e.g. `install_context = "Guild|User"` for a command of a user-installable app (application commands only)
+- `interaction_context`: Interaction contexts in which this command can be used, e.g. `interaction_context = "Guild|BotDm|PrivateChannel"` (application commands only)
 - `subcommand_required`: Requires a subcommand to be specified (prefix only)
+- `subcommand_group`: Registers this subcommand as a Discord subcommand group, for `/config logging enable` style command trees. The command can't have parameters, implies `subcommand_required` and is listed separately in help
 - `check`: Path to a function which is invoked for every invocation. If the function returns false, the command is not executed (can be used multiple times)
+- `middleware`: Path to a value implementing `poise::CommandMiddleware`, for example a unit struct, which wraps the execution of this command after the global middleware (can be used multiple times)
+- `skip_parent_checks`: Don't run the restrictions, checks and cooldowns of the parent commands of this subcommand, for subcommands that should be more permissive than their parent
+- `inherit_checks = false`: Don't run the `check`s of the parent commands of this subcommand, while still applying their restrictions and cooldowns
+- `final_check`: Like `check`, but if all final checks of this command pass, the checks of its subcommands are skipped (can be used multiple times)
+    - Checks run in this order: the global `command_check`, then the checks of parent commands from the outermost one down, then the checks of the invoked command. Final checks run after the regular checks of the same command
 
 ## Help-related arguments
 
 - `hide_in_help`: Hide this command in help menus
 - `help_text_fn`: Path to a string-returning function which is used for command help text instead of documentation comments
     - Useful if you have many commands with very similar help messages: you can abstract the common parts into a function
+- `help_text_localized`: Adds localized help text `help_text_localized("locale", "Help text")`, shown by the help builtins instead of the help text for users with that locale
+- `help_text_localized_fn`: Path to a function returning a `HashMap<String, String>` of localized help texts by locale, the localized counterpart to `help_text_fn`
+    - Entries given via `help_text_localized` take precedence
+- `example`: An example invocation like `example = "~add 1 2"`, shown by the help builtins and in argument parse errors (can be used multiple times)
 
 ## Edit tracking (prefix only)
 
@@ -73,14 +94,32 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `guild_cooldown`: Minimum duration in seconds between invocations, per guild
 - `channel_cooldown`: Minimum duration in seconds between invocations, per channel
 - `member_cooldown`: Minimum duration in seconds between invocations, per guild member
+- `cooldown_wait`: If the remaining cooldown is at most this many seconds, the invocation waits for
+  the cooldown to expire instead of failing with a cooldown error
+- `cooldown_max_waiting`: Maximum number of invocations waiting for a cooldown at once, per invoking
+  user, guild and channel. Unlimited by default
+- `bucket`: Name of a rate limit bucket registered in `FrameworkOptions::buckets` that limits this
+  command, e.g. `bucket = "expensive"`
+- `max_concurrent_invocations`: Maximum number of invocations of this command that may run at the
+  same time. Further invocations fail until one finishes. Unlimited by default
 
 ## Other
 
 - `on_error`: Error handling function
+- `invocation_logger`: Async function taking a `poise::InvocationRecord`, called after every
+  invocation of this command instead of the logger of any `poise::InvocationLog` observer
 - `broadcast_typing`: Trigger a typing indicator while command runs (prefix only)
 - `discard_spare_arguments`: Don't throw an error if the user supplies too many arguments (prefix only)
 - `ephemeral`: Make bot responses ephemeral if possible (slash only)
     - Only poise's functions, like `poise::send_reply`, respect this preference
+- `auto_defer`: Defer the response if the command hasn't responded after
+  `FrameworkOptions::auto_defer_after`, or two seconds if that's unset (slash only)
+- `on_create_slash`: Path to a `fn(serenity::CreateCommand) -> serenity::CreateCommand` applied
+  to the slash command builder before registration, to set fields poise doesn't support (yet)
+- `auto_reply`: Send the value returned by the command function as the response
+    - The function returns `Result<T, E>` where `T: Into<poise::CreateReply>`, for example
+      `Result<String, Error>`
+    - Requires `E: From<serenity::Error>` to report errors while sending the response
 
 # Function parameters
 
@@ -102,17 +141,75 @@ are multiple attributes you can use on parameters:
 
 ## Input filter (slash only)
 
-- `#[channel_types("", "")]`: For channel parameters, restricts allowed channel types (slash-only)
-- `#[min = 0]`: Minimum value for this number parameter (slash-only)
-- `#[max = 0]`: Maximum value for this number parameter (slash-only)
-- `#[min_length = 0]`: Minimum length for this string parameter (slash-only)
-- `#[max_length = 1]`: Maximum length for this string parameter (slash-only)
+- `#[channel_types("", "")]`: For channel parameters, restricts allowed channel types. Arguments of other channel types are rejected with `FrameworkError::InvalidChannelType` (for prefix commands, only if the parameter type has a known channel type, e.g. `serenity::Channel` or `serenity::GuildChannel` but not `serenity::ChannelId`)
+    - Channel types are given as `ChannelType` variant names like `"Text"`, or as paths like `serenity::ChannelType::Text` or `MY_CHANNEL_TYPE`
+    - To share a set of channel types between commands, pass a constant slice or array instead: `#[channel_types = TEXT_CHANNELS]`
+- `#[min = 0]`: Minimum value for this number parameter
+- `#[max = 0]`: Maximum value for this number parameter
+- `#[min_length = 0]`: Minimum length for this string parameter
+- `#[max_length = 1]`: Maximum length for this string parameter
+- Arguments violating `min`, `max`, `min_length` or `max_length` are rejected with `FrameworkError::ParameterConstraintViolation`, for prefix commands too
+- Instead of literals, `min`, `max`, `min_length` and `max_length` also accept constant expressions, like `#[max_length = MAX_REASON_LENGTH]` or `#[max = MAX_AMOUNT * 2]`
+- `#[choices("a", "b")]`: Restricts input to the given literals, shown as a drop-down (slash-only)
+- `#[choices_localized("locale", "a", "b")]`: Adds localized labels for `#[choices]`, one per choice in the same order (slash-only)
+- `#[option_modifier = "modify_option"]`: Path to a `fn(serenity::CreateCommandOption) -> serenity::CreateCommandOption` applied to the option builder after poise has filled it in, to set fields poise doesn't support (yet) (slash-only)
+
+## Type detection
+
+The macro can't resolve types, so it recognizes optional parameters by the spelling of their type:
+`Option<T>` and `Vec<T>`, also with a path like `std::option::Option<T>`.
+
+- `#[required = false]`: Makes the parameter optional even if its type isn't spelled as `Option<T>`, for example a type alias like `type MaybeUser = Option<serenity::User>`. The type must be an `Option`
+- `#[required = true]`: Makes the slash command option of an `Option` or `Vec` parameter required, so Discord always sends a value (slash-only)
+
+## Custom parsing
+
+- `#[custom_parser = "MyParser"]`: Parses this parameter with `MyParser`, which implements `poise::CustomParameterParser<T>` for the parameter type `T`, instead of deciding from the spelling of the type
+    - The parser decides whether the parameter is required, creates the slash command option, extracts the slash command value and, if implemented, parses the prefix command argument
+    - Useful for wrapper types and type aliases, which the macro can't see through
+    - Can't be combined with `#[choices]`, `#[flag]`, `#[lazy]`, `#[rest]` or `#[from_reply]`
+
+## Conditional compilation
+
+Parameters can be annotated with `#[cfg(...)]` like regular function parameters. If the predicate
+doesn't hold, the parameter is removed from the command entirely.
+
+Note: argument parsing code is generated once for every combination of cfg-gated parameters, so
+at most four parameters of a command can be cfg-gated.
 
 ## Parser settings (prefix only)
 - `#[rest]`: Use the entire rest of the message for this parameter (prefix-only)
 - `#[lazy]`: Can be used on Option and Vec parameters and is equivalent to regular expressions' laziness (prefix-only)
 - `#[flag]`: Can be used on a bool parameter to set the bool to true if the user typed the parameter name literally (prefix-only)
     - For example with `async fn my_command(ctx: Context<'_>, #[flag] my_flag: bool)`, `~my_command` would set my_flag to false, and `~my_command my_flag` would set my_flag to true
+- `#[from_reply]`: Can be used on Attachment and `Option<Attachment>` parameters to take the attachment from the message that the invocation replies to, if the invocation message itself has no attachments (prefix-only)
+
+In prefix commands, `Attachment`, `Option<Attachment>` and `Vec<Attachment>` parameters take the
+message's attachments in order. If the message has fewer attachments than required,
+`poise::MissingAttachment` is thrown; if it has more than the parameters take,
+`poise::TooManyAttachments` is thrown (unless `discard_spare_arguments` is set).
+
+# Generic commands
+
+Command functions can be generic, for example to share commands between bots with different user
+data and error types. Bounds can be given inline or in a `where` clause:
+
+```rust
+#[poise::command(slash_command)]
+pub async fn count<U, E>(ctx: poise::Context<'_, U, E>) -> Result<(), E>
+where
+    U: AsRef<Counter> + Send + Sync + 'static,
+    E: From<serenity::Error>,
+{
+    let counter: &Counter = ctx.data().as_ref();
+    ctx.say(counter.to_string()).await?;
+    Ok(())
+}
+```
+
+Generic parameters don't need to appear in the Context type directly, so a set of bounds can be
+bundled into a trait with associated types and used as
+`Context<'_, <B as MyTrait>::Data, <B as MyTrait>::Error>`. See the `generic_commands` example.
 
 # Help text
 
@@ -145,6 +242,26 @@ poise::Command {
 }
 ```
 
+# Localization file
+
+The file passed to `localizations` is read at compile time. It contains one table per command,
+keyed by the command's function name (or `identifying_name`), with locale-to-text tables for
+`name`, `description` and `help_text`. Parameters are keyed by their name (after `rename`):
+
+```toml
+[ping]
+name = { de = "ping", es-ES = "ping" }
+description = { de = "Antwortet mit Pong", es-ES = "Responde con pong" }
+help_text = { de = "Misst, wie schnell der Bot antwortet" }
+
+[ping.parameters.target]
+name = { de = "ziel" }
+description = { de = "Wer angepingt werden soll" }
+```
+
+Commands without a table in the file are left unlocalized, so a single file can be shared between
+all commands.
+
 # Internals
 
 Internally, this attribute macro generates a function with a single `poise::Command`
@@ -246,8 +363,31 @@ pub enum Food {
 When invoking your slash command, users will be shown the name matching their locale.
 
 You can also set localized choice names programmatically; see `CommandParameter::choices`
+
+# Stable values
+
+By default, Discord sends the index of the selected choice, which changes when variants are
+reordered. If you store choices somewhere, give each variant an explicit integer or string value
+with `#[value = ...]` or an explicit discriminant. `ChoiceParameter::to_value` and
+`ChoiceParameter::from_value` convert between variants and these values.
+
+```rust
+#[derive(poise::ChoiceParameter)]
+pub enum Size {
+    #[value = "s"]
+    Small,
+    #[value = "l"]
+    Large,
+}
+
+#[derive(poise::ChoiceParameter)]
+pub enum Priority {
+    Low = 10,
+    High = 20,
+}
+```
 */
-#[proc_macro_derive(ChoiceParameter, attributes(name, name_localized))]
+#[proc_macro_derive(ChoiceParameter, attributes(name, name_localized, value))]
 pub fn choice_parameter(input: TokenStream) -> TokenStream {
     let enum_ = syn::parse_macro_input!(input as syn::DeriveInput);
 
@@ -267,7 +407,17 @@ pub fn slash_choice_parameter(input: TokenStream) -> TokenStream {
 /// See `Modal` trait documentation
 #[proc_macro_derive(
     Modal,
-    attributes(name, placeholder, min_length, max_length, paragraph)
+    attributes(
+        name,
+        placeholder,
+        min_length,
+        max_length,
+        paragraph,
+        regex,
+        min_value,
+        max_value,
+        validate
+    )
 )]
 pub fn modal(input: TokenStream) -> TokenStream {
     let struct_ = syn::parse_macro_input!(input as syn::DeriveInput);
@@ -277,3 +427,127 @@ pub fn modal(input: TokenStream) -> TokenStream {
         Err(e) => e.write_errors().into(),
     }
 }
+
+/**
+Collects all `#[poise::command]` functions of a module into a `Vec<poise::Command<U, E>>`, for use
+in `FrameworkOptions::commands`. Newly added commands are picked up automatically.
+
+```rust,ignore
+let options = poise::FrameworkOptions {
+    commands: poise::commands_from_module!(commands::moderation),
+    ..Default::default()
+};
+```
+
+The macro reads the module's source file at compile time. Its location is derived from the module
+path relative to the crate root: `commands::moderation` is looked up at
+`src/commands/moderation.rs` or `src/commands/moderation/mod.rs`. If your module lives elsewhere,
+pass the path to its file relative to `Cargo.toml` explicitly:
+
+```rust,ignore
+poise::commands_from_module!(commands::moderation, "examples/my_bot/moderation.rs")
+```
+
+Commands that are listed in another command's `subcommands(...)` are left out, since they are
+reached via their parent. `#[cfg(...)]` attributes on command functions are respected. Nested
+modules and commands defined by other macros aren't detected.
+*/
+#[proc_macro]
+pub fn commands_from_module(input: TokenStream) -> TokenStream {
+    match commands_from_module::commands_from_module(input) {
+        Ok(x) => x,
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+/**
+Use this derive macro on an enum to generate a command with one subcommand per variant. Every
+subcommand dispatches to a single handler function, which receives the parsed variant. This way,
+all actions of a command group can be handled with an exhaustive `match`.
+
+```rust,ignore
+/// Moderation actions
+#[derive(poise::CommandGroup)]
+#[command_group(handler = "admin", context = "Context<'_>", slash_command, prefix_command)]
+pub enum Admin {
+    /// Bans a user
+    Ban {
+        #[description = "User to ban"]
+        user: serenity::UserId,
+        #[description = "Reason for the ban"]
+        reason: String,
+    },
+    /// Kicks a user
+    Kick {
+        #[description = "User to kick"]
+        user: serenity::UserId,
+    },
+    /// Lists active bans
+    #[name = "banlist"]
+    ListBans,
+}
+
+async fn admin(ctx: Context<'_>, action: Admin) -> Result<(), Error> {
+    match action {
+        Admin::Ban { user, reason } => { ... }
+        Admin::Kick { user } => { ... }
+        Admin::ListBans => { ... }
+    }
+    Ok(())
+}
+
+// Register `Admin::command()` in FrameworkOptions::commands
+```
+
+# Enum attributes
+
+- `#[command_group(...)]` (required):
+    - `handler`: Path to the async function handling all subcommands
+    - `context`: The Context type of your bot, as taken by the handler
+    - `prefix_command`, `slash_command`: Which command types to generate
+    - `rename`: Name of the parent command. Defaults to the enum name in snake_case
+
+Doc comments on the enum and its variants are used as command descriptions, like in
+[`macro@command`].
+
+# Variant attributes
+
+- `#[name = "..."]`: Name of the subcommand. Defaults to the variant name in snake_case
+
+Variants must have named fields or no fields. The fields become the subcommand parameters and
+accept the same attributes as [`macro@command`] parameters.
+*/
+#[proc_macro_derive(
+    CommandGroup,
+    attributes(
+        command_group,
+        name,
+        description,
+        rename,
+        name_localized,
+        description_localized,
+        autocomplete,
+        channel_types,
+        choices,
+        choices_localized,
+        min,
+        max,
+        min_length,
+        max_length,
+        option_modifier,
+        custom_parser,
+        required,
+        lazy,
+        flag,
+        rest,
+        from_reply
+    )
+)]
+pub fn command_group(input: TokenStream) -> TokenStream {
+    let enum_ = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match command_group::command_group(enum_) {
+        Ok(x) => x,
+        Err(e) => e.write_errors().into(),
+    }
+}
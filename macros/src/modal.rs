@@ -7,6 +7,7 @@ use proc_macro::TokenStream;
 #[darling(allow_unknown_fields, default)]
 struct StructAttributes {
     name: Option<String>,
+    validate: Option<syn::Path>,
 }
 
 /// Representation of the struct field attributes
@@ -18,6 +19,21 @@ struct FieldAttributes {
     min_length: Option<u16>,
     max_length: Option<u16>,
     paragraph: Option<()>,
+    regex: Option<String>,
+    min_value: Option<syn::Lit>,
+    max_value: Option<syn::Lit>,
+    validate: Option<syn::Path>,
+}
+
+/// Converts a `min_value`/`max_value` literal into an `Option<f64>` expression
+fn bound(lit: Option<&syn::Lit>) -> Result<proc_macro2::TokenStream, darling::Error> {
+    let value = match lit {
+        Some(syn::Lit::Int(int)) => int.base10_parse::<f64>()?,
+        Some(syn::Lit::Float(float)) => float.base10_parse::<f64>()?,
+        Some(lit) => return Err(syn::Error::new(lit.span(), "bounds must be numbers").into()),
+        None => return Ok(quote::quote! { None }),
+    };
+    Ok(quote::quote! { Some(#value) })
 }
 
 pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
@@ -44,6 +60,7 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
 
     let mut builders = Vec::new();
     let mut parsers = Vec::new();
+    let mut validators = Vec::new();
     for field in fields {
         // Extract data from syn::Field
         let field_attrs: Vec<_> = field
@@ -83,6 +100,33 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
             }),
         });
 
+        // Create validation code for this field. Optional fields are only validated if filled in
+        let mut checks = Vec::new();
+        if let Some(regex) = &field_attrs.regex {
+            checks.push(quote::quote! { poise::validate_modal_regex(value, #regex, #label)?; });
+        }
+        if field_attrs.min_value.is_some() || field_attrs.max_value.is_some() {
+            let min = bound(field_attrs.min_value.as_ref())?;
+            let max = bound(field_attrs.max_value.as_ref())?;
+            checks.push(quote::quote! {
+                poise::validate_modal_range(value, #min, #max, #label)?;
+            });
+        }
+        if let Some(validate) = &field_attrs.validate {
+            checks.push(quote::quote! { #validate(value)?; });
+        }
+        if !checks.is_empty() {
+            let value = match required {
+                true => quote::quote! { Some(self.#field_ident.as_str()) },
+                false => quote::quote! { self.#field_ident.as_deref() },
+            };
+            validators.push(quote::quote! {
+                if let Some(value) = #value {
+                    #( #checks )*
+                }
+            });
+        }
+
         // Create modal parser code for this field
         let ok_or = if required {
             let error = format!("missing {}", field_ident);
@@ -95,6 +139,10 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
         });
     }
 
+    if let Some(validate) = &struct_attrs.validate {
+        validators.push(quote::quote! { #validate(self)?; });
+    }
+
     let modal_title = struct_attrs.name.unwrap_or(input.ident.to_string());
     let struct_ident = input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -111,6 +159,13 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
                 Ok(Self { #( #parsers )* })
             }
         }
+
+        impl #impl_generics poise::ModalValidation for #struct_ident #ty_generics #where_clause {
+            fn validate(&self) -> ::std::result::Result<(), String> {
+                #( #validators )*
+                Ok(())
+            }
+        }
     }; }
     .into())
 }
@@ -1,11 +1,13 @@
 // ngl this is ugly
-// transforms a type of form `OuterType<T>` into `T`
+// transforms a type of form `OuterType<T>` into `T`. The macro can't resolve types, so only the
+// last path segment is compared, to also recognize paths like `std::option::Option<T>`
 pub fn extract_type_parameter<'a>(outer_type: &str, t: &'a syn::Type) -> Option<&'a syn::Type> {
     if let syn::Type::Path(path) = t {
-        if path.path.segments.len() == 1 {
-            let path = &path.path.segments[0];
-            if path.ident == outer_type {
-                if let syn::PathArguments::AngleBracketed(generics) = &path.arguments {
+        if path.qself.is_none() {
+            let mut segments = path.path.segments.iter().rev();
+            let last = segments.next()?;
+            if last.ident == outer_type && segments.all(|segment| segment.arguments.is_none()) {
+                if let syn::PathArguments::AngleBracketed(generics) = &last.arguments {
                     if generics.args.len() == 1 {
                         if let syn::GenericArgument::Type(t) = &generics.args[0] {
                             return Some(t);
@@ -69,6 +71,113 @@ impl<T> Default for List<T> {
     }
 }
 
+/// A single entry of the `subcommands(...)` command attribute
+#[derive(Debug)]
+pub struct Subcommand {
+    pub path: syn::Path,
+    /// Predicate of the surrounding `cfg(predicate, ...)` entry, if any
+    pub cfg: Option<darling::ast::NestedMeta>,
+}
+
+/// Darling utility type for `subcommands(...)`: accepts a list of paths, where paths may be
+/// grouped into `cfg(predicate, path1, path2...)` to include them only if the predicate holds
+#[derive(Debug, Default)]
+pub struct Subcommands(pub Vec<Subcommand>);
+impl darling::FromMeta for Subcommands {
+    fn from_list(items: &[::darling::ast::NestedMeta]) -> darling::Result<Self> {
+        let mut subcommands = Vec::new();
+        for item in items {
+            match item {
+                darling::ast::NestedMeta::Meta(syn::Meta::List(list))
+                    if list.path.is_ident("cfg") =>
+                {
+                    let mut nested =
+                        darling::ast::NestedMeta::parse_meta_list(list.tokens.clone())?.into_iter();
+                    let predicate = nested.next().ok_or_else(|| {
+                        syn::Error::new_spanned(list, "expected `cfg(predicate, subcommands...)`")
+                    })?;
+                    for path in nested {
+                        subcommands.push(Subcommand {
+                            path: <syn::Path as darling::FromMeta>::from_nested_meta(&path)?,
+                            cfg: Some(predicate.clone()),
+                        });
+                    }
+                }
+                _ => subcommands.push(Subcommand {
+                    path: <syn::Path as darling::FromMeta>::from_nested_meta(item)?,
+                    cfg: None,
+                }),
+            }
+        }
+        Ok(Self(subcommands))
+    }
+}
+
+/// Darling utility type for `#[channel_types]`. Accepts either a list of channel types, given as
+/// variant names (`"Text"`) or paths (`serenity::ChannelType::Text`, `MY_CHANNEL_TYPE`), or a
+/// single expression evaluating to a slice or array (`#[channel_types = MY_CHANNEL_TYPES]`)
+#[derive(Debug)]
+pub enum ChannelTypes {
+    List(Vec<syn::Expr>),
+    Slice(syn::Expr),
+}
+impl darling::FromMeta for ChannelTypes {
+    fn from_list(items: &[::darling::ast::NestedMeta]) -> darling::Result<Self> {
+        items
+            .iter()
+            .map(|item| match item {
+                darling::ast::NestedMeta::Lit(syn::Lit::Str(variant)) => {
+                    let variant = variant.parse::<syn::Ident>()?;
+                    Ok(syn::parse_quote! { poise::serenity_prelude::ChannelType::#variant })
+                }
+                darling::ast::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                    Ok(syn::parse_quote! { #path })
+                }
+                _ => Err(darling::Error::unexpected_type("non-path").with_span(item)),
+            })
+            .collect::<darling::Result<Vec<_>>>()
+            .map(Self::List)
+    }
+
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(path),
+                ..
+            }) => Ok(Self::Slice(path.parse()?)),
+            _ => Ok(Self::Slice(expr.clone())),
+        }
+    }
+}
+
+/// Darling utility type for `aliases`. Accepts either a list of string literals or a single
+/// expression evaluating to something iterable (`aliases = SHARED_ALIASES`)
+#[derive(Debug)]
+pub enum Aliases {
+    List(Vec<String>),
+    Expr(syn::Expr),
+}
+impl Default for Aliases {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+impl darling::FromMeta for Aliases {
+    fn from_list(items: &[::darling::ast::NestedMeta]) -> darling::Result<Self> {
+        List::from_list(items).map(|List(aliases)| Self::List(aliases))
+    }
+
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(expr),
+                ..
+            }) => Ok(Self::Expr(expr.parse()?)),
+            _ => Ok(Self::Expr(expr.clone())),
+        }
+    }
+}
+
 /// Darling utility type that accepts a 2-tuple list of things, e.g. `#[attr(thing1, thing2)]`
 #[derive(Debug)]
 pub struct Tuple2<T>(pub T, pub T);
@@ -0,0 +1,202 @@
+//! Building blocks for autocomplete callbacks, like [`fuzzy_filter`] and [`CachedAutocomplete`]
+
+use crate::serenity_prelude as serenity;
+
+/// Maximum number of autocomplete choices Discord accepts
+pub const MAX_CHOICES: usize = 25;
+
+/// How well a candidate matches the partial input. Lower is better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchScore {
+    /// The candidate equals the input, ignoring case
+    Exact,
+    /// The candidate starts with the input
+    Prefix,
+    /// A word of the candidate starts with the input
+    WordPrefix,
+    /// The candidate contains the input
+    Substring,
+    /// The start of the candidate is within the given edit distance of the input
+    Fuzzy(usize),
+}
+
+/// Scores the candidate against the lowercased input, or returns None if it doesn't match at all
+fn score(candidate: &str, partial: &str) -> Option<MatchScore> {
+    let candidate = candidate.to_lowercase();
+    if candidate == partial {
+        return Some(MatchScore::Exact);
+    }
+    if candidate.starts_with(partial) {
+        return Some(MatchScore::Prefix);
+    }
+    if candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(partial))
+    {
+        return Some(MatchScore::WordPrefix);
+    }
+    if candidate.contains(partial) {
+        return Some(MatchScore::Substring);
+    }
+
+    // Compare against the start of the candidate, so that long candidates aren't penalized
+    let partial_len = partial.chars().count();
+    let candidate_start = candidate.chars().take(partial_len).collect::<String>();
+    let distance = crate::choice_parameter::edit_distance(partial, &candidate_start);
+    (distance <= (partial_len / 3).max(1)).then_some(MatchScore::Fuzzy(distance))
+}
+
+/// Filters and sorts the candidates by how well they match the user's partial input, and
+/// truncates them to Discord's limit of [`MAX_CHOICES`].
+///
+/// Exact matches come first, followed by candidates that start with the input, candidates with a
+/// word that starts with the input, candidates that contain the input, and finally candidates
+/// whose start is similar to the input, to tolerate typos. Matching ignores case. Candidates that
+/// match equally well keep their original order. If the input is empty, the first candidates are
+/// returned unchanged.
+///
+/// ```rust
+/// let fruits = ["Apple", "Pineapple", "Apricot", "Banana", "Green apple"];
+/// assert_eq!(
+///     poise::autocomplete::fuzzy_filter(fruits, "ap"),
+///     ["Apple", "Apricot", "Green apple", "Pineapple"],
+/// );
+/// // Tolerates typos
+/// assert_eq!(poise::autocomplete::fuzzy_filter(fruits, "bananna"), ["Banana"]);
+/// ```
+pub fn fuzzy_filter<T: AsRef<str>>(
+    candidates: impl IntoIterator<Item = T>,
+    partial: &str,
+) -> Vec<T> {
+    let partial = partial.trim().to_lowercase();
+    if partial.is_empty() {
+        return candidates.into_iter().take(MAX_CHOICES).collect();
+    }
+
+    let mut scored = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((score(candidate.as_ref(), &partial)?, candidate)))
+        .collect::<Vec<_>>();
+    // Stable sort, so equally good matches keep their order
+    scored.sort_by_key(|(score, _)| *score);
+    scored
+        .into_iter()
+        .take(MAX_CHOICES)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Suggests the emojis of the guild that match the user's partial input, for use as
+/// `#[autocomplete = "poise::autocomplete::guild_emojis"]` on [`serenity::ReactionType`] and
+/// [`serenity::Emoji`] parameters.
+///
+/// Suggests nothing outside of guilds or if the emojis couldn't be fetched.
+pub async fn guild_emojis<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let emojis = match crate::fetch_guild_emojis(ctx.serenity_context(), guild_id).await {
+        Ok(emojis) => emojis,
+        Err(e) => {
+            tracing::warn!("failed to fetch guild emojis: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let names = emojis.iter().map(|emoji| emoji.name.as_str());
+    let partial = partial.trim_matches(':');
+    fuzzy_filter(names, partial)
+        .into_iter()
+        .filter_map(|name| emojis.iter().find(|emoji| emoji.name == name))
+        .map(|emoji| {
+            serenity::AutocompleteChoice::new(format!(":{}:", emoji.name), emoji.to_string())
+        })
+        .collect()
+}
+
+/// Memoizes expensive autocomplete candidate lists, for example from a database or an HTTP API,
+/// for a given time to live.
+///
+/// Lists are cached per key, e.g. per guild. Use `()` as the key for a single global list. Store
+/// the cache in your user data so that all invocations share it.
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # async fn fetch_tags(guild_id: Option<poise::serenity_prelude::GuildId>) -> Result<Vec<String>, Error> { todo!() }
+/// struct Data {
+///     tags: poise::autocomplete::CachedAutocomplete<
+///         Option<poise::serenity_prelude::GuildId>,
+///         String,
+///     >,
+/// }
+/// type Context<'a> = poise::Context<'a, Data, Error>;
+///
+/// async fn autocomplete_tag(ctx: Context<'_>, partial: &str) -> Vec<String> {
+///     let guild_id = ctx.guild_id();
+///     let tags = ctx.data().tags.get_or_fetch(guild_id, || fetch_tags(guild_id)).await;
+///     match tags {
+///         Ok(tags) => poise::autocomplete::fuzzy_filter(tags.iter().cloned(), partial),
+///         Err(_) => Vec::new(),
+///     }
+/// }
+///
+/// let data = Data {
+///     tags: poise::autocomplete::CachedAutocomplete::new(std::time::Duration::from_secs(60)),
+/// };
+/// ```
+#[derive(Debug)]
+pub struct CachedAutocomplete<K, T> {
+    /// How long fetched lists stay valid
+    ttl: std::time::Duration,
+    /// Fetched lists per key, with the time they were fetched
+    entries: parking_lot::Mutex<
+        std::collections::HashMap<K, (std::time::Instant, std::sync::Arc<Vec<T>>)>,
+    >,
+}
+
+impl<K: std::hash::Hash + Eq, T> CachedAutocomplete<K, T> {
+    /// Creates an empty cache whose lists are valid for the given duration
+    pub fn new(ttl: std::time::Duration) -> Self {
+        Self {
+            ttl,
+            entries: parking_lot::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Returns the cached list for the key if it hasn't expired yet. Otherwise, calls `fetch` and
+    /// caches its result. Errors aren't cached
+    pub async fn get_or_fetch<F, Fut, Err>(
+        &self,
+        key: K,
+        fetch: F,
+    ) -> Result<std::sync::Arc<Vec<T>>, Err>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<T>, Err>>,
+    {
+        if let Some((fetched_at, candidates)) = self.entries.lock().get(&key) {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(candidates.clone());
+            }
+        }
+
+        let candidates = std::sync::Arc::new(fetch().await?);
+        self.entries
+            .lock()
+            .insert(key, (std::time::Instant::now(), candidates.clone()));
+        Ok(candidates)
+    }
+
+    /// Removes the cached list of the key, so it is fetched again on the next call
+    pub fn invalidate(&self, key: &K) {
+        self.entries.lock().remove(key);
+    }
+
+    /// Removes all cached lists
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
@@ -0,0 +1,140 @@
+//! Yes/no prompts with buttons, see [`confirm`]
+
+use crate::serenity_prelude as serenity;
+
+/// Custom ID of the confirm button. The collector is bound to the prompt message, so it doesn't
+/// need to be unique across messages
+const CONFIRM: &str = "poise_confirm_yes";
+/// Custom ID of the cancel button, see [`CONFIRM`]
+const CANCEL: &str = "poise_confirm_no";
+
+/// Configuration for [`confirm`]
+#[derive(Clone, Debug)]
+pub struct ConfirmOptions {
+    /// Label of the confirm button. `"Confirm"` by default
+    pub confirm_label: String,
+    /// Label of the cancel button. `"Cancel"` by default
+    pub cancel_label: String,
+    /// Style of the confirm button, for example [`serenity::ButtonStyle::Danger`] for destructive
+    /// actions. [`serenity::ButtonStyle::Success`] by default
+    pub confirm_style: serenity::ButtonStyle,
+    /// How long to wait for a button press. 60 seconds by default
+    pub timeout: std::time::Duration,
+    /// Whether to send the prompt as an ephemeral message (slash-only). False by default
+    pub ephemeral: bool,
+    /// Appended to the prompt once confirmed
+    pub confirmed_text: String,
+    /// Appended to the prompt once cancelled
+    pub cancelled_text: String,
+    /// Appended to the prompt if the timeout expired without a button press
+    pub timeout_text: String,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for ConfirmOptions {
+    fn default() -> Self {
+        Self {
+            confirm_label: "Confirm".into(),
+            cancel_label: "Cancel".into(),
+            confirm_style: serenity::ButtonStyle::Success,
+            timeout: std::time::Duration::from_secs(60),
+            ephemeral: false,
+            confirmed_text: "✅ Confirmed".into(),
+            cancelled_text: "❌ Cancelled".into(),
+            timeout_text: "⌛ Timed out".into(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Asks the invoking user to confirm an action by pressing a Confirm or Cancel button. Works for
+/// both prefix and application commands.
+///
+/// Returns true if the user confirmed, and false if they cancelled or the timeout expired.
+/// Afterwards, the buttons are removed and the outcome is appended to the prompt. Other users
+/// pressing the buttons are told in an ephemeral message that only the invoker can answer.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let options = poise::builtins::ConfirmOptions {
+///     confirm_label: "Delete".into(),
+///     confirm_style: serenity::ButtonStyle::Danger,
+///     ephemeral: true,
+///     ..Default::default()
+/// };
+/// if poise::builtins::confirm(ctx, "Really delete all data?", options).await? {
+///     // ...
+/// }
+/// # Ok(()) }
+/// ```
+pub async fn confirm<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    prompt: impl Into<String>,
+    options: ConfirmOptions,
+) -> Result<bool, serenity::Error> {
+    let prompt = prompt.into();
+    let buttons = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(CONFIRM)
+            .label(&options.confirm_label)
+            .style(options.confirm_style),
+        serenity::CreateButton::new(CANCEL)
+            .label(&options.cancel_label)
+            .style(serenity::ButtonStyle::Secondary),
+    ]);
+    let reply = crate::CreateReply::default()
+        .content(prompt.clone())
+        .components(vec![buttons])
+        .ephemeral(options.ephemeral);
+    let handle = ctx.send(reply).await?;
+
+    let collector = handle
+        .component_interaction_collector(ctx)
+        .await?
+        .timeout(options.timeout);
+    let mut presses = collector.stream();
+    let (confirmed, outcome, press) = loop {
+        let Some(press) = futures_util::StreamExt::next(&mut presses).await else {
+            break (false, options.timeout_text, None);
+        };
+
+        if press.user.id != ctx.author().id {
+            let response = serenity::CreateInteractionResponseMessage::new()
+                .content("Only the user who invoked this command can answer")
+                .ephemeral(true);
+            press
+                .create_response(ctx, serenity::CreateInteractionResponse::Message(response))
+                .await?;
+            continue;
+        }
+
+        match &*press.data.custom_id {
+            CONFIRM => break (true, options.confirmed_text, Some(press)),
+            CANCEL => break (false, options.cancelled_text, Some(press)),
+            _ => continue,
+        }
+    };
+
+    let content = format!("{}\n\n{}", prompt, outcome);
+    match press {
+        Some(press) => {
+            let response = serenity::CreateInteractionResponseMessage::new()
+                .content(content)
+                .components(Vec::new());
+            press
+                .create_response(
+                    ctx,
+                    serenity::CreateInteractionResponse::UpdateMessage(response),
+                )
+                .await?;
+        }
+        None => {
+            let reply = crate::CreateReply::default()
+                .content(content)
+                .components(Vec::new());
+            handle.edit(ctx, reply).await?;
+        }
+    }
+    Ok(confirmed)
+}
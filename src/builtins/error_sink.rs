@@ -0,0 +1,103 @@
+//! Reporting uncaught errors to a Discord channel or webhook, see [`ErrorSink`]
+
+use crate::serenity_prelude as serenity;
+
+/// Maximum length of an embed field value
+const FIELD_LIMIT: usize = 1024;
+
+/// Where to post reports about uncaught errors, see [`crate::FrameworkOptions::error_sink`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorSink {
+    /// Post reports as the bot in the given channel
+    Channel(serenity::ChannelId),
+    /// Post reports via the webhook with the given URL
+    Webhook(String),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Truncates the string to fit into an embed field, wrapped in a code block
+fn code_block_field(text: &str) -> String {
+    // Leave room for the code block fences and the ellipsis
+    let limit = FIELD_LIMIT - 10;
+    let mut text = text.replace("```", "`\u{200B}``");
+    if let Some((end, _)) = text.char_indices().nth(limit) {
+        text.truncate(end);
+        text.push('…');
+    }
+    format!("```\n{}\n```", text)
+}
+
+/// Builds a report embed with the invocation, user, guild and error details for the given error.
+///
+/// Returns None for errors that aren't worth reporting to operators, i.e. everything but command
+/// errors, command panics, event handler errors, background task errors and component handler
+/// errors.
+pub fn error_report<U, E: std::fmt::Display + std::fmt::Debug>(
+    error: &crate::FrameworkError<'_, U, E>,
+) -> Option<serenity::CreateEmbed> {
+    let details = match error {
+        crate::FrameworkError::Command { error, .. } => format!("{:?}", error),
+        crate::FrameworkError::CommandPanic { payload, .. } => payload
+            .clone()
+            .unwrap_or_else(|| "<panic payload is not a string>".into()),
+        crate::FrameworkError::EventHandler { error, .. }
+        | crate::FrameworkError::BackgroundTask { error, .. }
+        | crate::FrameworkError::ComponentHandler { error, .. } => format!("{:?}", error),
+        _ => return None,
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(error.to_string())
+        .color((255, 0, 0))
+        .timestamp(serenity::Timestamp::now());
+    if let Some(ctx) = error.ctx() {
+        let guild = match ctx.guild_id() {
+            Some(guild_id) => guild_id.to_string(),
+            None => "DMs".into(),
+        };
+        embed = embed
+            .field(
+                "Invocation",
+                code_block_field(&ctx.invocation_string()),
+                false,
+            )
+            .field("User", format!("<@{0}> ({0})", ctx.author().id), true)
+            .field("Guild", guild, true)
+            .field("Channel", format!("<#{0}> ({0})", ctx.channel_id()), true);
+    }
+    Some(embed.field("Error", code_block_field(&details), false))
+}
+
+impl ErrorSink {
+    /// Posts the given report, as built by [`error_report`], to this sink.
+    ///
+    /// [`crate::builtins::on_error`] calls this if [`crate::FrameworkOptions::error_sink`] is set.
+    pub async fn send(
+        &self,
+        http: impl AsRef<serenity::Http>,
+        report: serenity::CreateEmbed,
+    ) -> Result<(), serenity::Error> {
+        let http = http.as_ref();
+        // Reports may contain arbitrary user input
+        let mentions = serenity::CreateAllowedMentions::new();
+
+        match self {
+            Self::Channel(channel_id) => {
+                let message = serenity::CreateMessage::new()
+                    .embed(report)
+                    .allowed_mentions(mentions);
+                channel_id.send_message(http, message).await?;
+            }
+            Self::Webhook(url) => {
+                let webhook = serenity::Webhook::from_url(http, url).await?;
+                let message = serenity::ExecuteWebhook::new()
+                    .embed(report)
+                    .allowed_mentions(mentions);
+                webhook.execute(http, false, message).await?;
+            }
+            Self::__NonExhaustive => unreachable!(),
+        }
+        Ok(())
+    }
+}
@@ -16,6 +16,13 @@ pub struct HelpConfiguration<'a> {
     pub show_subcommands: bool,
     /// Whether to include [`crate::Command::description`] (above [`crate::Command::help_text`]).
     pub include_description: bool,
+    /// Whether to show the command overview as one embed per category, with select menus to
+    /// switch categories and to show the help of a single command. Falls back to the plain text
+    /// overview if the categories don't fit in a select menu or the bot can't send the message
+    pub interactive: bool,
+    /// How long the select menus of the [interactive](Self::interactive) help stay active after
+    /// the last selection
+    pub interactive_timeout: std::time::Duration,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
@@ -28,6 +35,8 @@ impl Default for HelpConfiguration<'_> {
             show_context_menu_commands: false,
             show_subcommands: false,
             include_description: true,
+            interactive: false,
+            interactive_timeout: std::time::Duration::from_secs(60 * 10),
             __non_exhaustive: (),
         }
     }
@@ -89,15 +98,11 @@ pub(super) async fn get_prefix_from_options<U, E>(ctx: crate::Context<'_, U, E>)
     let options = &ctx.framework().options().prefix_options;
     match &options.prefix {
         Some(fixed_prefix) => Some(fixed_prefix.clone()),
-        None => match options.dynamic_prefix {
-            Some(dynamic_prefix_callback) => {
-                match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
-                    Ok(Some(dynamic_prefix)) => Some(dynamic_prefix),
-                    _ => None,
-                }
-            }
-            None => None,
-        },
+        None => crate::dispatch::resolve_dynamic_prefixes(crate::PartialContext::from(ctx))
+            .await
+            .ok()?
+            .into_iter()
+            .next(),
     }
 }
 
@@ -106,6 +111,7 @@ fn format_context_menu_name<U, E>(command: &crate::Command<U, E>) -> Option<Stri
     let kind = match command.context_menu_action {
         Some(crate::ContextMenuCommandAction::User(_)) => "user",
         Some(crate::ContextMenuCommandAction::Message(_)) => "message",
+        Some(crate::ContextMenuCommandAction::UserAndMessage(_)) => "user and message",
         Some(crate::ContextMenuCommandAction::__NonExhaustive) => unreachable!(),
         None => return None,
     };
@@ -125,7 +131,21 @@ async fn help_single_command<U, E>(
     command_name: &str,
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
-    let commands = &ctx.framework().options().commands;
+    let reply = CreateReply::default()
+        .content(single_command_text(ctx, command_name, &config).await)
+        .ephemeral(config.ephemeral);
+
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Generates the help text of a specific command, or an error message if there's no such command
+async fn single_command_text<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command_name: &str,
+    config: &HelpConfiguration<'_>,
+) -> String {
+    let commands = ctx.framework().commands;
     // Try interpret the command name as a context menu command first
     let mut command = commands.iter().find(|command| {
         if let Some(context_menu_name) = &command.context_menu_name {
@@ -142,7 +162,7 @@ async fn help_single_command<U, E>(
         }
     }
 
-    let reply = if let Some(command) = command {
+    if let Some(command) = command {
         let mut invocations = Vec::new();
         let mut subprefix = None;
         if command.slash_action.is_some() {
@@ -174,24 +194,36 @@ async fn help_single_command<U, E>(
         assert!(!invocations.is_empty());
         let invocations = invocations.join("\n");
 
-        let mut text = match (&command.description, &command.help_text) {
+        let description = ctx
+            .localized(&command.description_localizations)
+            .or(command.description.as_deref());
+        let help_text = ctx
+            .localized(&command.help_text_localizations)
+            .or(command.help_text.as_deref());
+        let mut text = match (description, help_text) {
             (Some(description), Some(help_text)) => {
                 if config.include_description {
                     format!("{}\n\n{}", description, help_text)
                 } else {
-                    help_text.clone()
+                    help_text.to_owned()
                 }
             }
             (Some(description), None) => description.to_owned(),
-            (None, Some(help_text)) => help_text.clone(),
+            (None, Some(help_text)) => help_text.to_owned(),
             (None, None) => "No help available".to_string(),
         };
         if !command.parameters.is_empty() {
             text += "\n\n```\nParameters:\n";
             let mut parameterlist = TwoColumnList::new();
             for parameter in &command.parameters {
-                let name = parameter.name.clone();
-                let description = parameter.description.as_deref().unwrap_or("");
+                let name = ctx
+                    .localized(&parameter.name_localizations)
+                    .unwrap_or(&parameter.name)
+                    .to_owned();
+                let description = ctx
+                    .localized(&parameter.description_localizations)
+                    .or(parameter.description.as_deref())
+                    .unwrap_or("");
                 let description = format!(
                     "({}) {}",
                     if parameter.required {
@@ -206,41 +238,58 @@ async fn help_single_command<U, E>(
             text += &parameterlist.into_string();
             text += "```";
         }
-        if !command.subcommands.is_empty() {
+        if !command.examples.is_empty() {
+            text += "\n\n```\nExamples:\n";
+            for example in &command.examples {
+                text += example;
+                text += "\n";
+            }
+            text += "```";
+        }
+        let subprefix = subprefix.unwrap_or_else(|| String::from("  "));
+        if command.subcommands.iter().any(|c| !c.subcommand_group) {
             text += "\n\n```\nSubcommands:\n";
             let mut commandlist = TwoColumnList::new();
             // Subcommands can exist on context menu commands, but there's no
             // hierarchy in the menu, so just display them as a list without
             // subprefix.
-            preformat_subcommands(
-                &mut commandlist,
-                command,
-                &subprefix.unwrap_or_else(|| String::from("  ")),
-            );
+            preformat_subcommands(ctx, &mut commandlist, command, &subprefix);
+            text += &commandlist.into_string();
+            text += "```";
+        }
+        if command.subcommands.iter().any(|c| c.subcommand_group) {
+            text += "\n\n```\nSubcommand groups:\n";
+            let mut commandlist = TwoColumnList::new();
+            preformat_subcommand_groups(ctx, &mut commandlist, command, &subprefix);
             text += &commandlist.into_string();
             text += "```";
         }
         format!("**{}**\n\n{}", invocations, text)
     } else {
         format!("No such command `{}`", command_name)
-    };
-
-    let reply = CreateReply::default()
-        .content(reply)
-        .ephemeral(config.ephemeral);
+    }
+}
 
-    ctx.send(reply).await?;
-    Ok(())
+/// Formats the description of the given command for a command list
+fn command_description<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+) -> String {
+    ctx.localized(&command.description_localizations)
+        .or(command.description.as_deref())
+        .unwrap_or("")
+        .to_string()
 }
 
-/// Recursively formats all subcommands
+/// Formats all subcommands that aren't subcommand groups
 fn preformat_subcommands<U, E>(
+    ctx: crate::Context<'_, U, E>,
     commands: &mut TwoColumnList,
     command: &crate::Command<U, E>,
     prefix: &str,
 ) {
     let as_context_command = command.slash_action.is_none() && command.prefix_action.is_none();
-    for subcommand in &command.subcommands {
+    for subcommand in command.subcommands.iter().filter(|c| !c.subcommand_group) {
         let command = if as_context_command {
             let name = format_context_menu_name(subcommand);
             if name.is_none() {
@@ -250,16 +299,36 @@ fn preformat_subcommands<U, E>(
         } else {
             format!("{} {}", prefix, subcommand.name)
         };
-        let description = subcommand.description.as_deref().unwrap_or("").to_string();
-        commands.push_two_colums(command, description);
+        commands.push_two_colums(command, command_description(ctx, subcommand));
         // We could recurse here, but things can get cluttered quickly.
         // Instead, we show (using this function) subsubcommands when
         // the user asks for help on the subcommand.
     }
 }
 
+/// Formats all subcommand groups of the given command, each followed by its subcommands. Groups
+/// can't be nested, so unlike [`preformat_subcommands`] this lists all levels below `command`
+fn preformat_subcommand_groups<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    commands: &mut TwoColumnList,
+    command: &crate::Command<U, E>,
+    prefix: &str,
+) {
+    for group in command.subcommands.iter().filter(|c| c.subcommand_group) {
+        let group_prefix = format!("{} {}", prefix, group.name);
+        commands.push_two_colums(group_prefix.clone(), command_description(ctx, group));
+        for subcommand in &group.subcommands {
+            commands.push_two_colums(
+                format!("{} {}", group_prefix, subcommand.name),
+                command_description(ctx, subcommand),
+            );
+        }
+    }
+}
+
 /// Preformat lines (except for padding,) like `("  /ping", "Emits a ping message")`
 fn preformat_command<U, E>(
+    ctx: crate::Context<'_, U, E>,
     commands: &mut TwoColumnList,
     config: &HelpConfiguration<'_>,
     command: &crate::Command<U, E>,
@@ -277,48 +346,52 @@ fn preformat_command<U, E>(
     };
 
     let prefix = format!("{}{}{}", indent, prefix, command.name);
-    commands.push_two_colums(
-        prefix.clone(),
-        command.description.as_deref().unwrap_or("").to_string(),
-    );
+    let description = ctx
+        .localized(&command.description_localizations)
+        .or(command.description.as_deref())
+        .unwrap_or("");
+    commands.push_two_colums(prefix.clone(), description.to_string());
     if config.show_subcommands {
-        preformat_subcommands(commands, command, &prefix)
+        preformat_subcommands(ctx, commands, command, &prefix);
+        preformat_subcommand_groups(ctx, commands, command, &prefix);
     }
 }
 
-/// Create help text for `help_all_commands`
-///
-/// This is a separate function so we can have tests for it
-async fn generate_all_commands<U, E>(
+/// Groups the prefix and slash commands shown in the help menu by category. Categories without
+/// any such commands are left out
+fn categorize_commands<U, E>(
     ctx: crate::Context<'_, U, E>,
-    config: &HelpConfiguration<'_>,
-) -> Result<String, serenity::Error> {
+) -> indexmap::IndexMap<Option<&str>, Vec<&crate::Command<U, E>>> {
     let mut categories = indexmap::IndexMap::<Option<&str>, Vec<&crate::Command<U, E>>>::new();
-    for cmd in &ctx.framework().options().commands {
+    for cmd in ctx.framework().commands {
+        if cmd.hide_in_help || (cmd.prefix_action.is_none() && cmd.slash_action.is_none()) {
+            continue;
+        }
         categories
             .entry(cmd.category.as_deref())
             .or_default()
             .push(cmd);
     }
+    categories
+}
 
+/// Create help text for `help_all_commands`
+///
+/// This is a separate function so we can have tests for it
+async fn generate_all_commands<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: &HelpConfiguration<'_>,
+) -> Result<String, serenity::Error> {
     let options_prefix = get_prefix_from_options(ctx).await;
 
     let mut menu = String::from("```\n");
 
     let mut commandlist = TwoColumnList::new();
-    for (category_name, commands) in categories {
-        let commands = commands
-            .into_iter()
-            .filter(|cmd| {
-                !cmd.hide_in_help && (cmd.prefix_action.is_some() || cmd.slash_action.is_some())
-            })
-            .collect::<Vec<_>>();
-        if commands.is_empty() {
-            continue;
-        }
+    for (category_name, commands) in categorize_commands(ctx) {
         commandlist.push_heading(category_name.unwrap_or("Commands"));
         for command in commands {
             preformat_command(
+                ctx,
                 &mut commandlist,
                 config,
                 command,
@@ -332,7 +405,7 @@ async fn generate_all_commands<U, E>(
     if config.show_context_menu_commands {
         menu += "\nContext menu commands:\n";
 
-        for command in &ctx.framework().options().commands {
+        for command in ctx.framework().commands {
             let name = format_context_menu_name(command);
             if name.is_none() {
                 continue;
@@ -349,7 +422,7 @@ async fn generate_all_commands<U, E>(
 }
 
 /// Code for printing an overview of all commands (e.g. `~help`)
-async fn help_all_commands<U, E>(
+pub(super) async fn help_all_commands<U, E>(
     ctx: crate::Context<'_, U, E>,
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
@@ -415,6 +488,9 @@ async fn help_all_commands<U, E>(
 /// Type ?help command for more info on a command.
 /// You can edit your message to the bot and the bot will edit its response.
 /// ```
+///
+/// With [`HelpConfiguration::interactive`], the overview instead shows one category at a time,
+/// with select menus to switch between categories and to open the help of a single command.
 pub async fn help<U, E>(
     ctx: crate::Context<'_, U, E>,
     command: Option<&str>,
@@ -422,6 +498,275 @@ pub async fn help<U, E>(
 ) -> Result<(), serenity::Error> {
     match command {
         Some(command) => help_single_command(ctx, command, config).await,
+        None if config.interactive => help_interactive(ctx, config).await,
         None => help_all_commands(ctx, config).await,
     }
 }
+
+/// Custom ID of the category select menu of the interactive help. The collector is bound to the
+/// help message, so it doesn't need to be unique across messages
+const CATEGORY_SELECT: &str = "poise_help_category";
+/// Custom ID of the command select menu of the interactive help, see [`CATEGORY_SELECT`]
+const COMMAND_SELECT: &str = "poise_help_command";
+/// Custom ID of the button leading back from a command to its category, see [`CATEGORY_SELECT`]
+const BACK: &str = "poise_help_back";
+
+/// Discord's maximum number of options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Page of the interactive help that is currently shown
+#[derive(Clone, Copy)]
+enum HelpPage {
+    /// Overview of the category at the given index
+    Category(usize),
+    /// Help of a single command, identified by the index of its category and its index within
+    Command(usize, usize),
+}
+
+/// Commands of a category in the interactive help
+struct HelpCategory<'a, U, E> {
+    /// Name of the category
+    name: &'a str,
+    /// Commands in this category. Unlike [`categorize_commands`], this can contain context menu
+    /// commands
+    commands: Vec<&'a crate::Command<U, E>>,
+}
+
+/// Name to look up the given command with in [`single_command_text`]
+fn lookup_name<U, E>(command: &crate::Command<U, E>) -> &str {
+    match command.prefix_action.is_none() && command.slash_action.is_none() {
+        true => command
+            .context_menu_name
+            .as_deref()
+            .unwrap_or(&command.name),
+        false => &command.name,
+    }
+}
+
+/// Truncates the text to Discord's maximum length of an embed description
+fn truncate_description(mut text: String) -> String {
+    /// Discord's maximum length of an embed description
+    const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+    if let Some((cutoff, _)) = text.char_indices().nth(MAX_DESCRIPTION_LENGTH - 1) {
+        text.truncate(cutoff);
+        text.push('…');
+    }
+    text
+}
+
+/// Renders the embed and components of the given page of the interactive help
+async fn render_help_page<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: &HelpConfiguration<'_>,
+    categories: &[HelpCategory<'_, U, E>],
+    options_prefix: Option<&str>,
+    page: HelpPage,
+) -> (serenity::CreateEmbed, Vec<serenity::CreateActionRow>) {
+    let (HelpPage::Category(category_index) | HelpPage::Command(category_index, _)) = page;
+    let category = &categories[category_index];
+
+    let category_options = categories
+        .iter()
+        .enumerate()
+        .map(|(i, category)| {
+            serenity::CreateSelectMenuOption::new(
+                category.name.chars().take(100).collect::<String>(),
+                i.to_string(),
+            )
+            .default_selection(i == category_index)
+        })
+        .collect();
+    let mut components = vec![serenity::CreateActionRow::SelectMenu(
+        serenity::CreateSelectMenu::new(
+            CATEGORY_SELECT,
+            serenity::CreateSelectMenuKind::String {
+                options: category_options,
+            },
+        )
+        .placeholder("Category"),
+    )];
+
+    let embed = match page {
+        HelpPage::Category(_) => {
+            let mut commandlist = TwoColumnList::new();
+            for &command in &category.commands {
+                match format_context_menu_name(command) {
+                    Some(name)
+                        if command.prefix_action.is_none() && command.slash_action.is_none() =>
+                    {
+                        commandlist.push_two_colums(name, command_description(ctx, command));
+                    }
+                    _ => preformat_command(
+                        ctx,
+                        &mut commandlist,
+                        config,
+                        command,
+                        "",
+                        options_prefix,
+                    ),
+                }
+            }
+            let text = format!("```\n{}```", commandlist.into_string());
+
+            // Select menus are limited in size, so commands beyond that can only be looked up
+            // with the help command directly
+            let command_options = category
+                .commands
+                .iter()
+                .take(MAX_SELECT_OPTIONS)
+                .enumerate()
+                .map(|(i, command)| {
+                    let option = serenity::CreateSelectMenuOption::new(
+                        lookup_name(command).chars().take(100).collect::<String>(),
+                        i.to_string(),
+                    );
+                    match command_description(ctx, command) {
+                        description if description.is_empty() => option,
+                        description => {
+                            option.description(description.chars().take(100).collect::<String>())
+                        }
+                    }
+                })
+                .collect();
+            components.push(serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    COMMAND_SELECT,
+                    serenity::CreateSelectMenuKind::String {
+                        options: command_options,
+                    },
+                )
+                .placeholder("Show help of a command"),
+            ));
+
+            serenity::CreateEmbed::new()
+                .title(category.name)
+                .description(truncate_description(text))
+        }
+        HelpPage::Command(_, command_index) => {
+            let command = category.commands[command_index];
+            let text = single_command_text(ctx, lookup_name(command), config).await;
+            components.push(serenity::CreateActionRow::Buttons(vec![
+                serenity::CreateButton::new(BACK)
+                    .label(format!("Back to {}", category.name))
+                    .style(serenity::ButtonStyle::Secondary),
+            ]));
+
+            serenity::CreateEmbed::new().description(truncate_description(text))
+        }
+    };
+    let embed = match config.extra_text_at_bottom {
+        "" => embed,
+        extra_text => embed.footer(serenity::CreateEmbedFooter::new(extra_text)),
+    };
+    (embed, components)
+}
+
+/// Code for the interactive overview of all commands, see [`HelpConfiguration::interactive`]
+async fn help_interactive<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: HelpConfiguration<'_>,
+) -> Result<(), serenity::Error> {
+    let mut categories = categorize_commands(ctx)
+        .into_iter()
+        .map(|(name, commands)| HelpCategory {
+            name: name.unwrap_or("Commands"),
+            commands,
+        })
+        .collect::<Vec<_>>();
+    if config.show_context_menu_commands {
+        let commands = ctx
+            .framework()
+            .commands
+            .into_iter()
+            .filter(|command| {
+                command.prefix_action.is_none()
+                    && command.slash_action.is_none()
+                    && format_context_menu_name(command).is_some()
+            })
+            .collect::<Vec<_>>();
+        if !commands.is_empty() {
+            categories.push(HelpCategory {
+                name: "Context menu commands",
+                commands,
+            });
+        }
+    }
+
+    // Embeds are silently dropped without the Embed Links permission
+    let bot_permissions =
+        crate::dispatch::invocation_permissions(ctx, ctx.framework().bot_id).await;
+    let can_embed = bot_permissions.map_or(true, |permissions| permissions.embed_links());
+    if categories.is_empty() || categories.len() > MAX_SELECT_OPTIONS || !can_embed {
+        return help_all_commands(ctx, config).await;
+    }
+
+    let options_prefix = get_prefix_from_options(ctx).await;
+    let mut page = HelpPage::Category(0);
+    let (embed, components) =
+        render_help_page(ctx, &config, &categories, options_prefix.as_deref(), page).await;
+    let reply = CreateReply::default()
+        .embed(embed)
+        .components(components)
+        .ephemeral(config.ephemeral);
+    let handle = ctx.send(reply).await?;
+
+    loop {
+        let press = handle
+            .component_interaction_collector(ctx)
+            .await?
+            .timeout(config.interactive_timeout)
+            .next()
+            .await;
+        let Some(press) = press else { break };
+
+        if press.user.id != ctx.author().id {
+            let response = serenity::CreateInteractionResponseMessage::new()
+                .content("Only the user who invoked this command can navigate the help")
+                .ephemeral(true);
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    serenity::CreateInteractionResponse::Message(response),
+                )
+                .await?;
+            continue;
+        }
+
+        let selected = match &press.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => {
+                values.first().and_then(|value| value.parse::<usize>().ok())
+            }
+            _ => None,
+        };
+        let (HelpPage::Category(category) | HelpPage::Command(category, _)) = page;
+        page = match (&*press.data.custom_id, selected) {
+            (CATEGORY_SELECT, Some(selected)) if selected < categories.len() => {
+                HelpPage::Category(selected)
+            }
+            (COMMAND_SELECT, Some(selected)) if selected < categories[category].commands.len() => {
+                HelpPage::Command(category, selected)
+            }
+            (BACK, _) => HelpPage::Category(category),
+            _ => continue,
+        };
+
+        let (embed, components) =
+            render_help_page(ctx, &config, &categories, options_prefix.as_deref(), page).await;
+        let response = serenity::CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .components(components);
+        press
+            .create_response(
+                ctx.serenity_context(),
+                serenity::CreateInteractionResponse::UpdateMessage(response),
+            )
+            .await?;
+    }
+
+    let (embed, _) =
+        render_help_page(ctx, &config, &categories, options_prefix.as_deref(), page).await;
+    let reply = CreateReply::default().embed(embed).components(Vec::new());
+    handle.edit(ctx, reply).await?;
+    Ok(())
+}
@@ -12,6 +12,21 @@ pub use pretty_help::*;
 mod register;
 pub use register::*;
 
+mod error_sink;
+pub use error_sink::*;
+
+mod paginator;
+pub use paginator::*;
+
+mod confirm;
+pub use confirm::*;
+
+mod owner_tools;
+pub use owner_tools::*;
+
+mod usage_stats;
+pub use usage_stats::*;
+
 #[cfg(feature = "chrono")]
 mod paginate;
 #[cfg(feature = "chrono")]
@@ -26,6 +41,10 @@ use crate::{serenity::CreateAllowedMentions, serenity_prelude as serenity, Creat
 /// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command
 /// help is displayed and the user is directed to the help menu.
 ///
+/// If [`crate::FrameworkOptions::error_sink`] is set, command errors, command panics, event
+/// handler errors, background task errors and component handler errors are additionally reported
+/// there, see [`error_report`].
+///
 /// Can return an error if sending the Discord error message failed. You can decide for yourself
 /// how to handle this, for example:
 /// ```rust,no_run
@@ -38,6 +57,24 @@ use crate::{serenity::CreateAllowedMentions, serenity_prelude as serenity, Creat
 pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
     error: crate::FrameworkError<'_, U, E>,
 ) -> Result<(), serenity::Error> {
+    let error_sink = match &error {
+        crate::FrameworkError::EventHandler { framework, .. }
+        | crate::FrameworkError::BackgroundTask { framework, .. } => {
+            framework.options.error_sink.as_ref()
+        }
+        crate::FrameworkError::ComponentHandler { ctx, .. } => {
+            ctx.framework.options.error_sink.as_ref()
+        }
+        _ => error
+            .ctx()
+            .and_then(|ctx| ctx.framework().options.error_sink.as_ref()),
+    };
+    if let Some((error_sink, report)) = error_sink.zip(error_report(&error)) {
+        if let Err(e) = error_sink.send(error.serenity_context(), report).await {
+            tracing::warn!("failed to report error to error sink: {}", e);
+        }
+    }
+
     match error {
         crate::FrameworkError::Setup { error, .. } => {
             eprintln!("Error in user data setup: {}", error);
@@ -47,7 +84,28 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             event.snake_case_name(),
             error
         ),
+        crate::FrameworkError::BackgroundTask { error, .. } => {
+            tracing::error!("Background task encountered an error: {}", error)
+        }
+        crate::FrameworkError::ComponentHandler { error, ctx } => tracing::error!(
+            "Component handler for custom ID `{}` encountered an error: {}",
+            ctx.custom_id(),
+            error
+        ),
         crate::FrameworkError::Command { ctx, error } => {
+            if let Some(user_facing) = ctx.framework().options.error_formatter.map(|f| f(&error)) {
+                log_with_severity(
+                    user_facing.severity,
+                    format_args!(
+                        "An error occured in command `{}`: {:?}",
+                        ctx.command().qualified_name,
+                        error
+                    ),
+                );
+                send_user_facing_error(ctx, user_facing).await?;
+                return Ok(());
+            }
+
             let error = error.to_string();
             eprintln!("An error occured in a command: {}", error);
 
@@ -87,21 +145,39 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             ctx.send(CreateReply::default().embed(embed).ephemeral(true))
                 .await?;
         }
-        crate::FrameworkError::ArgumentParse { ctx, input, error } => {
+        crate::FrameworkError::ArgumentParse {
+            ctx,
+            input,
+            parameter,
+            error,
+        } => {
             // If we caught an argument parse error, give a helpful error message with the
             // command explanation if available
-            let usage = match &ctx.command().help_text {
-                Some(help_text) => &**help_text,
-                None => "Please check the help menu for usage information",
+            let command = ctx.command();
+            let usage = ctx
+                .localized(&command.help_text_localizations)
+                .or(command.help_text.as_deref())
+                .unwrap_or("Please check the help menu for usage information");
+            let argument = match parameter {
+                Some(parameter) => format!("`{}`", parameter.name),
+                None => "argument".to_owned(),
             };
-            let response = if let Some(input) = input {
+            let mut response = if let Some(input) = input {
                 format!(
-                    "**Cannot parse `{}` as argument: {}**\n{}",
-                    input, error, usage
+                    "**Cannot parse `{}` as {}: {}**\n{}",
+                    input, argument, error, usage
                 )
+            } else if let Some(parameter) = parameter {
+                format!("**`{}`: {}**\n{}", parameter.name, error, usage)
             } else {
                 format!("**{}**\n{}", error, usage)
             };
+            if !command.examples.is_empty() {
+                response += "\n\nExamples:";
+                for example in &command.examples {
+                    response += &format!("\n`{}`", example);
+                }
+            }
 
             let mentions = CreateAllowedMentions::new()
                 .everyone(false)
@@ -115,6 +191,29 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             )
             .await?;
         }
+        crate::FrameworkError::InvalidChannelType { ctx, expected, got } => {
+            let expected = expected
+                .iter()
+                .map(|kind| format!("`{}`", kind.name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let response = format!(
+                "A `{}` channel can't be used here, please pick one of these channel types: {}",
+                got.name(),
+                expected
+            );
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::ParameterConstraintViolation {
+            ctx,
+            parameter,
+            constraint,
+        } => {
+            let response = format!("**`{}` {}**", parameter, constraint);
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
         crate::FrameworkError::CommandStructureMismatch { ctx, description } => {
             tracing::error!(
                 "Error: failed to deserialize interaction arguments for `/{}`: {}",
@@ -123,6 +222,27 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             );
         }
         crate::FrameworkError::CommandCheckFailed { ctx, error } => {
+            let user_facing = match (&error, ctx.framework().options.error_formatter) {
+                (Some(error), Some(error_formatter)) => {
+                    let user_facing = error_formatter(error);
+                    log_with_severity(
+                        user_facing.severity,
+                        format_args!(
+                            "A command check failed in command {} for user {}: {:?}",
+                            ctx.command().name,
+                            ctx.author().name,
+                            error,
+                        ),
+                    );
+                    Some(user_facing)
+                }
+                _ => None,
+            };
+            if let Some(user_facing) = user_facing {
+                send_user_facing_error(ctx, user_facing).await?;
+                return Ok(());
+            }
+
             tracing::error!(
                 "A command check failed in command {} for user {}: {:?}",
                 ctx.command().name,
@@ -141,6 +261,23 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             ctx.send(CreateReply::default().content(msg).ephemeral(true))
                 .await?;
         }
+        crate::FrameworkError::RatelimitDelayed { delay, ctx } => {
+            let msg = format!(
+                "You're being rate limited. Your command will run in {} seconds",
+                delay.as_secs().max(1)
+            );
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::TooManyConcurrentInvocations { ctx, .. } => {
+            let msg = format!(
+                "`{}{}` is busy. Please try again later",
+                ctx.prefix(),
+                ctx.command().name,
+            );
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+        }
         crate::FrameworkError::MissingBotPermissions {
             missing_permissions,
             ctx,
@@ -173,6 +310,21 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             ctx.send(CreateReply::default().content(response).ephemeral(true))
                 .await?;
         }
+        crate::FrameworkError::PermissionsUnverifiable { user_id, ctx } => {
+            let whose = if user_id == ctx.framework().bot_id {
+                "the bot's"
+            } else {
+                "your"
+            };
+            let response = format!(
+                "Could not verify {} permissions for `{}{}`. Not executing for safety",
+                whose,
+                ctx.prefix(),
+                ctx.command().name,
+            );
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
         crate::FrameworkError::NotAnOwner { ctx } => {
             let response = "Only bot owners can call this command";
             ctx.send(CreateReply::default().content(response).ephemeral(true))
@@ -223,6 +375,41 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
     Ok(())
 }
 
+/// Logs the message at the tracing level corresponding to the severity
+fn log_with_severity(severity: crate::ErrorSeverity, message: std::fmt::Arguments<'_>) {
+    match severity {
+        crate::ErrorSeverity::Debug => tracing::debug!("{}", message),
+        crate::ErrorSeverity::Info => tracing::info!("{}", message),
+        crate::ErrorSeverity::Warning => tracing::warn!("{}", message),
+        crate::ErrorSeverity::Error | crate::ErrorSeverity::__NonExhaustive => {
+            tracing::error!("{}", message)
+        }
+    }
+}
+
+/// Shows the message of a [`crate::UserFacingError`] to the user, or a generic message if it has
+/// none
+async fn send_user_facing_error<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    user_facing: crate::UserFacingError,
+) -> Result<(), serenity::Error> {
+    let message = user_facing
+        .message
+        .unwrap_or_else(|| "An internal error occurred.".into());
+    let mentions = CreateAllowedMentions::new()
+        .everyone(false)
+        .all_roles(false)
+        .all_users(false);
+    ctx.send(
+        CreateReply::default()
+            .content(message)
+            .allowed_mentions(mentions)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
 /// An autocomplete function that can be used for the command parameter in your help function.
 ///
 /// See `examples/feature_showcase` for an example
@@ -232,9 +419,8 @@ pub async fn autocomplete_command<'a, U, E>(
     partial: &'a str,
 ) -> impl Iterator<Item = String> + 'a {
     ctx.framework()
-        .options()
         .commands
-        .iter()
+        .into_iter()
         .filter(move |cmd| cmd.name.starts_with(partial))
         .map(|cmd| cmd.name.to_string())
 }
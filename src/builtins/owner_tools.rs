@@ -0,0 +1,245 @@
+//! Owner-only commands for administrating a running bot, see [`owner_tools`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Generates the prefix and slash actions of an owner tool from its implementation, which takes
+/// the context and the raw argument string
+macro_rules! owner_tool_actions {
+    ($( $implementation:ident => $prefix_action:ident, $slash_action:ident; )*) => { $(
+        /// Prefix action of the owner tool of the same name
+        fn $prefix_action<U: Send + Sync, E: From<serenity::Error>>(
+            ctx: crate::PrefixContext<'_, U, E>,
+        ) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>> {
+            Box::pin(async move {
+                $implementation(ctx.into(), ctx.args)
+                    .await
+                    .map_err(|error| crate::FrameworkError::new_command(ctx.into(), error.into()))
+            })
+        }
+
+        /// Slash action of the owner tool of the same name
+        fn $slash_action<U: Send + Sync, E: From<serenity::Error>>(
+            ctx: crate::ApplicationContext<'_, U, E>,
+        ) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>> {
+            Box::pin(async move {
+                let args = ctx
+                    .args
+                    .iter()
+                    .find_map(|arg| match arg.value {
+                        serenity::ResolvedValue::String(value) => Some(value),
+                        _ => None,
+                    })
+                    .unwrap_or("");
+                $implementation(ctx.into(), args)
+                    .await
+                    .map_err(|error| crate::FrameworkError::new_command(ctx.into(), error.into()))
+            })
+        }
+    )* };
+}
+
+owner_tool_actions! {
+    group => group_prefix, group_slash;
+    shutdown => shutdown_prefix, shutdown_slash;
+    latency => latency_prefix, latency_slash;
+    set_activity => set_activity_prefix, set_activity_slash;
+    shard_status => shard_status_prefix, shard_status_slash;
+}
+#[cfg(feature = "cache")]
+owner_tool_actions! {
+    servers => servers_prefix, servers_slash;
+}
+
+/// Creates an owner-only command group with common administration commands:
+/// - `shutdown`: shuts down all shards, which makes [`serenity::Client::start`] return
+/// - `latency`: shows the gateway latency of the current shard
+/// - `set_activity`: sets the bot's activity, e.g. `set_activity watching the logs`. The text may
+///   start with `playing`, `listening`, `watching` or `competing`; without any text, the
+///   activity is cleared
+/// - `servers`: lists the servers the bot is in, see [`crate::builtins::servers`]. Requires the
+///   `cache` feature
+/// - `shard_status`: shows the connection stage and latency of every shard of this process
+///
+/// The commands are registered as prefix and slash commands and are hidden from the help menu.
+/// Only users in [`crate::FrameworkOptions::owners`] can invoke them.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     commands: vec![poise::builtins::owner_tools()],
+///     ..Default::default()
+/// };
+/// ```
+pub fn owner_tools<U: Send + Sync, E: From<serenity::Error>>() -> crate::Command<U, E> {
+    /// Starts building one of the owner tools with the given actions
+    fn tool<U, E>(
+        name: &str,
+        description: &str,
+        prefix_action: for<'a> fn(
+            crate::PrefixContext<'a, U, E>,
+        )
+            -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>,
+        slash_action: for<'a> fn(
+            crate::ApplicationContext<'a, U, E>,
+        )
+            -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>,
+    ) -> crate::CommandBuilder<U, E> {
+        crate::Command::builder(name)
+            .description(description)
+            .prefix_action(prefix_action)
+            .slash_action(slash_action)
+            .owners_only(true)
+            .hide_in_help(true)
+    }
+
+    let shutdown = tool(
+        "shutdown",
+        "Shuts down the bot",
+        shutdown_prefix,
+        shutdown_slash,
+    );
+    let latency = tool(
+        "latency",
+        "Shows the gateway latency",
+        latency_prefix,
+        latency_slash,
+    );
+    let set_activity = tool(
+        "set_activity",
+        "Sets the bot's activity",
+        set_activity_prefix,
+        set_activity_slash,
+    )
+    .parameter(
+        crate::CommandParameter::builder("activity")
+            .description(
+                "Activity, optionally starting with playing, listening, watching or competing",
+            )
+            .required(false)
+            .build(),
+    );
+    let shard_status = tool(
+        "shard_status",
+        "Shows the status of all shards",
+        shard_status_prefix,
+        shard_status_slash,
+    );
+
+    let command = tool(
+        "owner_tools",
+        "Bot administration commands",
+        group_prefix,
+        group_slash,
+    )
+    .subcommand_required(true)
+    .subcommand(shutdown.build())
+    .subcommand(latency.build())
+    .subcommand(set_activity.build());
+    #[cfg(feature = "cache")]
+    let command = command.subcommand(
+        tool(
+            "servers",
+            "Lists the servers the bot is in",
+            servers_prefix,
+            servers_slash,
+        )
+        .build(),
+    );
+    command.subcommand(shard_status.build()).build()
+}
+
+/// Action of the group command itself, which is never invoked because a subcommand is required
+async fn group<U, E>(_: crate::Context<'_, U, E>, _: &str) -> Result<(), serenity::Error> {
+    Ok(())
+}
+
+/// Implementation of the `shutdown` owner tool
+async fn shutdown<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    _: &str,
+) -> Result<(), serenity::Error> {
+    ctx.say("Shutting down").await?;
+    ctx.framework().shard_manager().shutdown_all().await;
+    Ok(())
+}
+
+/// Implementation of the `latency` owner tool
+async fn latency<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    _: &str,
+) -> Result<(), serenity::Error> {
+    let latency = ctx.ping().await;
+    let response = match latency.is_zero() {
+        true => "Gateway latency isn't known yet, try again in a minute".to_owned(),
+        false => format!("Gateway latency: {} ms", latency.as_millis()),
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Implementation of the `set_activity` owner tool
+async fn set_activity<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    args: &str,
+) -> Result<(), serenity::Error> {
+    let args = args.trim();
+    let (kind, text) = args.split_once(' ').unwrap_or((args, ""));
+    let activity = match kind.to_lowercase().as_str() {
+        "" => None,
+        "playing" => Some(serenity::ActivityData::playing(text)),
+        "listening" => Some(serenity::ActivityData::listening(text)),
+        "watching" => Some(serenity::ActivityData::watching(text)),
+        "competing" => Some(serenity::ActivityData::competing(text)),
+        _ => Some(serenity::ActivityData::playing(args)),
+    };
+
+    let response = match &activity {
+        Some(activity) => format!("Activity set to `{}`", activity.name),
+        None => "Activity cleared".to_owned(),
+    };
+    ctx.serenity_context().set_activity(activity);
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Implementation of the `servers` owner tool
+#[cfg(feature = "cache")]
+async fn servers<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    _: &str,
+) -> Result<(), serenity::Error> {
+    crate::builtins::servers(ctx).await
+}
+
+/// Implementation of the `shard_status` owner tool
+async fn shard_status<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    _: &str,
+) -> Result<(), serenity::Error> {
+    use std::fmt::Write as _;
+
+    let shard_manager = ctx.framework().shard_manager();
+    let mut response = String::new();
+    {
+        let runners = shard_manager.runners.lock().await;
+        let mut shards = runners.iter().collect::<Vec<_>>();
+        shards.sort_by_key(|(shard_id, _)| shard_id.0);
+        for (shard_id, runner) in shards {
+            let latency = match runner.latency {
+                Some(latency) => format!("{} ms", latency.as_millis()),
+                None => "unknown latency".to_owned(),
+            };
+            let _ = writeln!(
+                response,
+                "- Shard {}: {} ({})",
+                shard_id.0, runner.stage, latency
+            );
+        }
+    }
+    if response.is_empty() {
+        response = "No shards are running in this process".to_owned();
+    }
+
+    ctx.say(response).await?;
+    Ok(())
+}
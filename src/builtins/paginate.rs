@@ -13,6 +13,8 @@ use crate::serenity_prelude as serenity;
 /// - remove message after navigation timeout
 /// - ...
 ///
+/// For a ready-to-use paginator with more features, see [`crate::builtins::Paginator`].
+///
 /// Note: this is a long-running function. It will only return once the timeout for navigation
 /// button interactions has been reached.
 ///
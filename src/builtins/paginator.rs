@@ -0,0 +1,236 @@
+//! Full-featured pagination with navigation buttons, see [`Paginator`]
+
+use crate::serenity_prelude as serenity;
+
+/// Custom IDs of the navigation buttons. The collector is bound to the paginated message, so
+/// they don't need to be unique across messages
+const FIRST: &str = "poise_paginator_first";
+/// See [`FIRST`]
+const PREV: &str = "poise_paginator_prev";
+/// See [`FIRST`]
+const JUMP: &str = "poise_paginator_jump";
+/// See [`FIRST`]
+const NEXT: &str = "poise_paginator_next";
+/// See [`FIRST`]
+const LAST: &str = "poise_paginator_last";
+
+/// Sends paginated embeds with first/previous/jump/next/last buttons and handles the button
+/// presses until the timeout expires. Works for both prefix and application commands.
+///
+/// The jump button shows the current page number and opens a modal that asks for the page to
+/// jump to.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = ["Apples", "Bananas", "Cherries"]
+///     .iter()
+///     .map(|fruit| serenity::CreateEmbed::new().title(*fruit))
+///     .collect();
+/// poise::builtins::Paginator::new(pages).run(ctx).await?;
+///
+/// // Pages can also be generated on demand
+/// poise::builtins::Paginator::from_fn(1000, |page| {
+///     serenity::CreateEmbed::new().description(format!("{} squared is {}", page, page * page))
+/// })
+/// .timeout(std::time::Duration::from_secs(60))
+/// .run(ctx)
+/// .await?;
+/// # Ok(()) }
+/// ```
+#[must_use]
+pub struct Paginator<'a> {
+    /// Number of pages
+    page_count: usize,
+    /// Generates the embed of the given page
+    page: Box<dyn Fn(usize) -> serenity::CreateEmbed + Send + Sync + 'a>,
+    /// Page that is shown first
+    start_page: usize,
+    /// How long to wait for a button press before removing the buttons
+    timeout: std::time::Duration,
+    /// Whether only the invoking user can flip pages
+    author_only: bool,
+    /// Whether to send the pages as an ephemeral message
+    ephemeral: bool,
+}
+
+impl<'a> Paginator<'a> {
+    /// Paginates the given embeds
+    pub fn new(pages: Vec<serenity::CreateEmbed>) -> Self {
+        Self::from_fn(pages.len(), move |page| pages[page].clone())
+    }
+
+    /// Paginates `page_count` pages that are generated on demand by the given function, which is
+    /// passed the zero-based page index
+    pub fn from_fn(
+        page_count: usize,
+        page: impl Fn(usize) -> serenity::CreateEmbed + Send + Sync + 'a,
+    ) -> Self {
+        Self {
+            page_count,
+            page: Box::new(page),
+            start_page: 0,
+            timeout: std::time::Duration::from_secs(60 * 10),
+            author_only: true,
+            ephemeral: false,
+        }
+    }
+
+    /// Sets the zero-based index of the page that is shown first. 0 by default
+    pub fn start_page(mut self, start_page: usize) -> Self {
+        self.start_page = start_page;
+        self
+    }
+
+    /// Sets how long to wait for a button press before the buttons are removed. 10 minutes by
+    /// default
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether only the invoking user can flip pages. Other users are told so in an
+    /// ephemeral message. True by default
+    pub fn author_only(mut self, author_only: bool) -> Self {
+        self.author_only = author_only;
+        self
+    }
+
+    /// Sets whether the pages are sent as an ephemeral message (slash-only). False by default
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Builds the navigation buttons for the given page
+    fn buttons(&self, page: usize) -> Vec<serenity::CreateActionRow> {
+        let last_page = self.page_count.saturating_sub(1);
+        vec![serenity::CreateActionRow::Buttons(vec![
+            serenity::CreateButton::new(FIRST)
+                .emoji('⏮')
+                .disabled(page == 0),
+            serenity::CreateButton::new(PREV)
+                .emoji('◀')
+                .disabled(page == 0),
+            serenity::CreateButton::new(JUMP)
+                .label(format!("{}/{}", page + 1, self.page_count))
+                .style(serenity::ButtonStyle::Secondary)
+                .disabled(self.page_count <= 1),
+            serenity::CreateButton::new(NEXT)
+                .emoji('▶')
+                .disabled(page >= last_page),
+            serenity::CreateButton::new(LAST)
+                .emoji('⏭')
+                .disabled(page >= last_page),
+        ])]
+    }
+
+    /// Sends the first page and handles button presses until the timeout expires, then removes
+    /// the buttons.
+    ///
+    /// Note: this is a long-running function. It only returns once no button has been pressed for
+    /// the [timeout](Self::timeout).
+    pub async fn run<U: Send + Sync, E>(
+        self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Result<(), serenity::Error> {
+        if self.page_count == 0 {
+            return Ok(());
+        }
+        let mut page = self.start_page.min(self.page_count - 1);
+
+        let reply = crate::CreateReply::default()
+            .embed((self.page)(page))
+            .components(self.buttons(page))
+            .ephemeral(self.ephemeral);
+        let handle = ctx.send(reply).await?;
+
+        loop {
+            let press = handle
+                .component_interaction_collector(ctx)
+                .await?
+                .timeout(self.timeout)
+                .next()
+                .await;
+            let Some(press) = press else { break };
+
+            if self.author_only && press.user.id != ctx.author().id {
+                let response = serenity::CreateInteractionResponseMessage::new()
+                    .content("Only the user who invoked this command can flip pages")
+                    .ephemeral(true);
+                press
+                    .create_response(ctx, serenity::CreateInteractionResponse::Message(response))
+                    .await?;
+                continue;
+            }
+
+            let last_page = self.page_count - 1;
+            page = match &*press.data.custom_id {
+                FIRST => 0,
+                PREV => page.saturating_sub(1),
+                NEXT => (page + 1).min(last_page),
+                LAST => last_page,
+                JUMP => {
+                    let jump = crate::execute_modal_on_component_interaction::<JumpModal>(
+                        ctx,
+                        press,
+                        None,
+                        Some(std::time::Duration::from_secs(60)),
+                    )
+                    .await?;
+                    // The modal submission was already acknowledged, so edit the message instead
+                    // of responding to the interaction
+                    if let Some(target) = jump.and_then(|jump| jump.page.trim().parse().ok()) {
+                        page = usize::clamp(target, 1, self.page_count) - 1;
+                        let reply = crate::CreateReply::default()
+                            .embed((self.page)(page))
+                            .components(self.buttons(page));
+                        handle.edit(ctx, reply).await?;
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let response = serenity::CreateInteractionResponseMessage::new()
+                .embed((self.page)(page))
+                .components(self.buttons(page));
+            press
+                .create_response(
+                    ctx,
+                    serenity::CreateInteractionResponse::UpdateMessage(response),
+                )
+                .await?;
+        }
+
+        let reply = crate::CreateReply::default()
+            .embed((self.page)(page))
+            .components(Vec::new());
+        handle.edit(ctx, reply).await?;
+        Ok(())
+    }
+}
+
+/// Modal of the jump button, asking for the page number to jump to
+struct JumpModal {
+    /// One-based page number as entered by the user
+    page: String,
+}
+
+impl crate::Modal for JumpModal {
+    fn create(_: Option<Self>, custom_id: String) -> serenity::CreateInteractionResponse {
+        let input = serenity::CreateInputText::new(serenity::InputTextStyle::Short, "Page", "page")
+            .placeholder("Page number")
+            .min_length(1)
+            .max_length(10);
+        serenity::CreateInteractionResponse::Modal(
+            serenity::CreateModal::new(custom_id, "Jump to page")
+                .components(vec![serenity::CreateActionRow::InputText(input)]),
+        )
+    }
+
+    fn parse(mut data: serenity::ModalInteractionData) -> Result<Self, &'static str> {
+        let page = crate::find_modal_text(&mut data, "page").ok_or("missing page number")?;
+        Ok(Self { page })
+    }
+}
@@ -4,7 +4,27 @@ use crate::{serenity_prelude as serenity, CreateReply};
 use std::fmt::Write as _;
 
 /// Optional configuration for how the help message from [`pretty_help()`] looks
-pub struct PrettyHelpConfiguration<'a> {
+///
+/// The embed layout of the command overview can be changed with [`Self::format_command`] and
+/// [`Self::format_category`]:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let config = poise::builtins::PrettyHelpConfiguration::<(), Error> {
+///     title: "Commands",
+///     thumbnail: Some("https://example.com/logo.png"),
+///     format_command: Some(|command| {
+///         let description = command.description.as_deref().unwrap_or("");
+///         format!("**{}** – {}", command.name, description)
+///     }),
+///     format_category: Some(|category, commands| {
+///         let name = format!("{} ({})", category, commands.len());
+///         let commands = commands.iter().map(|command| format!("`{}`", command.name));
+///         (name, commands.collect::<Vec<_>>().join(", "))
+///     }),
+///     ..Default::default()
+/// };
+/// ```
+pub struct PrettyHelpConfiguration<'a, U, E> {
     /// Extra text displayed at the bottom of your message. Can be used for help and tips specific
     /// to your bot
     pub extra_text_at_bottom: &'a str,
@@ -18,11 +38,25 @@ pub struct PrettyHelpConfiguration<'a> {
     pub include_description: bool,
     /// Color of the Embed
     pub color: (u8, u8, u8),
+    /// Title of the command overview embed. `"Help"` by default
+    pub title: &'a str,
+    /// URL of a thumbnail image shown in the embeds
+    pub thumbnail: Option<&'a str>,
+    /// URL of an icon shown next to [`Self::extra_text_at_bottom`] in the footer
+    pub footer_icon: Option<&'a str>,
+    /// Formats the line of a command (or subcommand, if [`Self::show_subcommands`] is enabled)
+    /// in the command overview. By default, the command is shown with its prefix and description
+    pub format_command: Option<fn(&crate::Command<U, E>) -> String>,
+    /// Formats the embed field of a category in the command overview, from the category name
+    /// (empty for commands without category) and the commands in it. Returns the name and value
+    /// of the field. By default, the field is named after the category and lists the commands
+    /// one per line, formatted with [`Self::format_command`]
+    pub format_category: Option<fn(&str, &[&crate::Command<U, E>]) -> (String, String)>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
-impl Default for PrettyHelpConfiguration<'_> {
+impl<U, E> Default for PrettyHelpConfiguration<'_, U, E> {
     fn default() -> Self {
         Self {
             extra_text_at_bottom: "",
@@ -31,6 +65,11 @@ impl Default for PrettyHelpConfiguration<'_> {
             show_subcommands: false,
             include_description: true,
             color: (0, 110, 51),
+            title: "Help",
+            thumbnail: None,
+            footer_icon: None,
+            format_command: None,
+            format_category: None,
             __non_exhaustive: (),
         }
     }
@@ -41,7 +80,7 @@ impl Default for PrettyHelpConfiguration<'_> {
 pub async fn pretty_help<U, E>(
     ctx: crate::Context<'_, U, E>,
     command: Option<&str>,
-    config: PrettyHelpConfiguration<'_>,
+    config: PrettyHelpConfiguration<'_, U, E>,
 ) -> Result<(), serenity::Error> {
     match command {
         Some(command) => pretty_help_single_command(ctx, command, config).await,
@@ -52,9 +91,10 @@ pub async fn pretty_help<U, E>(
 /// Printing an overview of all commands (e.g. `~help`)
 async fn pretty_help_all_commands<U, E>(
     ctx: crate::Context<'_, U, E>,
-    config: PrettyHelpConfiguration<'_>,
+    config: PrettyHelpConfiguration<'_, U, E>,
 ) -> Result<(), serenity::Error> {
-    let commands = ctx.framework().options().commands.iter().filter(|cmd| {
+    let commands = ctx.framework().commands;
+    let commands = commands.into_iter().filter(|cmd| {
         !cmd.hide_in_help
             && (cmd.prefix_action.is_some()
                 || cmd.slash_action.is_some()
@@ -78,45 +118,41 @@ async fn pretty_help_all_commands<U, E>(
             // get context menu items at the bottom
             cmds.sort_by_key(|cmd| cmd.slash_action.is_none() && cmd.prefix_action.is_none());
 
-            let mut buffer = String::new();
-
-            for cmd in cmds {
-                let name = cmd.context_menu_name.as_deref().unwrap_or(&cmd.name);
-                let prefix = format_cmd_prefix(cmd, &options_prefix);
-
-                if let Some(description) = cmd.description.as_deref() {
-                    writeln!(buffer, "{}{}`: *{}*", prefix, name, description).ok();
-                } else {
-                    writeln!(buffer, "{}{}`.", prefix, name).ok();
-                }
-
-                if config.show_subcommands {
-                    for sbcmd in &cmd.subcommands {
-                        let name = sbcmd.context_menu_name.as_deref().unwrap_or(&sbcmd.name);
-                        let prefix = format_cmd_prefix(sbcmd, &options_prefix);
-
-                        if let Some(description) = sbcmd.description.as_deref() {
-                            writeln!(buffer, "> {}{}`: *{}*", prefix, name, description).ok();
-                        } else {
-                            writeln!(buffer, "> {}{}`.", prefix, name).ok();
+            let category = category.unwrap_or_default();
+            let (name, mut buffer) = match config.format_category {
+                Some(format_category) => format_category(category, &cmds),
+                None => {
+                    let mut buffer = String::new();
+                    for cmd in cmds {
+                        let line = format_command_line(ctx, &config, cmd, &options_prefix);
+                        writeln!(buffer, "{}", line).ok();
+
+                        if config.show_subcommands {
+                            for sbcmd in &cmd.subcommands {
+                                let line =
+                                    format_command_line(ctx, &config, sbcmd, &options_prefix);
+                                writeln!(buffer, "> {}", line).ok();
+                            }
                         }
                     }
+                    (category.to_owned(), buffer)
                 }
-            }
+            };
             if let Some((i, _)) = buffer.char_indices().nth(1024) {
                 buffer.truncate(i);
             }
-            (category.unwrap_or_default(), buffer, false)
+            (name, buffer, false)
         })
         .collect::<Vec<_>>();
 
-    let embed = serenity::CreateEmbed::new()
-        .title("Help")
+    let mut footer = serenity::CreateEmbedFooter::new(config.extra_text_at_bottom);
+    if let Some(footer_icon) = config.footer_icon {
+        footer = footer.icon_url(footer_icon);
+    }
+    let embed = style_embed(serenity::CreateEmbed::new(), &config)
+        .title(config.title)
         .fields(fields)
-        .color(config.color)
-        .footer(serenity::CreateEmbedFooter::new(
-            config.extra_text_at_bottom,
-        ));
+        .footer(footer);
 
     let reply = crate::CreateReply::default()
         .embed(embed)
@@ -127,6 +163,42 @@ async fn pretty_help_all_commands<U, E>(
     Ok(())
 }
 
+/// Formats the line of a command in the command overview, see
+/// [`PrettyHelpConfiguration::format_command`]
+fn format_command_line<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: &PrettyHelpConfiguration<'_, U, E>,
+    cmd: &crate::Command<U, E>,
+    options_prefix: &Option<String>,
+) -> String {
+    if let Some(format_command) = config.format_command {
+        return format_command(cmd);
+    }
+
+    let name = cmd.context_menu_name.as_deref().unwrap_or(&cmd.name);
+    let prefix = format_cmd_prefix(cmd, options_prefix);
+    let description = ctx
+        .localized(&cmd.description_localizations)
+        .or(cmd.description.as_deref());
+    if let Some(description) = description {
+        format!("{}{}`: *{}*", prefix, name, description)
+    } else {
+        format!("{}{}`.", prefix, name)
+    }
+}
+
+/// Applies the color and thumbnail of the configuration to the embed
+fn style_embed<U, E>(
+    embed: serenity::CreateEmbed,
+    config: &PrettyHelpConfiguration<'_, U, E>,
+) -> serenity::CreateEmbed {
+    let embed = embed.color(config.color);
+    match config.thumbnail {
+        Some(thumbnail) => embed.thumbnail(thumbnail),
+        None => embed,
+    }
+}
+
 /// Figures out which prefix a command should have
 fn format_cmd_prefix<U, E>(cmd: &crate::Command<U, E>, options_prefix: &Option<String>) -> String {
     if cmd.slash_action.is_some() {
@@ -137,6 +209,9 @@ fn format_cmd_prefix<U, E>(cmd: &crate::Command<U, E>, options_prefix: &Option<S
         match cmd.context_menu_action {
             Some(crate::ContextMenuCommandAction::Message(_)) => "Message menu: `".into(),
             Some(crate::ContextMenuCommandAction::User(_)) => "User menu: `".into(),
+            Some(crate::ContextMenuCommandAction::UserAndMessage(_)) => {
+                "User and message menu: `".into()
+            }
             Some(crate::ContextMenuCommandAction::__NonExhaustive) | None => {
                 unreachable!()
             }
@@ -150,9 +225,9 @@ fn format_cmd_prefix<U, E>(cmd: &crate::Command<U, E>, options_prefix: &Option<S
 async fn pretty_help_single_command<U, E>(
     ctx: crate::Context<'_, U, E>,
     command_name: &str,
-    config: PrettyHelpConfiguration<'_>,
+    config: PrettyHelpConfiguration<'_, U, E>,
 ) -> Result<(), serenity::Error> {
-    let commands = &ctx.framework().options().commands;
+    let commands = ctx.framework().commands;
 
     // Try interpret the command name as a context menu command first
     let command = commands
@@ -196,6 +271,7 @@ async fn pretty_help_single_command<U, E>(
         let kind = match command.context_menu_action {
             Some(crate::ContextMenuCommandAction::User(_)) => "user",
             Some(crate::ContextMenuCommandAction::Message(_)) => "message",
+            Some(crate::ContextMenuCommandAction::UserAndMessage(_)) => "user and message",
             Some(crate::ContextMenuCommandAction::__NonExhaustive) | None => unreachable!(),
         };
         invocations.push(format!(
@@ -217,12 +293,18 @@ async fn pretty_help_single_command<U, E>(
         .reduce(|x, y| format!("{x}\n{y}"))
         .map(|s| ("", s, false));
 
-    let description = match (&command.description, &command.help_text) {
+    let description = ctx
+        .localized(&command.description_localizations)
+        .or(command.description.as_deref());
+    let help_text = ctx
+        .localized(&command.help_text_localizations)
+        .or(command.help_text.as_deref());
+    let description = match (description, help_text) {
         (Some(description), Some(help_text)) if config.include_description => {
             format!("{}\n\n{}", description, help_text)
         }
-        (_, Some(help_text)) => help_text.clone(),
-        (Some(description), None) => description.clone(),
+        (_, Some(help_text)) => help_text.to_owned(),
+        (Some(description), None) => description.to_owned(),
         (None, None) => "No help available".to_string(),
     };
 
@@ -235,22 +317,39 @@ async fn pretty_help_single_command<U, E>(
             } else {
                 "optional"
             };
-            if let Some(description) = parameter.description.as_deref() {
-                format!("`{}` ({}) *{} *.", parameter.name, req, description)
+            let name = ctx
+                .localized(&parameter.name_localizations)
+                .unwrap_or(&parameter.name);
+            let description = ctx
+                .localized(&parameter.description_localizations)
+                .or(parameter.description.as_deref());
+            if let Some(description) = description {
+                format!("`{}` ({}) *{} *.", name, req, description)
             } else {
-                format!("`{}` ({}).", parameter.name, req)
+                format!("`{}` ({}).", name, req)
             }
         })
         .reduce(|x, y| format!("{x}\n{y}"))
         .map(|s| ("Parameters", s, false));
 
+    let examples = command
+        .examples
+        .iter()
+        .map(|example| format!("`{}`", example))
+        .reduce(|x, y| format!("{x}\n{y}"))
+        .map(|s| ("Examples", s, false));
+
     let sbcmds = command
         .subcommands
         .iter()
+        .filter(|sbcmd| !sbcmd.subcommand_group)
         .map(|sbcmd| {
             let prefix = format_cmd_prefix(sbcmd, &subprefix); // i have no idea about this really
             let name = sbcmd.context_menu_name.as_deref().unwrap_or(&sbcmd.name);
-            if let Some(description) = sbcmd.description.as_deref() {
+            let description = ctx
+                .localized(&sbcmd.description_localizations)
+                .or(sbcmd.description.as_deref());
+            if let Some(description) = description {
                 format!("> {}{}`: *{} *", prefix, name, description)
             } else {
                 format!("> {}{}`", prefix, name,)
@@ -259,12 +358,41 @@ async fn pretty_help_single_command<U, E>(
         .reduce(|x, y| format!("{x}\n{y}"))
         .map(|s| ("Subcommands", s, false));
 
+    // Groups can't be nested, so list the subcommands of each group right below it
+    let groups = command
+        .subcommands
+        .iter()
+        .filter(|group| group.subcommand_group)
+        .flat_map(|group| {
+            let prefix = format_cmd_prefix(group, &subprefix);
+            std::iter::once((format!("{}{}", prefix, group.name), group)).chain(
+                group
+                    .subcommands
+                    .iter()
+                    .map(move |sbcmd| (format!("{}{} {}", prefix, group.name, sbcmd.name), sbcmd)),
+            )
+        })
+        .map(|(name, cmd)| {
+            let description = ctx
+                .localized(&cmd.description_localizations)
+                .or(cmd.description.as_deref());
+            if let Some(description) = description {
+                format!("> {}`: *{} *", name, description)
+            } else {
+                format!("> {}`", name)
+            }
+        })
+        .reduce(|x, y| format!("{x}\n{y}"))
+        .map(|s| ("Subcommand groups", s, false));
+
     let fields = invocations
         .into_iter()
-        .chain(parameters.into_iter())
-        .chain(sbcmds.into_iter());
+        .chain(parameters)
+        .chain(examples)
+        .chain(sbcmds)
+        .chain(groups);
 
-    let embed = serenity::CreateEmbed::default()
+    let embed = style_embed(serenity::CreateEmbed::default(), &config)
         .description(description)
         .fields(fields);
 
@@ -18,6 +18,13 @@ use crate::serenity_prelude as serenity;
 /// ```
 pub fn create_application_commands<U, E>(
     commands: &[crate::Command<U, E>],
+) -> Vec<serenity::CreateCommand> {
+    create_selected_application_commands(commands)
+}
+
+/// Like [`create_application_commands`], for any selection of top-level commands
+pub(crate) fn create_selected_application_commands<'a, U: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
 ) -> Vec<serenity::CreateCommand> {
     /// We decided to extract context menu commands recursively, despite the subcommand hierarchy
     /// not being preserved. Because it's more confusing to just silently discard context menu
@@ -27,19 +34,20 @@ pub fn create_application_commands<U, E>(
         builder: &mut Vec<serenity::CreateCommand>,
         command: &crate::Command<U, E>,
     ) {
-        if let Some(context_menu_command) = command.create_as_context_menu_command() {
-            builder.push(context_menu_command);
-        }
+        builder.extend(command.create_as_context_menu_commands());
         for subcommand in &command.subcommands {
             recursively_add_context_menu_commands(builder, subcommand);
         }
     }
 
-    let mut commands_builder = Vec::with_capacity(commands.len());
+    let mut commands_builder = Vec::new();
     for command in commands {
         if let Some(slash_command) = command.create_as_slash_command() {
             commands_builder.push(slash_command);
         }
+        if let Some(entry_point_command) = command.create_as_entry_point_command() {
+            commands_builder.push(entry_point_command);
+        }
         recursively_add_context_menu_commands(&mut commands_builder, command);
     }
     commands_builder
@@ -72,6 +80,129 @@ pub async fn register_in_guild<U, E>(
     Ok(())
 }
 
+/// Where to register application commands, see [`crate::FrameworkBuilder::auto_register`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegisterTarget {
+    /// Register as global commands, see [`register_globally`]
+    Global,
+    /// Register as guild-specific commands in the given guild, see [`register_in_guild`]
+    Guild(serenity::GuildId),
+    /// Register in the guilds listed in the `GUILD_IDS` environment variable (comma-separated),
+    /// or globally if the variable isn't set.
+    ///
+    /// Useful to register instantly in a test guild during development, while registering globally
+    /// in production.
+    GuildsFromEnv,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl RegisterTarget {
+    /// Registers the given commands at this target. Existing commands at the target that aren't
+    /// in `commands` are removed.
+    ///
+    /// The registered commands are fetched first, and left alone if they already match `commands`,
+    /// to save the bulk overwrite on every startup.
+    pub async fn register<U, E>(
+        &self,
+        http: impl AsRef<serenity::Http>,
+        commands: &[crate::Command<U, E>],
+    ) -> Result<(), serenity::Error> {
+        self.set_commands(http, create_application_commands(commands))
+            .await
+    }
+
+    /// Like [`Self::register`], with the commands already converted into builders
+    pub(crate) async fn set_commands(
+        &self,
+        http: impl AsRef<serenity::Http>,
+        builders: Vec<serenity::CreateCommand>,
+    ) -> Result<(), serenity::Error> {
+        let http = http.as_ref();
+        match self {
+            Self::Global => set_global_commands_if_changed(http, builders).await?,
+            Self::Guild(guild_id) => {
+                set_guild_commands_if_changed(http, *guild_id, builders).await?
+            }
+            Self::GuildsFromEnv => {
+                let Ok(guild_ids) = std::env::var("GUILD_IDS") else {
+                    return set_global_commands_if_changed(http, builders).await;
+                };
+                for guild_id in guild_ids.split(',').map(str::trim) {
+                    match guild_id.parse::<serenity::GuildId>() {
+                        Ok(guild_id) => {
+                            set_guild_commands_if_changed(http, guild_id, builders.clone()).await?;
+                        }
+                        Err(_) => tracing::warn!("invalid guild ID in GUILD_IDS: {:?}", guild_id),
+                    }
+                }
+            }
+            Self::__NonExhaustive => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+/// Whether the registered commands already match the builders, so that overwriting them wouldn't
+/// change anything. False if the commands can't be compared
+///
+/// Contexts only apply to global commands, so they're ignored for guild commands.
+fn commands_up_to_date(
+    registered: &[serenity::Command],
+    builders: &[serenity::CreateCommand],
+    global: bool,
+) -> bool {
+    let registered = registered
+        .iter()
+        .map(CommandShape::from_registered)
+        .collect::<Result<Vec<_>, _>>();
+    let new = builders
+        .iter()
+        .map(CommandShape::from_builder)
+        .collect::<Result<Vec<_>, _>>();
+    match (registered, new) {
+        (Ok(registered), Ok(mut new)) => {
+            if !global {
+                for shape in &mut new {
+                    shape.contexts = None;
+                    shape.integration_types = None;
+                }
+            }
+            diff_commands(&registered, &new).is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Replaces the global commands with the builders, unless they're already up to date
+async fn set_global_commands_if_changed(
+    http: &serenity::Http,
+    builders: Vec<serenity::CreateCommand>,
+) -> Result<(), serenity::Error> {
+    let registered = serenity::Command::get_global_commands_with_localizations(http).await?;
+    if commands_up_to_date(&registered, &builders, true) {
+        tracing::debug!("global commands are up to date, not registering");
+        return Ok(());
+    }
+    serenity::Command::set_global_commands(http, builders).await?;
+    Ok(())
+}
+
+/// Replaces the guild's commands with the builders, unless they're already up to date
+async fn set_guild_commands_if_changed(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    builders: Vec<serenity::CreateCommand>,
+) -> Result<(), serenity::Error> {
+    let registered = guild_id.get_commands_with_localizations(http).await?;
+    if commands_up_to_date(&registered, &builders, false) {
+        tracing::debug!("commands of guild {guild_id} are up to date, not registering");
+        return Ok(());
+    }
+    guild_id.set_commands(http, builders).await?;
+    Ok(())
+}
+
 /// _Note: you probably want [`register_application_commands_buttons`] instead; it's easier and more
 /// powerful_
 ///
@@ -95,7 +226,7 @@ pub async fn register_application_commands<U, E>(
         return Ok(());
     }
 
-    let commands_builder = create_application_commands(&ctx.framework().options().commands);
+    let commands_builder = create_selected_application_commands(ctx.framework().commands);
     let num_commands = commands_builder.len();
 
     if global {
@@ -123,8 +254,14 @@ pub async fn register_application_commands<U, E>(
 
 /// Spawns four buttons to register or delete application commands globally or in the current guild
 ///
+/// When invoked in a guild, a select menu additionally offers to register only specific commands
+/// or categories in the guild, replacing its current commands. Handy when testing a single new
+/// command. Discord limits select menus to 25 entries; categories are listed first.
+///
 /// Upgraded version of [`register_application_commands`]
 ///
+/// To review the changes before overwriting the global commands, see [`register_diff`]
+///
 /// ![Screenshot of output](https://imgur.com/rTbTaDs.png)
 ///
 /// You probably want to use this by wrapping it in a small `register` command:
@@ -151,7 +288,7 @@ pub async fn register_application_commands<U, E>(
 pub async fn register_application_commands_buttons<U, E>(
     ctx: crate::Context<'_, U, E>,
 ) -> Result<(), serenity::Error> {
-    let create_commands = create_application_commands(&ctx.framework().options().commands);
+    let create_commands = create_selected_application_commands(ctx.framework().commands);
     let num_commands = create_commands.len();
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
@@ -179,9 +316,16 @@ pub async fn register_application_commands_buttons<U, E>(
             .emoji('🗑'),
     ]);
 
+    let mut components = vec![components];
+    if ctx.guild_id().is_some() {
+        if let Some(select_menu) = selection_menu(ctx.framework().commands) {
+            components.push(select_menu);
+        }
+    }
+
     let builder = crate::CreateReply::default()
         .content("Choose what to do with the commands:")
-        .components(vec![components]);
+        .components(components);
 
     let reply = ctx.send(builder).await?;
 
@@ -209,6 +353,12 @@ pub async fn register_application_commands_buttons<U, E>(
         }
     };
 
+    if let Some(serenity::ComponentInteractionDataKind::StringSelect { values }) =
+        interaction.as_ref().map(|m| &m.data.kind)
+    {
+        return register_selection_in_guild(ctx, values).await;
+    }
+
     let (register, global) = match &**pressed_button_id {
         "register.global" => (true, true),
         "unregister.global" => (false, true),
@@ -263,3 +413,462 @@ pub async fn register_application_commands_buttons<U, E>(
 
     Ok(())
 }
+
+/// Prefix of select menu values that select a category, see [`selection_menu`]
+const CATEGORY_VALUE_PREFIX: &str = "category:";
+/// Prefix of select menu values that select a single command, see [`selection_menu`]
+const COMMAND_VALUE_PREFIX: &str = "command:";
+
+/// Whether this top-level command produces any application command
+fn is_application_command<U, E>(command: &crate::Command<U, E>) -> bool {
+    command.slash_action.is_some()
+        || command.context_menu_action.is_some()
+        || command.entry_point_action.is_some()
+        || command.subcommands.iter().any(is_application_command)
+}
+
+/// Builds the select menu of [`register_application_commands_buttons`] for choosing categories
+/// and commands to register in the guild. Returns None if there's nothing to choose from
+fn selection_menu<U, E>(
+    commands: &crate::ActiveCommands<'_, U, E>,
+) -> Option<serenity::CreateActionRow> {
+    /// Discord's maximum number of select menu options
+    const MAX_OPTIONS: usize = 25;
+
+    let commands = commands
+        .into_iter()
+        .filter(|command| is_application_command(command))
+        .collect::<Vec<_>>();
+    let mut categories = commands
+        .iter()
+        .filter_map(|command| command.category.as_deref())
+        .collect::<Vec<_>>();
+    categories.sort_unstable();
+    categories.dedup();
+
+    let category_options = categories.into_iter().map(|category| {
+        serenity::CreateSelectMenuOption::new(
+            format!("📁 {category}"),
+            format!("{CATEGORY_VALUE_PREFIX}{category}"),
+        )
+    });
+    let command_options = commands.iter().map(|command| {
+        let option = serenity::CreateSelectMenuOption::new(
+            &command.name,
+            format!("{COMMAND_VALUE_PREFIX}{}", command.name),
+        );
+        match &command.description {
+            Some(description) => {
+                option.description(description.chars().take(100).collect::<String>())
+            }
+            None => option,
+        }
+    });
+    let options = category_options.chain(command_options).collect::<Vec<_>>();
+    if options.is_empty() {
+        return None;
+    }
+    if options.len() > MAX_OPTIONS {
+        tracing::warn!(
+            "only the first {} of {} categories and commands can be selected for registration",
+            MAX_OPTIONS,
+            options.len()
+        );
+    }
+    let options = options.into_iter().take(MAX_OPTIONS).collect::<Vec<_>>();
+    let max_values = options.len() as u8;
+
+    let select_menu = serenity::CreateSelectMenu::new(
+        "register.guild.selection",
+        serenity::CreateSelectMenuKind::String { options },
+    )
+    .placeholder("Register only specific commands in guild...")
+    .min_values(1)
+    .max_values(max_values);
+    Some(serenity::CreateActionRow::SelectMenu(select_menu))
+}
+
+/// Replaces the guild's application commands with the commands and categories selected in
+/// [`selection_menu`]
+async fn register_selection_in_guild<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    selected: &[String],
+) -> Result<(), serenity::Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        ctx.say(":x: Must be called in guild").await?;
+        return Ok(());
+    };
+
+    let is_selected = |command: &crate::Command<U, E>| {
+        selected.iter().any(|value| {
+            if let Some(category) = value.strip_prefix(CATEGORY_VALUE_PREFIX) {
+                command.category.as_deref() == Some(category)
+            } else if let Some(name) = value.strip_prefix(COMMAND_VALUE_PREFIX) {
+                command.name == name
+            } else {
+                false
+            }
+        })
+    };
+    let create_commands = create_selected_application_commands(
+        ctx.framework()
+            .commands
+            .into_iter()
+            .filter(|command| is_selected(command)),
+    );
+    let num_commands = create_commands.len();
+
+    let start_time = std::time::Instant::now();
+    ctx.say(format!(
+        ":gear: Registering {num_commands} selected guild commands, replacing all others...",
+    ))
+    .await?;
+    guild_id.set_commands(ctx, create_commands).await?;
+
+    let time_taken = start_time.elapsed();
+    ctx.say(format!(
+        ":white_check_mark: Done! Took {}ms",
+        time_taken.as_millis()
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// The parts of an application command that [`register_diff`] compares. Both the registered
+/// [`serenity::Command`]s and the [`serenity::CreateCommand`] builders are converted into this
+#[derive(serde::Deserialize)]
+struct CommandShape {
+    /// Command name
+    name: String,
+    /// Command type, absent for slash commands in the builder
+    #[serde(rename = "type", default)]
+    kind: Option<serenity::CommandType>,
+    /// Command description, absent for context menu commands in the builder
+    #[serde(default)]
+    description: Option<String>,
+    /// Localized command names, absent or null if there are none
+    #[serde(default)]
+    name_localizations: Option<std::collections::HashMap<String, String>>,
+    /// Localized command descriptions, absent or null if there are none
+    #[serde(default)]
+    description_localizations: Option<std::collections::HashMap<String, String>>,
+    /// Parameters or subcommands
+    #[serde(default)]
+    options: Vec<serenity::CommandOption>,
+    /// Permissions required by default to see the command
+    #[serde(default)]
+    default_member_permissions: Option<serenity::Permissions>,
+    /// Whether the command is age-restricted
+    #[serde(default)]
+    nsfw: bool,
+    /// Interaction contexts, absent if Discord's default is used
+    #[serde(default)]
+    contexts: Option<Vec<serenity::InteractionContext>>,
+    /// Installation contexts, absent or empty if Discord's default is used
+    #[serde(default)]
+    integration_types: Option<Vec<serenity::InstallationContext>>,
+}
+
+impl CommandShape {
+    /// Extracts the compared parts of a registered command. The command must have been fetched
+    /// with localizations, otherwise only the localizations of one locale are included
+    #[allow(clippy::result_large_err)]
+    fn from_registered(command: &serenity::Command) -> Result<Self, serenity::Error> {
+        serenity::json::from_value(serenity::json::to_value(command)?)
+    }
+
+    /// Extracts the compared parts of a command builder by going through its JSON representation
+    #[allow(clippy::result_large_err)]
+    fn from_builder(builder: &serenity::CreateCommand) -> Result<Self, serenity::Error> {
+        serenity::json::from_value(serenity::json::to_value(builder)?)
+    }
+
+    /// How the command is displayed in the diff, e.g. `/ban` or `Ban user (user context menu)`
+    fn label(&self) -> String {
+        match self.kind.unwrap_or(serenity::CommandType::ChatInput) {
+            serenity::CommandType::ChatInput => format!("`/{}`", self.name),
+            serenity::CommandType::User => format!("`{}` (user context menu)", self.name),
+            serenity::CommandType::Message => format!("`{}` (message context menu)", self.name),
+            _ => format!("`{}`", self.name),
+        }
+    }
+
+    /// Whether both shapes describe the same registered command, regardless of content
+    fn same_command(&self, other: &Self) -> bool {
+        let kind = |shape: &Self| shape.kind.unwrap_or(serenity::CommandType::ChatInput);
+        self.name == other.name && kind(self) == kind(other)
+    }
+
+    /// Lists which command-level settings differ from the registered command `old`. Contexts that
+    /// the new command leaves to Discord's default aren't compared, because Discord reports the
+    /// resolved default for registered commands
+    fn changed_settings(&self, old: &Self) -> Vec<&'static str> {
+        /// Treats missing localizations like empty ones
+        fn localizations(
+            map: &Option<std::collections::HashMap<String, String>>,
+        ) -> Option<&std::collections::HashMap<String, String>> {
+            map.as_ref().filter(|map| !map.is_empty())
+        }
+
+        let mut changes = Vec::new();
+        if old.description.as_deref().unwrap_or("") != self.description.as_deref().unwrap_or("") {
+            changes.push("description changed");
+        }
+        if localizations(&old.name_localizations) != localizations(&self.name_localizations)
+            || localizations(&old.description_localizations)
+                != localizations(&self.description_localizations)
+        {
+            changes.push("localizations changed");
+        }
+        if old.default_member_permissions != self.default_member_permissions {
+            changes.push("default permissions changed");
+        }
+        if old.nsfw != self.nsfw {
+            changes.push("NSFW flag changed");
+        }
+        if self.contexts.is_some() && old.contexts != self.contexts {
+            changes.push("interaction contexts changed");
+        }
+        let integration_types = |shape: &Self| {
+            let mut types = shape.integration_types.clone().unwrap_or_default();
+            types.sort_unstable_by_key(|&kind| u8::from(kind));
+            types
+        };
+        if self.integration_types.is_some() && integration_types(old) != integration_types(self) {
+            changes.push("install contexts changed");
+        }
+        changes
+    }
+}
+
+/// Lists the differences between the registered commands and the commands about to be
+/// registered, one line per added, removed or changed command or parameter
+fn diff_commands(registered: &[CommandShape], new: &[CommandShape]) -> Vec<String> {
+    /// Converts an option into JSON in which equal options compare equal, regardless of whether
+    /// they came from Discord or from a builder
+    fn normalized(option: &serenity::CommandOption) -> serde_json::Value {
+        /// Removes null and empty object fields like missing localizations, and turns all numbers
+        /// into floats, because Discord may return `1` for a minimum value set as `1.0`
+        fn normalize(value: &mut serde_json::Value) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    map.retain(|_, field| {
+                        !field.is_null() && field.as_object().map_or(true, |o| !o.is_empty())
+                    });
+                    map.values_mut().for_each(normalize);
+                }
+                serde_json::Value::Array(values) => values.iter_mut().for_each(normalize),
+                serde_json::Value::Number(number) => {
+                    if let Some(float) = number.as_f64().and_then(serde_json::Number::from_f64) {
+                        *number = float;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut value = serde_json::to_value(option).unwrap_or_default();
+        normalize(&mut value);
+        value
+    }
+
+    let mut lines = Vec::new();
+    for command in new {
+        let Some(old) = registered.iter().find(|old| old.same_command(command)) else {
+            lines.push(format!("➕ {} added", command.label()));
+            continue;
+        };
+
+        let mut changes = command
+            .changed_settings(old)
+            .into_iter()
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        for option in &command.options {
+            match old.options.iter().find(|old| old.name == option.name) {
+                None => changes.push(format!("parameter `{}` added", option.name)),
+                Some(old) if normalized(old) != normalized(option) => {
+                    changes.push(format!("parameter `{}` changed", option.name));
+                }
+                Some(_) => {}
+            }
+        }
+        for old_option in &old.options {
+            if !command.options.iter().any(|o| o.name == old_option.name) {
+                changes.push(format!("parameter `{}` removed", old_option.name));
+            }
+        }
+        let old_order = old.options.iter().map(|o| &o.name);
+        let new_order = command.options.iter().map(|o| &o.name);
+        if changes.is_empty() && !old_order.eq(new_order) {
+            changes.push("parameters reordered".to_owned());
+        }
+
+        if !changes.is_empty() {
+            lines.push(format!("✏️ {}: {}", command.label(), changes.join(", ")));
+        }
+    }
+    for old in registered {
+        if !new.iter().any(|command| command.same_command(old)) {
+            lines.push(format!("➖ {} removed", old.label()));
+        }
+    }
+    lines
+}
+
+/// Shows what registering the commands globally would change, and only registers them once the
+/// invoking bot owner confirms.
+///
+/// Fetches the currently registered global commands from Discord and compares them against
+/// [`create_application_commands`], listing added and removed commands, changed command settings
+/// like descriptions, localizations, default permissions and contexts, and added, removed or
+/// changed parameters in an embed. Unlike [`register_application_commands_buttons`], which
+/// overwrites unconditionally, nothing is sent to Discord if there are no changes or the
+/// confirmation is cancelled.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, owners_only)]
+/// pub async fn register(ctx: Context<'_>) -> Result<(), Error> {
+///     poise::builtins::register_diff(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn register_diff<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+) -> Result<(), serenity::Error> {
+    /// Discord's maximum length of an embed description
+    const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+    let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
+    if !is_bot_owner {
+        ctx.say("Can only be used by bot owner").await?;
+        return Ok(());
+    }
+
+    let create_commands = create_selected_application_commands(ctx.framework().commands);
+    let new = create_commands
+        .iter()
+        .map(CommandShape::from_builder)
+        .collect::<Result<Vec<_>, _>>()?;
+    let registered = serenity::Command::get_global_commands_with_localizations(ctx)
+        .await?
+        .iter()
+        .map(CommandShape::from_registered)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lines = diff_commands(&registered, &new);
+    if lines.is_empty() {
+        ctx.say(":white_check_mark: Global commands are already up to date")
+            .await?;
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let omitted = format!("\n... and {} more", lines.len() - i);
+        if description.len() + line.len() + 1 + omitted.len() > MAX_DESCRIPTION_LENGTH {
+            description += &omitted;
+            break;
+        }
+        description += line;
+        description.push('\n');
+    }
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("Pending global command changes ({})", lines.len()))
+        .description(description);
+    ctx.send(crate::CreateReply::default().embed(embed)).await?;
+
+    let options = super::ConfirmOptions {
+        confirm_label: "Register".into(),
+        ..Default::default()
+    };
+    let prompt = format!(
+        "Register {} global commands with these changes?",
+        create_commands.len()
+    );
+    if !super::confirm(ctx, prompt, options).await? {
+        return Ok(());
+    }
+
+    let start_time = std::time::Instant::now();
+    serenity::Command::set_global_commands(ctx, create_commands).await?;
+    ctx.say(format!(
+        ":white_check_mark: Done! Took {}ms",
+        start_time.elapsed().as_millis()
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_diff_commands() {
+    use serenity::json::json;
+
+    let builder = serenity::CreateCommand::new("roll")
+        .description("Roll a die")
+        .add_option(
+            serenity::CreateCommandOption::new(
+                serenity::CommandOptionType::Number,
+                "sides",
+                "Sides",
+            )
+            .min_number_value(1.0),
+        );
+    // As returned by Discord: integral floats become integers and unset fields are null
+    let registered = json!({
+        "id": "1",
+        "application_id": "1",
+        "version": "1",
+        "type": 1,
+        "name": "roll",
+        "name_localizations": null,
+        "description": "Roll a die",
+        "description_localizations": null,
+        "options": [{
+            "type": 10,
+            "name": "sides",
+            "description": "Sides",
+            "min_value": 1,
+        }],
+        "default_member_permissions": null,
+        "nsfw": false,
+        "integration_types": [0],
+        "contexts": null,
+    });
+    let registered_command: serenity::Command = serenity::json::from_value(registered).unwrap();
+    let registered = [CommandShape::from_registered(&registered_command).unwrap()];
+
+    let new = [CommandShape::from_builder(&builder).unwrap()];
+    assert_eq!(diff_commands(&registered, &new), Vec::<String>::new());
+
+    // Contexts only apply to global commands
+    let guild_only = [builder
+        .clone()
+        .contexts(vec![serenity::InteractionContext::Guild])];
+    let registered_commands = [registered_command];
+    assert!(commands_up_to_date(
+        &registered_commands,
+        &guild_only,
+        false
+    ));
+    assert!(!commands_up_to_date(
+        &registered_commands,
+        &guild_only,
+        true
+    ));
+
+    let builder = builder
+        .nsfw(true)
+        .name_localized("de", "würfeln")
+        .integration_types(vec![serenity::InstallationContext::User]);
+    let new = [CommandShape::from_builder(&builder).unwrap()];
+    assert_eq!(
+        diff_commands(&registered, &new),
+        ["✏️ `/roll`: localizations changed, NSFW flag changed, install contexts changed"]
+    );
+}
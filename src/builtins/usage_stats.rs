@@ -0,0 +1,72 @@
+//! Contains a built-in command that displays command usage statistics
+
+use crate::serenity_prelude as serenity;
+
+/// Shows the most used commands and most active users of the last day, week and of all retained
+/// statistics, from the given [`crate::stats::UsageTracker`].
+///
+/// Users are mentioned within an embed, so they aren't pinged. In guilds, the number of
+/// invocations in the current guild is shown too.
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, Data, Error>;
+/// struct Data {
+///     // Also registered in FrameworkOptions::invocation_observers
+///     usage: std::sync::Arc<poise::stats::UsageTracker>,
+/// }
+///
+/// #[poise::command(prefix_command, slash_command)]
+/// pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+///     poise::builtins::usage_stats(ctx, &ctx.data().usage).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn usage_stats<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    tracker: &crate::stats::UsageTracker,
+) -> Result<(), serenity::Error> {
+    use std::fmt::Write as _;
+
+    let day = std::time::Duration::from_secs(60 * 60 * 24);
+    let windows = [
+        ("Last day", Some(day)),
+        ("Last week", Some(day * 7)),
+        ("All time", None),
+    ];
+
+    let mut embed = serenity::CreateEmbed::new().title("Command usage");
+    for (name, window) in windows {
+        let mut value = format!("{} invocations\n", tracker.total(window));
+        for (command, count) in tracker.commands(window).into_iter().take(5) {
+            let _ = writeln!(value, "`{}`: {}", command, count);
+        }
+        embed = embed.field(name, value, true);
+    }
+
+    let mut users = String::new();
+    for (user_id, count) in tracker.users(None).into_iter().take(5) {
+        let _ = writeln!(users, "<@{}>: {}", user_id, count);
+    }
+    if !users.is_empty() {
+        embed = embed.field("Most active users", users, false);
+    }
+
+    if let Some(guild_id) = ctx.guild_id() {
+        let count = tracker
+            .guilds(None)
+            .into_iter()
+            .find(|&(id, _)| id == guild_id)
+            .map_or(0, |(_, count)| count);
+        embed = embed.field("This server", format!("{} invocations", count), false);
+    }
+
+    let days = tracker.retention().as_secs() / day.as_secs();
+    embed = embed.footer(serenity::CreateEmbedFooter::new(format!(
+        "Statistics are kept for {} days",
+        days
+    )));
+
+    ctx.send(crate::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
@@ -18,8 +18,91 @@ pub trait ChoiceParameter: Sized {
     /// Returns the non-localized name of this choice
     fn name(&self) -> &'static str;
 
+    /// Returns an instance of [`Self`] corresponding to the given value, as sent by Discord.
+    ///
+    /// By default, choices have integer values that correspond to their index in [`Self::list()`].
+    fn from_value(value: &crate::ChoiceValue) -> Option<Self> {
+        match value {
+            crate::ChoiceValue::Integer(index) => Self::from_index(usize::try_from(*index).ok()?),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of this choice, as sent by Discord. Stable across reorderings of the
+    /// choices if explicit values are set.
+    fn to_value(&self) -> crate::ChoiceValue {
+        let index = Self::list()
+            .iter()
+            .position(|choice| choice.name == self.name())
+            .expect("name() must return the name of a choice in list()");
+        crate::ChoiceValue::Integer(index as _)
+    }
+
     /// Returns the localized name for the given locale, if one is set
     fn localized_name(&self, locale: &str) -> Option<&'static str>;
+
+    /// Returns the localized name for the first locale in the fallback chain of `locale` that has
+    /// one, or the non-localized name otherwise
+    fn resolve_localized_name(
+        &self,
+        locale: &str,
+        fallbacks: &crate::localization::LocaleFallbacks,
+    ) -> &'static str {
+        fallbacks
+            .chain(locale)
+            .into_iter()
+            .find_map(|locale| self.localized_name(locale))
+            .unwrap_or_else(|| self.name())
+    }
+}
+
+/// Parses a choice from its name in any locale, for prefix invocations where the user's locale is
+/// not known
+fn from_any_localized_name<T: ChoiceParameter>(name: &str) -> Option<T> {
+    let index = T::list().iter().position(|choice| {
+        choice
+            .localizations
+            .values()
+            .any(|localized_name| localized_name.eq_ignore_ascii_case(name))
+    })?;
+    T::from_index(index)
+}
+
+/// Number of single-character insertions, deletions and substitutions to turn `a` into `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Builds the error for a prefix argument that matches no choice, with the closest choice name as
+/// a suggestion if it's similar enough to the input
+fn invalid_choice<T: ChoiceParameter>(input: &str) -> crate::InvalidChoice {
+    let valid_choices = T::list()
+        .into_iter()
+        .map(|choice| choice.name)
+        .collect::<Vec<_>>();
+    let input_lowercase = input.to_lowercase();
+    let closest_match = valid_choices
+        .iter()
+        .map(|name| (edit_distance(&input_lowercase, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= (input.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.clone());
+    crate::InvalidChoice {
+        valid_choices,
+        closest_match,
+        __non_exhaustive: (),
+    }
 }
 
 #[async_trait::async_trait]
@@ -32,22 +115,39 @@ impl<T: ChoiceParameter> crate::SlashArgument for T {
         #[allow(unused_imports)]
         use ::serenity::json::*; // Required for simd-json :|
 
-        let choice_key = match value {
-            serenity::ResolvedValue::Integer(int) => *int as u64,
+        let choice_value = match value {
+            serenity::ResolvedValue::Integer(int) => match i32::try_from(*int) {
+                Ok(int) => crate::ChoiceValue::Integer(int),
+                Err(_) => {
+                    return Err(crate::SlashArgError::CommandStructureMismatch {
+                        description: "out of bounds choice key",
+                    })
+                }
+            },
+            serenity::ResolvedValue::String(string) => {
+                crate::ChoiceValue::String(string.to_string())
+            }
             _ => {
                 return Err(crate::SlashArgError::CommandStructureMismatch {
-                    description: "expected u64",
+                    description: "expected integer or string choice value",
                 })
             }
         };
 
-        Self::from_index(choice_key as _).ok_or(crate::SlashArgError::CommandStructureMismatch {
+        Self::from_value(&choice_value).ok_or(crate::SlashArgError::CommandStructureMismatch {
             description: "out of bounds choice key",
         })
     }
 
     fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
-        builder.kind(serenity::CommandOptionType::Integer)
+        let has_string_values = Self::list()
+            .iter()
+            .any(|choice| matches!(choice.value, Some(crate::ChoiceValue::String(_))));
+        builder.kind(if has_string_values {
+            serenity::CommandOptionType::String
+        } else {
+            serenity::CommandOptionType::Integer
+        })
     }
 
     fn choices() -> Vec<crate::CommandParameterChoice> {
@@ -67,15 +167,23 @@ impl<'a, T: ChoiceParameter> crate::PopArgument<'a> for T {
         let (args, attachment_index, s) =
             crate::pop_prefix_argument!(String, args, attachment_index, ctx, msg).await?;
 
-        Ok((
-            args,
-            attachment_index,
-            Self::from_name(&s).ok_or((
-                Box::new(crate::InvalidChoice {
-                    __non_exhaustive: (),
-                }) as Box<dyn std::error::Error + Send + Sync>,
-                Some(s),
-            ))?,
-        ))
+        match Self::from_name(&s).or_else(|| from_any_localized_name(&s)) {
+            Some(choice) => Ok((args, attachment_index, choice)),
+            None => Err((Box::new(invalid_choice::<Self>(&s)), Some(s))),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_edit_distance() {
+    for &(a, b, distance) in &[
+        ("pizza", "pizza", 0),
+        ("pizzza", "pizza", 1),
+        ("piza", "pizza", 1),
+        ("pasta", "pizza", 3),
+        ("", "abc", 3),
+    ] {
+        assert_eq!(edit_distance(a, b), distance);
     }
 }
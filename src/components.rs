@@ -0,0 +1,231 @@
+//! Higher-level abstractions over message components, like [`SelectMenuPrompt`]
+
+use crate::serenity_prelude as serenity;
+
+/// Custom ID of the select menu. The interaction is collected on the prompt message, so it doesn't
+/// need to be unique across messages
+const SELECT_MENU: &str = "poise_select_menu";
+
+/// A type that can be selected in a [`SelectMenuPrompt`]: [`String`] for string select menus,
+/// [`serenity::UserId`], [`serenity::RoleId`] and [`serenity::ChannelId`] for the respective
+/// select menus, and [`serenity::GenericId`] for mentionable (user or role) select menus
+pub trait SelectMenuValue: Sized {
+    /// Extracts the selected values from the interaction data. Returns None if the data belongs to
+    /// a different kind of select menu
+    fn extract(data: serenity::ComponentInteractionDataKind) -> Option<Vec<Self>>;
+}
+
+/// Implements [`SelectMenuValue`] by extracting the given [`serenity::ComponentInteractionDataKind`]
+/// variant
+macro_rules! impl_select_menu_value {
+    ($($type:ty => $variant:ident),* $(,)?) => { $(
+        impl SelectMenuValue for $type {
+            fn extract(data: serenity::ComponentInteractionDataKind) -> Option<Vec<Self>> {
+                match data {
+                    serenity::ComponentInteractionDataKind::$variant { values } => Some(values),
+                    _ => None,
+                }
+            }
+        }
+    )* };
+}
+
+impl_select_menu_value! {
+    String => StringSelect,
+    serenity::UserId => UserSelect,
+    serenity::RoleId => RoleSelect,
+    serenity::ChannelId => ChannelSelect,
+    serenity::GenericId => MentionableSelect,
+}
+
+/// Sends a message with a select menu and waits for the invoking user to make a selection, which
+/// is returned as Rust values. Works for both prefix and application commands.
+///
+/// Create one via [`Self::string`], [`Self::users`], [`Self::roles`], [`Self::channels`] or
+/// [`Self::mentionables`]. Once the user made a selection or the timeout expired, the select menu
+/// is removed from the message.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let users = poise::components::SelectMenuPrompt::users()
+///     .content("Who do you want to invite?")
+///     .max_values(5)
+///     .run(ctx)
+///     .await?;
+/// if let Some(users) = users {
+///     ctx.say(format!("Inviting {} users", users.len())).await?;
+/// }
+///
+/// let options = vec![
+///     serenity::CreateSelectMenuOption::new("Small", "s"),
+///     serenity::CreateSelectMenuOption::new("Large", "l"),
+/// ];
+/// let size = poise::components::SelectMenuPrompt::string(options)
+///     .placeholder("Pick a size")
+///     .run(ctx)
+///     .await?;
+/// # Ok(()) }
+/// ```
+#[must_use]
+pub struct SelectMenuPrompt<T> {
+    /// What kind of select menu to send
+    kind: serenity::CreateSelectMenuKind,
+    /// Content of the prompt message
+    content: String,
+    /// Placeholder text of the select menu
+    placeholder: Option<String>,
+    /// Minimum number of values to select
+    min_values: u8,
+    /// Maximum number of values to select
+    max_values: u8,
+    /// How long to wait for a selection
+    timeout: std::time::Duration,
+    /// Whether to send the prompt as an ephemeral message
+    ephemeral: bool,
+    /// The type of the selected values
+    _value: std::marker::PhantomData<fn() -> T>,
+}
+
+impl SelectMenuPrompt<String> {
+    /// Prompts for a selection from the given options. The values of the selected options are
+    /// returned
+    pub fn string(options: Vec<serenity::CreateSelectMenuOption>) -> Self {
+        Self::new(serenity::CreateSelectMenuKind::String { options })
+    }
+}
+
+impl SelectMenuPrompt<serenity::UserId> {
+    /// Prompts for a selection of users
+    pub fn users() -> Self {
+        Self::new(serenity::CreateSelectMenuKind::User {
+            default_users: None,
+        })
+    }
+}
+
+impl SelectMenuPrompt<serenity::RoleId> {
+    /// Prompts for a selection of roles
+    pub fn roles() -> Self {
+        Self::new(serenity::CreateSelectMenuKind::Role {
+            default_roles: None,
+        })
+    }
+}
+
+impl SelectMenuPrompt<serenity::ChannelId> {
+    /// Prompts for a selection of channels. If `channel_types` is given, only channels of these
+    /// types can be selected
+    pub fn channels(channel_types: Option<Vec<serenity::ChannelType>>) -> Self {
+        Self::new(serenity::CreateSelectMenuKind::Channel {
+            channel_types,
+            default_channels: None,
+        })
+    }
+}
+
+impl SelectMenuPrompt<serenity::GenericId> {
+    /// Prompts for a selection of users and roles
+    pub fn mentionables() -> Self {
+        Self::new(serenity::CreateSelectMenuKind::Mentionable {
+            default_users: None,
+            default_roles: None,
+        })
+    }
+}
+
+impl<T: SelectMenuValue> SelectMenuPrompt<T> {
+    /// Creates a prompt for the given kind of select menu, which must match `T`
+    fn new(kind: serenity::CreateSelectMenuKind) -> Self {
+        Self {
+            kind,
+            content: "Please make a selection".into(),
+            placeholder: None,
+            min_values: 1,
+            max_values: 1,
+            timeout: std::time::Duration::from_secs(60 * 5),
+            ephemeral: false,
+            _value: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the content of the prompt message
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Sets the placeholder text shown in the select menu while nothing is selected
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Sets the minimum number of values the user must select. 1 by default
+    pub fn min_values(mut self, min_values: u8) -> Self {
+        self.min_values = min_values;
+        self
+    }
+
+    /// Sets the maximum number of values the user can select. 1 by default
+    pub fn max_values(mut self, max_values: u8) -> Self {
+        self.max_values = max_values;
+        self
+    }
+
+    /// Sets how long to wait for a selection. 5 minutes by default
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether the prompt is sent as an ephemeral message (slash-only). False by default
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Sends the prompt and waits for the invoking user to make a selection.
+    ///
+    /// Returns None if the timeout expired before a selection was made.
+    pub async fn run<U: Send + Sync, E>(
+        self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Result<Option<Vec<T>>, serenity::Error> {
+        let mut menu = serenity::CreateSelectMenu::new(SELECT_MENU, self.kind)
+            .min_values(self.min_values)
+            .max_values(self.max_values);
+        if let Some(placeholder) = self.placeholder {
+            menu = menu.placeholder(placeholder);
+        }
+        let reply = crate::CreateReply::default()
+            .content(self.content.clone())
+            .components(vec![serenity::CreateActionRow::SelectMenu(menu)])
+            .ephemeral(self.ephemeral);
+        let handle = ctx.send(reply).await?;
+
+        let Some(interaction) = handle
+            .await_component_interaction(ctx, self.timeout)
+            .await?
+        else {
+            let reply = crate::CreateReply::default()
+                .content(self.content)
+                .components(Vec::new());
+            handle.edit(ctx, reply).await?;
+            return Ok(None);
+        };
+
+        let response = serenity::CreateInteractionResponseMessage::new().components(Vec::new());
+        interaction
+            .create_response(
+                ctx,
+                serenity::CreateInteractionResponse::UpdateMessage(response),
+            )
+            .await?;
+
+        match T::extract(interaction.data.kind) {
+            Some(values) => Ok(Some(values)),
+            None => Err(serenity::Error::Other("unexpected select menu kind")),
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use crate::serenity_prelude as serenity;
 // I usually don't really do imports, but these are very convenient
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Subset of [`crate::Context`] so that [`Cooldowns`] can be used without requiring a full [Context](`crate::Context`)
 /// (ie from within an `event_handler`)
@@ -30,10 +30,106 @@ pub struct CooldownConfig {
     pub channel: Option<Duration>,
     /// This cooldown operates on a per-member basis
     pub member: Option<Duration>,
+    /// If set and the remaining cooldown is at most this long, the invocation is delayed until the
+    /// cooldown expires instead of failing with [`crate::FrameworkError::CooldownHit`].
+    ///
+    /// Application commands are deferred before waiting, because Discord only accepts an initial
+    /// response within three seconds. The eventual response becomes an edit of the deferred one.
+    ///
+    /// If set, the cooldown starts as soon as an invocation passes the cooldown check instead of
+    /// after argument parsing, so that only one of the waiting invocations runs per cooldown
+    /// period. Invocations that then fail argument parsing still start the cooldown.
+    pub wait_threshold: Option<Duration>,
+    /// Maximum number of invocations that may wait for a cooldown at once, per
+    /// [`CooldownContext`]. Further invocations fail with [`crate::FrameworkError::CooldownHit`].
+    ///
+    /// Waiting invocations are counted per process in [`crate::Command::cooldowns`], even if
+    /// [`crate::FrameworkOptions::cooldown_store`] is set.
+    ///
+    /// None (the default) means unlimited. Only relevant with [`Self::wait_threshold`].
+    pub max_waiting: Option<u32>,
+    /// Alternative cooldowns for users with certain roles, for example shorter cooldowns for
+    /// supporters. The cooldowns of the first role the user has are used instead of the ones in
+    /// this configuration.
+    ///
+    /// The framework picks the tier from the invoker's roles. When using [`CooldownTracker`]
+    /// directly, pass the configuration returned by [`Self::for_roles`].
+    pub role_tiers: Vec<(serenity::RoleId, RoleCooldowns)>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
+impl CooldownConfig {
+    /// Returns this configuration with the cooldowns of the first [role tier](Self::role_tiers)
+    /// matching the given roles, if any
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// use poise::serenity_prelude::RoleId;
+    ///
+    /// let config = poise::CooldownConfig {
+    ///     user: Some(Duration::from_secs(60)),
+    ///     role_tiers: vec![(
+    ///         RoleId::new(1),
+    ///         poise::RoleCooldowns {
+    ///             user: Some(Duration::from_secs(10)),
+    ///             ..Default::default()
+    ///         },
+    ///     )],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.for_roles(&[RoleId::new(1)]).user, Some(Duration::from_secs(10)));
+    /// assert_eq!(config.for_roles(&[RoleId::new(2)]).user, Some(Duration::from_secs(60)));
+    /// ```
+    pub fn for_roles(&self, role_ids: &[serenity::RoleId]) -> Self {
+        let Some((_, tier)) = self
+            .role_tiers
+            .iter()
+            .find(|(role_id, _)| role_ids.contains(role_id))
+        else {
+            return self.clone();
+        };
+        Self {
+            global: tier.global,
+            user: tier.user,
+            guild: tier.guild,
+            channel: tier.channel,
+            member: tier.member,
+            wait_threshold: self.wait_threshold,
+            max_waiting: self.max_waiting,
+            role_tiers: Vec::new(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Cooldowns for users with a certain role, see [`CooldownConfig::role_tiers`]
+#[derive(Default, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct RoleCooldowns {
+    /// Replaces [`CooldownConfig::global`]
+    pub global: Option<Duration>,
+    /// Replaces [`CooldownConfig::user`]
+    pub user: Option<Duration>,
+    /// Replaces [`CooldownConfig::guild`]
+    pub guild: Option<Duration>,
+    /// Replaces [`CooldownConfig::channel`]
+    pub channel: Option<Duration>,
+    /// Replaces [`CooldownConfig::member`]
+    pub member: Option<Duration>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Returns the roles of the invoker in the guild, if known, to pick the
+/// [role tier](CooldownConfig::role_tiers) of a command's cooldowns
+pub(crate) fn invoker_role_ids<'a, U, E>(ctx: crate::Context<'a, U, E>) -> &'a [serenity::RoleId] {
+    let role_ids = match ctx {
+        crate::Context::Application(ctx) => ctx.interaction.member.as_ref().map(|m| &m.roles),
+        crate::Context::Prefix(ctx) => ctx.msg.member.as_ref().map(|m| &m.roles),
+    };
+    role_ids.map_or(&[], |role_ids| role_ids)
+}
+
 /// Tracks all types of cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[poise::command]` automatically generates a
@@ -50,11 +146,14 @@ pub struct CooldownTracker {
     channel_invocations: HashMap<serenity::ChannelId, Instant>,
     /// Stores the timestamps of the last invocation per member (user and guild)
     member_invocations: HashMap<(serenity::UserId, serenity::GuildId), Instant>,
+    /// Stores the number of invocations currently waiting for a cooldown to expire
+    waiting: HashMap<CooldownContext, u32>,
 }
 
 /// Possible types of command cooldowns.
 ///
 /// Currently used for [CooldownTracker::set_last_invocation]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum CooldownType {
     /// A global cooldown that applies to all users, channels, and guilds.
@@ -81,6 +180,7 @@ impl CooldownTracker {
             guild_invocations: HashMap::new(),
             channel_invocations: HashMap::new(),
             member_invocations: HashMap::new(),
+            waiting: HashMap::new(),
         }
     }
 
@@ -164,6 +264,436 @@ impl CooldownTracker {
     }
 }
 
+/// Persistent storage for cooldowns, for example in redis or a database, so that cooldowns survive
+/// restarts. See [`crate::FrameworkOptions::cooldown_store`].
+///
+/// Timestamps are stored as [`SystemTime`], since [`Instant`]s are meaningless after a restart.
+/// Implementors only need to provide [`Self::get`] and [`Self::set`].
+#[async_trait::async_trait]
+pub trait CooldownStore: Send + Sync {
+    /// Returns the time of the last invocation of the command in the given cooldown bucket, if
+    /// any. Errors should be logged and treated as no invocation
+    async fn get(&self, command: &str, bucket: CooldownType) -> Option<SystemTime>;
+
+    /// Stores the time of the last invocation of the command in the given cooldown bucket. The
+    /// entry is no longer needed after the cooldown has passed, so it may be expired after that
+    async fn set(
+        &self,
+        command: &str,
+        bucket: CooldownType,
+        invoked_at: SystemTime,
+        cooldown: Duration,
+    );
+
+    /// Like [`CooldownTracker::remaining_cooldown`], but for the cooldowns of the command stored
+    /// in this store
+    async fn remaining_cooldown(
+        &self,
+        command: &str,
+        ctx: &CooldownContext,
+        cooldown_durations: &CooldownConfig,
+    ) -> Option<Duration> {
+        let mut remaining = None;
+        for (cooldown, bucket) in configured_buckets(ctx, cooldown_durations) {
+            let Some(invoked_at) = self.get(command, bucket).await else {
+                continue;
+            };
+            let duration_since = SystemTime::now()
+                .duration_since(invoked_at)
+                .unwrap_or_default();
+            if let Some(cooldown_left) = cooldown.checked_sub(duration_since) {
+                remaining = remaining.max(Some(cooldown_left));
+            }
+        }
+        remaining
+    }
+
+    /// Like [`CooldownTracker::start_cooldown`], but stores the cooldowns of the command in this
+    /// store. Only buckets with a configured cooldown are stored
+    async fn start_cooldown(
+        &self,
+        command: &str,
+        ctx: &CooldownContext,
+        cooldown_durations: &CooldownConfig,
+    ) {
+        let now = SystemTime::now();
+        for (cooldown, bucket) in configured_buckets(ctx, cooldown_durations) {
+            self.set(command, bucket, now, cooldown).await;
+        }
+    }
+}
+
+/// Returns the cooldown buckets applying to the context which have a cooldown configured, with
+/// their cooldown
+fn configured_buckets(
+    ctx: &CooldownContext,
+    cooldown_durations: &CooldownConfig,
+) -> Vec<(Duration, CooldownType)> {
+    let mut buckets = vec![
+        (cooldown_durations.global, CooldownType::Global),
+        (cooldown_durations.user, CooldownType::User(ctx.user_id)),
+        (
+            cooldown_durations.channel,
+            CooldownType::Channel(ctx.channel_id),
+        ),
+    ];
+    if let Some(guild_id) = ctx.guild_id {
+        buckets.push((cooldown_durations.guild, CooldownType::Guild(guild_id)));
+        buckets.push((
+            cooldown_durations.member,
+            CooldownType::Member((ctx.user_id, guild_id)),
+        ));
+    }
+    buckets
+        .into_iter()
+        .filter_map(|(cooldown, bucket)| Some((cooldown?, bucket)))
+        .collect()
+}
+
+/// [`CooldownStore`] that keeps cooldowns in memory. Mostly useful for testing, or as a starting
+/// point for custom stores: cooldowns are lost on restart, like with [`CooldownTracker`]
+///
+/// ```rust
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// use poise::CooldownStore as _;
+/// let store = poise::MemoryCooldownStore::default();
+/// let ctx = poise::CooldownContext::default();
+/// let config = poise::CooldownConfig {
+///     user: Some(std::time::Duration::from_secs(60)),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(store.remaining_cooldown("ping", &ctx, &config).await, None);
+/// store.start_cooldown("ping", &ctx, &config).await;
+/// assert!(store.remaining_cooldown("ping", &ctx, &config).await.is_some());
+/// assert_eq!(store.remaining_cooldown("pong", &ctx, &config).await, None);
+/// # });
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryCooldownStore {
+    /// Time of the last invocation per command and cooldown bucket
+    invocations: parking_lot::Mutex<HashMap<(String, CooldownType), SystemTime>>,
+}
+
+#[async_trait::async_trait]
+impl CooldownStore for MemoryCooldownStore {
+    async fn get(&self, command: &str, bucket: CooldownType) -> Option<SystemTime> {
+        self.invocations
+            .lock()
+            .get(&(command.to_owned(), bucket))
+            .copied()
+    }
+
+    async fn set(
+        &self,
+        command: &str,
+        bucket: CooldownType,
+        invoked_at: SystemTime,
+        _cooldown: Duration,
+    ) {
+        self.invocations
+            .lock()
+            .insert((command.to_owned(), bucket), invoked_at);
+    }
+}
+
+/// Starts the cooldowns of the invoked command, in [`crate::FrameworkOptions::cooldown_store`] if
+/// set, or else in [`crate::Command::cooldowns`].
+///
+/// Called automatically after argument parsing, unless
+/// [`crate::FrameworkOptions::manual_cooldowns`] is set.
+pub async fn start_cooldown<U, E>(ctx: crate::Context<'_, U, E>) {
+    let cooldown_context = ctx.cooldown_context();
+    let command = ctx.command();
+    match &ctx.framework().options().cooldown_store {
+        Some(store) => {
+            let config = command
+                .cooldown_config
+                .read()
+                .unwrap()
+                .for_roles(invoker_role_ids(ctx));
+            store
+                .start_cooldown(&command.qualified_name, &cooldown_context, &config)
+                .await;
+        }
+        None => command
+            .cooldowns
+            .lock()
+            .unwrap()
+            .start_cooldown(cooldown_context),
+    }
+}
+
+/// Registration of an invocation waiting for a cooldown to expire, see
+/// [`CooldownConfig::wait_threshold`]. Unregisters itself on drop
+pub(crate) struct CooldownWaiter<'a> {
+    /// Cooldown tracker of the command the invocation waits for
+    tracker: &'a std::sync::Mutex<CooldownTracker>,
+    /// Context of the waiting invocation
+    ctx: CooldownContext,
+}
+
+impl<'a> CooldownWaiter<'a> {
+    /// Registers a waiting invocation, or returns None if [`CooldownConfig::max_waiting`]
+    /// invocations are already waiting
+    pub(crate) fn register(
+        tracker: &'a std::sync::Mutex<CooldownTracker>,
+        ctx: CooldownContext,
+        max_waiting: Option<u32>,
+    ) -> Option<Self> {
+        let mut cooldowns = tracker.lock().unwrap();
+        let waiting = cooldowns.waiting.entry(ctx.clone()).or_insert(0);
+        if max_waiting.is_some_and(|max_waiting| *waiting >= max_waiting) {
+            return None;
+        }
+        *waiting += 1;
+        Some(Self { tracker, ctx })
+    }
+}
+
+impl Drop for CooldownWaiter<'_> {
+    fn drop(&mut self) {
+        let mut cooldowns = self.tracker.lock().unwrap();
+        if let Some(waiting) = cooldowns.waiting.get_mut(&self.ctx) {
+            *waiting -= 1;
+            if *waiting == 0 {
+                cooldowns.waiting.remove(&self.ctx);
+            }
+        }
+    }
+}
+
+/// Who a [`CooldownBucket`] limits, see [`CooldownBucket::scope`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BucketScope {
+    /// All invocations share the limit
+    Global,
+    /// Each user has their own limit
+    #[default]
+    User,
+    /// Each guild has its own limit. In DMs, each channel has its own limit
+    Guild,
+    /// Each channel has its own limit
+    Channel,
+    /// Each member has their own limit. In DMs, each user has their own limit
+    Member,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Outcome of [`CooldownBucket::acquire`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BucketOutcome {
+    /// A use was granted and may proceed right away
+    Allowed,
+    /// A use was reserved, and may proceed after the given delay
+    Delayed(Duration),
+    /// No use was granted. The next use will be available after the given duration
+    Ratelimited(Duration),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// A rate limit bucket like in serenity's standard framework: allows [`Self::limit`] uses per
+/// [`Self::time_span`], optionally with a [`Self::delay`] between uses.
+///
+/// Unlike [`CooldownConfig`], buckets are registered by name in
+/// [`crate::FrameworkOptions::buckets`] and shared by all commands that use them via
+/// [`crate::Command::bucket`] (`#[poise::command(bucket = "...")]`).
+///
+/// ```rust
+/// # use std::time::Duration;
+/// // Three uses per minute, at least five seconds apart. Users may have one invocation waiting
+/// // for the rate limit instead of being rejected
+/// let bucket = poise::CooldownBucket::new()
+///     .limit(3)
+///     .time_span(Duration::from_secs(60))
+///     .delay(Duration::from_secs(5))
+///     .scope(poise::BucketScope::User)
+///     .await_ratelimits(1);
+///
+/// let ctx = poise::CooldownContext::default();
+/// assert_eq!(bucket.acquire(&ctx, true), poise::BucketOutcome::Allowed);
+/// // Too early due to the delay, so this invocation waits
+/// assert!(matches!(bucket.acquire(&ctx, true), poise::BucketOutcome::Delayed(_)));
+/// // Another invocation is already waiting
+/// assert!(matches!(bucket.acquire(&ctx, true), poise::BucketOutcome::Ratelimited(_)));
+/// ```
+#[derive(Debug, Default)]
+pub struct CooldownBucket {
+    /// Number of uses allowed per [`Self::time_span`]. 1 by default; zero is treated as 1
+    pub limit: u32,
+    /// Time span in which at most [`Self::limit`] uses are allowed. Zero (the default) means no
+    /// limit
+    pub time_span: Duration,
+    /// Minimum time between two uses. None by default
+    pub delay: Option<Duration>,
+    /// Who the limit applies to. Per user by default
+    pub scope: BucketScope,
+    /// How many invocations per [scope](Self::scope) may wait for the rate limit to pass instead
+    /// of being rejected with [`crate::FrameworkError::CooldownHit`]. Waiting invocations are
+    /// announced with [`crate::FrameworkError::RatelimitDelayed`]. Application commands that
+    /// wait are deferred afterwards unless the error handler responded already, because Discord
+    /// only accepts an initial response within three seconds.
+    ///
+    /// Zero (the default) means invocations never wait.
+    pub await_ratelimits: u32,
+    /// Timestamps of the uses in the current time span per scope, in ascending order. Timestamps
+    /// in the future are reservations of waiting invocations
+    uses: std::sync::Mutex<HashMap<CooldownType, std::collections::VecDeque<Instant>>>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl CooldownBucket {
+    /// Creates a bucket that allows one use per [time span](Self::time_span), per user
+    pub fn new() -> Self {
+        Self {
+            limit: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets [`Self::limit`]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets [`Self::time_span`]
+    pub fn time_span(mut self, time_span: Duration) -> Self {
+        self.time_span = time_span;
+        self
+    }
+
+    /// Sets [`Self::delay`]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Sets [`Self::scope`]
+    pub fn scope(mut self, scope: BucketScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Sets [`Self::await_ratelimits`]
+    pub fn await_ratelimits(mut self, await_ratelimits: u32) -> Self {
+        self.await_ratelimits = await_ratelimits;
+        self
+    }
+
+    /// Returns the key under which the uses of the given context are tracked
+    fn key(&self, ctx: &CooldownContext) -> CooldownType {
+        match (self.scope, ctx.guild_id) {
+            (BucketScope::Global, _) => CooldownType::Global,
+            (BucketScope::Guild, Some(guild_id)) => CooldownType::Guild(guild_id),
+            (BucketScope::Guild | BucketScope::Channel, _) => CooldownType::Channel(ctx.channel_id),
+            (BucketScope::Member, Some(guild_id)) => CooldownType::Member((ctx.user_id, guild_id)),
+            (BucketScope::User | BucketScope::Member, _) => CooldownType::User(ctx.user_id),
+            (BucketScope::__NonExhaustive, _) => unreachable!(),
+        }
+    }
+
+    /// Tries to take a use from this bucket.
+    ///
+    /// If no use is available right away, a use is reserved if `may_wait` is true and fewer than
+    /// [`Self::await_ratelimits`] invocations of the same scope are already waiting. The caller
+    /// must then wait for the returned delay before proceeding.
+    pub fn acquire(&self, ctx: &CooldownContext, may_wait: bool) -> BucketOutcome {
+        let (outcome, reservation) = self.reserve(ctx, may_wait);
+        if let Some(reservation) = reservation {
+            reservation.commit();
+        }
+        outcome
+    }
+
+    /// Like [`Self::acquire`], but the use is given back when the returned reservation is dropped
+    /// without being [committed](BucketReservation::commit)
+    pub(crate) fn reserve(
+        &self,
+        ctx: &CooldownContext,
+        may_wait: bool,
+    ) -> (BucketOutcome, Option<BucketReservation<'_>>) {
+        let now = Instant::now();
+        let key = self.key(ctx);
+        let mut uses = self.uses.lock().unwrap();
+        let uses = uses.entry(key).or_default();
+        // Uses older than both the time span and the delay are irrelevant
+        let retention = self.time_span.max(self.delay.unwrap_or_default());
+        while uses
+            .front()
+            .is_some_and(|&first| now.saturating_duration_since(first) >= retention)
+        {
+            uses.pop_front();
+        }
+
+        let mut next_use = now;
+        if let (Some(delay), Some(&last)) = (self.delay, uses.back()) {
+            next_use = next_use.max(last + delay);
+        }
+        let limit = self.limit.max(1) as usize;
+        if !self.time_span.is_zero() && uses.len() >= limit {
+            next_use = next_use.max(uses[uses.len() - limit] + self.time_span);
+        }
+
+        let wait = next_use - now;
+        let outcome = if wait.is_zero() {
+            BucketOutcome::Allowed
+        } else if may_wait
+            && uses.iter().filter(|&&use_| use_ > now).count() < self.await_ratelimits as usize
+        {
+            BucketOutcome::Delayed(wait)
+        } else {
+            return (BucketOutcome::Ratelimited(wait), None);
+        };
+        uses.push_back(next_use);
+        let reservation = BucketReservation {
+            bucket: self,
+            key,
+            use_: next_use,
+            committed: false,
+        };
+        (outcome, Some(reservation))
+    }
+}
+
+/// A use taken from a [`CooldownBucket`], which is given back on drop unless committed. Lets the
+/// framework take uses during the checks of an invocation, and give them back if a later check
+/// denies the invocation
+pub(crate) struct BucketReservation<'a> {
+    /// Bucket the use was taken from
+    bucket: &'a CooldownBucket,
+    /// Scope of the use in the bucket
+    key: CooldownType,
+    /// Time of the use
+    use_: Instant,
+    /// Whether the use is final
+    committed: bool,
+}
+
+impl BucketReservation<'_> {
+    /// Makes the use final, so that it isn't given back on drop
+    pub(crate) fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for BucketReservation<'_> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        let mut uses = self.bucket.uses.lock().unwrap();
+        if let Some(uses) = uses.get_mut(&self.key) {
+            if let Some(index) = uses.iter().position(|&use_| use_ == self.use_) {
+                uses.remove(index);
+            }
+        }
+    }
+}
+
 impl<'a> From<&'a serenity::Message> for CooldownContext {
     fn from(message: &'a serenity::Message) -> Self {
         Self {
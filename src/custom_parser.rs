@@ -0,0 +1,134 @@
+//! Contains the [`CustomParameterParser`] and [`OptionalArgument`] traits, used by the
+//! `#[custom_parser = "..."]` and `#[required = false]` parameter attributes of
+//! [`crate::command`]
+
+use crate::serenity_prelude as serenity;
+
+/// Parsing logic for a command parameter that bypasses the type detection of the
+/// [`crate::command`] macro. Select it with `#[custom_parser = "MyParser"]` on the parameter,
+/// where `MyParser` implements `CustomParameterParser<T>` for the parameter type `T`.
+///
+/// The macro normally decides how to parse a parameter from how its type is spelled, for example
+/// whether it's written as `Option<...>`. With a custom parser, the parameter type is passed
+/// through untouched and the parser alone decides whether the parameter is required, how the
+/// slash command option is created and how its value is extracted. This makes wrapper types and
+/// type aliases behave deterministically.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// /// Comma-separated list of words
+/// struct Words(Vec<String>);
+///
+/// struct WordsParser;
+///
+/// #[poise::async_trait]
+/// impl poise::CustomParameterParser<Option<Words>> for WordsParser {
+///     fn required() -> bool {
+///         false
+///     }
+///
+///     fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+///         builder.kind(serenity::CommandOptionType::String)
+///     }
+///
+///     async fn extract(
+///         _: &serenity::Context,
+///         _: &serenity::CommandInteraction,
+///         value: Option<&serenity::ResolvedValue<'_>>,
+///     ) -> Result<Option<Words>, poise::SlashArgError> {
+///         match value {
+///             Some(serenity::ResolvedValue::String(s)) => Ok(Some(Words(
+///                 s.split(',').map(|word| word.trim().to_owned()).collect(),
+///             ))),
+///             Some(_) => Err(poise::SlashArgError::new_command_structure_mismatch(
+///                 "expected string",
+///             )),
+///             None => Ok(None),
+///         }
+///     }
+/// }
+///
+/// #[poise::command(slash_command)]
+/// async fn count(
+///     ctx: Context<'_>,
+///     #[custom_parser = "WordsParser"] words: Option<Words>,
+/// ) -> Result<(), Error> {
+///     let count = words.map_or(0, |words| words.0.len());
+///     ctx.say(format!("{} words", count)).await?;
+///     Ok(())
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait CustomParameterParser<T: Send> {
+    /// Whether the user has to supply this parameter in slash commands. If not, [`Self::extract`]
+    /// is called with `None` when the parameter is omitted
+    fn required() -> bool {
+        true
+    }
+
+    /// Sets the type of the slash command option, like [`crate::SlashArgument::create`]. The
+    /// caller fills in the name, description and whether it's required
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption;
+
+    /// If this is a choice parameter, returns the choices
+    fn choices() -> Vec<crate::CommandParameterChoice> {
+        Vec::new()
+    }
+
+    /// Extracts the parameter value from the slash command argument, or from None if the user
+    /// omitted the argument
+    async fn extract(
+        ctx: &serenity::Context,
+        interaction: &serenity::CommandInteraction,
+        value: Option<&serenity::ResolvedValue<'_>>,
+    ) -> Result<T, crate::SlashArgError>;
+
+    /// Parses the parameter from the front of the prefix command arguments, like
+    /// [`crate::PopArgument::pop_from`].
+    ///
+    /// By default, parsing fails, so only implement this for parameters of prefix commands
+    async fn pop_from<'a>(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let _ = (attachment_index, ctx, msg);
+        Err((
+            "this parameter is only supported in slash commands".into(),
+            Some(args.to_owned()),
+        ))
+    }
+}
+
+/// Implemented for [`Option<T>`], to name `T` in parameters whose type the [`crate::command`]
+/// macro can't see through, like type aliases. Used by the `#[required = false]` parameter
+/// attribute.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// type MaybeUser = Option<serenity::User>;
+///
+/// #[poise::command(slash_command, prefix_command)]
+/// async fn avatar(
+///     ctx: Context<'_>,
+///     #[required = false] user: MaybeUser,
+/// ) -> Result<(), Error> {
+///     let user = user.as_ref().unwrap_or_else(|| ctx.author());
+///     ctx.say(user.face()).await?;
+///     Ok(())
+/// }
+/// ```
+pub trait OptionalArgument {
+    /// The type inside the [`Option`]
+    type Inner;
+}
+
+impl<T> OptionalArgument for Option<T> {
+    type Inner = T;
+}
@@ -2,8 +2,14 @@
 
 use crate::serenity_prelude as serenity;
 
-/// Retrieves user permissions in the given channel. If unknown, returns None. If in DMs, returns
-/// `Permissions::all()`.
+/// Permissions resolved during an invocation, stored in its invocation data so that parent and
+/// subcommand checks don't repeat the lookup. None means the permissions couldn't be determined
+struct ResolvedPermissions(
+    std::collections::HashMap<serenity::UserId, Option<serenity::Permissions>>,
+);
+
+/// Retrieves user permissions in the given channel via cache or HTTP, taking channel overwrites
+/// into account. If unknown, returns None. If in DMs, returns `Permissions::all()`.
 async fn user_permissions(
     ctx: &serenity::Context,
     guild_id: Option<serenity::GuildId>,
@@ -18,7 +24,7 @@ async fn user_permissions(
     let guild = guild_id.to_partial_guild(ctx).await.ok()?;
 
     // Use to_channel so that it can fallback on HTTP for threads (which aren't in cache usually)
-    let channel = match channel_id.to_channel(ctx).await {
+    let mut channel = match channel_id.to_channel(ctx).await {
         Ok(serenity::Channel::Guild(channel)) => channel,
         Ok(_other_channel) => {
             tracing::warn!(
@@ -29,21 +35,43 @@ async fn user_permissions(
         Err(_) => return None,
     };
 
+    // Threads don't have overwrites of their own, they inherit them from their parent channel
+    if channel.thread_metadata.is_some() {
+        let parent_id = channel.parent_id?;
+        channel = match parent_id.to_channel(ctx).await {
+            Ok(serenity::Channel::Guild(parent)) => parent,
+            _ => return None,
+        };
+    }
+
     let member = guild.member(ctx, user_id).await.ok()?;
 
     Some(guild.user_permissions_in(&channel, &member))
 }
 
-/// Retrieves the set of permissions that are lacking, relative to the given required permission set
-///
-/// Returns None if permissions couldn't be retrieved
-async fn missing_permissions<U, E>(
+/// Retrieves the permissions of the given user in the invocation channel, preferring the resolved
+/// permissions that Discord sends along with interactions. Memoized per invocation.
+pub(crate) async fn invocation_permissions<U, E>(
     ctx: crate::Context<'_, U, E>,
     user: serenity::UserId,
-    required_permissions: serenity::Permissions,
 ) -> Option<serenity::Permissions> {
-    if required_permissions.is_empty() {
-        return Some(serenity::Permissions::empty());
+    if let crate::Context::Application(actx) = ctx {
+        let interaction_permissions = if user == actx.interaction.user.id {
+            actx.interaction.member.as_ref().and_then(|m| m.permissions)
+        } else if user == ctx.framework().bot_id {
+            actx.interaction.app_permissions
+        } else {
+            None
+        };
+        if let Some(permissions) = interaction_permissions {
+            return Some(permissions);
+        }
+    }
+
+    if let Some(resolved) = ctx.invocation_data_map().await.get::<ResolvedPermissions>() {
+        if let Some(&permissions) = resolved.0.get(&user) {
+            return permissions;
+        }
     }
 
     let permissions = user_permissions(
@@ -53,22 +81,49 @@ async fn missing_permissions<U, E>(
         user,
     )
     .await;
+
+    let mut invocation_data = ctx.invocation_data_map().await;
+    match invocation_data.get_mut::<ResolvedPermissions>() {
+        Some(resolved) => {
+            resolved.0.insert(user, permissions);
+        }
+        None => {
+            invocation_data.insert(ResolvedPermissions([(user, permissions)].into()));
+        }
+    }
+    permissions
+}
+
+/// Retrieves the set of permissions that are lacking, relative to the given required permission set
+///
+/// Returns None if permissions couldn't be retrieved
+async fn missing_permissions<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    user: serenity::UserId,
+    required_permissions: serenity::Permissions,
+) -> Option<serenity::Permissions> {
+    if required_permissions.is_empty() {
+        return Some(serenity::Permissions::empty());
+    }
+
+    let permissions = invocation_permissions(ctx, user).await;
     Some(required_permissions - permissions?)
 }
 
-/// See [`check_permissions_and_cooldown`]. Runs the check only for a single command. The caller
-/// should call this multiple time for each parent command to achieve the check inheritance logic.
-async fn check_permissions_and_cooldown_single<'a, U, E>(
+/// Addresses of the check functions that already passed during an invocation, stored in its
+/// invocation data so that each check runs at most once per invocation.
+///
+/// Function addresses aren't guaranteed to be unique or stable, so this is best-effort; see
+/// [`crate::Command::checks`]
+struct PassedChecks(std::collections::HashSet<usize>);
+
+/// See [`check_permissions_and_cooldown`]. Checks the restrictions and permission requirements of
+/// a single command. The caller should call this for each parent command to achieve the check
+/// inheritance logic.
+async fn check_restrictions_single<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
     cmd: &'a crate::Command<U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
-    // Skip command checks if `FrameworkOptions::skip_checks_for_owners` is set to true
-    if ctx.framework().options.skip_checks_for_owners
-        && ctx.framework().options().owners.contains(&ctx.author().id)
-    {
-        return Ok(());
-    }
-
     if cmd.owners_only && !ctx.framework().options().owners.contains(&ctx.author().id) {
         return Err(crate::FrameworkError::NotAnOwner { ctx });
     }
@@ -121,9 +176,9 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
         }
         // Better safe than sorry: when perms are unknown, restrict access
         None => {
-            return Err(crate::FrameworkError::MissingUserPermissions {
+            return Err(crate::FrameworkError::PermissionsUnverifiable {
                 ctx,
-                missing_permissions: None,
+                user_id: ctx.author().id,
             })
         }
     }
@@ -137,43 +192,222 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
                 missing_permissions,
             })
         }
-        // When in doubt, just let it run. Not getting fancy missing permissions errors is better
-        // than the command not executing at all
-        None => {}
+        // The command may fail halfway through if the bot lacks permissions, so don't guess
+        None => {
+            return Err(crate::FrameworkError::PermissionsUnverifiable {
+                ctx,
+                user_id: ctx.framework().bot_id,
+            })
+        }
     }
 
-    // Only continue if command checks returns true
-    // First perform global checks, then command checks (if necessary)
-    for check in Option::iter(&ctx.framework().options().command_check).chain(&cmd.checks) {
-        match check(ctx).await {
-            Ok(true) => {}
-            Ok(false) => {
-                return Err(crate::FrameworkError::CommandCheckFailed { ctx, error: None })
-            }
-            Err(error) => {
-                return Err(crate::FrameworkError::CommandCheckFailed {
-                    error: Some(error),
-                    ctx,
-                })
+    Ok(())
+}
+
+/// Runs a single check function, unless it already passed earlier in this invocation. The global
+/// check and checks shared by parent and subcommands would otherwise run multiple times
+async fn run_check<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    check: fn(crate::Context<'_, U, E>) -> crate::BoxFuture<'_, Result<bool, E>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let check_id = check as usize;
+    let already_passed = ctx
+        .invocation_data_map()
+        .await
+        .get::<PassedChecks>()
+        .is_some_and(|passed| passed.0.contains(&check_id));
+    if already_passed {
+        return Ok(());
+    }
+    match check(ctx).await {
+        Ok(true) => {
+            let mut invocation_data = ctx.invocation_data_map().await;
+            match invocation_data.get_mut::<PassedChecks>() {
+                Some(passed) => {
+                    passed.0.insert(check_id);
+                }
+                None => {
+                    invocation_data.insert(PassedChecks([check_id].into()));
+                }
             }
+            Ok(())
         }
+        Ok(false) => Err(crate::FrameworkError::CommandCheckFailed { ctx, error: None }),
+        Err(error) => Err(crate::FrameworkError::CommandCheckFailed {
+            error: Some(error),
+            ctx,
+        }),
     }
+}
 
-    if !ctx.framework().options().manual_cooldowns {
-        let cooldowns = cmd.cooldowns.lock().unwrap();
-        let config = cmd.cooldown_config.read().unwrap();
-        let remaining_cooldown = cooldowns.remaining_cooldown(ctx.cooldown_context(), &config);
-        if let Some(remaining_cooldown) = remaining_cooldown {
-            return Err(crate::FrameworkError::CooldownHit {
-                ctx,
-                remaining_cooldown,
-            });
+/// Runs the global check, then the checks of the given commands from the outermost parent down to
+/// the invoked command. See [`crate::Command::checks`] for the exact order
+async fn run_checks<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    commands: &[&'a crate::Command<U, E>],
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    if let Some(check) = ctx.framework().options().command_check {
+        run_check(ctx, check).await?;
+    }
+    for command in commands {
+        for &check in &command.checks {
+            run_check(ctx, check).await?;
+        }
+        for &check in &command.final_checks {
+            run_check(ctx, check).await?;
+        }
+        // All final checks passed, so the checks of the subcommands don't need to run anymore
+        if !command.final_checks.is_empty() {
+            break;
         }
     }
 
     Ok(())
 }
 
+/// Reserves a use from the [`crate::CooldownBucket`] of a single command, if it has one. If the
+/// bucket allows waiting, notifies the error handler with
+/// [`crate::FrameworkError::RatelimitDelayed`] and waits.
+///
+/// The use is given back when the returned reservation is dropped without being committed
+async fn check_bucket<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    cmd: &'a crate::Command<U, E>,
+) -> Result<Option<crate::cooldown::BucketReservation<'a>>, crate::FrameworkError<'a, U, E>> {
+    // Autocomplete requests are not invocations, so they don't use up the bucket
+    if let crate::Context::Application(crate::ApplicationContext {
+        interaction_type: crate::CommandInteractionType::Autocomplete,
+        ..
+    }) = ctx
+    {
+        return Ok(None);
+    }
+    let Some(bucket_name) = &cmd.bucket else {
+        return Ok(None);
+    };
+    let Some(bucket) = ctx.framework().options().buckets.get(bucket_name) else {
+        tracing::warn!(
+            "command `{}` uses unknown bucket `{}`",
+            cmd.qualified_name,
+            bucket_name
+        );
+        return Ok(None);
+    };
+
+    match bucket.reserve(&ctx.cooldown_context(), true) {
+        (crate::BucketOutcome::Allowed, reservation) => Ok(reservation),
+        (crate::BucketOutcome::Delayed(delay), reservation) => {
+            crate::FrameworkError::RatelimitDelayed { delay, ctx }
+                .handle(ctx.framework().options())
+                .await;
+            defer_before_waiting(ctx).await;
+            tokio::time::sleep(delay).await;
+            Ok(reservation)
+        }
+        (crate::BucketOutcome::Ratelimited(remaining_cooldown), _) => {
+            Err(crate::FrameworkError::CooldownHit {
+                remaining_cooldown,
+                ctx,
+            })
+        }
+        (crate::BucketOutcome::__NonExhaustive, _) => unreachable!(),
+    }
+}
+
+/// Checks the cooldowns of a single command. If a cooldown is active and shorter than
+/// [`crate::CooldownConfig::wait_threshold`], waits for it to expire instead of erroring
+///
+/// If waiting is enabled, an invocation that passes starts the cooldown right away, under the same
+/// lock as the check. Otherwise, all invocations waiting for the same cooldown would pass at once
+/// when it expires, because the cooldown is only started again after argument parsing
+async fn check_cooldown<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    cmd: &'a crate::Command<U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let cooldown_context = ctx.cooldown_context();
+    // Autocomplete requests must be answered quickly, so they never wait
+    let may_wait = !matches!(
+        ctx,
+        crate::Context::Application(crate::ApplicationContext {
+            interaction_type: crate::CommandInteractionType::Autocomplete,
+            ..
+        })
+    );
+    let store = &ctx.framework().options().cooldown_store;
+    let mut waiter = None;
+    loop {
+        let config = cmd
+            .cooldown_config
+            .read()
+            .unwrap()
+            .for_roles(crate::cooldown::invoker_role_ids(ctx));
+        let claim = may_wait && config.wait_threshold.is_some();
+
+        let stored_cooldown = match store {
+            Some(store) => {
+                store
+                    .remaining_cooldown(&cmd.qualified_name, &cooldown_context, &config)
+                    .await
+            }
+            None => None,
+        };
+        // With a cooldown store, the in-memory tracker only holds the cooldowns claimed by this
+        // process, so that concurrent invocations of this process can't pass together
+        let remaining_cooldown = {
+            let mut tracker = cmd.cooldowns.lock().unwrap();
+            let remaining_cooldown =
+                stored_cooldown.max(tracker.remaining_cooldown(cooldown_context.clone(), &config));
+            if remaining_cooldown.is_none() && claim {
+                tracker.start_cooldown(cooldown_context.clone());
+            }
+            remaining_cooldown
+        };
+        let Some(remaining_cooldown) = remaining_cooldown else {
+            if let (Some(store), true) = (store, claim) {
+                store
+                    .start_cooldown(&cmd.qualified_name, &cooldown_context, &config)
+                    .await;
+            }
+            return Ok(());
+        };
+
+        // Another invocation may have claimed the cooldown in the meantime, so we keep our place
+        // in the queue and check again after waiting
+        if waiter.is_none() {
+            if may_wait
+                && config
+                    .wait_threshold
+                    .is_some_and(|threshold| remaining_cooldown <= threshold)
+            {
+                waiter = crate::CooldownWaiter::register(
+                    &cmd.cooldowns,
+                    cooldown_context.clone(),
+                    config.max_waiting,
+                );
+            }
+            if waiter.is_none() {
+                return Err(crate::FrameworkError::CooldownHit {
+                    ctx,
+                    remaining_cooldown,
+                });
+            }
+        }
+        defer_before_waiting(ctx).await;
+        tokio::time::sleep(remaining_cooldown).await;
+    }
+}
+
+/// Defers the response of application commands that are about to wait for a cooldown or bucket,
+/// unless they responded already. Discord only accepts an initial response within three seconds,
+/// which a long wait would otherwise miss
+async fn defer_before_waiting<U, E>(ctx: crate::Context<'_, U, E>) {
+    if let crate::Context::Application(ctx) = ctx {
+        if let Err(error) = ctx.defer_response(ctx.command.ephemeral).await {
+            tracing::warn!("failed to defer response before waiting: {}", error);
+        }
+    }
+}
+
 /// Checks if the invoker is allowed to execute this command at this point in time
 ///
 /// Doesn't actually start the cooldown timer! This should be done by the caller later, after
@@ -183,10 +417,208 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
 pub async fn check_permissions_and_cooldown<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
-    for parent_command in ctx.parent_commands() {
-        check_permissions_and_cooldown_single(ctx, parent_command).await?;
+    // Parent commands above the innermost command with skip_parent_checks are skipped
+    let mut commands = ctx.parent_commands().to_vec();
+    commands.push(ctx.command());
+    let first = commands
+        .iter()
+        .rposition(|command| command.skip_parent_checks)
+        .unwrap_or(0);
+    // Likewise, only the check functions of parent commands above the innermost command with
+    // inherit_checks disabled are skipped
+    let first_checked = commands
+        .iter()
+        .rposition(|command| !command.inherit_checks)
+        .unwrap_or(0)
+        .max(first);
+
+    // Skip command checks if `FrameworkOptions::skip_checks_for_owners` is set to true
+    if ctx.framework().options.skip_checks_for_owners
+        && ctx.framework().options().owners.contains(&ctx.author().id)
+    {
+        return Ok(());
+    }
+
+    if let crate::Context::Application(_) = ctx {
+        for command in &commands[1..] {
+            check_subcommand_default_member_permissions(ctx, command).await?;
+        }
+    }
+
+    for command in &commands[first..] {
+        check_restrictions_single(ctx, command).await?;
+    }
+    run_checks(ctx, &commands[first_checked..]).await?;
+    // Cooldowns come last, so that buckets aren't used up by invocations failing a check
+    if !ctx.framework().options().manual_cooldowns {
+        // Bucket uses are only committed once the cooldowns and buckets of all commands passed,
+        // so that a parent's bucket isn't used up when the subcommand is denied
+        let mut reservations = Vec::new();
+        for command in &commands[first..] {
+            check_cooldown(ctx, command).await?;
+            reservations.extend(check_bucket(ctx, command).await?);
+        }
+        reservations
+            .into_iter()
+            .for_each(crate::cooldown::BucketReservation::commit);
     }
-    check_permissions_and_cooldown_single(ctx, ctx.command()).await?;
 
     Ok(())
 }
+
+/// Discord only honors [`crate::Command::default_member_permissions`] on top-level commands, so
+/// enforce it for subcommands of slash invocations here
+async fn check_subcommand_default_member_permissions<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    cmd: &'a crate::Command<U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    match missing_permissions(ctx, ctx.author().id, cmd.default_member_permissions).await {
+        Some(missing_permissions) if missing_permissions.is_empty() => Ok(()),
+        Some(missing_permissions) => Err(crate::FrameworkError::MissingUserPermissions {
+            ctx,
+            missing_permissions: Some(missing_permissions),
+        }),
+        None => Err(crate::FrameworkError::PermissionsUnverifiable {
+            ctx,
+            user_id: ctx.author().id,
+        }),
+    }
+}
+
+/// Counts a running invocation towards [`crate::Command::max_concurrent_invocations`] until it's
+/// dropped
+pub(crate) struct InvocationSlot<'a> {
+    /// [`crate::Command::active_invocations`] of the invoked command
+    active_invocations: &'a std::sync::atomic::AtomicUsize,
+}
+
+impl Drop for InvocationSlot<'_> {
+    fn drop(&mut self) {
+        self.active_invocations
+            .fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+/// Registers the invocation as running, or fails if
+/// [`crate::Command::max_concurrent_invocations`] invocations of the command are running already.
+/// The returned slot must be kept alive until the invocation finishes
+#[allow(clippy::result_large_err)]
+pub(crate) fn acquire_invocation_slot<U, E>(
+    ctx: crate::Context<'_, U, E>,
+) -> Result<InvocationSlot<'_>, crate::FrameworkError<'_, U, E>> {
+    let command = ctx.command();
+    let limit = command.max_concurrent_invocations.unwrap_or(usize::MAX);
+    command
+        .active_invocations
+        .fetch_update(
+            std::sync::atomic::Ordering::SeqCst,
+            std::sync::atomic::Ordering::SeqCst,
+            |active| (active < limit).then_some(active + 1),
+        )
+        .map_err(|_| crate::FrameworkError::TooManyConcurrentInvocations { limit, ctx })?;
+    Ok(InvocationSlot {
+        active_invocations: &command.active_invocations,
+    })
+}
+
+/// Runs the given command action. If [`crate::FrameworkOptions::command_timeout`] elapses before
+/// the action completes, the invocation's [`crate::CancellationToken`] is cancelled.
+///
+/// The action itself keeps running; commands are expected to watch the token and abort
+/// cooperatively.
+pub(crate) async fn cancel_on_timeout<U, E, T>(
+    ctx: crate::Context<'_, U, E>,
+    action: impl std::future::Future<Output = T>,
+) -> T {
+    let Some(timeout) = ctx.framework().options.command_timeout else {
+        return action.await;
+    };
+    let token = ctx.cancellation_token().await;
+    let cancel = async {
+        tokio::time::sleep(timeout).await;
+        token.cancel();
+        std::future::pending::<std::convert::Infallible>().await
+    };
+
+    futures_util::pin_mut!(action, cancel);
+    match futures_util::future::select(action, cancel).await {
+        futures_util::future::Either::Left((output, _)) => output,
+        futures_util::future::Either::Right((never, _)) => match never {},
+    }
+}
+
+/// Runs the given application command action. If the command hasn't responded after
+/// [`crate::FrameworkOptions::auto_defer_after`] (or after [`DEFAULT_AUTO_DEFER_DELAY`] with
+/// [`crate::Command::auto_defer`]), the response is deferred before the action continues.
+///
+/// The action isn't polled while the deferral is being sent, so it can't start an initial response
+/// of its own meanwhile. Responses that were already in flight when the delay expired set
+/// [`crate::ApplicationContext::has_sent_initial_response`] before sending, so the deferral is
+/// skipped for them.
+pub(crate) async fn defer_after_delay<U, E, T>(
+    ctx: crate::ApplicationContext<'_, U, E>,
+    action: impl std::future::Future<Output = T>,
+) -> T {
+    let delay = match ctx.framework.options.auto_defer_after {
+        Some(delay) => delay,
+        None if ctx.command.auto_defer => DEFAULT_AUTO_DEFER_DELAY,
+        None => return action.await,
+    };
+    let sleep = tokio::time::sleep(delay);
+
+    futures_util::pin_mut!(action, sleep);
+    if let futures_util::future::Either::Left((output, _)) =
+        futures_util::future::select(action.as_mut(), sleep).await
+    {
+        return output;
+    }
+    if let Err(error) = ctx.defer_response(ctx.command.ephemeral).await {
+        tracing::warn!("failed to defer response automatically: {}", error);
+    }
+    action.await
+}
+
+/// Delay after which commands with [`crate::Command::auto_defer`] are deferred if
+/// [`crate::FrameworkOptions::auto_defer_after`] is unset. Leaves some headroom to Discord's
+/// three second deadline
+const DEFAULT_AUTO_DEFER_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Runs an invocation that was matched to a command: catches panics, instruments it for
+/// OpenTelemetry and reports it to the [`crate::InvocationObserver`]s. Failures are reported with
+/// [`report_failure`], but handling them is left to the caller
+pub(crate) async fn run_observed<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    invocation: impl std::future::Future<Output = Result<(), crate::FrameworkError<'a, U, E>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let options = ctx.framework().options;
+    crate::observer::notify(options, |o| o.on_parsed(ctx));
+    let started = std::time::Instant::now();
+    let invocation = async {
+        match crate::catch_unwind_maybe(invocation).await {
+            Ok(result) => result,
+            Err(payload) => Err(crate::FrameworkError::CommandPanic { payload, ctx }),
+        }
+    };
+    let result = crate::otel::instrument_command(ctx, invocation).await;
+
+    if let Err(error) = &result {
+        report_failure(error).await;
+    }
+    let duration = started.elapsed();
+    crate::observer::notify(options, |o| o.on_completed(ctx, duration, &result));
+    crate::observer::log_invocation(ctx, duration, &result);
+    result
+}
+
+/// Reports a failed invocation to the [`crate::InvocationObserver`]s and, if it was denied by a
+/// check, to [`crate::FrameworkOptions::on_invocation_denied`]. Called before the error is handled
+pub(crate) async fn report_failure<U, E>(error: &crate::FrameworkError<'_, U, E>) {
+    let Some(ctx) = error.ctx() else {
+        return;
+    };
+    let options = ctx.framework().options;
+    crate::observer::notify_error(options, error);
+    if let Some(reason) = error.denial_reason() {
+        (options.on_invocation_denied)(ctx, reason).await;
+    }
+}
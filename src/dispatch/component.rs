@@ -0,0 +1,48 @@
+//! Routes component and modal interactions to [`crate::FrameworkOptions::component_handlers`]
+
+use crate::serenity_prelude as serenity;
+
+/// Finds the most specific handler whose pattern matches the custom ID, see
+/// [`crate::CustomIdPattern`]
+fn find_handler<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    custom_id: &str,
+) -> Option<(&'a crate::CustomIdPattern, crate::ComponentHandler<U, E>)> {
+    framework
+        .options
+        .component_handlers
+        .iter()
+        .filter(|(pattern, _)| pattern.matches(custom_id).is_some())
+        .max_by_key(|(pattern, _)| pattern.specificity())
+        .map(|(pattern, &handler)| (pattern, handler))
+}
+
+/// Runs the component handler matching the custom ID of the interaction, if any. Interactions
+/// without a matching handler are ignored, as they may be awaited by a collector instead
+pub async fn dispatch_component_interaction<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    interaction: crate::RoutedInteraction<'a>,
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let custom_id = match interaction {
+        crate::RoutedInteraction::Component(interaction) => &interaction.data.custom_id,
+        crate::RoutedInteraction::Modal(interaction) => &interaction.data.custom_id,
+    };
+    let Some((pattern, handler)) = find_handler(framework, custom_id) else {
+        return Ok(());
+    };
+
+    let ctx = crate::ComponentContext {
+        serenity_context: ctx,
+        framework,
+        interaction,
+        pattern,
+        custom_id_args: pattern.matches(custom_id).unwrap_or_default(),
+        has_sent_initial_response,
+        __non_exhaustive: (),
+    };
+    handler(ctx)
+        .await
+        .map_err(|error| crate::FrameworkError::ComponentHandler { error, ctx })
+}
@@ -1,12 +1,16 @@
 //! Contains all code to dispatch incoming events onto framework commands
 
 mod common;
+mod component;
 mod prefix;
 mod slash;
+mod synthetic;
 
 pub use common::*;
+pub use component::*;
 pub use prefix::*;
 pub use slash::*;
+pub use synthetic::*;
 
 use crate::serenity_prelude as serenity;
 
@@ -18,6 +22,9 @@ pub struct FrameworkContext<'a, U, E> {
     pub bot_id: serenity::UserId,
     /// Framework configuration
     pub options: &'a crate::FrameworkOptions<U, E>,
+    /// Snapshot of the active commands, usually taken when the event came in. Dispatch looks up
+    /// commands here. See [`crate::FrameworkOptions::active_commands`]
+    pub commands: &'a crate::ActiveCommands<'a, U, E>,
     /// Your provided user data
     pub user_data: &'a U,
     /// Serenity shard manager. Can be used for example to shutdown the bot
@@ -32,6 +39,46 @@ impl<U, E> Clone for FrameworkContext<'_, U, E> {
     }
 }
 impl<'a, U, E> FrameworkContext<'a, U, E> {
+    /// Creates a framework context from its parts, for example to run your own event loop.
+    ///
+    /// `commands` is the snapshot that dispatch looks up commands in. Take it with
+    /// [`crate::FrameworkOptions::active_commands`] when the event comes in:
+    ///
+    /// ```rust,no_run
+    /// # use poise::serenity_prelude as serenity;
+    /// # async fn _test(
+    /// #     ctx: serenity::Context,
+    /// #     options: poise::FrameworkOptions<(), ()>,
+    /// #     shard_manager: std::sync::Arc<serenity::ShardManager>,
+    /// #     event: serenity::FullEvent,
+    /// # ) {
+    /// let commands = options.active_commands();
+    /// let framework = poise::FrameworkContext::new(
+    ///     ctx.cache.current_user().id,
+    ///     &options,
+    ///     &commands,
+    ///     &(),
+    ///     &shard_manager,
+    /// );
+    /// poise::dispatch_event(framework, &ctx, event).await;
+    /// # }
+    /// ```
+    pub fn new(
+        bot_id: serenity::UserId,
+        options: &'a crate::FrameworkOptions<U, E>,
+        commands: &'a crate::ActiveCommands<'a, U, E>,
+        user_data: &'a U,
+        shard_manager: &'a std::sync::Arc<serenity::ShardManager>,
+    ) -> Self {
+        Self {
+            bot_id,
+            options,
+            commands,
+            user_data,
+            shard_manager,
+        }
+    }
+
     /// Returns the stored framework options, including commands.
     ///
     /// This function exists for API compatiblity with [`crate::Framework`]. On this type, you can
@@ -56,6 +103,59 @@ impl<'a, U, E> FrameworkContext<'a, U, E> {
     pub async fn user_data(&self) -> &'a U {
         self.user_data
     }
+
+    /// Removes the cached prefixes of the given guild, see [`crate::Framework::invalidate_prefix`]
+    pub fn invalidate_prefix(&self, guild_id: serenity::GuildId) {
+        if let Some(cache) = &self.options.prefix_options.prefix_cache {
+            cache.invalidate(guild_id);
+        }
+    }
+
+    /// Runs a command as if it was invoked by `invoker` in the given channel, for example for
+    /// admin "run as" tools or console interfaces. If `invoker` is None, the bot itself is the
+    /// invoker.
+    ///
+    /// `name` may include subcommand names (`"config set"`) and `args` is parsed like the
+    /// arguments of a prefix invocation. Like [`crate::ScheduledCommand`], the invocation goes
+    /// through the prefix code path with a synthetic invocation message, so the command must have
+    /// `prefix_command` enabled. The trigger is [`crate::MessageDispatchTrigger::Programmatic`].
+    ///
+    /// Command errors are forwarded to the framework's error handlers like normal command errors.
+    /// Returns false if no prefix command matches or the channel couldn't be resolved.
+    pub async fn invoke_command(
+        self,
+        ctx: &serenity::Context,
+        channel_id: serenity::ChannelId,
+        name: &str,
+        args: &str,
+        invoker: Option<&serenity::User>,
+        run_checks: bool,
+    ) -> bool
+    where
+        U: Send + Sync,
+    {
+        let invocation = format!("{} {}", name, args);
+        synthetic::run_synthetic_invocation(
+            self,
+            ctx,
+            invocation.trim_end(),
+            channel_id,
+            invoker,
+            crate::MessageDispatchTrigger::Programmatic,
+            run_checks,
+        )
+        .await
+    }
+}
+
+/// Creates the invocation data for a new command invocation, containing the invocation's
+/// [`crate::CancellationToken`] (see [`crate::Context::cancellation_token`])
+pub(crate) fn new_invocation_data<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+) -> tokio::sync::Mutex<crate::InvocationData> {
+    let mut invocation_data = crate::InvocationData::new();
+    invocation_data.insert(framework.options.shutdown_token.child_token());
+    tokio::sync::Mutex::new(invocation_data)
 }
 
 /// Central event handling function of this library
@@ -64,9 +164,84 @@ pub async fn dispatch_event<U: Send + Sync, E>(
     ctx: &serenity::Context,
     event: serenity::FullEvent,
 ) {
-    match &event {
+    let span = crate::otel::event_span(&event);
+    tracing::Instrument::instrument(dispatch_event_inner(framework, ctx, event), span).await;
+}
+
+/// See [`dispatch_event`]
+async fn dispatch_event_inner<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: serenity::FullEvent,
+) {
+    crate::observer::notify(framework.options, |o| o.on_event(&event));
+
+    if framework.options.concurrent_event_handling {
+        return run_concurrently(framework, ctx, &event).await;
+    }
+
+    run_command_invocation(framework, ctx, &event).await;
+    for map_event in &framework.options.event_commands {
+        if let Some(invocation) = map_event(ctx, &event) {
+            run_event_invocation(framework, ctx, invocation).await;
+        }
+    }
+    run_event_handler(framework, ctx, &event).await;
+}
+
+/// Runs the command invocation, the [`crate::FrameworkOptions::event_commands`] and the event
+/// handler concurrently, see [`crate::FrameworkOptions::concurrent_event_handling`]
+///
+/// Not an async fn, so that the large futures are assembled in a short-lived stack frame and only
+/// a pointer remains in the caller's frame.
+fn run_concurrently<'a, U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    event: &'a serenity::FullEvent,
+) -> std::pin::Pin<Box<impl std::future::Future<Output = ()> + 'a>> {
+    let event_invocations = framework
+        .options
+        .event_commands
+        .iter()
+        .filter_map(|map_event| map_event(ctx, event))
+        .map(move |invocation| run_event_invocation(framework, ctx, invocation));
+    Box::pin(async move {
+        futures_util::future::join3(
+            run_command_invocation(framework, ctx, event),
+            futures_util::future::join_all(event_invocations),
+            run_event_handler(framework, ctx, event),
+        )
+        .await;
+    })
+}
+
+/// Runs an invocation produced by [`crate::FrameworkOptions::event_commands`]
+async fn run_event_invocation<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    invocation: crate::EventInvocation,
+) {
+    synthetic::run_synthetic_invocation(
+        framework,
+        ctx,
+        &invocation.invocation,
+        invocation.channel_id,
+        invocation.author.as_ref(),
+        crate::MessageDispatchTrigger::Event,
+        invocation.run_checks,
+    )
+    .await;
+}
+
+/// Runs the prefix or application command that the event invokes, if any
+async fn run_command_invocation<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+) {
+    match event {
         serenity::FullEvent::Message { new_message } => {
-            let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+            let invocation_data = new_invocation_data(framework);
             let mut parent_commands = Vec::new();
             let trigger = crate::MessageDispatchTrigger::MessageCreate;
             if let Err(error) = prefix::dispatch_message(
@@ -93,7 +268,7 @@ pub async fn dispatch_event<U: Send + Sync, E>(
                 );
 
                 if let Some((msg, previously_tracked)) = msg {
-                    let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+                    let invocation_data = new_invocation_data(framework);
                     let mut parent_commands = Vec::new();
                     let trigger = match previously_tracked {
                         true => crate::MessageDispatchTrigger::MessageEdit,
@@ -132,7 +307,7 @@ pub async fn dispatch_event<U: Send + Sync, E>(
         serenity::FullEvent::InteractionCreate {
             interaction: serenity::Interaction::Command(interaction),
         } => {
-            let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+            let invocation_data = new_invocation_data(framework);
             let mut parent_commands = Vec::new();
             if let Err(error) = slash::dispatch_interaction(
                 framework,
@@ -151,7 +326,7 @@ pub async fn dispatch_event<U: Send + Sync, E>(
         serenity::FullEvent::InteractionCreate {
             interaction: serenity::Interaction::Autocomplete(interaction),
         } => {
-            let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+            let invocation_data = new_invocation_data(framework);
             let mut parent_commands = Vec::new();
             if let Err(error) = slash::dispatch_autocomplete(
                 framework,
@@ -167,18 +342,55 @@ pub async fn dispatch_event<U: Send + Sync, E>(
                 error.handle(framework.options).await;
             }
         }
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(interaction),
+        } => {
+            let interaction = crate::RoutedInteraction::Component(interaction);
+            dispatch_component(framework, ctx, interaction).await;
+        }
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Modal(interaction),
+        } => {
+            let interaction = crate::RoutedInteraction::Modal(interaction);
+            dispatch_component(framework, ctx, interaction).await;
+        }
         _ => {}
     }
+}
+
+/// Runs the [`crate::FrameworkOptions::component_handlers`] entry matching the interaction
+async fn dispatch_component<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    interaction: crate::RoutedInteraction<'_>,
+) {
+    if let Err(error) = component::dispatch_component_interaction(
+        framework,
+        ctx,
+        interaction,
+        &std::sync::atomic::AtomicBool::new(false),
+    )
+    .await
+    {
+        error.handle(framework.options).await;
+    }
+}
 
+/// Runs [`crate::FrameworkOptions::event_handler`] on the event
+async fn run_event_handler<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+) {
     // Do this after the framework's Ready handling, so that get_user_data() doesnt
     // potentially block infinitely
     if let Err(error) =
-        (framework.options.event_handler)(ctx, &event, framework, framework.user_data).await
+        (framework.options.event_handler)(ctx, event, framework, framework.user_data).await
     {
         let error = crate::FrameworkError::EventHandler {
             error,
             ctx,
-            event: &event,
+            event,
             framework,
         };
         (framework.options.on_error)(error).await;
@@ -2,77 +2,78 @@
 
 use crate::serenity_prelude as serenity;
 
-/// Checks if this message is a bot invocation by attempting to strip the prefix
-///
-/// Returns tuple of stripped prefix and rest of the message, if any prefix matches
-async fn strip_prefix<'a, U, E>(
-    framework: crate::FrameworkContext<'a, U, E>,
-    ctx: &'a serenity::Context,
-    msg: &'a serenity::Message,
-) -> Option<(&'a str, &'a str)> {
-    let partial_ctx = crate::PartialContext {
-        guild_id: msg.guild_id,
-        channel_id: msg.channel_id,
-        author: &msg.author,
-        serenity_context: ctx,
-        framework,
-        data: framework.user_data,
-        __non_exhaustive: (),
-    };
+/// Runs [`crate::PrefixFrameworkOptions::dynamic_prefix`] and
+/// [`crate::PrefixFrameworkOptions::dynamic_prefixes`], or takes their result from
+/// [`crate::PrefixFrameworkOptions::prefix_cache`]
+pub(crate) async fn resolve_dynamic_prefixes<U, E>(
+    ctx: crate::PartialContext<'_, U, E>,
+) -> Result<Vec<String>, E> {
+    let options = &ctx.framework.options.prefix_options;
+    if options.dynamic_prefix.is_none() && options.dynamic_prefixes.is_none() {
+        return Ok(Vec::new());
+    }
 
-    if let Some(dynamic_prefix) = framework.options.prefix_options.dynamic_prefix {
-        match dynamic_prefix(partial_ctx).await {
-            Ok(prefix) => {
-                if let Some(prefix) = prefix {
-                    if msg.content.starts_with(&prefix) {
-                        return Some(msg.content.split_at(prefix.len()));
-                    }
-                }
-            }
-            Err(error) => {
-                (framework.options.on_error)(crate::FrameworkError::DynamicPrefix {
-                    error,
-                    ctx: partial_ctx,
-                    msg,
-                })
-                .await;
-            }
+    let cache = options.prefix_cache.as_ref().zip(ctx.guild_id);
+    if let Some((cache, guild_id)) = cache {
+        if let Some(prefixes) = cache.get(guild_id) {
+            return Ok(prefixes);
         }
     }
 
-    if let Some(prefix) = &framework.options.prefix_options.prefix {
-        if let Some(content) = msg.content.strip_prefix(prefix) {
-            return Some((prefix, content));
-        }
+    let mut prefixes = Vec::new();
+    if let Some(dynamic_prefix) = options.dynamic_prefix {
+        prefixes.extend(dynamic_prefix(ctx).await?);
+    }
+    if let Some(dynamic_prefixes) = options.dynamic_prefixes {
+        prefixes.extend(dynamic_prefixes(ctx).await?);
     }
 
-    if let Some((prefix, content)) = framework
-        .options
-        .prefix_options
-        .additional_prefixes
-        .iter()
-        .find_map(|prefix| match prefix {
-            &crate::Prefix::Literal(prefix) => Some((prefix, msg.content.strip_prefix(prefix)?)),
-            crate::Prefix::Regex(prefix) => {
-                let regex_match = prefix.find(&msg.content)?;
-                if regex_match.start() == 0 {
-                    Some(msg.content.split_at(regex_match.end()))
-                } else {
-                    None
-                }
-            }
-            crate::Prefix::__NonExhaustive => unreachable!(),
-        })
-    {
-        return Some((prefix, content));
+    if let Some((cache, guild_id)) = cache {
+        cache.insert(guild_id, prefixes.clone());
     }
+    Ok(prefixes)
+}
 
-    if let Some(dynamic_prefix) = framework.options.prefix_options.stripped_dynamic_prefix {
-        match dynamic_prefix(ctx, msg, framework.user_data).await {
-            Ok(result) => {
-                if let Some((prefix, content)) = result {
-                    return Some((prefix, content));
-                }
+/// Strips a code block that wraps the entire message, including its language tag, see
+/// [`crate::PrefixFrameworkOptions::unwrap_code_blocks`]. Returns the message unchanged if it's
+/// not wrapped in a code block
+fn unwrap_code_block(content: &str) -> &str {
+    let Some(inner) = content
+        .trim()
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    else {
+        return content;
+    };
+
+    // A first line consisting of a single word without punctuation is a language tag
+    let inner = match inner.split_once('\n') {
+        Some((language, rest))
+            if language
+                .chars()
+                .all(|c| c.is_alphanumeric() || "+-#_".contains(c)) =>
+        {
+            rest
+        }
+        _ => inner,
+    };
+    inner.trim()
+}
+
+/// Tries a single kind of prefix, see [`crate::PrefixSource`]
+async fn strip_prefix_from_source<'a, U, E>(
+    source: crate::PrefixSource,
+    partial_ctx: crate::PartialContext<'a, U, E>,
+    msg: &'a serenity::Message,
+    content: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let framework = partial_ctx.framework;
+    let options = &framework.options.prefix_options;
+    match source {
+        crate::PrefixSource::Dynamic => match resolve_dynamic_prefixes(partial_ctx).await {
+            Ok(prefixes) => {
+                let prefix = prefixes.iter().find(|p| content.starts_with(p.as_str()))?;
+                Some(content.split_at(prefix.len()))
             }
             Err(error) => {
                 (framework.options.on_error)(crate::FrameworkError::DynamicPrefix {
@@ -81,28 +82,82 @@ async fn strip_prefix<'a, U, E>(
                     msg,
                 })
                 .await;
+                None
             }
+        },
+        crate::PrefixSource::Static => {
+            let prefix = options.prefix.as_deref()?;
+            Some((prefix, content.strip_prefix(prefix)?))
         }
-    }
-
-    if framework.options.prefix_options.mention_as_prefix {
-        // Mentions are either <@USER_ID> or <@!USER_ID>
-        if let Some(stripped_content) = (|| {
-            msg.content
+        crate::PrefixSource::Additional => options
+            .additional_prefixes
+            .iter()
+            .find_map(|prefix| prefix.strip(content)),
+        crate::PrefixSource::StrippedDynamic => {
+            let dynamic_prefix = options.stripped_dynamic_prefix?;
+            match dynamic_prefix(partial_ctx.serenity_context, msg, framework.user_data).await {
+                Ok(result) => result,
+                Err(error) => {
+                    (framework.options.on_error)(crate::FrameworkError::DynamicPrefix {
+                        error,
+                        ctx: partial_ctx,
+                        msg,
+                    })
+                    .await;
+                    None
+                }
+            }
+        }
+        crate::PrefixSource::Mention => {
+            if !options.mention_as_prefix {
+                return None;
+            }
+            // Mentions are either <@USER_ID> or <@!USER_ID>
+            let stripped_content = content
                 .strip_prefix("<@")?
                 .trim_start_matches('!')
                 .strip_prefix(&framework.bot_id.to_string())?
-                .strip_prefix('>')
-        })() {
-            let mention_prefix = &msg.content[..(msg.content.len() - stripped_content.len())];
-            return Some((mention_prefix, stripped_content));
+                .strip_prefix('>')?;
+            let mention_prefix = &content[..(content.len() - stripped_content.len())];
+            Some((mention_prefix, stripped_content))
         }
+        crate::PrefixSource::__NonExhaustive => unreachable!(),
     }
+}
+
+/// Checks if this message is a bot invocation by attempting to strip the prefix. The kinds of
+/// prefixes are tried in the order of [`crate::PrefixFrameworkOptions::prefix_resolver`].
+///
+/// Returns tuple of stripped prefix and rest of the message, if any prefix matches
+pub async fn strip_prefix<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    msg: &'a serenity::Message,
+) -> Option<(&'a str, &'a str)> {
+    let content = if framework.options.prefix_options.unwrap_code_blocks {
+        unwrap_code_block(&msg.content)
+    } else {
+        &msg.content
+    };
+    let partial_ctx = crate::PartialContext {
+        guild_id: msg.guild_id,
+        channel_id: msg.channel_id,
+        author: &msg.author,
+        serenity_context: ctx,
+        framework,
+        data: framework.user_data,
+        __non_exhaustive: (),
+    };
 
+    for &source in framework.options.prefix_options.prefix_resolver.sources() {
+        if let Some(result) = strip_prefix_from_source(source, partial_ctx, msg, content).await {
+            return Some(result);
+        }
+    }
     None
 }
 
-/// Find a command or subcommand within `&[Command]`, given a command invocation without a prefix.
+/// Find a command or subcommand within a list of commands, given a command invocation without a prefix.
 /// Returns the verbatim command name string as well as the command arguments (i.e. the remaining
 /// string).
 ///
@@ -138,8 +193,8 @@ async fn strip_prefix<'a, U, E>(
 ///     Some((&commands[1], "CoMmAnD2", "cOmMaNd99 my arguments")),
 /// );
 /// assert!(parent_commands.is_empty());
-pub fn find_command<'a, U, E>(
-    commands: &'a [crate::Command<U, E>],
+pub fn find_command<'a, U: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
     remaining_message: &'a str,
     case_insensitive: bool,
     parent_commands: &mut Vec<&'a crate::Command<U, E>>,
@@ -189,7 +244,7 @@ pub async fn dispatch_message<'a, U: Send + Sync, E>(
     ctx: &'a serenity::Context,
     msg: &'a serenity::Message,
     trigger: crate::MessageDispatchTrigger,
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
     if let Some(ctx) = parse_invocation(
@@ -202,12 +257,7 @@ pub async fn dispatch_message<'a, U: Send + Sync, E>(
     )
     .await?
     {
-        crate::catch_unwind_maybe(run_invocation(ctx))
-            .await
-            .map_err(|payload| crate::FrameworkError::CommandPanic {
-                payload,
-                ctx: ctx.into(),
-            })??;
+        super::common::run_observed(ctx.into(), run_invocation(ctx)).await?;
     } else if let Some(non_command_message) = framework.options.prefix_options.non_command_message {
         non_command_message(&framework, ctx, msg)
             .await
@@ -232,7 +282,7 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
     ctx: &'a serenity::Context,
     msg: &'a serenity::Message,
     trigger: crate::MessageDispatchTrigger,
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<Option<crate::PrefixContext<'a, U, E>>, crate::FrameworkError<'a, U, E>> {
     // Check if we're allowed to invoke from bot messages
@@ -261,7 +311,7 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
     let msg_content = msg_content.trim_start();
 
     let (command, invoked_command_name, args) = find_command(
-        &framework.options.commands,
+        framework.commands,
         msg_content,
         framework.options.prefix_options.case_insensitive_commands,
         parent_commands,
@@ -294,14 +344,41 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
         command,
         invocation_data,
         trigger,
+        locale: resolve_locale(framework, ctx, msg).await,
         action,
         __non_exhaustive: (),
     }))
 }
 
+/// Runs [`crate::FrameworkOptions::locale_provider`] for a prefix invocation message
+pub(crate) async fn resolve_locale<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+) -> Option<&'static str> {
+    let locale_provider = framework.options.locale_provider?;
+    let partial_ctx = crate::PartialContext {
+        guild_id: msg.guild_id,
+        channel_id: msg.channel_id,
+        author: &msg.author,
+        serenity_context: ctx,
+        framework,
+        data: framework.user_data,
+        __non_exhaustive: (),
+    };
+    let locale = locale_provider(partial_ctx).await?;
+    let known_locale = crate::localization::DISCORD_LOCALES
+        .iter()
+        .find(|&&known_locale| known_locale == locale);
+    if known_locale.is_none() {
+        tracing::warn!("locale provider returned unknown locale `{}`", locale);
+    }
+    known_locale.copied()
+}
+
 /// Given an existing parsed command invocation from [`parse_invocation`], run it, including all the
 /// before and after code like checks and built in filters from edit tracking
-pub async fn run_invocation<U, E>(
+pub async fn run_invocation<U: Send + Sync, E>(
     ctx: crate::PrefixContext<'_, U, E>,
 ) -> Result<(), crate::FrameworkError<'_, U, E>> {
     // Check if we should disregard this invocation if it was triggered by an edit
@@ -323,6 +400,9 @@ pub async fn run_invocation<U, E>(
     }
 
     super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    let _slot = super::common::acquire_invocation_slot(ctx.into())?;
+
+    crate::observer::notify(ctx.framework.options, |o| o.on_started(ctx.into()));
 
     // Typing is broadcasted as long as this object is alive
     let _typing_broadcaster = if ctx.command.broadcast_typing {
@@ -345,9 +425,13 @@ pub async fn run_invocation<U, E>(
     }
 
     // Execute command
-    (ctx.action)(ctx).await?;
+    let action = || (ctx.action)(ctx);
+    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+    super::common::cancel_on_timeout(ctx.into(), invocation).await?;
 
     (ctx.framework.options.post_command)(crate::Context::Prefix(ctx)).await;
 
+    crate::observer::notify(ctx.framework.options, |o| o.on_succeeded(ctx.into()));
+
     Ok(())
 }
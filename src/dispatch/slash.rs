@@ -3,13 +3,13 @@
 use crate::serenity_prelude as serenity;
 
 /// Check if the interaction with the given name and arguments matches any framework command
-fn find_matching_command<'a, 'b, U, E>(
+fn find_matching_command<'a, 'b, U: 'a, E: 'a>(
     interaction_name: &str,
     interaction_options: &'b [serenity::ResolvedOption<'b>],
-    commands: &'a [crate::Command<U, E>],
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
     parent_commands: &mut Vec<&'a crate::Command<U, E>>,
 ) -> Option<(&'a crate::Command<U, E>, &'b [serenity::ResolvedOption<'b>])> {
-    commands.iter().find_map(|cmd| {
+    commands.into_iter().find_map(|cmd| {
         if interaction_name != cmd.name
             && Some(interaction_name) != cmd.context_menu_name.as_deref()
         {
@@ -38,20 +38,21 @@ fn find_matching_command<'a, 'b, U, E>(
 /// After this, the [`crate::ApplicationContext`] should be passed into [`run_command`] or
 /// [`run_autocomplete`].
 #[allow(clippy::too_many_arguments)] // We need to pass them all in to create Context.
+#[allow(clippy::result_large_err)]
 fn extract_command<'a, U, E>(
     framework: crate::FrameworkContext<'a, U, E>,
     ctx: &'a serenity::Context,
     interaction: &'a serenity::CommandInteraction,
     interaction_type: crate::CommandInteractionType,
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<crate::ApplicationContext<'a, U, E>, crate::FrameworkError<'a, U, E>> {
     let search_result = find_matching_command(
         &interaction.data.name,
         options,
-        &framework.options.commands,
+        framework.commands,
         parent_commands,
     );
     let (command, leaf_interaction_options) =
@@ -84,7 +85,7 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
     interaction: &'a serenity::CommandInteraction,
     interaction_type: crate::CommandInteractionType,
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<crate::ApplicationContext<'a, U, E>, crate::FrameworkError<'a, U, E>> {
@@ -102,12 +103,42 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
     Ok(ctx)
 }
 
+/// Rejects arguments that violate the channel types, min/max or length constraints of their
+/// parameter. Discord only enforces these for the registered version of the command, and clients
+/// can send arbitrary values anyways.
+///
+/// Runs before checks and cooldowns, so that invalid arguments don't use them up
+#[allow(clippy::result_large_err)]
+fn check_arguments<U, E>(
+    ctx: crate::ApplicationContext<'_, U, E>,
+) -> Result<(), crate::FrameworkError<'_, U, E>> {
+    for arg in ctx.args {
+        let measure = match arg.value {
+            serenity::ResolvedValue::Channel(channel) => {
+                crate::check_channel_types(ctx.into(), arg.name, [channel.kind])?;
+                continue;
+            }
+            serenity::ResolvedValue::Integer(value) => crate::ArgumentMeasure::Number(value as f64),
+            serenity::ResolvedValue::Number(value) => crate::ArgumentMeasure::Number(value),
+            serenity::ResolvedValue::String(value) => {
+                crate::ArgumentMeasure::Length(value.chars().count())
+            }
+            _ => continue,
+        };
+        crate::check_parameter_constraints(ctx.into(), arg.name, [measure])?;
+    }
+    Ok(())
+}
+
 /// Given the extracted application command data from [`extract_command`], runs the command,
 /// including all the before and after code like checks.
-async fn run_command<U, E>(
+async fn run_command<U: Send + Sync, E>(
     ctx: crate::ApplicationContext<'_, U, E>,
 ) -> Result<(), crate::FrameworkError<'_, U, E>> {
     super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    let _slot = super::common::acquire_invocation_slot(ctx.into())?;
+
+    crate::observer::notify(ctx.framework.options, |o| o.on_started(ctx.into()));
 
     (ctx.framework.options.pre_command)(crate::Context::Application(ctx)).await;
 
@@ -124,7 +155,10 @@ async fn run_command<U, E>(
                 .command
                 .slash_action
                 .ok_or(command_structure_mismatch_error)?;
-            action(ctx).await
+            let action = || action(ctx);
+            let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+            let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+            super::common::defer_after_delay(ctx, invocation).await
         }
         serenity::CommandType::User => {
             match (
@@ -134,7 +168,21 @@ async fn run_command<U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::User(action)),
                     Some(serenity::ResolvedTarget::User(user, _)),
-                ) => action(ctx, (*user).clone()).await,
+                ) => {
+                    let action = || action(ctx, (*user).clone());
+                    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+                    let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+                    super::common::defer_after_delay(ctx, invocation).await
+                }
+                (
+                    Some(crate::ContextMenuCommandAction::UserAndMessage(action)),
+                    Some(serenity::ResolvedTarget::User(user, _)),
+                ) => {
+                    let action = || action(ctx, crate::ContextMenuTarget::User((*user).clone()));
+                    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+                    let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+                    super::common::defer_after_delay(ctx, invocation).await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -146,10 +194,37 @@ async fn run_command<U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::Message(action)),
                     Some(serenity::ResolvedTarget::Message(message)),
-                ) => action(ctx, (*message).clone()).await,
+                ) => {
+                    let action = || action(ctx, (*message).clone());
+                    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+                    let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+                    super::common::defer_after_delay(ctx, invocation).await
+                }
+                (
+                    Some(crate::ContextMenuCommandAction::UserAndMessage(action)),
+                    Some(serenity::ResolvedTarget::Message(message)),
+                ) => {
+                    let target = crate::ContextMenuTarget::Message(Box::new((*message).clone()));
+                    let action = || action(ctx, target.clone());
+                    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+                    let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+                    super::common::defer_after_delay(ctx, invocation).await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
+        kind if crate::ApplicationCommandType::from_serenity(kind)
+            == Some(crate::ApplicationCommandType::PrimaryEntryPoint) =>
+        {
+            let action = ctx
+                .command
+                .entry_point_action
+                .ok_or(command_structure_mismatch_error)?;
+            let action = || action(ctx);
+            let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+            let invocation = super::common::cancel_on_timeout(ctx.into(), invocation);
+            super::common::defer_after_delay(ctx, invocation).await
+        }
         other => {
             tracing::warn!("unknown interaction command type: {:?}", other);
             return Ok(());
@@ -159,18 +234,20 @@ async fn run_command<U, E>(
 
     (ctx.framework.options.post_command)(crate::Context::Application(ctx)).await;
 
+    crate::observer::notify(ctx.framework.options, |o| o.on_succeeded(ctx.into()));
+
     Ok(())
 }
 
 /// Dispatches this interaction onto framework commands, i.e. runs the associated command
-pub async fn dispatch_interaction<'a, U, E>(
+pub async fn dispatch_interaction<'a, U: Send + Sync, E>(
     framework: crate::FrameworkContext<'a, U, E>,
     ctx: &'a serenity::Context,
     interaction: &'a serenity::CommandInteraction,
     // Need to pass this in from outside because of lifetime issues
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
     // Need to pass this in from outside because of lifetime issues
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     // Need to pass this in from outside because of lifetime issues
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
@@ -186,14 +263,11 @@ pub async fn dispatch_interaction<'a, U, E>(
         parent_commands,
     )?;
 
-    crate::catch_unwind_maybe(run_command(ctx))
-        .await
-        .map_err(|payload| crate::FrameworkError::CommandPanic {
-            payload,
-            ctx: ctx.into(),
-        })??;
-
-    Ok(())
+    let invocation = async {
+        check_arguments(ctx)?;
+        run_command(ctx).await
+    };
+    super::common::run_observed(ctx.into(), invocation).await
 }
 
 /// Given the extracted application command data from [`extract_command`], runs the autocomplete
@@ -266,7 +340,7 @@ pub async fn dispatch_autocomplete<'a, U, E>(
     interaction: &'a serenity::CommandInteraction,
     // Need to pass the following in from outside because of lifetime issues
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
-    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
@@ -281,12 +355,16 @@ pub async fn dispatch_autocomplete<'a, U, E>(
         parent_commands,
     )?;
 
-    crate::catch_unwind_maybe(run_autocomplete(ctx))
-        .await
-        .map_err(|payload| crate::FrameworkError::CommandPanic {
-            payload,
-            ctx: ctx.into(),
-        })??;
+    let autocomplete = async {
+        match crate::catch_unwind_maybe(run_autocomplete(ctx)).await {
+            Ok(result) => result,
+            Err(payload) => Err(crate::FrameworkError::CommandPanic {
+                payload,
+                ctx: ctx.into(),
+            }),
+        }
+    };
+    crate::otel::instrument_command(ctx.into(), autocomplete).await?;
 
     Ok(())
 }
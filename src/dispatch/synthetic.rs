@@ -0,0 +1,169 @@
+//! Dispatching commands that weren't invoked by a user message, for example scheduled commands or
+//! commands bound to gateway events
+
+use crate::serenity_prelude as serenity;
+
+/// A command invocation derived from a gateway event. Returned by the callbacks in
+/// [`crate::FrameworkOptions::event_commands`].
+///
+/// Like [`crate::ScheduledCommand`], the invocation is dispatched through the prefix code path
+/// with a synthetic invocation message, so the invoked command must have `prefix_command` enabled.
+/// The trigger of such invocations is [`crate::MessageDispatchTrigger::Event`].
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// fn welcome_on_join(
+///     _: &serenity::Context,
+///     event: &serenity::FullEvent,
+/// ) -> Option<poise::EventInvocation> {
+///     let serenity::FullEvent::GuildMemberAddition { new_member } = event else {
+///         return None;
+///     };
+///     let channel_id = serenity::ChannelId::new(123456789);
+///     Some(poise::EventInvocation {
+///         author: Some(new_member.user.clone()),
+///         ..poise::EventInvocation::new(format!("welcome {}", new_member.user.id), channel_id)
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct EventInvocation {
+    /// Command name and arguments, as if typed after the prefix, e.g. `"welcome 123456789"`.
+    ///
+    /// This is where event data is mapped onto the command's parameters.
+    pub invocation: String,
+    /// Channel in which the command is executed and where its responses are sent
+    pub channel_id: serenity::ChannelId,
+    /// User who the invocation is attributed to, e.g. the new member in GuildMemberAddition.
+    ///
+    /// If None, the bot itself is the author.
+    pub author: Option<serenity::User>,
+    /// Whether to run permission checks, command checks and cooldown checks. False by default.
+    pub run_checks: bool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl EventInvocation {
+    /// Creates a new invocation attributed to the bot, with checks disabled
+    pub fn new(invocation: impl Into<String>, channel_id: serenity::ChannelId) -> Self {
+        Self {
+            invocation: invocation.into(),
+            channel_id,
+            author: None,
+            run_checks: false,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Runs the command described by `invocation` with a synthetic invocation message in the given
+/// channel. If `author` is None, the bot itself is the author.
+///
+/// Errors are forwarded to the framework's error handlers like normal command errors. Returns false
+/// if the invocation couldn't be resolved to a command that can be run synthetically.
+pub(crate) async fn run_synthetic_invocation<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    invocation: &str,
+    channel_id: serenity::ChannelId,
+    author: Option<&serenity::User>,
+    trigger: crate::MessageDispatchTrigger,
+    run_checks: bool,
+) -> bool {
+    let guild_id = match channel_id.to_channel(ctx).await {
+        Ok(serenity::Channel::Guild(channel)) => Some(channel.guild_id),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("couldn't resolve channel for `{}`: {}", invocation, e);
+            return false;
+        }
+    };
+
+    let author = author.cloned().unwrap_or_else(|| {
+        let mut bot = serenity::User::default();
+        bot.id = framework.bot_id;
+        bot.bot = true;
+        bot
+    });
+
+    let mut msg = serenity::CustomMessage::new();
+    msg.channel_id(channel_id)
+        .author(author)
+        .content(invocation)
+        .timestamp(serenity::Timestamp::now());
+    if let Some(guild_id) = guild_id {
+        msg.guild_id(guild_id);
+    }
+    let msg = msg.build();
+
+    let mut parent_commands = Vec::new();
+    let Some((command, invoked_command_name, args)) = crate::find_command(
+        framework.commands,
+        invocation,
+        framework.options.prefix_options.case_insensitive_commands,
+        &mut parent_commands,
+    ) else {
+        tracing::warn!("synthetic invocation `{}` matches no command", invocation);
+        return false;
+    };
+    let Some(action) = command.prefix_action else {
+        tracing::warn!(
+            "command `{}` must have prefix_command enabled to be invoked synthetically",
+            command.qualified_name
+        );
+        return false;
+    };
+
+    let invocation_data = super::new_invocation_data(framework);
+    let ctx = crate::PrefixContext {
+        serenity_context: ctx,
+        msg: &msg,
+        prefix: "",
+        invoked_command_name,
+        args,
+        framework,
+        data: framework.user_data,
+        parent_commands: &parent_commands,
+        command,
+        invocation_data: &invocation_data,
+        trigger,
+        locale: super::prefix::resolve_locale(framework, ctx, &msg).await,
+        action,
+        __non_exhaustive: (),
+    };
+
+    let result = super::common::run_observed(ctx.into(), run_invocation(ctx, run_checks)).await;
+    if let Err(error) = result {
+        error.handle(framework.options).await;
+    }
+    true
+}
+
+/// Synthetic counterpart to [`crate::run_invocation`], without any edit tracking
+async fn run_invocation<U: Send + Sync, E>(
+    ctx: crate::PrefixContext<'_, U, E>,
+    run_checks: bool,
+) -> Result<(), crate::FrameworkError<'_, U, E>> {
+    if ctx.command.subcommand_required {
+        return Err(crate::FrameworkError::SubcommandRequired {
+            ctx: crate::Context::Prefix(ctx),
+        });
+    }
+
+    if run_checks {
+        super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    }
+    let _slot = super::common::acquire_invocation_slot(ctx.into())?;
+    crate::observer::notify(ctx.framework.options, |o| o.on_started(ctx.into()));
+
+    (ctx.framework.options.pre_command)(crate::Context::Prefix(ctx)).await;
+    let action = || (ctx.action)(ctx);
+    let invocation = crate::middleware::run_with_middleware(ctx.into(), &action);
+    super::common::cancel_on_timeout(ctx.into(), invocation).await?;
+    (ctx.framework.options.post_command)(crate::Context::Prefix(ctx)).await;
+    crate::observer::notify(ctx.framework.options, |o| o.on_succeeded(ctx.into()));
+
+    Ok(())
+}
@@ -27,6 +27,8 @@ pub struct FrameworkBuilder<U, E> {
     commands: Vec<crate::Command<U, E>>,
     /// See [`Self::initialize_owners()`]
     initialize_owners: bool,
+    /// See [`Self::auto_register()`]
+    auto_register: Option<crate::builtins::RegisterTarget>,
 }
 
 impl<U, E> Default for FrameworkBuilder<U, E> {
@@ -36,6 +38,7 @@ impl<U, E> Default for FrameworkBuilder<U, E> {
             options: Default::default(),
             commands: Default::default(),
             initialize_owners: true,
+            auto_register: None,
         }
     }
 }
@@ -74,13 +77,29 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Registers the application commands at the given target on startup, so the setup callback
+    /// doesn't have to. See [`crate::FrameworkOptions::auto_register`]
+    ///
+    /// ```rust,no_run
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// let framework = poise::Framework::<(), Error>::builder()
+    ///     .options(poise::FrameworkOptions::default())
+    ///     .auto_register(poise::builtins::RegisterTarget::GuildsFromEnv)
+    ///     .setup(|_, _, _| Box::pin(async { Ok(()) }))
+    ///     .build();
+    /// ```
+    pub fn auto_register(mut self, target: crate::builtins::RegisterTarget) -> Self {
+        self.auto_register = Some(target);
+        self
+    }
+
     /// Build the framework with the specified configuration.
     ///
     /// For more information, see [`FrameworkBuilder`]
     pub fn build(self) -> crate::Framework<U, E>
     where
         U: Send + Sync + 'static,
-        E: Send + 'static,
+        E: Send + Sync + 'static,
     {
         let setup = self
             .setup
@@ -90,6 +109,9 @@ impl<U, E> FrameworkBuilder<U, E> {
         // Build framework options by concatenating user-set options with commands and owners
         options.commands.extend(self.commands);
         options.initialize_owners = self.initialize_owners;
+        if let Some(target) = self.auto_register {
+            options.auto_register = Some(target);
+        }
 
         // Create framework with specified settings
         crate::Framework::new(options, setup)
@@ -17,8 +17,11 @@ mod builder;
 /// additional conveniences built-in:
 /// - fills in correct values for [`crate::Command::qualified_name`]: [`set_qualified_names`]
 /// - spawns a background task to periodically clear edit tracker cache
+/// - runs background tasks spawned via [`Self::spawn_periodic`] and [`Self::spawn_at`]
 /// - sets up user data on the first Ready event
 /// - keeps track of shard manager and bot ID automatically
+/// - runs [`crate::FrameworkOptions::scheduled_commands`] and other background tasks once the bot
+///   is ready
 ///
 /// You can build a bot without [`Framework`]: see the `manual_dispatch` example in the repository
 pub struct Framework<U, E> {
@@ -45,9 +48,6 @@ pub struct Framework<U, E> {
             >,
         >,
     >,
-
-    /// Handle to the background task in order to `abort()` it on `Drop`
-    edit_tracker_purge_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl<U, E> Framework<U, E> {
@@ -72,7 +72,7 @@ impl<U, E> Framework<U, E> {
     /// user ID or connected guilds can be made available to the user data setup function. The user
     /// data setup is not allowed to return Result because there would be no reasonable
     /// course of action on error.
-    pub fn new<F>(options: crate::FrameworkOptions<U, E>, setup: F) -> Self
+    pub fn new<F>(mut options: crate::FrameworkOptions<U, E>, setup: F) -> Self
     where
         F: Send
             + Sync
@@ -83,13 +83,16 @@ impl<U, E> Framework<U, E> {
                 &'a Self,
             ) -> BoxFuture<'a, Result<U, E>>,
         U: Send + Sync + 'static,
-        E: Send + 'static,
+        E: Send + Sync + 'static,
     {
+        for job in std::mem::take(&mut options.scheduled_commands) {
+            options.spawn_scheduled_command(job);
+        }
+
         Self {
             user_data: std::sync::OnceLock::new(),
             bot_id: std::sync::OnceLock::new(),
             setup: std::sync::Mutex::new(Some(Box::new(setup))),
-            edit_tracker_purge_task: None,
             shard_manager: None,
             options,
         }
@@ -100,6 +103,75 @@ impl<U, E> Framework<U, E> {
         &self.options
     }
 
+    /// Adds a command while the bot is running, see [`crate::FrameworkOptions::add_command`]
+    pub fn add_command(&self, command: crate::Command<U, E>) {
+        self.options.add_command(command);
+    }
+
+    /// Removes a command while the bot is running, see
+    /// [`crate::FrameworkOptions::remove_command`]
+    pub fn remove_command(&self, name: &str) -> bool {
+        self.options.remove_command(name)
+    }
+
+    /// Replaces all commands while the bot is running, see
+    /// [`crate::FrameworkOptions::replace_commands`]
+    pub fn replace_commands(&self, commands: Vec<crate::Command<U, E>>) {
+        self.options.replace_commands(commands);
+    }
+
+    /// Runs the given action periodically in the background, see
+    /// [`crate::FrameworkOptions::spawn_periodic`]
+    ///
+    /// ```rust,no_run
+    /// # use poise::serenity_prelude as serenity;
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// let framework = poise::Framework::<(), Error>::builder()
+    ///     .setup(|ctx, _ready, framework| {
+    ///         Box::pin(async move {
+    ///             framework.spawn_periodic(std::time::Duration::from_secs(60 * 60), |ctx| {
+    ///                 Box::pin(async move {
+    ///                     let guilds = ctx.serenity_context.http.get_guilds(None, None).await?;
+    ///                     println!("In {} guilds", guilds.len());
+    ///                     Ok(())
+    ///                 })
+    ///             });
+    ///             Ok(())
+    ///         })
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn spawn_periodic<F>(&self, interval: std::time::Duration, action: F)
+    where
+        F: for<'a> Fn(crate::TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.options.spawn_periodic(interval, action);
+    }
+
+    /// Runs the given action once at the given point in time in the background, see
+    /// [`crate::FrameworkOptions::spawn_at`]
+    pub fn spawn_at<F>(&self, when: impl Into<serenity::Timestamp>, action: F)
+    where
+        F: for<'a> Fn(crate::TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.options.spawn_at(when, action);
+    }
+
+    /// Removes the cached prefixes of the given guild from
+    /// [`crate::PrefixFrameworkOptions::prefix_cache`], so a changed prefix takes effect with the
+    /// next message. Does nothing if no prefix cache is configured.
+    pub fn invalidate_prefix(&self, guild_id: serenity::GuildId) {
+        if let Some(cache) = &self.options.prefix_options.prefix_cache {
+            cache.invalidate(guild_id);
+        }
+    }
+
     /// Returns the serenity's client shard manager.
     // Returns a reference so you can plug it into [`FrameworkContext`]
     pub fn shard_manager(&self) -> &Arc<serenity::ShardManager> {
@@ -122,9 +194,7 @@ impl<U, E> Framework<U, E> {
 
 impl<U, E> Drop for Framework<U, E> {
     fn drop(&mut self) {
-        if let Some(task) = &mut self.edit_tracker_purge_task {
-            task.abort()
-        }
+        self.options.shutdown_token.cancel();
     }
 }
 
@@ -141,10 +211,11 @@ impl<U: Send + Sync, E: Send + Sync> serenity::Framework for Framework<U, E> {
         self.shard_manager = Some(client.shard_manager.clone());
 
         if self.options.initialize_owners {
-            if let Err(e) = insert_owners_from_http(
+            if let Err(e) = insert_owners_from_http_with(
                 &client.http,
                 &mut self.options.owners,
                 &self.options.initialized_team_roles,
+                self.options.initialize_application_owner,
             )
             .await
             {
@@ -152,9 +223,8 @@ impl<U: Send + Sync, E: Send + Sync> serenity::Framework for Framework<U, E> {
             }
         }
 
-        if let Some(edit_tracker) = &self.options.prefix_options.edit_tracker {
-            self.edit_tracker_purge_task =
-                Some(spawn_edit_tracker_purge_task(edit_tracker.clone()));
+        if self.options.prefix_options.edit_tracker.is_some() {
+            spawn_edit_tracker_purge_task(&self.options);
         }
     }
 
@@ -172,10 +242,17 @@ async fn raw_dispatch_event<U, E>(
 ) where
     U: Send + Sync,
 {
+    let mut is_first_ready = false;
     if let serenity::FullEvent::Ready { data_about_bot } = &event {
         let _: Result<_, _> = framework.bot_id.set(data_about_bot.user.id);
         let setup = Option::take(&mut *framework.setup.lock().unwrap());
         if let Some(setup) = setup {
+            is_first_ready = true;
+            if framework.options.auto_register.is_some() {
+                if let Err(e) = framework.options.reregister_commands(&ctx).await {
+                    tracing::warn!("Failed to register application commands: {e}");
+                }
+            }
             match setup(&ctx, data_about_bot, framework).await {
                 Ok(user_data) => {
                     let _: Result<_, _> = framework.user_data.set(user_data);
@@ -201,13 +278,21 @@ async fn raw_dispatch_event<U, E>(
         .bot_id
         .get()
         .expect("bot ID not set even though we awaited Ready");
-    let framework = crate::FrameworkContext {
+    let commands = framework.options.active_commands();
+    let framework = crate::FrameworkContext::new(
         bot_id,
-        options: &framework.options,
+        &framework.options,
+        &commands,
         user_data,
-        shard_manager: framework.shard_manager(),
-    };
+        framework.shard_manager(),
+    );
     crate::dispatch_event(framework, &ctx, event).await;
+
+    // Every event is dispatched in its own task, so we can keep this one alive to drive the
+    // background tasks, including the scheduled commands
+    if is_first_ready {
+        crate::run_background_tasks(framework, &ctx).await;
+    }
 }
 
 /// Traverses commands recursively and sets [`crate::Command::qualified_name`] to its actual value
@@ -231,6 +316,7 @@ fn message_content_intent_sanity_check<U, E>(
 ) {
     let is_prefix_configured = prefix_options.prefix.is_some()
         || prefix_options.dynamic_prefix.is_some()
+        || prefix_options.dynamic_prefixes.is_some()
         || prefix_options.stripped_dynamic_prefix.is_some();
     let can_receive_message_content = intents.contains(serenity::GatewayIntents::MESSAGE_CONTENT);
     if is_prefix_configured && !can_receive_message_content {
@@ -246,10 +332,23 @@ pub async fn insert_owners_from_http(
     http: &serenity::Http,
     owners: &mut std::collections::HashSet<serenity::UserId>,
     initialized_teams: &Option<Vec<serenity::TeamMemberRole>>,
+) -> Result<(), serenity::Error> {
+    insert_owners_from_http_with(http, owners, initialized_teams, true).await
+}
+
+/// Like [`insert_owners_from_http`], but lets you exclude the application owner, see
+/// [`crate::FrameworkOptions::initialize_application_owner`]
+///
+/// Existing entries in `owners` are kept.
+pub async fn insert_owners_from_http_with(
+    http: &serenity::Http,
+    owners: &mut std::collections::HashSet<serenity::UserId>,
+    initialized_teams: &Option<Vec<serenity::TeamMemberRole>>,
+    include_application_owner: bool,
 ) -> Result<(), serenity::Error> {
     let application_info = http.get_current_application_info().await?;
 
-    if let Some(owner) = application_info.owner {
+    if let Some(owner) = application_info.owner.filter(|_| include_application_owner) {
         owners.insert(owner.id);
     }
 
@@ -269,7 +368,7 @@ pub async fn insert_owners_from_http(
             };
 
             // s has specified the teams they want to be considered "Owner".
-            if initialized_teams.iter().any(|r| *r == member.role) {
+            if initialized_teams.contains(&member.role) {
                 owners.insert(member.user.id);
             }
         }
@@ -281,19 +380,14 @@ pub async fn insert_owners_from_http(
 /// Spawns a background task that periodically purges outdated entries from the edit tracker cache
 ///
 /// Important to avoid the edit tracker gobbling up unlimited memory
-///
-/// NOT PUB because it's not useful to outside users because it requires a full blown Framework
-/// Because e.g. taking a `PrefixFrameworkOptions` reference won't work because tokio tasks need to be
-/// 'static
-fn spawn_edit_tracker_purge_task(
-    edit_tracker: Arc<std::sync::RwLock<crate::EditTracker>>,
-) -> tokio::task::JoinHandle<()> {
-    tokio::spawn(async move {
-        loop {
-            edit_tracker.write().unwrap().purge();
-
-            // not sure if the purging interval should be configurable
-            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
-        }
-    })
+fn spawn_edit_tracker_purge_task<U, E>(options: &crate::FrameworkOptions<U, E>) {
+    // not sure if the purging interval should be configurable
+    options.spawn_periodic(std::time::Duration::from_secs(60), |ctx| {
+        Box::pin(async move {
+            if let Some(edit_tracker) = &ctx.framework.options.prefix_options.edit_tracker {
+                edit_tracker.write().unwrap().purge();
+            }
+            Ok(())
+        })
+    });
 }
@@ -3,6 +3,7 @@
 // native #[non_exhaustive] is awful because you can't do struct update syntax with it (??)
 #![allow(clippy::manual_non_exhaustive)]
 #![allow(clippy::type_complexity)]
+#![allow(clippy::test_attr_in_doctest)] // the crate docs showcase how to unit test a command
 #![warn(
     clippy::missing_docs_in_private_items,
     clippy::unused_async,
@@ -388,17 +389,31 @@ underlying this framework, so that's what I chose.
 Also, poise is a stat in Dark Souls
 */
 
+pub mod autocomplete;
 pub mod builtins;
 pub mod choice_parameter;
+pub mod components;
 pub mod cooldown;
+pub mod custom_parser;
 pub mod dispatch;
 pub mod framework;
+pub mod localization;
+pub mod metrics;
+pub mod middleware;
 pub mod modal;
+pub mod observer;
+mod otel;
 pub mod prefix_argument;
 pub mod reply;
+pub mod scheduler;
 pub mod slash_argument;
+pub mod stats;
 pub mod structs;
+pub mod tasks;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod track_edits;
+pub mod types;
 pub mod macros {
     //! Procedural macros used in poise, like [`command`]
     #[doc(inline)]
@@ -407,8 +422,9 @@ pub mod macros {
 
 #[doc(no_inline)]
 pub use {
-    choice_parameter::*, cooldown::*, dispatch::*, framework::*, macros::*, modal::*,
-    prefix_argument::*, reply::*, slash_argument::*, structs::*, track_edits::*,
+    choice_parameter::*, cooldown::*, custom_parser::*, dispatch::*, framework::*, macros::*,
+    metrics::*, middleware::*, modal::*, observer::*, prefix_argument::*, reply::*, scheduler::*,
+    slash_argument::*, structs::*, tasks::*, track_edits::*,
 };
 
 /// See [`builtins`]
@@ -420,6 +436,9 @@ pub mod samples {
 #[doc(hidden)]
 pub use {async_trait::async_trait, futures_util};
 
+#[doc(no_inline)]
+pub use tokio_util::sync::CancellationToken;
+
 /// This module re-exports a bunch of items from all over serenity. Useful if you can't
 /// remember the full paths of serenity items.
 ///
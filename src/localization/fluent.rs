@@ -0,0 +1,389 @@
+//! Localization based on [Fluent](https://projectfluent.org/) translation files
+//!
+//! Each `.ftl` file in a translations folder is named after the locale it translates, for example
+//! `en-US.ftl` or `de.ftl`. Command metadata is looked up under the command name:
+//!
+//! ```ftl
+//! welcome = welcome
+//!     .description = Welcomes a user
+//!     .help = Sends a welcome message mentioning the given user
+//!     .user = user
+//!     .user-description = The user to welcome
+//! ```
+//!
+//! The `.help` attribute is optional and provides the command's help text.
+//!
+//! Subcommands use their parent's identifier as a prefix, e.g. `config-set` for `/config set`.
+//! Choice parameter values are looked up under their name.
+
+pub use ::fluent::{FluentArgs, FluentResource, FluentValue};
+
+/// A thread-safe Fluent bundle holding the messages of a single locale
+pub type FluentBundle =
+    ::fluent::bundle::FluentBundle<FluentResource, intl_memoizer::concurrent::IntlLangMemoizer>;
+
+/// Error that occurred while loading `.ftl` translation files
+#[derive(Debug)]
+pub enum LoadError {
+    /// A translation file or directory couldn't be read
+    Io(std::io::Error),
+    /// A translation file name isn't a valid locale identifier
+    InvalidLocale(String),
+    /// A translation file has syntax errors
+    Parse {
+        /// Path of the file that failed to parse
+        path: std::path::PathBuf,
+        /// Descriptions of the errors reported by the Fluent parser
+        errors: Vec<String>,
+    },
+    /// A translation file defines the same message multiple times
+    Conflict {
+        /// Path of the file with the conflicting messages
+        path: std::path::PathBuf,
+    },
+    /// The main locale passed to [`Translations::load_dir`] has no translation file
+    MissingMainLocale(String),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read translation file: {}", e),
+            Self::InvalidLocale(locale) => write!(f, "invalid locale `{}`", locale),
+            Self::Parse { path, errors } => {
+                write!(
+                    f,
+                    "failed to parse {}: {}",
+                    path.display(),
+                    errors.join(", ")
+                )
+            }
+            Self::Conflict { path } => {
+                write!(f, "duplicate message definitions in {}", path.display())
+            }
+            Self::MissingMainLocale(locale) => {
+                write!(f, "no translation file for main locale `{}`", locale)
+            }
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// A set of Fluent bundles, one per locale, with a main locale that is used as fallback and for
+/// the non-localized command strings.
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # type Data = (); type Error = ();
+/// # let mut commands: Vec<poise::Command<Data, Error>> = vec![];
+/// use poise::localization::fluent::Translations;
+///
+/// let translations = Translations::load_dir("translations", "en-US")?;
+/// translations.apply_to_commands(&mut commands);
+/// # Ok(()) }
+/// ```
+pub struct Translations {
+    /// Locale identifier of the fallback bundle. Always a key in [`Self::bundles`]
+    main_locale: String,
+    /// Bundles of all locales, keyed by Discord locale identifier
+    bundles: std::collections::HashMap<String, FluentBundle>,
+}
+
+impl std::fmt::Debug for Translations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Translations")
+            .field("main_locale", &self.main_locale)
+            .field("locales", &self.bundles.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses a single `.ftl` file into a bundle for the given locale
+fn bundle_from_file(locale: &str, path: &std::path::Path) -> Result<FluentBundle, LoadError> {
+    let file_contents = std::fs::read_to_string(path)?;
+    let resource =
+        FluentResource::try_new(file_contents).map_err(|(_, errors)| LoadError::Parse {
+            path: path.to_owned(),
+            errors: errors.iter().map(|e| e.to_string()).collect(),
+        })?;
+
+    let langid = locale
+        .parse()
+        .map_err(|_| LoadError::InvalidLocale(locale.to_owned()))?;
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    bundle
+        .add_resource(resource)
+        .map_err(|_| LoadError::Conflict {
+            path: path.to_owned(),
+        })?;
+    Ok(bundle)
+}
+
+impl Translations {
+    /// Loads every `.ftl` file in the given directory. The file stem is used as locale identifier.
+    ///
+    /// `main_locale` selects the bundle which is used as fallback for missing translations and
+    /// for the non-localized command names and descriptions.
+    pub fn load_dir(
+        dir: impl AsRef<std::path::Path>,
+        main_locale: &str,
+    ) -> Result<Self, LoadError> {
+        let mut bundles = std::collections::HashMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "ftl") {
+                continue;
+            }
+            let locale = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| LoadError::InvalidLocale(path.display().to_string()))?
+                .to_owned();
+
+            let bundle = bundle_from_file(&locale, &path)?;
+            bundles.insert(locale, bundle);
+        }
+
+        if !bundles.contains_key(main_locale) {
+            return Err(LoadError::MissingMainLocale(main_locale.to_owned()));
+        }
+        Ok(Self {
+            main_locale: main_locale.to_owned(),
+            bundles,
+        })
+    }
+
+    /// Returns the bundle of the main locale
+    fn main(&self) -> &FluentBundle {
+        &self.bundles[&self.main_locale]
+    }
+
+    /// Returns the main locale identifier
+    pub fn main_locale(&self) -> &str {
+        &self.main_locale
+    }
+
+    /// Returns the bundle for the given locale, if a translation file was loaded for it
+    pub fn bundle(&self, locale: &str) -> Option<&FluentBundle> {
+        self.bundles.get(locale)
+    }
+
+    /// Formats a message, or one of its attributes if `attr` is given, in the given locale.
+    ///
+    /// Falls back to the main locale if the message is not translated in `locale`. Returns None if
+    /// the message doesn't exist in either.
+    pub fn format(
+        &self,
+        locale: Option<&str>,
+        id: &str,
+        attr: Option<&str>,
+        args: Option<&FluentArgs<'_>>,
+    ) -> Option<String> {
+        locale
+            .and_then(|locale| format(self.bundles.get(locale)?, id, attr, args))
+            .or_else(|| format(self.main(), id, attr, args))
+    }
+
+    /// Like [`Self::format`], but falls back to the message identifier itself if the message
+    /// doesn't exist
+    pub fn get(&self, locale: Option<&str>, id: &str, args: Option<&FluentArgs<'_>>) -> String {
+        self.format(locale, id, None, args).unwrap_or_else(|| {
+            tracing::warn!("unknown fluent message identifier `{}`", id);
+            id.to_owned()
+        })
+    }
+
+    /// Fills in command names, descriptions and their localizations for the given commands and
+    /// all their subcommands.
+    ///
+    /// Commands without a message in the main locale are left untouched.
+    pub fn apply_to_commands<U, E>(&self, commands: &mut [crate::Command<U, E>]) {
+        for command in commands {
+            self.apply_to_command(&command.name.clone(), command);
+        }
+    }
+
+    /// Applies translations to a single command, looked up under the message identifier `id`,
+    /// and recurses into its subcommands
+    fn apply_to_command<U, E>(&self, id: &str, command: &mut crate::Command<U, E>) {
+        for subcommand in &mut command.subcommands {
+            let subcommand_id = format!("{}-{}", id, subcommand.name);
+            self.apply_to_command(&subcommand_id, subcommand);
+        }
+
+        let main = self.main();
+        if main.get_message(id).is_none() {
+            return;
+        }
+
+        for (locale, bundle) in &self.bundles {
+            apply_bundle(bundle, locale, id, command);
+        }
+
+        // Set fallback strings for users with locales we don't have translations for
+        if let Some(name) = format(main, id, None, None) {
+            command.name = name;
+        }
+        if let Some(description) = format(main, id, Some("description"), None) {
+            command.description = Some(description);
+        }
+        if let Some(help_text) = format(main, id, Some("help"), None) {
+            command.help_text = Some(help_text);
+        }
+        for parameter in &mut command.parameters {
+            let description_attr = format!("{}-description", parameter.name);
+            if let Some(description) = format(main, id, Some(&description_attr), None) {
+                parameter.description = Some(description);
+            }
+            if let Some(name) = format(main, id, Some(&parameter.name), None) {
+                parameter.name = name;
+            }
+            for choice in &mut parameter.choices {
+                if let Some(name) = format(main, &choice.name, None, None) {
+                    choice.name = name;
+                }
+            }
+        }
+    }
+}
+
+/// Inserts the localizations of a single locale into the command
+fn apply_bundle<U, E>(
+    bundle: &FluentBundle,
+    locale: &str,
+    id: &str,
+    command: &mut crate::Command<U, E>,
+) {
+    let localize = |map: &mut std::collections::HashMap<String, String>, attr: Option<&str>| {
+        if let Some(text) = format(bundle, id, attr, None) {
+            map.insert(locale.to_owned(), text);
+        }
+    };
+
+    localize(&mut command.name_localizations, None);
+    localize(&mut command.description_localizations, Some("description"));
+    localize(&mut command.help_text_localizations, Some("help"));
+    for parameter in &mut command.parameters {
+        localize(&mut parameter.name_localizations, Some(&parameter.name));
+        localize(
+            &mut parameter.description_localizations,
+            Some(&format!("{}-description", parameter.name)),
+        );
+        for choice in &mut parameter.choices {
+            if let Some(name) = format(bundle, &choice.name, None, None) {
+                choice.localizations.insert(locale.to_owned(), name);
+            }
+        }
+    }
+}
+
+/// Formats a message, or one of its attributes if `attr` is given, from a single bundle
+pub fn format(
+    bundle: &FluentBundle,
+    id: &str,
+    attr: Option<&str>,
+    args: Option<&FluentArgs<'_>>,
+) -> Option<String> {
+    let message = bundle.get_message(id)?;
+    let pattern = match attr {
+        Some(attribute) => message.get_attribute(attribute)?.value(),
+        None => message.value()?,
+    };
+    let formatted = bundle.format_pattern(pattern, args, &mut vec![]);
+    Some(formatted.into_owned())
+}
+
+impl<U: AsRef<Translations>, E> crate::Context<'_, U, E> {
+    /// Resolves a message in the invoking user's locale, see [`tr()`]. For the arguments, the
+    /// [`crate::tr`] macro is usually more convenient.
+    ///
+    /// ```rust,no_run
+    /// # use poise::localization::fluent::{FluentArgs, Translations};
+    /// # struct Data(Translations);
+    /// # impl AsRef<Translations> for Data { fn as_ref(&self) -> &Translations { &self.0 } }
+    /// # async fn _f(ctx: poise::Context<'_, Data, ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let mut args = FluentArgs::new();
+    /// args.set("name", ctx.author().name.as_str());
+    /// ctx.say(ctx.t("greeting", Some(&args))).await?;
+    /// ctx.say(ctx.t("goodbye", None)).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn t(self, id: &str, args: Option<&FluentArgs<'_>>) -> String {
+        tr(self, id, args)
+    }
+}
+
+/// Resolves a message in the invoking user's locale, as given by [`crate::Context::locale`].
+///
+/// If there's no translation file for the user's locale, the other locales in its
+/// [fallback chain](crate::FrameworkOptions::locale_fallbacks) are tried before the main locale.
+///
+/// Your user data type must provide the loaded [`Translations`] via [`AsRef`]. See [`crate::tr`]
+/// for a more convenient way to pass arguments.
+pub fn tr<U: AsRef<Translations>, E>(
+    ctx: crate::Context<'_, U, E>,
+    id: &str,
+    args: Option<&FluentArgs<'_>>,
+) -> String {
+    let translations = ctx.data().as_ref();
+    let fallbacks = &ctx.framework().options().locale_fallbacks;
+    let locale = ctx.locale().and_then(|locale| {
+        fallbacks
+            .chain(locale)
+            .into_iter()
+            .find(|&locale| translations.bundle(locale).is_some())
+    });
+    translations.get(locale, id, args)
+}
+
+/// Retrieves a translated message in the invoking user's locale, optionally with arguments. Use
+/// like:
+/// - `tr!(ctx, "identifier")` (no arguments)
+/// - `tr!(ctx, "identifier", arg1: VALUE1, arg2: VALUE2)` (with arguments)
+///
+/// Your user data type must implement `AsRef<poise::localization::fluent::Translations>`.
+///
+/// ```rust,no_run
+/// # use poise::localization::fluent::Translations;
+/// struct Data {
+///     translations: Translations,
+/// }
+/// impl AsRef<Translations> for Data {
+///     fn as_ref(&self) -> &Translations {
+///         &self.translations
+///     }
+/// }
+///
+/// # async fn _f(ctx: poise::Context<'_, Data, ()>) {
+/// let greeting = poise::tr!(ctx, "greeting", name: ctx.author().name.as_str());
+/// # }
+/// ```
+///
+/// Doesn't support retrieving message attributes
+#[macro_export]
+macro_rules! tr {
+    ( $ctx:expr, $id:expr $(, $argname:ident: $argvalue:expr )* $(,)? ) => {{
+        #[allow(unused_mut)]
+        let mut args = $crate::localization::fluent::FluentArgs::new();
+        $( args.set(stringify!($argname), $argvalue); )*
+
+        $crate::localization::fluent::tr($ctx, $id, Some(&args))
+    }};
+}
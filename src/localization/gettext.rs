@@ -0,0 +1,101 @@
+//! Localization based on [gettext](https://www.gnu.org/software/gettext/) PO catalogs
+//!
+//! In gettext, the strings as written in the source code serve as message identifiers. So, each
+//! catalog is expected to contain the command names, descriptions, parameter names, parameter
+//! descriptions and choice names as they appear in your code as `msgid`s:
+//!
+//! ```po
+//! msgid "welcome"
+//! msgstr "begrüßen"
+//!
+//! msgid "Welcomes a user"
+//! msgstr "Begrüßt einen Nutzer"
+//! ```
+//!
+//! Help texts are looked up as a whole, so the `msgid` must contain the complete help text.
+//!
+//! Untranslated and fuzzy entries are ignored.
+
+pub use polib::{catalog::Catalog, po_file::POParseError};
+
+/// Loads every `.po` file in the given directory into a catalog, keyed by the file stem, which
+/// must be the Discord locale identifier (for example `de.po` or `es-ES.po`)
+pub fn load_po_dir(
+    dir: impl AsRef<std::path::Path>,
+) -> Result<std::collections::HashMap<String, Catalog>, POParseError> {
+    let mut catalogs = std::collections::HashMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "po") {
+            continue;
+        }
+        let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        catalogs.insert(locale.to_owned(), polib::po_file::parse(&path)?);
+    }
+    Ok(catalogs)
+}
+
+/// Looks up the translation of `msgid`. Returns None for missing, untranslated or fuzzy entries
+pub fn translate(catalog: &Catalog, msgid: &str) -> Option<String> {
+    let message = catalog.find_message(None, msgid, None)?;
+    if !message.is_translated() || message.is_fuzzy() {
+        return None;
+    }
+    message.msgstr().ok().map(|msgstr| msgstr.to_owned())
+}
+
+/// Applies all given catalogs to the commands. See [`apply_catalog`]
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut commands: Vec<poise::Command<(), ()>> = vec![];
+/// let catalogs = poise::localization::gettext::load_po_dir("locales")?;
+/// poise::localization::gettext::apply_catalogs(&catalogs, &mut commands);
+/// # Ok(()) }
+/// ```
+pub fn apply_catalogs<U, E>(
+    catalogs: &std::collections::HashMap<String, Catalog>,
+    commands: &mut [crate::Command<U, E>],
+) {
+    for (locale, catalog) in catalogs {
+        apply_catalog(locale, catalog, commands);
+    }
+}
+
+/// Fills in the localizations for `locale` of the given commands and all their subcommands:
+/// command names, descriptions and help texts, parameter names and descriptions, and choice names.
+///
+/// Strings without a translation in the catalog are left untouched.
+pub fn apply_catalog<U, E>(locale: &str, catalog: &Catalog, commands: &mut [crate::Command<U, E>]) {
+    let localize = |map: &mut std::collections::HashMap<String, String>, msgid: Option<&str>| {
+        if let Some(translation) = msgid.and_then(|msgid| translate(catalog, msgid)) {
+            map.insert(locale.to_owned(), translation);
+        }
+    };
+
+    for command in commands {
+        apply_catalog(locale, catalog, &mut command.subcommands);
+
+        localize(&mut command.name_localizations, Some(&command.name));
+        localize(
+            &mut command.description_localizations,
+            command.description.as_deref(),
+        );
+        localize(
+            &mut command.help_text_localizations,
+            command.help_text.as_deref(),
+        );
+        for parameter in &mut command.parameters {
+            localize(&mut parameter.name_localizations, Some(&parameter.name));
+            localize(
+                &mut parameter.description_localizations,
+                parameter.description.as_deref(),
+            );
+            for choice in &mut parameter.choices {
+                localize(&mut choice.localizations, Some(&choice.name));
+            }
+        }
+    }
+}
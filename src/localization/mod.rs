@@ -0,0 +1,101 @@
+//! Utilities for localizing command metadata and responses
+
+#[cfg(feature = "fluent")]
+pub mod fluent;
+#[cfg(feature = "gettext")]
+pub mod gettext;
+
+/// All locales supported by Discord, see <https://discord.com/developers/docs/reference#locales>
+pub const DISCORD_LOCALES: &[&str] = &[
+    "id", "da", "de", "en-GB", "en-US", "es-ES", "es-419", "fr", "hr", "it", "lt", "hu", "nl",
+    "no", "pl", "pt-BR", "ro", "fi", "sv-SE", "vi", "tr", "cs", "el", "bg", "ru", "uk", "hi", "th",
+    "zh-CN", "ja", "zh-TW", "ko",
+];
+
+/// Returns the language part of a locale identifier, e.g. `en` for `en-GB`
+fn language(locale: &str) -> &str {
+    locale.split('-').next().unwrap_or(locale)
+}
+
+/// Configures which locales are tried, in order, when a localization is missing for the user's
+/// locale.
+///
+/// For a given locale, the chain is:
+/// 1. the locale itself
+/// 2. the locales configured in [`Self::fallbacks`] for it
+/// 3. if [`Self::match_language`] is set, the other Discord locales of the same language (e.g.
+///    `en-US` for `en-GB`)
+/// 4. [`Self::default_locales`]
+///
+/// If no locale in the chain has a localization, the non-localized string is used.
+///
+/// ```rust
+/// # use std::collections::HashMap;
+/// let fallbacks = poise::localization::LocaleFallbacks::default();
+/// let localizations = HashMap::from([("en-US".to_string(), "color".to_string())]);
+/// assert_eq!(fallbacks.resolve("en-GB", &localizations).map(|s| s.as_str()), Some("color"));
+/// assert_eq!(fallbacks.resolve("de", &localizations), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocaleFallbacks {
+    /// Explicit fallback locales, keyed by the locale they apply to.
+    ///
+    /// For example, `"pt-BR" => vec!["es-ES"]`
+    pub fallbacks: std::collections::HashMap<String, Vec<String>>,
+    /// Whether to fall back to other Discord locales of the same language. True by default
+    pub match_language: bool,
+    /// Locales to try after all others, usually the main language of your bot. Empty by default
+    pub default_locales: Vec<String>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for LocaleFallbacks {
+    fn default() -> Self {
+        Self {
+            fallbacks: std::collections::HashMap::new(),
+            match_language: true,
+            default_locales: Vec::new(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl LocaleFallbacks {
+    /// Returns the locales to try for the given locale, in order, starting with the locale itself
+    pub fn chain<'a>(&'a self, locale: &'a str) -> Vec<&'a str> {
+        let mut chain = vec![locale];
+        let mut push = |candidate: &'a str| {
+            if !chain.contains(&candidate) {
+                chain.push(candidate);
+            }
+        };
+
+        for fallback in self.fallbacks.get(locale).into_iter().flatten() {
+            push(fallback);
+        }
+        if self.match_language {
+            for &sibling in DISCORD_LOCALES {
+                if language(sibling) == language(locale) {
+                    push(sibling);
+                }
+            }
+        }
+        for default in &self.default_locales {
+            push(default);
+        }
+        chain
+    }
+
+    /// Looks up the first locale of the fallback chain that has an entry in `localizations`
+    pub fn resolve<'m, V>(
+        &self,
+        locale: &str,
+        localizations: &'m std::collections::HashMap<String, V>,
+    ) -> Option<&'m V> {
+        self.chain(locale)
+            .into_iter()
+            .find_map(|locale| localizations.get(locale))
+    }
+}
@@ -0,0 +1,274 @@
+//! Hooks for recording metrics like invocation counts and command durations, see [`MetricsSink`]
+//! and [`MetricsObserver`]
+
+use crate::serenity_prelude as serenity;
+
+/// Identifies the command of an invocation in [`MetricsSink`] callbacks. Both fields have low
+/// cardinality, so they can be used as metric labels directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricLabels<'a> {
+    /// Qualified name of the command, e.g. `"config set"`
+    pub command: &'a str,
+    /// How the command was invoked: `"prefix"`, `"slash"` or `"context_menu"`
+    pub kind: &'static str,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// How a command invocation ended, see [`MetricsSink::command_completed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandOutcome {
+    /// The command ran and returned Ok
+    Success,
+    /// The invocation was denied by a check, missing permissions, a cooldown etc. before the
+    /// command ran
+    Denied {
+        /// [`crate::FrameworkError::code`] of the denial, e.g. `"cooldown_hit"`
+        code: &'static str,
+    },
+    /// The invocation failed, for example because the command returned an error, the arguments
+    /// were invalid or the command panicked
+    Failed {
+        /// [`crate::FrameworkError::code`] of the failure, e.g. `"command"` or `"argument_parse"`
+        code: &'static str,
+    },
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl CommandOutcome {
+    /// Determines the outcome from the result of an invocation
+    pub(crate) fn of<U, E>(result: &Result<(), crate::FrameworkError<'_, U, E>>) -> Self {
+        match result {
+            Ok(()) => Self::Success,
+            Err(error) => {
+                let code = error.code();
+                match error.denial_reason() {
+                    Some(_) => Self::Denied { code },
+                    None => Self::Failed { code },
+                }
+            }
+        }
+    }
+
+    /// Returns `"success"`, `"denied"` or `"failed"`, for use as a metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Denied { .. } => "denied",
+            Self::Failed { .. } | Self::__NonExhaustive => "failed",
+        }
+    }
+}
+
+/// Receives metric events from the framework, for forwarding them to Prometheus, StatsD or
+/// similar. Register it wrapped in a [`MetricsObserver`]; with the `metrics` feature,
+/// [`MetricsRecorder`] forwards them to the [`metrics`](https://docs.rs/metrics) crate.
+///
+/// All methods have no-op default implementations. Like [`crate::InvocationObserver`]s, the
+/// callbacks are called inline during dispatch, so they should return quickly. Unlike
+/// observers, they don't get the invocation context, which keeps this trait free of the
+/// framework's type parameters.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// #[derive(Default)]
+/// struct Counters {
+///     invocations: AtomicU64,
+///     failures: AtomicU64,
+/// }
+///
+/// impl poise::MetricsSink for Counters {
+///     fn command_completed(
+///         &self,
+///         _: poise::MetricLabels<'_>,
+///         _: std::time::Duration,
+///         outcome: poise::CommandOutcome,
+///     ) {
+///         self.invocations.fetch_add(1, Ordering::Relaxed);
+///         if outcome != poise::CommandOutcome::Success {
+///             self.failures.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+///
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     invocation_observers: vec![Box::new(poise::MetricsObserver(Counters::default()))],
+///     ..Default::default()
+/// };
+/// ```
+pub trait MetricsSink: Send + Sync {
+    /// Called when an invocation was matched to a command, before any checks run
+    fn command_started(&self, labels: MetricLabels<'_>) {
+        let _ = labels;
+    }
+
+    /// Called when an invocation ended, with the time since [`Self::command_started`]
+    fn command_completed(
+        &self,
+        labels: MetricLabels<'_>,
+        duration: std::time::Duration,
+        outcome: CommandOutcome,
+    ) {
+        let _ = (labels, duration, outcome);
+    }
+
+    /// Called when the arguments of an invocation couldn't be parsed. Called before
+    /// [`Self::command_completed`]
+    fn argument_parse_failed(&self, labels: MetricLabels<'_>) {
+        let _ = labels;
+    }
+
+    /// Called when an invocation was denied because of an active cooldown. Called before
+    /// [`Self::command_completed`]
+    fn cooldown_hit(&self, labels: MetricLabels<'_>, remaining_cooldown: std::time::Duration) {
+        let _ = (labels, remaining_cooldown);
+    }
+
+    /// Called for every gateway event the framework receives, with
+    /// [`serenity::FullEvent::snake_case_name`]
+    fn event_received(&self, event: &str) {
+        let _ = event;
+    }
+}
+
+/// Returns how the command was invoked: `"prefix"`, `"slash"`, `"context_menu"` or
+/// `"autocomplete"`
+pub(crate) fn invocation_kind<U, E>(ctx: crate::Context<'_, U, E>) -> &'static str {
+    match ctx {
+        crate::Context::Prefix(_) => "prefix",
+        crate::Context::Application(ctx) => {
+            match (ctx.interaction_type, ctx.interaction.data.kind) {
+                (crate::CommandInteractionType::Autocomplete, _) => "autocomplete",
+                (_, serenity::CommandType::ChatInput) => "slash",
+                _ => "context_menu",
+            }
+        }
+    }
+}
+
+/// Labels of the invocation's command
+fn labels<U, E>(ctx: crate::Context<'_, U, E>) -> MetricLabels<'_> {
+    MetricLabels {
+        command: &ctx.command().qualified_name,
+        kind: invocation_kind(ctx),
+        __non_exhaustive: (),
+    }
+}
+
+/// [`crate::InvocationObserver`] that reports invocations and events to the wrapped
+/// [`MetricsSink`]. Register it in [`crate::FrameworkOptions::invocation_observers`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsObserver<S>(pub S);
+
+impl<U, E, S: MetricsSink> crate::InvocationObserver<U, E> for MetricsObserver<S> {
+    fn on_parsed(&self, ctx: crate::Context<'_, U, E>) {
+        self.0.command_started(labels(ctx));
+    }
+
+    fn on_completed(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        duration: std::time::Duration,
+        result: &Result<(), crate::FrameworkError<'_, U, E>>,
+    ) {
+        let labels = labels(ctx);
+        match result {
+            Err(crate::FrameworkError::ArgumentParse { .. }) => {
+                self.0.argument_parse_failed(labels)
+            }
+            Err(crate::FrameworkError::CooldownHit {
+                remaining_cooldown, ..
+            }) => self.0.cooldown_hit(labels, *remaining_cooldown),
+            _ => {}
+        }
+        self.0
+            .command_completed(labels, duration, CommandOutcome::of(result));
+    }
+
+    fn on_event(&self, event: &serenity::FullEvent) {
+        self.0.event_received(event.snake_case_name());
+    }
+}
+
+/// [`MetricsSink`] that records metrics with the [`metrics`](https://docs.rs/metrics) crate, so
+/// that they can be exported with any of its exporters, like `metrics-exporter-prometheus`.
+/// Register it as `MetricsObserver(MetricsRecorder)`.
+///
+/// Records the following metrics:
+/// - `poise_commands_started_total` (counter; labels `command`, `kind`)
+/// - `poise_commands_completed_total` (counter; labels `command`, `kind`, `outcome`, `error`)
+/// - `poise_command_duration_seconds` (histogram; labels `command`, `kind`, `outcome`)
+/// - `poise_argument_parse_failures_total` (counter; labels `command`, `kind`)
+/// - `poise_cooldown_hits_total` (counter; labels `command`, `kind`)
+/// - `poise_events_received_total` (counter; label `event`)
+///
+/// The `error` label is the [`crate::FrameworkError::code`] of denied and failed invocations, and
+/// empty for successful ones.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsRecorder;
+
+#[cfg(feature = "metrics")]
+impl MetricsSink for MetricsRecorder {
+    fn command_started(&self, labels: MetricLabels<'_>) {
+        ::metrics::counter!(
+            "poise_commands_started_total",
+            "command" => labels.command.to_owned(),
+            "kind" => labels.kind,
+        )
+        .increment(1);
+    }
+
+    fn command_completed(
+        &self,
+        labels: MetricLabels<'_>,
+        duration: std::time::Duration,
+        outcome: CommandOutcome,
+    ) {
+        let error = match outcome {
+            CommandOutcome::Denied { code } | CommandOutcome::Failed { code } => code,
+            CommandOutcome::Success | CommandOutcome::__NonExhaustive => "",
+        };
+        ::metrics::counter!(
+            "poise_commands_completed_total",
+            "command" => labels.command.to_owned(),
+            "kind" => labels.kind,
+            "outcome" => outcome.as_str(),
+            "error" => error,
+        )
+        .increment(1);
+        ::metrics::histogram!(
+            "poise_command_duration_seconds",
+            "command" => labels.command.to_owned(),
+            "kind" => labels.kind,
+            "outcome" => outcome.as_str(),
+        )
+        .record(duration.as_secs_f64());
+    }
+
+    fn argument_parse_failed(&self, labels: MetricLabels<'_>) {
+        ::metrics::counter!(
+            "poise_argument_parse_failures_total",
+            "command" => labels.command.to_owned(),
+            "kind" => labels.kind,
+        )
+        .increment(1);
+    }
+
+    fn cooldown_hit(&self, labels: MetricLabels<'_>, _: std::time::Duration) {
+        ::metrics::counter!(
+            "poise_cooldown_hits_total",
+            "command" => labels.command.to_owned(),
+            "kind" => labels.kind,
+        )
+        .increment(1);
+    }
+
+    fn event_received(&self, event: &str) {
+        ::metrics::counter!("poise_events_received_total", "event" => event.to_owned())
+            .increment(1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Wrapping command execution in reusable layers, see [`CommandMiddleware`]
+
+use crate::BoxFuture;
+
+/// The command action at the end of a middleware chain. Can be called multiple times, e.g. by
+/// retrying middleware
+type Action<'n, 'a, U, E> =
+    &'n (dyn Fn() -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>> + Send + Sync);
+
+/// A layer around command execution, for example for timing, tracing spans, retries or rejecting
+/// invocations with custom errors.
+///
+/// Each middleware receives the invocation context and a [`Next`] continuation that runs the rest
+/// of the chain and finally the command. A middleware can run code before and after calling
+/// [`Next::run`], call it multiple times, or not call it at all to short-circuit the invocation.
+///
+/// Register global middleware in [`crate::FrameworkOptions::middleware`] and per-command
+/// middleware via `#[poise::command(middleware = "...")]` or [`crate::Command::middleware`].
+/// Global middleware runs first, in list order, followed by the command's middleware.
+///
+/// The chain runs after the checks and [`crate::FrameworkOptions::pre_command`], and errors it
+/// returns are handled like command errors.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// struct Timing;
+///
+/// #[poise::async_trait]
+/// impl<U: Send + Sync, E> poise::CommandMiddleware<U, E> for Timing {
+///     async fn handle<'a>(
+///         &self,
+///         ctx: poise::Context<'a, U, E>,
+///         next: poise::Next<'_, 'a, U, E>,
+///     ) -> Result<(), poise::FrameworkError<'a, U, E>> {
+///         let start = std::time::Instant::now();
+///         let result = next.run().await;
+///         println!("{} took {:?}", ctx.command().qualified_name, start.elapsed());
+///         result
+///     }
+/// }
+///
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     middleware: vec![Box::new(Timing)],
+///     ..Default::default()
+/// };
+/// ```
+#[async_trait::async_trait]
+pub trait CommandMiddleware<U, E>: Send + Sync {
+    /// Handles an invocation. Call [`Next::run`] to continue with the rest of the chain
+    async fn handle<'a>(
+        &self,
+        ctx: crate::Context<'a, U, E>,
+        next: Next<'_, 'a, U, E>,
+    ) -> Result<(), crate::FrameworkError<'a, U, E>>;
+}
+
+/// Continuation passed to [`CommandMiddleware::handle`], which runs the rest of the middleware
+/// chain and finally the command
+pub struct Next<'n, 'a, U, E> {
+    /// Context of the invocation
+    ctx: crate::Context<'a, U, E>,
+    /// Middleware that hasn't run yet
+    middleware: &'n [&'a dyn CommandMiddleware<U, E>],
+    /// The command action
+    action: Action<'n, 'a, U, E>,
+}
+
+impl<U, E> Copy for Next<'_, '_, U, E> {}
+impl<U, E> Clone for Next<'_, '_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'n, 'a: 'n, U, E> Next<'n, 'a, U, E> {
+    /// Runs the rest of the chain and the command. Can be called multiple times, for example to
+    /// retry failed invocations
+    pub fn run(self) -> BoxFuture<'n, Result<(), crate::FrameworkError<'a, U, E>>> {
+        match self.middleware.split_first() {
+            Some((middleware, rest)) => middleware.handle(
+                self.ctx,
+                Next {
+                    middleware: rest,
+                    ..self
+                },
+            ),
+            None => (self.action)(),
+        }
+    }
+}
+
+/// Runs the command action through the global middleware and the command's middleware
+pub(crate) async fn run_with_middleware<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    action: Action<'_, 'a, U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let command = ctx.command();
+    let global = &ctx.framework().options().middleware;
+    if global.is_empty() && command.middleware.is_empty() {
+        return action().await;
+    }
+
+    let middleware = global
+        .iter()
+        .chain(&command.middleware)
+        .map(|middleware| &**middleware)
+        .collect::<Vec<_>>();
+    Next {
+        ctx,
+        middleware: &middleware,
+        action,
+    }
+    .run()
+    .await
+}
@@ -38,6 +38,47 @@ pub fn find_modal_text(
     None
 }
 
+/// Meant for use in derived [`ModalValidation::validate`] implementations
+///
+/// Checks that the whole value matches the regex `pattern`
+#[doc(hidden)]
+pub fn validate_modal_regex(value: &str, pattern: &str, label: &str) -> Result<(), String> {
+    let regex = match regex::Regex::new(&format!("^(?:{})$", pattern)) {
+        Ok(regex) => regex,
+        Err(e) => {
+            tracing::warn!("invalid regex for modal field {}: {}", label, e);
+            return Err(format!("{} can't be validated", label));
+        }
+    };
+    match regex.is_match(value) {
+        true => Ok(()),
+        false => Err(format!("{} has an invalid format", label)),
+    }
+}
+
+/// Meant for use in derived [`ModalValidation::validate`] implementations
+///
+/// Checks that the value is a number within the given bounds
+#[doc(hidden)]
+pub fn validate_modal_range(
+    value: &str,
+    min: Option<f64>,
+    max: Option<f64>,
+    label: &str,
+) -> Result<(), String> {
+    let value = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("{} must be a number", label))?;
+    if let Some(min) = min.filter(|&min| value < min) {
+        return Err(format!("{} must be at least {}", label, min));
+    }
+    if let Some(max) = max.filter(|&max| value > max) {
+        return Err(format!("{} must be at most {}", label, max));
+    }
+    Ok(())
+}
+
 /// Underlying code for the modal spawning convenience function which abstracts over the kind of
 /// interaction
 async fn execute_modal_generic<
@@ -50,15 +91,37 @@ async fn execute_modal_generic<
     defaults: Option<M>,
     timeout: Option<std::time::Duration>,
 ) -> Result<Option<M>, serenity::Error> {
-    // Send modal
-    create_interaction_response(M::create(defaults, modal_custom_id.clone())).await?;
+    let span = crate::otel::modal_span(&modal_custom_id);
+    let modal = show_modal_and_wait(
+        ctx,
+        create_interaction_response,
+        modal_custom_id,
+        defaults,
+        timeout,
+    );
+    tracing::Instrument::instrument(modal, span).await
+}
 
-    // Wait for user to submit
-    let response = serenity::collector::ModalInteractionCollector::new(&ctx.shard)
-        .filter(move |d| d.data.custom_id == modal_custom_id)
-        .timeout(timeout.unwrap_or(std::time::Duration::from_secs(3600)))
-        .await;
-    let response = match response {
+/// See [`execute_modal_generic`]
+async fn show_modal_and_wait<
+    M: Modal,
+    F: std::future::Future<Output = Result<(), serenity::Error>>,
+>(
+    ctx: &serenity::Context,
+    create_interaction_response: impl FnOnce(serenity::CreateInteractionResponse) -> F,
+    modal_custom_id: String,
+    defaults: Option<M>,
+    timeout: Option<std::time::Duration>,
+) -> Result<Option<M>, serenity::Error> {
+    let response = match show_modal(
+        ctx,
+        create_interaction_response,
+        modal_custom_id,
+        defaults,
+        timeout,
+    )
+    .await?
+    {
         Some(x) => x,
         None => return Ok(None),
     };
@@ -73,6 +136,26 @@ async fn execute_modal_generic<
     ))
 }
 
+/// Sends the modal and waits for the user to submit, without acknowledging the submission
+async fn show_modal<M: Modal, F: std::future::Future<Output = Result<(), serenity::Error>>>(
+    ctx: &serenity::Context,
+    create_interaction_response: impl FnOnce(serenity::CreateInteractionResponse) -> F,
+    modal_custom_id: String,
+    defaults: Option<M>,
+    timeout: Option<std::time::Duration>,
+) -> Result<Option<serenity::ModalInteraction>, serenity::Error> {
+    // Send modal
+    create_interaction_response(M::create(defaults, modal_custom_id.clone())).await?;
+
+    // Wait for user to submit
+    Ok(
+        serenity::collector::ModalInteractionCollector::new(&ctx.shard)
+            .filter(move |d| d.data.custom_id == modal_custom_id)
+            .timeout(timeout.unwrap_or(std::time::Duration::from_secs(3600)))
+            .await,
+    )
+}
+
 /// Convenience function for showing the modal and waiting for a response.
 ///
 /// If the user doesn't submit before the timeout expires, `None` is returned.
@@ -94,17 +177,19 @@ pub async fn execute_modal<U: Send + Sync, E, M: Modal>(
     timeout: Option<std::time::Duration>,
 ) -> Result<Option<M>, serenity::Error> {
     let interaction = ctx.interaction;
-    let response = execute_modal_generic(
+    execute_modal_generic(
         ctx.serenity_context,
-        |resp| interaction.create_response(ctx, resp),
+        |resp| {
+            // Set before sending, so that an automatic deferral doesn't race the modal
+            ctx.has_sent_initial_response
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            interaction.create_response(ctx, resp)
+        },
         interaction.id.to_string(),
         defaults,
         timeout,
     )
-    .await?;
-    ctx.has_sent_initial_response
-        .store(true, std::sync::atomic::Ordering::SeqCst);
-    Ok(response)
+    .await
 }
 
 /// Convenience function for showing the modal on a message interaction and waiting for a response.
@@ -135,6 +220,187 @@ pub async fn execute_modal_on_component_interaction<M: Modal>(
     .await
 }
 
+/// Configuration for [`execute_validated_modal`]
+#[derive(Clone, Debug)]
+pub struct ModalOptions {
+    /// How long to wait for the user to submit the modal or press the retry button. One hour by
+    /// default
+    pub timeout: std::time::Duration,
+    /// How many submissions the user gets before giving up. 3 by default
+    pub max_attempts: usize,
+    /// Label of the button that reopens the modal after an invalid submission
+    pub retry_label: String,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for ModalOptions {
+    fn default() -> Self {
+        Self {
+            timeout: std::time::Duration::from_secs(3600),
+            max_attempts: 3,
+            retry_label: "Try again".into(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Like [`execute_modal`], but validates submissions via [`ModalValidation::validate`].
+///
+/// Discord doesn't allow responding to a modal submission with another modal. So if a submission
+/// is invalid, the user is shown the validation error in an ephemeral message with a retry button,
+/// which reopens the modal pre-filled with the previous input. After
+/// [`ModalOptions::max_attempts`] invalid submissions, or if the user doesn't react before the
+/// timeout, `None` is returned.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// #[derive(poise::Modal)]
+/// struct Signup {
+///     #[regex = "[a-z0-9_]{3,16}"]
+///     username: String,
+///     #[min_value = 13]
+///     #[max_value = 150]
+///     age: String,
+/// }
+///
+/// # async fn _test(ctx: poise::ApplicationContext<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let signup = poise::execute_validated_modal::<_, _, Signup>(ctx, None, Default::default()).await?;
+/// # Ok(()) }
+/// ```
+pub async fn execute_validated_modal<U: Send + Sync, E, M: ModalValidation>(
+    ctx: crate::ApplicationContext<'_, U, E>,
+    mut defaults: Option<M>,
+    options: ModalOptions,
+) -> Result<Option<M>, serenity::Error> {
+    // The first modal opens on the command interaction, retries on the retry button presses
+    let mut retry_press: Option<serenity::ComponentInteraction> = None;
+    // Submission whose response is the current validation error message
+    let mut invalid_submission: Option<serenity::ModalInteraction> = None;
+
+    for attempt in 1..=options.max_attempts {
+        let submission = match &retry_press {
+            None => {
+                let interaction = ctx.interaction;
+                show_modal(
+                    ctx.serenity_context,
+                    |resp| {
+                        // Set before sending, so that an automatic deferral doesn't race the modal
+                        ctx.has_sent_initial_response
+                            .store(true, std::sync::atomic::Ordering::SeqCst);
+                        interaction.create_response(ctx, resp)
+                    },
+                    interaction.id.to_string(),
+                    defaults.take(),
+                    Some(options.timeout),
+                )
+                .await?
+            }
+            Some(press) => {
+                show_modal(
+                    ctx.serenity_context,
+                    |resp| press.create_response(ctx, resp),
+                    press.id.to_string(),
+                    defaults.take(),
+                    Some(options.timeout),
+                )
+                .await?
+            }
+        };
+        let Some(submission) = submission else {
+            return Ok(None);
+        };
+        // The old error message is obsolete either way
+        if let Some(invalid_submission) = invalid_submission.take() {
+            if let Err(e) = invalid_submission.delete_response(ctx).await {
+                tracing::warn!("couldn't delete modal validation message: {}", e);
+            }
+        }
+
+        let modal = M::parse(submission.data.clone()).map_err(serenity::Error::Other)?;
+        let error = match modal.validate() {
+            Ok(()) => {
+                submission
+                    .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+                    .await?;
+                return Ok(Some(modal));
+            }
+            Err(error) => error,
+        };
+
+        let retry_id = format!("{}-retry", submission.id);
+        let mut response = serenity::CreateInteractionResponseMessage::new()
+            .content(error)
+            .ephemeral(true);
+        if attempt < options.max_attempts {
+            response = response.components(vec![serenity::CreateActionRow::Buttons(vec![
+                serenity::CreateButton::new(retry_id.clone()).label(&options.retry_label),
+            ])]);
+        }
+        submission
+            .create_response(ctx, serenity::CreateInteractionResponse::Message(response))
+            .await?;
+        if attempt == options.max_attempts {
+            break;
+        }
+
+        let press = serenity::ComponentInteractionCollector::new(ctx)
+            .filter(move |press| press.data.custom_id == retry_id)
+            .timeout(options.timeout)
+            .await;
+        let Some(press) = press else {
+            return Ok(None);
+        };
+        retry_press = Some(press);
+        invalid_submission = Some(submission);
+        defaults = Some(modal);
+    }
+    Ok(None)
+}
+
+/// Validation of submitted modal data, for [`execute_validated_modal`].
+///
+/// Implemented by `#[derive(Modal)]`, with the validators given via the `regex`, `min_value`,
+/// `max_value` and `validate` field attributes, as well as a struct-level `validate` attribute
+/// for checks that involve several fields:
+///
+/// ```rust
+/// fn no_spaces(value: &str) -> Result<(), String> {
+///     match value.contains(' ') {
+///         true => Err("Tags can't contain spaces".into()),
+///         false => Ok(()),
+///     }
+/// }
+///
+/// fn check_range(range: &Range) -> Result<(), String> {
+///     match (range.start.parse::<f64>(), range.end.parse::<f64>()) {
+///         (Ok(start), Ok(end)) if start > end => Err("The start must come before the end".into()),
+///         _ => Ok(()),
+///     }
+/// }
+///
+/// #[derive(poise::Modal)]
+/// #[validate = "check_range"]
+/// struct Range {
+///     #[min_value = 0]
+///     start: String,
+///     #[min_value = 0]
+///     end: String,
+///     #[validate = "no_spaces"]
+///     tag: Option<String>,
+/// }
+/// ```
+///
+/// Optional fields are only validated if filled in. When implementing [`Modal`] manually, an
+/// empty `impl ModalValidation for MyModal {}` accepts all submissions.
+pub trait ModalValidation: Modal {
+    /// Checks the submitted data, returning an error message for the user if it's invalid
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
 /// Derivable trait for modal interactions, Discords version of interactive forms
 ///
 /// You don't need to implement this trait manually; use `#[derive(poise::Modal)]` instead
@@ -159,6 +425,8 @@ pub async fn execute_modal_on_component_interaction<M: Modal>(
 ///     #[name = "Second input label"]
 ///     #[paragraph] // Switches from single-line input to multiline text box
 ///     second_input: Option<String>, // Option means optional input
+///     #[regex = "[0-9]+"] // Validators, see `ModalValidation`
+///     third_input: Option<String>,
 /// }
 ///
 /// #[poise::command(slash_command)]
@@ -0,0 +1,245 @@
+//! Observing the lifecycle of command invocations, see [`InvocationObserver`] and
+//! [`InvocationRecord`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Receives a callback at each stage of every command invocation. Useful for analytics pipelines
+/// that want the full [`crate::Context`] and error details of invocations.
+///
+/// Register observers in [`crate::FrameworkOptions::invocation_observers`]. All methods have
+/// no-op default implementations, so you only need to implement the stages you're interested in.
+///
+/// The callbacks are synchronous and called inline during dispatch, so they should return quickly.
+/// To do asynchronous work like database writes, send the relevant data to a background task.
+///
+/// Autocomplete requests aren't observed.
+///
+/// Poise's own integrations are observers too: [`crate::MetricsObserver`],
+/// [`crate::stats::UsageTracker`] and [`InvocationLog`].
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// struct InvocationLog;
+///
+/// impl<U, E> poise::InvocationObserver<U, E> for InvocationLog {
+///     fn on_succeeded(&self, ctx: poise::Context<'_, U, E>) {
+///         println!("{} ran {}", ctx.author().name, ctx.command().qualified_name);
+///     }
+/// }
+///
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     invocation_observers: vec![Box::new(InvocationLog)],
+///     ..Default::default()
+/// };
+/// ```
+pub trait InvocationObserver<U, E>: Send + Sync {
+    /// Called when an invocation was matched to a command, before any checks run
+    fn on_parsed(&self, ctx: crate::Context<'_, U, E>) {
+        let _ = ctx;
+    }
+
+    /// Called when all checks passed, right before [`crate::FrameworkOptions::pre_command`] and
+    /// the command itself run
+    fn on_started(&self, ctx: crate::Context<'_, U, E>) {
+        let _ = ctx;
+    }
+
+    /// Called when the command completed successfully, after
+    /// [`crate::FrameworkOptions::post_command`]
+    fn on_succeeded(&self, ctx: crate::Context<'_, U, E>) {
+        let _ = ctx;
+    }
+
+    /// Called when the invocation failed, for example because of an error returned by the
+    /// command, invalid arguments or a panic. Called before the error handler runs.
+    fn on_failed(&self, error: &crate::FrameworkError<'_, U, E>) {
+        let _ = error;
+    }
+
+    /// Called when the invocation was denied by a check, for example
+    /// [`crate::FrameworkError::CommandCheckFailed`], missing permissions or an active cooldown.
+    /// Called before the error handler runs.
+    fn on_skipped_by_check(&self, error: &crate::FrameworkError<'_, U, E>) {
+        let _ = error;
+    }
+
+    /// Called when the invocation ended, however it ended, after the other callbacks. `duration`
+    /// is the time since [`Self::on_parsed`]
+    fn on_completed(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        duration: std::time::Duration,
+        result: &Result<(), crate::FrameworkError<'_, U, E>>,
+    ) {
+        let _ = (ctx, duration, result);
+    }
+
+    /// Called for every gateway event the framework receives, before it's dispatched
+    fn on_event(&self, event: &serenity::FullEvent) {
+        let _ = event;
+    }
+}
+
+impl<U, E, T: InvocationObserver<U, E> + ?Sized> InvocationObserver<U, E> for std::sync::Arc<T> {
+    fn on_parsed(&self, ctx: crate::Context<'_, U, E>) {
+        (**self).on_parsed(ctx);
+    }
+
+    fn on_started(&self, ctx: crate::Context<'_, U, E>) {
+        (**self).on_started(ctx);
+    }
+
+    fn on_succeeded(&self, ctx: crate::Context<'_, U, E>) {
+        (**self).on_succeeded(ctx);
+    }
+
+    fn on_failed(&self, error: &crate::FrameworkError<'_, U, E>) {
+        (**self).on_failed(error);
+    }
+
+    fn on_skipped_by_check(&self, error: &crate::FrameworkError<'_, U, E>) {
+        (**self).on_skipped_by_check(error);
+    }
+
+    fn on_completed(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        duration: std::time::Duration,
+        result: &Result<(), crate::FrameworkError<'_, U, E>>,
+    ) {
+        (**self).on_completed(ctx, duration, result);
+    }
+
+    fn on_event(&self, event: &serenity::FullEvent) {
+        (**self).on_event(event);
+    }
+}
+
+/// Calls the given callback on all observers
+pub(crate) fn notify<U, E>(
+    options: &crate::FrameworkOptions<U, E>,
+    callback: impl Fn(&dyn InvocationObserver<U, E>),
+) {
+    for observer in &options.invocation_observers {
+        callback(&**observer);
+    }
+}
+
+/// Notifies all observers of a failed invocation, distinguishing between check failures and other
+/// errors
+pub(crate) fn notify_error<U, E>(
+    options: &crate::FrameworkOptions<U, E>,
+    error: &crate::FrameworkError<'_, U, E>,
+) {
+    let denied_by_check = error.denial_reason().is_some();
+    notify(options, |observer| match denied_by_check {
+        true => observer.on_skipped_by_check(error),
+        false => observer.on_failed(error),
+    });
+}
+
+/// Summary of a finished command invocation, passed to the logger of [`InvocationLog`] and to
+/// [`crate::Command::invocation_logger`].
+///
+/// Unlike [`crate::FrameworkOptions::post_command`], the logger is also called for failed and
+/// denied invocations, and the record includes the duration.
+#[derive(Debug, Clone)]
+pub struct InvocationRecord {
+    /// Qualified name of the invoked command, e.g. `"config set"`
+    pub command: String,
+    /// How the command was invoked: `"prefix"`, `"slash"` or `"context_menu"`
+    pub kind: &'static str,
+    /// The invoking user
+    pub user_id: serenity::UserId,
+    /// The guild of the invocation, or None in DMs
+    pub guild_id: Option<serenity::GuildId>,
+    /// The channel of the invocation
+    pub channel_id: serenity::ChannelId,
+    /// The invocation with its arguments, see [`crate::Context::invocation_string`]
+    pub invocation: String,
+    /// Time from the invocation being matched to a command until it finished, including checks
+    pub duration: std::time::Duration,
+    /// Whether the invocation succeeded, was denied or failed, including the
+    /// [`crate::FrameworkError::code`] of the error
+    pub outcome: crate::CommandOutcome,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Callback type of [`InvocationLog`] and [`crate::Command::invocation_logger`]
+pub type InvocationLogger = fn(InvocationRecord) -> BoxFuture<'static, ()>;
+
+/// [`InvocationObserver`] that passes a summary of every finished command invocation, including
+/// failed and denied ones, to the given logger, for example to write an audit log. See
+/// [`InvocationRecord`].
+///
+/// Commands with their own [`crate::Command::invocation_logger`] are passed to that one instead.
+/// The logger runs in a spawned task, so it doesn't delay dispatch.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let audit_log = poise::InvocationLog(|record| {
+///     Box::pin(async move {
+///         println!(
+///             "{} ran `{}` in {:?}: {}",
+///             record.user_id,
+///             record.invocation,
+///             record.duration,
+///             record.outcome.as_str(),
+///         );
+///     })
+/// });
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     invocation_observers: vec![Box::new(audit_log)],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct InvocationLog(pub InvocationLogger);
+
+impl<U, E> InvocationObserver<U, E> for InvocationLog {
+    fn on_completed(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        duration: std::time::Duration,
+        result: &Result<(), crate::FrameworkError<'_, U, E>>,
+    ) {
+        // Commands with their own logger are logged by the framework directly, see
+        // `log_invocation`
+        if ctx.command().invocation_logger.is_none() {
+            spawn_logger(self.0, ctx, duration, result);
+        }
+    }
+}
+
+/// Passes the record of a finished invocation to [`crate::Command::invocation_logger`], if set
+pub(crate) fn log_invocation<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    duration: std::time::Duration,
+    result: &Result<(), crate::FrameworkError<'_, U, E>>,
+) {
+    if let Some(logger) = ctx.command().invocation_logger {
+        spawn_logger(logger, ctx, duration, result);
+    }
+}
+
+/// Builds the record of a finished invocation and runs the logger with it in a new task
+fn spawn_logger<U, E>(
+    logger: InvocationLogger,
+    ctx: crate::Context<'_, U, E>,
+    duration: std::time::Duration,
+    result: &Result<(), crate::FrameworkError<'_, U, E>>,
+) {
+    let record = InvocationRecord {
+        command: ctx.command().qualified_name.clone(),
+        kind: crate::metrics::invocation_kind(ctx),
+        user_id: ctx.author().id,
+        guild_id: ctx.guild_id(),
+        channel_id: ctx.channel_id(),
+        invocation: ctx.invocation_string(),
+        duration,
+        outcome: crate::CommandOutcome::of(result),
+        __non_exhaustive: (),
+    };
+    tokio::spawn(logger(record));
+}
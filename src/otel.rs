@@ -0,0 +1,130 @@
+//! OpenTelemetry-style instrumentation of command invocations and gateway events
+//!
+//! With the `otel` feature, poise wraps command invocations, autocomplete requests, modals and
+//! gateway event handling in [`tracing`] spans whose names and fields follow OpenTelemetry
+//! conventions (`otel.name`, `otel.kind`, `otel.status_code`, `messaging.*`), so that they can be
+//! exported with `tracing-opentelemetry`. Modals are awaited inside the command, so their spans
+//! are children of the command span. Autocomplete requests precede the command invocation and
+//! share its `discord.command.name` attribute for correlation.
+//!
+//! Without the feature, all helpers return disabled spans, which cost next to nothing.
+
+use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
+
+/// Span for a command invocation or autocomplete request
+#[allow(unused_variables)]
+fn command_span<U, E>(ctx: crate::Context<'_, U, E>) -> tracing::Span {
+    #[cfg(feature = "otel")]
+    {
+        let kind = crate::metrics::invocation_kind(ctx);
+        let invocation_id = match ctx {
+            crate::Context::Prefix(ctx) => ctx.msg.id.get(),
+            crate::Context::Application(ctx) => ctx.interaction.id.get(),
+        };
+        let command_name = &ctx.command().qualified_name;
+        let span = tracing::info_span!(
+            "poise.command",
+            otel.name = %format_args!("{} {}", kind, command_name),
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            messaging.system = "discord",
+            messaging.operation = "process",
+            discord.command.name = %command_name,
+            discord.command.kind = kind,
+            discord.invocation.id = invocation_id,
+            discord.user.id = ctx.author().id.get(),
+            discord.channel.id = ctx.channel_id().get(),
+            discord.guild.id = tracing::field::Empty,
+            "error.type" = tracing::field::Empty,
+        );
+        if let Some(guild_id) = ctx.guild_id() {
+            span.record("discord.guild.id", guild_id.get());
+        }
+        span
+    }
+    #[cfg(not(feature = "otel"))]
+    tracing::Span::none()
+}
+
+/// Span for the handling of a gateway event
+#[allow(unused_variables)]
+pub(crate) fn event_span(event: &serenity::FullEvent) -> tracing::Span {
+    #[cfg(feature = "otel")]
+    {
+        let event_name = event.snake_case_name();
+        tracing::info_span!(
+            "poise.event",
+            otel.name = %format_args!("event {}", event_name),
+            otel.kind = "consumer",
+            messaging.system = "discord",
+            messaging.operation = "receive",
+            discord.event.name = event_name,
+        )
+    }
+    #[cfg(not(feature = "otel"))]
+    tracing::Span::none()
+}
+
+/// Span for showing a modal and waiting for its submission
+#[allow(unused_variables)]
+pub(crate) fn modal_span(custom_id: &str) -> tracing::Span {
+    #[cfg(feature = "otel")]
+    {
+        tracing::info_span!(
+            "poise.modal",
+            otel.name = "modal",
+            otel.kind = "internal",
+            discord.modal.custom_id = custom_id,
+        )
+    }
+    #[cfg(not(feature = "otel"))]
+    tracing::Span::none()
+}
+
+/// Runs a command invocation or autocomplete request in its span and records the outcome
+pub(crate) async fn instrument_command<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    invocation: impl std::future::Future<Output = Result<(), crate::FrameworkError<'a, U, E>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let span = command_span(ctx);
+    let result = invocation.instrument(span.clone()).await;
+    #[cfg(feature = "otel")]
+    match &result {
+        Ok(()) => {
+            span.record("otel.status_code", "OK");
+        }
+        Err(error) => {
+            span.record("otel.status_code", "ERROR");
+            span.record("error.type", error_type(error));
+        }
+    }
+    result
+}
+
+/// Stable name of the error kind, for the `error.type` attribute
+#[cfg(feature = "otel")]
+fn error_type<U, E>(error: &crate::FrameworkError<'_, U, E>) -> &'static str {
+    use crate::FrameworkError as Error;
+    match error {
+        Error::Command { .. } => "command",
+        Error::SubcommandRequired { .. } => "subcommand_required",
+        Error::CommandPanic { .. } => "command_panic",
+        Error::ArgumentParse { .. } => "argument_parse",
+        Error::InvalidChannelType { .. } => "invalid_channel_type",
+        Error::ParameterConstraintViolation { .. } => "parameter_constraint_violation",
+        Error::CommandStructureMismatch { .. } => "command_structure_mismatch",
+        Error::CooldownHit { .. } => "cooldown_hit",
+        Error::RatelimitDelayed { .. } => "ratelimit_delayed",
+        Error::TooManyConcurrentInvocations { .. } => "too_many_concurrent_invocations",
+        Error::MissingBotPermissions { .. } => "missing_bot_permissions",
+        Error::MissingUserPermissions { .. } => "missing_user_permissions",
+        Error::PermissionsUnverifiable { .. } => "permissions_unverifiable",
+        Error::NotAnOwner { .. } => "not_an_owner",
+        Error::GuildOnly { .. } => "guild_only",
+        Error::DmOnly { .. } => "dm_only",
+        Error::NsfwOnly { .. } => "nsfw_only",
+        Error::CommandCheckFailed { .. } => "command_check_failed",
+        _ => "other",
+    }
+}
@@ -129,7 +129,14 @@ impl<'a> PopArgumentHack<'a, serenity::Attachment> for &PhantomData<serenity::At
         let attachment = msg
             .attachments
             .get(attachment_index)
-            .ok_or_else(|| (MissingAttachment::default().into(), None))?
+            .ok_or_else(|| {
+                let error = MissingAttachment {
+                    index: attachment_index,
+                    available: msg.attachments.len(),
+                    __non_exhaustive: (),
+                };
+                (error.into(), None)
+            })?
             .clone(); // `.clone()` is more clear than `.to_owned()` and is the same.
 
         Ok((args, attachment_index + 1, attachment))
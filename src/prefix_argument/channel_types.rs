@@ -0,0 +1,106 @@
+//! Runtime validation of [`crate::CommandParameter::channel_types`] for parsed prefix arguments
+
+use crate::serenity_prelude as serenity;
+
+/// Parameter types whose channel type is known after parsing, so that it can be checked against
+/// [`crate::CommandParameter::channel_types`]
+#[doc(hidden)]
+pub trait ChannelArgument {
+    /// Returns the channel types of the parsed channels. Empty if no channel was given
+    fn channel_types(&self) -> Vec<serenity::ChannelType>;
+}
+
+impl ChannelArgument for serenity::Channel {
+    fn channel_types(&self) -> Vec<serenity::ChannelType> {
+        match self {
+            serenity::Channel::Guild(channel) => vec![channel.kind],
+            serenity::Channel::Private(_) => vec![serenity::ChannelType::Private],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl ChannelArgument for serenity::GuildChannel {
+    fn channel_types(&self) -> Vec<serenity::ChannelType> {
+        vec![self.kind]
+    }
+}
+
+impl ChannelArgument for serenity::PartialChannel {
+    fn channel_types(&self) -> Vec<serenity::ChannelType> {
+        vec![self.kind]
+    }
+}
+
+impl<T: ChannelArgument> ChannelArgument for Option<T> {
+    fn channel_types(&self) -> Vec<serenity::ChannelType> {
+        self.iter().flat_map(T::channel_types).collect()
+    }
+}
+
+impl<T: ChannelArgument> ChannelArgument for Vec<T> {
+    fn channel_types(&self) -> Vec<serenity::ChannelType> {
+        self.iter().flat_map(T::channel_types).collect()
+    }
+}
+
+/// Wraps a parsed argument for [`ChannelTypesHack`]
+#[doc(hidden)]
+pub struct ChannelTypesOf<'a, T>(pub &'a T);
+
+impl<T> Clone for ChannelTypesOf<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for ChannelTypesOf<'_, T> {}
+
+/// Returns the channel types of a parsed argument via [`ChannelArgument`], or nothing for types
+/// whose channel type isn't known without fetching the channel, like [`serenity::ChannelId`].
+///
+/// Uses the auto-deref specialization hack; see [`crate::PopArgumentHack`]
+#[doc(hidden)]
+pub trait ChannelTypesHack {
+    fn channel_types(self) -> Vec<serenity::ChannelType>;
+}
+
+impl<T> ChannelTypesHack for ChannelTypesOf<'_, T> {
+    fn channel_types(self) -> Vec<serenity::ChannelType> {
+        Vec::new()
+    }
+}
+
+impl<T: ChannelArgument> ChannelTypesHack for &ChannelTypesOf<'_, T> {
+    fn channel_types(self) -> Vec<serenity::ChannelType> {
+        self.0.channel_types()
+    }
+}
+
+/// Checks the channel types of the given channel parameter's argument against its
+/// [`crate::CommandParameter::channel_types`], returning
+/// [`crate::FrameworkError::InvalidChannelType`] for the first channel of a type that isn't
+/// allowed
+#[doc(hidden)]
+#[allow(clippy::result_large_err)]
+pub fn check_channel_types<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_name: &str,
+    got: impl IntoIterator<Item = serenity::ChannelType>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let parameter = ctx
+        .command()
+        .parameters
+        .iter()
+        .find(|parameter| parameter.name == parameter_name);
+    let Some(expected) = parameter.and_then(|parameter| parameter.channel_types.as_ref()) else {
+        return Ok(());
+    };
+    match got.into_iter().find(|kind| !expected.contains(kind)) {
+        Some(got) => Err(crate::FrameworkError::InvalidChannelType {
+            expected: expected.clone(),
+            got,
+            ctx,
+        }),
+        None => Ok(()),
+    }
+}
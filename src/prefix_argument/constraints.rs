@@ -0,0 +1,130 @@
+//! Runtime validation of the min/max and length constraints of [`crate::CommandParameter`]
+
+/// What a parameter constraint is checked against: the value of a number, or the length of a
+/// string in characters
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgumentMeasure {
+    /// Value of a number argument
+    Number(f64),
+    /// Length of a string argument in characters
+    Length(usize),
+}
+
+/// Parameter types that can be checked against [`crate::CommandParameter::min`],
+/// [`crate::CommandParameter::max`], [`crate::CommandParameter::min_length`] and
+/// [`crate::CommandParameter::max_length`]
+#[doc(hidden)]
+pub trait ConstrainedArgument {
+    /// Returns the measures of the parsed arguments. Empty if no argument was given
+    fn measures(&self) -> Vec<ArgumentMeasure>;
+}
+
+/// Implements [`ConstrainedArgument`] for number types
+macro_rules! impl_constrained_number {
+    ($($t:ty)*) => { $(
+        impl ConstrainedArgument for $t {
+            fn measures(&self) -> Vec<ArgumentMeasure> {
+                // Discord only has f64 precision, so the constraints are f64 too
+                vec![ArgumentMeasure::Number(*self as f64)]
+            }
+        }
+    )* };
+}
+impl_constrained_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+impl ConstrainedArgument for String {
+    fn measures(&self) -> Vec<ArgumentMeasure> {
+        vec![ArgumentMeasure::Length(self.chars().count())]
+    }
+}
+
+impl<T: ConstrainedArgument> ConstrainedArgument for Option<T> {
+    fn measures(&self) -> Vec<ArgumentMeasure> {
+        self.iter().flat_map(T::measures).collect()
+    }
+}
+
+impl<T: ConstrainedArgument> ConstrainedArgument for Vec<T> {
+    fn measures(&self) -> Vec<ArgumentMeasure> {
+        self.iter().flat_map(T::measures).collect()
+    }
+}
+
+/// Wraps a parsed argument for [`MeasuresHack`]
+#[doc(hidden)]
+pub struct MeasuresOf<'a, T>(pub &'a T);
+
+impl<T> Clone for MeasuresOf<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for MeasuresOf<'_, T> {}
+
+/// Returns the measures of a parsed argument via [`ConstrainedArgument`], or nothing for types
+/// that can't be constrained.
+///
+/// Uses the auto-deref specialization hack; see [`crate::PopArgumentHack`]
+#[doc(hidden)]
+pub trait MeasuresHack {
+    fn measures(self) -> Vec<ArgumentMeasure>;
+}
+
+impl<T> MeasuresHack for MeasuresOf<'_, T> {
+    fn measures(self) -> Vec<ArgumentMeasure> {
+        Vec::new()
+    }
+}
+
+impl<T: ConstrainedArgument> MeasuresHack for &MeasuresOf<'_, T> {
+    fn measures(self) -> Vec<ArgumentMeasure> {
+        self.0.measures()
+    }
+}
+
+/// Checks the given parameter's arguments against its constraints, returning
+/// [`crate::FrameworkError::ParameterConstraintViolation`] for the first violated constraint
+#[doc(hidden)]
+#[allow(clippy::result_large_err)]
+pub fn check_parameter_constraints<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_name: &str,
+    measures: impl IntoIterator<Item = ArgumentMeasure>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let Some(parameter) = ctx
+        .command()
+        .parameters
+        .iter()
+        .find(|parameter| parameter.name == parameter_name)
+    else {
+        return Ok(());
+    };
+
+    for measure in measures {
+        let violation = match measure {
+            ArgumentMeasure::Number(value) => match (parameter.min, parameter.max) {
+                (Some(min), _) if value < min => Some(crate::ParameterConstraint::Min(min)),
+                (_, Some(max)) if value > max => Some(crate::ParameterConstraint::Max(max)),
+                _ => None,
+            },
+            ArgumentMeasure::Length(length) => match (parameter.min_length, parameter.max_length) {
+                (Some(min), _) if length < min.into() => {
+                    Some(crate::ParameterConstraint::MinLength(min))
+                }
+                (_, Some(max)) if length > max.into() => {
+                    Some(crate::ParameterConstraint::MaxLength(max))
+                }
+                _ => None,
+            },
+        };
+        if let Some(constraint) = violation {
+            return Err(crate::FrameworkError::ParameterConstraintViolation {
+                parameter: parameter.name.clone(),
+                constraint,
+                ctx,
+            });
+        }
+    }
+    Ok(())
+}
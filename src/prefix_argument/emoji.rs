@@ -0,0 +1,126 @@
+//! Parsing of [`serenity::ReactionType`] parameters from unicode emojis, custom emoji syntax and
+//! guild emoji names
+
+use super::PopArgument;
+use crate::serenity_prelude as serenity;
+
+/// Error thrown when the user enters a string that is neither a unicode emoji, nor custom emoji
+/// syntax like `<:name:id>`, nor the name of an emoji of the guild
+#[derive(Default, Debug)]
+pub struct InvalidEmoji {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected an emoji or the name of an emoji of this server")
+    }
+}
+impl std::error::Error for InvalidEmoji {}
+
+/// Returns the emojis of the guild, from the cache if possible
+pub(crate) async fn fetch_guild_emojis(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+) -> Result<Vec<serenity::Emoji>, serenity::Error> {
+    #[cfg(feature = "cache")]
+    if let Some(guild) = ctx.cache.guild(guild_id) {
+        return Ok(guild.emojis.values().cloned().collect());
+    }
+    guild_id.emojis(ctx).await
+}
+
+/// Whether the string looks like a unicode emoji. There's no complete list of emojis to check
+/// against, so this only makes sure that the string isn't a word or a number
+fn is_unicode_emoji(input: &str) -> bool {
+    !input.is_ascii()
+        && !input
+            .chars()
+            .any(|c| c.is_alphabetic() || c.is_whitespace())
+}
+
+/// Parses a unicode emoji, custom emoji syntax like `<:name:id>`, or the name of an emoji of the
+/// guild, optionally surrounded by colons like `:name:`
+async fn parse_reaction_type(
+    ctx: &serenity::Context,
+    guild_id: Option<serenity::GuildId>,
+    input: &str,
+) -> Result<serenity::ReactionType, InvalidEmoji> {
+    if input.starts_with('<') {
+        return serenity::ReactionType::try_from(input).map_err(|_| InvalidEmoji::default());
+    }
+    if is_unicode_emoji(input) {
+        return Ok(serenity::ReactionType::Unicode(input.to_owned()));
+    }
+
+    let name = input.trim_matches(':');
+    let emojis = match guild_id {
+        Some(guild_id) if !name.is_empty() => fetch_guild_emojis(ctx, guild_id).await,
+        _ => return Err(InvalidEmoji::default()),
+    };
+    emojis
+        .map_err(|e| tracing::warn!("failed to fetch guild emojis: {}", e))
+        .ok()
+        .and_then(|emojis| {
+            emojis
+                .into_iter()
+                .find(|emoji| emoji.name.eq_ignore_ascii_case(name))
+        })
+        .map(serenity::ReactionType::from)
+        .ok_or_else(InvalidEmoji::default)
+}
+
+#[async_trait::async_trait]
+impl<'a> PopArgument<'a> for serenity::ReactionType {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let (args, string) = super::pop_string(args)
+            .map_err(|_| (crate::TooFewArguments::default().into(), None))?;
+        let emoji = parse_reaction_type(ctx, msg.guild_id, &string)
+            .await
+            .map_err(|e| (e.into(), Some(string)))?;
+
+        Ok((args.trim_start(), attachment_index, emoji))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::SlashArgument for serenity::ReactionType {
+    async fn extract(
+        ctx: &serenity::Context,
+        interaction: &serenity::CommandInteraction,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        let serenity::ResolvedValue::String(input) = *value else {
+            return Err(crate::SlashArgError::CommandStructureMismatch {
+                description: "expected string",
+            });
+        };
+        parse_reaction_type(ctx, interaction.guild_id, input.trim())
+            .await
+            .map_err(|error| crate::SlashArgError::Parse {
+                error: error.into(),
+                input: input.to_owned(),
+            })
+    }
+
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_is_unicode_emoji() {
+    for emoji in ["👍", "👍🏽", "❤️", "1️⃣", "🏳️‍🌈"] {
+        assert!(is_unicode_emoji(emoji), "{}", emoji);
+    }
+    for not_emoji in ["", "thumbsup", "1", "é", "日本", "👍 👍"] {
+        assert!(!is_unicode_emoji(not_emoji), "{}", not_emoji);
+    }
+}
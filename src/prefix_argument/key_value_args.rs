@@ -1,10 +1,14 @@
-//! Parsing code for [`KeyValueArgs`], a prefix-specific command parameter type
+//! Parsing code for [`KeyValueArgs`], a command parameter type for key-value args
 
 use super::*;
 
 /// A command parameter type for key-value args
 ///
 /// For example `key1=value1 key2="value2 with spaces"`
+///
+/// In prefix commands, as many key-value pairs as possible are read from the arguments. In slash
+/// commands, this is a single string option which must consist entirely of key-value pairs;
+/// otherwise, parsing fails with [`InvalidKeyValueArgs`].
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct KeyValueArgs(pub std::collections::HashMap<String, String>);
 
@@ -69,7 +73,39 @@ impl KeyValueArgs {
 
         (args, Self(pairs))
     }
+
+    /// Parses the whole string as key-value args, failing if anything else is left over
+    fn parse_all(args: &str) -> Result<Self, InvalidKeyValueArgs> {
+        let (remaining, kv_args) = Self::pop_from(args);
+        match remaining.trim() {
+            "" => Ok(kv_args),
+            unparsed => Err(InvalidKeyValueArgs {
+                unparsed: unparsed.to_owned(),
+                __non_exhaustive: (),
+            }),
+        }
+    }
+}
+
+/// Error thrown in slash commands when a [`KeyValueArgs`] parameter contains text that isn't a
+/// `key=value` pair
+#[derive(Default, Debug)]
+pub struct InvalidKeyValueArgs {
+    /// The input from the first part that couldn't be parsed onwards
+    pub unparsed: String,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidKeyValueArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected `key=value` pairs, but couldn't parse `{}`",
+            self.unparsed
+        )
+    }
 }
+impl std::error::Error for InvalidKeyValueArgs {}
 
 #[async_trait::async_trait]
 impl<'a> PopArgument<'a> for KeyValueArgs {
@@ -86,6 +122,29 @@ impl<'a> PopArgument<'a> for KeyValueArgs {
     }
 }
 
+#[async_trait::async_trait]
+impl crate::SlashArgument for KeyValueArgs {
+    async fn extract(
+        _: &serenity::Context,
+        _: &serenity::CommandInteraction,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        let serenity::ResolvedValue::String(input) = *value else {
+            return Err(crate::SlashArgError::CommandStructureMismatch {
+                description: "expected string",
+            });
+        };
+        Self::parse_all(input).map_err(|error| crate::SlashArgError::Parse {
+            error: error.into(),
+            input: input.to_owned(),
+        })
+    }
+
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
 #[cfg(test)]
 #[test]
 fn test_key_value_args() {
@@ -120,3 +179,14 @@ fn test_key_value_args() {
         assert_eq!(args, remaining_args);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_key_value_args_parse_all() {
+    let kv_args = KeyValueArgs::parse_all(r#"a=1 b="2 3" "#).unwrap();
+    assert_eq!(kv_args.get("a"), Some("1"));
+    assert_eq!(kv_args.get("b"), Some("2 3"));
+
+    let error = KeyValueArgs::parse_all("a=1 oops b=2").unwrap_err();
+    assert_eq!(error.unparsed, "oops b=2");
+}
@@ -26,7 +26,7 @@ macro_rules! _parse_prefix {
                 $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
                 // If the code gets here, parsing the rest of the argument has failed
             },
-            Err(e) => $error = e,
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
         }
         let token: Option<$type> = None;
         // Parse the next arguments without changing the current arg string, thereby skipping the
@@ -46,7 +46,7 @@ macro_rules! _parse_prefix {
                 let token: Option<$type> = Some(token);
                 $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
         }
     };
 
@@ -68,7 +68,7 @@ macro_rules! _parse_prefix {
                     let token = Some(token);
                     $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $error = (e.into(), Some(input.to_owned()), Some($crate::_count_tokens!($($preamble)*))),
             }
         }
     };
@@ -79,7 +79,9 @@ macro_rules! _parse_prefix {
         $( $rest:tt )*
     ) => {
         let mut tokens = Vec::new();
-        let mut token_rest_args = vec![$args.clone()];
+        // Remaining args and attachment index after each popped token, so that backtracking also
+        // gives back the attachments of the dropped tokens
+        let mut token_rest_args = vec![($args.clone(), $attachment_index)];
 
         let mut running_args = $args.clone();
         let mut attachment = $attachment_index;
@@ -88,12 +90,12 @@ macro_rules! _parse_prefix {
             match $crate::pop_prefix_argument!($type, &running_args, attachment, $ctx, $msg).await {
                 Ok((popped_args, new_attachment, token)) => {
                     tokens.push(token);
-                    token_rest_args.push(popped_args.clone());
+                    token_rest_args.push((popped_args.clone(), new_attachment));
                     running_args = popped_args;
                     attachment = new_attachment;
                 },
                 Err(e) => {
-                    // No `$error = e`, because e.g. parsing into a Vec<Attachment> parameter with
+                    // No `$error = ...`, because e.g. parsing into a Vec<Attachment> parameter with
                     // spare arguments would cause the error from the spare arguments to be the
                     // Attachment parse error ("missing attachment"), which is confusing
                     break;
@@ -103,7 +105,7 @@ macro_rules! _parse_prefix {
         }
 
         // This will run at least once
-        while let Some(token_rest_args) = token_rest_args.pop() {
+        while let Some((token_rest_args, attachment)) = token_rest_args.pop() {
             $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $($preamble)* tokens ] $($rest)* );
             tokens.pop();
         }
@@ -119,7 +121,7 @@ macro_rules! _parse_prefix {
     ) => {
         let input = $args.trim_start();
         if input.is_empty() {
-            $error = ($crate::TooFewArguments::default().into(), None);
+            $error = ($crate::TooFewArguments::default().into(), None, Some($crate::_count_tokens!($($preamble)*)));
         } else {
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
@@ -128,11 +130,44 @@ macro_rules! _parse_prefix {
                     let $args = "";
                     $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $error = (e.into(), Some(input.to_owned()), Some($crate::_count_tokens!($($preamble)*))),
             }
         }
     };
 
+    // Consume #[from_reply] Option<T> greedy-first, from the replied-to message if the invocation
+    // message has no attachments
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[from_reply] Option<$type:ty $(,)?>)
+        $( $rest:tt )*
+    ) => {
+        let source = $crate::attachment_source($msg);
+        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, source).await {
+            Ok(($args, $attachment_index, token)) => {
+                let token: Option<$type> = Some(token);
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
+        }
+        let token: Option<$type> = None;
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+    };
+
+    // Consume #[from_reply] T, from the replied-to message if the invocation message has no
+    // attachments
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[from_reply] $type:ty)
+        $( $rest:tt )*
+    ) => {
+        let source = $crate::attachment_source($msg);
+        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, source).await {
+            Ok(($args, $attachment_index, token)) => {
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
+        }
+    };
+
     // Consume #[flag] FLAGNAME
     ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (#[flag] $name:literal)
@@ -145,12 +180,25 @@ macro_rules! _parse_prefix {
             // only allow backtracking if the flag didn't match: it's confusing for the user if they
             // precisely set the flag but it's ignored
             _ => {
-                $error = (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None);
+                $error = (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None, Some($crate::_count_tokens!($($preamble)*)));
                 $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* false ] $($rest)* );
             }
         }
     };
 
+    // Consume #[custom] Parser => T, the form of #[custom_parser = "Parser"] T parameters
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[custom] $parser:ty => $type:ty)
+        $( $rest:tt )*
+    ) => {
+        match <$parser as $crate::CustomParameterParser<$type>>::pop_from(&$args, $attachment_index, $ctx, $msg).await {
+            Ok(($args, $attachment_index, token)) => {
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
+        }
+    };
+
     // Consume T
     ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         ($type:ty)
@@ -160,7 +208,7 @@ macro_rules! _parse_prefix {
             Ok(($args, $attachment_index, token)) => {
                 $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err((parse_error, parse_input)) => $error = (parse_error, parse_input, Some($crate::_count_tokens!($($preamble)*))),
         }
     };
 
@@ -217,6 +265,25 @@ assert_eq!(
 */
 #[macro_export]
 macro_rules! parse_prefix_args {
+    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
+        $( #[$attr:ident] )?
+        ( $($type:tt)* )
+    ),* $(,)? ) => {
+        async {
+            $crate::_parse_prefix_args_indexed!(
+                $ctx, $msg, $args, $attachment_index => $( $( #[$attr] )? ( $($type)* ), )*
+            )
+            .await
+            .map_err(|(error, input, _)| (error, input))
+        }
+    };
+}
+
+/// Like [`parse_prefix_args`], but the error additionally contains the index of the parameter
+/// that failed to parse, if any. Used by the [`crate::command`] macro
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _parse_prefix_args_indexed {
     ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
         $( #[$attr:ident] )?
         ( $($type:tt)* )
@@ -229,8 +296,8 @@ macro_rules! parse_prefix_args {
             let args = $args;
             let attachment_index = $attachment_index;
 
-            let mut error: (Box<dyn std::error::Error + Send + Sync>, Option<String>)
-                = (Box::new($crate::TooManyArguments { __non_exhaustive: () }) as _, None);
+            let mut error: (Box<dyn std::error::Error + Send + Sync>, Option<String>, Option<usize>)
+                = (Box::new($crate::TooManyArguments { __non_exhaustive: () }) as _, None, None);
 
             $crate::_parse_prefix!(
                 ctx msg args attachment_index => [error]
@@ -243,6 +310,15 @@ macro_rules! parse_prefix_args {
     };
 }
 
+/// Counts the given tokens. Used to get the index of the parameter being parsed from the list of
+/// already parsed parameters
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _count_tokens {
+    () => { 0usize };
+    ($head:tt $($tail:tt)*) => { 1usize + $crate::_count_tokens!($($tail)*) };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
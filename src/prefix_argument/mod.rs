@@ -2,9 +2,18 @@
 
 #![allow(unused)] // false positive from inside macro expansions
 
+mod channel_types;
+pub use channel_types::*;
+
 mod code_block;
 pub use code_block::*;
 
+mod constraints;
+pub use constraints::*;
+
+mod emoji;
+pub use emoji::*;
+
 mod key_value_args;
 pub use key_value_args::*;
 
@@ -85,29 +94,112 @@ impl std::fmt::Display for TooFewArguments {
 }
 impl std::error::Error for TooFewArguments {}
 
+/// Returns the message that `#[from_reply]` parameters take their attachments from: the invocation
+/// message itself, or the message it replies to if the invocation message has no attachments
+#[doc(hidden)]
+pub fn attachment_source(msg: &serenity::Message) -> &serenity::Message {
+    match &msg.referenced_message {
+        Some(replied) if msg.attachments.is_empty() => replied,
+        _ => msg,
+    }
+}
+
 /// Error thrown in prefix invocation when there's too few attachments
 #[derive(Default, Debug)]
 pub struct MissingAttachment {
+    /// Zero-based index of the attachment that was expected
+    pub index: usize,
+    /// Number of attachments on the message
+    pub available: usize,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 impl std::fmt::Display for MissingAttachment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("A required attachment is missing")
+        write!(
+            f,
+            "A required attachment is missing: expected at least {} attachment{}, but the \
+            message has {}",
+            self.index + 1,
+            if self.index == 0 { "" } else { "s" },
+            self.available,
+        )
     }
 }
 impl std::error::Error for MissingAttachment {}
 
+/// Error thrown in prefix invocation when the message has more attachments than the command's
+/// attachment parameters take
+#[derive(Default, Debug)]
+pub struct TooManyAttachments {
+    /// Number of attachments the command used
+    pub used: usize,
+    /// Number of attachments on the message
+    pub available: usize,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for TooManyAttachments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Too many attachments: the message has {}, but only {} could be used",
+            self.available, self.used,
+        )
+    }
+}
+impl std::error::Error for TooManyAttachments {}
+
+/// Counts how many message attachments a parsed prefix parameter consumed. Used by the
+/// [`crate::command`] macro to reject messages with spare attachments
+#[doc(hidden)]
+pub trait AttachmentCount {
+    /// Number of attachments contained in this value
+    fn attachment_count(&self) -> usize;
+}
+impl AttachmentCount for serenity::Attachment {
+    fn attachment_count(&self) -> usize {
+        1
+    }
+}
+impl AttachmentCount for Option<serenity::Attachment> {
+    fn attachment_count(&self) -> usize {
+        self.is_some() as usize
+    }
+}
+impl AttachmentCount for Vec<serenity::Attachment> {
+    fn attachment_count(&self) -> usize {
+        self.len()
+    }
+}
+
 /// Error thrown when the user enters a string that is not recognized by a
 /// ChoiceParameter-derived enum
 #[derive(Default, Debug)]
 pub struct InvalidChoice {
+    /// Names of all valid choices
+    pub valid_choices: Vec<String>,
+    /// The valid choice that is most similar to the user input, if any is reasonably similar
+    pub closest_match: Option<String>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 impl std::fmt::Display for InvalidChoice {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("You entered a non-existent choice")
+        f.write_str("You entered a non-existent choice")?;
+        if let Some(closest_match) = &self.closest_match {
+            write!(f, ". Did you mean `{}`?", closest_match)?;
+        }
+        if !self.valid_choices.is_empty() {
+            f.write_str(" Valid options: ")?;
+            for (i, choice) in self.valid_choices.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "`{}`", choice)?;
+            }
+        }
+        Ok(())
     }
 }
 impl std::error::Error for InvalidChoice {}
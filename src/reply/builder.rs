@@ -2,6 +2,47 @@
 
 use crate::serenity_prelude as serenity;
 
+/// How editing a message with a [`CreateReply`] treats the attachments already on the message,
+/// see [`CreateReply::attachment_edit_mode`]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttachmentEditMode {
+    /// Existing attachments are removed, only the reply's attachments remain
+    #[default]
+    Replace,
+    /// Existing attachments are kept, and the reply's attachments are added to them
+    Append,
+    /// Existing attachments are left as they are, and the reply's attachments are ignored. Avoids
+    /// uploading files again when only the text of a message changes
+    Keep,
+}
+
+/// Handles interactions with a component of an interactive reply, see
+/// [`CreateReply::on_component`]. Returning [`std::ops::ControlFlow::Break`] stops listening for
+/// further interactions
+pub type ReplyComponentHandler = std::sync::Arc<
+    dyn for<'a> Fn(
+            &'a serenity::Context,
+            serenity::ComponentInteraction,
+        )
+            -> crate::BoxFuture<'a, Result<std::ops::ControlFlow<()>, serenity::Error>>
+        + Send
+        + Sync,
+>;
+
+/// Generates the contents of a [`LazyAttachment`]
+type LazyAttachmentGenerator = Box<dyn FnOnce() -> crate::BoxFuture<'static, Vec<u8>> + Send>;
+
+/// An attachment whose contents are only generated right before the reply is sent, see
+/// [`CreateReply::attachment_lazy`]
+#[derive(Clone)]
+pub struct LazyAttachment {
+    /// Name of the file
+    pub filename: String,
+    /// Generates the file contents. Shared between clones of the reply, so the contents are
+    /// generated at most once
+    generate: std::sync::Arc<parking_lot::Mutex<Option<LazyAttachmentGenerator>>>,
+}
+
 /// Message builder that abstracts over prefix and application command responses
 #[derive(Default, Clone)]
 pub struct CreateReply {
@@ -11,6 +52,9 @@ pub struct CreateReply {
     pub embeds: Vec<serenity::CreateEmbed>,
     /// Message attachments.
     pub attachments: Vec<serenity::CreateAttachment>,
+    /// Attachments whose contents are generated when the reply is sent, after
+    /// [`crate::FrameworkOptions::reply_callback`] ran. See [`Self::attachment_lazy`]
+    pub lazy_attachments: Vec<LazyAttachment>,
     /// Whether the message is ephemeral (only has an effect in application commands)
     pub ephemeral: Option<bool>,
     /// Message components, that is, buttons and select menus.
@@ -19,6 +63,14 @@ pub struct CreateReply {
     pub allowed_mentions: Option<serenity::CreateAllowedMentions>,
     /// Whether this message is an inline reply.
     pub reply: bool,
+    /// Message in the same channel that this message replies to, instead of the invocation
+    /// message. See [`Self::reply_to`]
+    pub reply_to: Option<serenity::MessageId>,
+    /// How existing attachments are treated when this reply is used to edit a message.
+    pub attachment_edit_mode: AttachmentEditMode,
+    /// Handlers for interactions with the message's components, keyed by custom ID. Only run
+    /// when sent with [`crate::send_interactive_reply`]
+    pub component_handlers: Vec<(String, ReplyComponentHandler)>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
@@ -46,12 +98,247 @@ impl CreateReply {
         self
     }
 
+    /// Sets components along with handlers for interactions with them, keyed by custom ID. The
+    /// handlers are run when the reply is sent with [`crate::send_interactive_reply`].
+    ///
+    /// Any previously set components and handlers will be overwritten. Use [`Self::on_component`]
+    /// to add handlers from closures.
+    pub fn components_interactive(
+        mut self,
+        components: Vec<serenity::CreateActionRow>,
+        handlers: impl IntoIterator<Item = (impl Into<String>, ReplyComponentHandler)>,
+    ) -> Self {
+        self.components = Some(components);
+        self.component_handlers = handlers
+            .into_iter()
+            .map(|(custom_id, handler)| (custom_id.into(), handler))
+            .collect();
+        self
+    }
+
+    /// Adds a handler for interactions with the component of the given custom ID, which is run
+    /// when the reply is sent with [`crate::send_interactive_reply`].
+    ///
+    /// ```rust,no_run
+    /// # use poise::serenity_prelude as serenity;
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), serenity::Error> {
+    /// use std::ops::ControlFlow;
+    ///
+    /// let buttons = serenity::CreateActionRow::Buttons(vec![
+    ///     serenity::CreateButton::new("roll").label("Roll"),
+    ///     serenity::CreateButton::new("done").label("Done"),
+    /// ]);
+    /// let reply = poise::CreateReply::default()
+    ///     .content("Roll the dice!")
+    ///     .components(vec![buttons])
+    ///     .on_component("roll", |ctx, press| {
+    ///         Box::pin(async move {
+    ///             let response = serenity::CreateInteractionResponseMessage::new()
+    ///                 .content("Rolled a 4");
+    ///             press
+    ///                 .create_response(ctx, serenity::CreateInteractionResponse::UpdateMessage(response))
+    ///                 .await?;
+    ///             Ok(ControlFlow::Continue(()))
+    ///         })
+    ///     })
+    ///     .on_component("done", |ctx, press| {
+    ///         Box::pin(async move {
+    ///             press
+    ///                 .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+    ///                 .await?;
+    ///             Ok(ControlFlow::Break(()))
+    ///         })
+    ///     });
+    /// poise::send_interactive_reply(ctx, reply, std::time::Duration::from_secs(60)).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn on_component<F>(mut self, custom_id: impl Into<String>, handler: F) -> Self
+    where
+        F: for<'a> Fn(
+                &'a serenity::Context,
+                serenity::ComponentInteraction,
+            )
+                -> crate::BoxFuture<'a, Result<std::ops::ControlFlow<()>, serenity::Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.component_handlers
+            .push((custom_id.into(), std::sync::Arc::new(handler)));
+        self
+    }
+
     /// Add an attachment.
     pub fn attachment(mut self, attachment: serenity::CreateAttachment) -> Self {
         self.attachments.push(attachment);
         self
     }
 
+    /// Add an attachment with a description (alt text), which screen readers announce in place
+    /// of the file.
+    ///
+    /// Discord allows up to 1024 characters.
+    pub fn attachment_with_description(
+        self,
+        attachment: serenity::CreateAttachment,
+        description: impl Into<String>,
+    ) -> Self {
+        self.attachment(attachment.description(description))
+    }
+
+    /// Add an attachment that's hidden behind a spoiler until clicked.
+    ///
+    /// Discord marks files as spoilers by their file name, so this prefixes the file name with
+    /// `SPOILER_` (unless it is already).
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// let reply = poise::CreateReply::default()
+    ///     .spoiler_attachment(serenity::CreateAttachment::bytes(vec![], "ending.png"));
+    /// assert_eq!(reply.attachments[0].filename, "SPOILER_ending.png");
+    /// ```
+    pub fn spoiler_attachment(self, mut attachment: serenity::CreateAttachment) -> Self {
+        if !attachment.filename.starts_with("SPOILER_") {
+            attachment.filename.insert_str(0, "SPOILER_");
+        }
+        self.attachment(attachment)
+    }
+
+    /// Add an attachment whose contents are only generated when the reply is actually sent, after
+    /// [`crate::FrameworkOptions::reply_callback`] ran. Useful for expensive files like charts or
+    /// renders.
+    ///
+    /// The file is generated at most once, even if the reply is cloned. Commands re-run by edit
+    /// tracking build a new reply, so their files are generated again.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// # fn render_chart(_: &[u32]) -> Vec<u8> { Vec::new() }
+    /// let data = vec![1, 2, 3];
+    /// let reply = poise::CreateReply::default()
+    ///     .content("Here's your chart")
+    ///     .attachment_lazy("chart.png", move || async move { render_chart(&data) });
+    /// ctx.send(reply).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn attachment_lazy<F, Fut>(mut self, filename: impl Into<String>, generate: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Vec<u8>> + Send + 'static,
+    {
+        let generate: LazyAttachmentGenerator = Box::new(move || Box::pin(generate()));
+        self.lazy_attachments.push(LazyAttachment {
+            filename: filename.into(),
+            generate: std::sync::Arc::new(parking_lot::Mutex::new(Some(generate))),
+        });
+        self
+    }
+
+    /// Add an attachment containing the value serialized as pretty-printed JSON.
+    ///
+    /// ```rust
+    /// let reply = poise::CreateReply::default()
+    ///     .attachment_json("scores.json", &[("alice", 3), ("bob", 5)])?;
+    /// assert_eq!(reply.attachments[0].filename, "scores.json");
+    /// # Ok::<(), poise::serenity_prelude::Error>(())
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn attachment_json<T: serde::Serialize + ?Sized>(
+        self,
+        filename: impl Into<String>,
+        value: &T,
+    ) -> Result<Self, serenity::Error> {
+        let data = serenity::json::to_vec_pretty(value)?;
+        Ok(self.attachment(serenity::CreateAttachment::bytes(data, filename.into())))
+    }
+
+    /// Add an attachment containing the rows as CSV. Fields are quoted where necessary.
+    ///
+    /// ```rust
+    /// let reply = poise::CreateReply::default().attachment_csv(
+    ///     "scores.csv",
+    ///     [["name", "score"], ["alice", "3"], ["bob, jr.", "5"]],
+    /// );
+    /// assert_eq!(
+    ///     reply.attachments[0].data,
+    ///     b"name,score\r\nalice,3\r\n\"bob, jr.\",5\r\n",
+    /// );
+    /// ```
+    pub fn attachment_csv<R, F>(self, filename: impl Into<String>, rows: R) -> Self
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = F>,
+        F: std::fmt::Display,
+    {
+        let mut csv = String::new();
+        for row in rows {
+            for (i, field) in row.into_iter().enumerate() {
+                if i > 0 {
+                    csv.push(',');
+                }
+                let field = field.to_string();
+                if field.contains([',', '"', '\r', '\n']) {
+                    csv.push('"');
+                    csv.push_str(&field.replace('"', "\"\""));
+                    csv.push('"');
+                } else {
+                    csv.push_str(&field);
+                }
+            }
+            csv.push_str("\r\n");
+        }
+        self.attachment(serenity::CreateAttachment::bytes(
+            csv.into_bytes(),
+            filename.into(),
+        ))
+    }
+
+    /// Generates the contents of the [lazy attachments](Self::lazy_attachments) and adds them to
+    /// [`Self::attachments`]. Called automatically when the reply is sent.
+    ///
+    /// Attachments that were already generated by a clone of this reply are skipped.
+    pub async fn resolve_lazy_attachments(mut self) -> Self {
+        for lazy in std::mem::take(&mut self.lazy_attachments) {
+            let generate = lazy.generate.lock().take();
+            match generate {
+                Some(generate) => {
+                    let data = generate().await;
+                    self.attachments
+                        .push(serenity::CreateAttachment::bytes(data, lazy.filename));
+                }
+                None => tracing::warn!(
+                    "lazy attachment `{}` was already generated by a clone of this reply",
+                    lazy.filename
+                ),
+            }
+        }
+        self
+    }
+
+    /// Sets how attachments already on the message are treated when this reply is used to edit a
+    /// message, for example with [`crate::ReplyHandle::edit`] or with
+    /// [`crate::Command::reuse_response`]. Has no effect on new messages.
+    ///
+    /// By default, existing attachments are replaced with the reply's attachments.
+    pub fn attachment_edit_mode(mut self, attachment_edit_mode: AttachmentEditMode) -> Self {
+        self.attachment_edit_mode = attachment_edit_mode;
+        self
+    }
+
+    /// Keeps the attachments already on the message when this reply is used to edit a message,
+    /// adding the reply's attachments to them. Shorthand for
+    /// [`Self::attachment_edit_mode`] with [`AttachmentEditMode::Append`].
+    ///
+    /// ```rust
+    /// let reply = poise::CreateReply::default()
+    ///     .content("Updated caption")
+    ///     .keep_existing_attachments();
+    /// assert_eq!(reply.attachment_edit_mode, poise::AttachmentEditMode::Append);
+    /// ```
+    pub fn keep_existing_attachments(self) -> Self {
+        self.attachment_edit_mode(AttachmentEditMode::Append)
+    }
+
     /// Toggles whether the message is an ephemeral response (only invoking user can see it).
     ///
     /// This only has an effect in slash commands!
@@ -77,6 +364,50 @@ impl CreateReply {
         self.reply = reply;
         self
     }
+
+    /// Makes this message an inline reply to the given message in the same channel, instead of
+    /// the invocation message (prefix-only, ignored in application commands because their
+    /// responses are attached to the interaction).
+    ///
+    /// Implies [`Self::reply`].
+    pub fn reply_to(mut self, message_id: serenity::MessageId) -> Self {
+        self.reply = true;
+        self.reply_to = Some(message_id);
+        self
+    }
+}
+
+impl From<String> for CreateReply {
+    fn from(content: String) -> Self {
+        Self::default().content(content)
+    }
+}
+
+impl From<&str> for CreateReply {
+    fn from(content: &str) -> Self {
+        Self::default().content(content)
+    }
+}
+
+impl From<serenity::CreateEmbed> for CreateReply {
+    fn from(embed: serenity::CreateEmbed) -> Self {
+        Self::default().embed(embed)
+    }
+}
+
+impl CreateReply {
+    /// IDs of the attachments of `message` to keep when editing it with this reply, see
+    /// [`AttachmentEditMode::Append`]. Must be added to the edit builder before serializing this
+    /// reply into it
+    pub(crate) fn attachments_to_keep(
+        &self,
+        message: &serenity::Message,
+    ) -> Vec<serenity::AttachmentId> {
+        match self.attachment_edit_mode {
+            AttachmentEditMode::Append => message.attachments.iter().map(|a| a.id).collect(),
+            AttachmentEditMode::Replace | AttachmentEditMode::Keep => Vec::new(),
+        }
+    }
 }
 
 /// Methods to create a message builder from any type from this [`CreateReply`]. Used by poise
@@ -95,6 +426,10 @@ impl CreateReply {
             ephemeral,
             allowed_mentions,
             reply: _, // can't reply to a message in interactions
+            reply_to: _,
+            attachment_edit_mode: _,
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
             __non_exhaustive: (),
         } = self;
 
@@ -127,6 +462,10 @@ impl CreateReply {
             ephemeral,
             allowed_mentions,
             reply: _,
+            reply_to: _,
+            attachment_edit_mode: _, // new message
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
             __non_exhaustive: (),
         } = self;
 
@@ -155,11 +494,15 @@ impl CreateReply {
         let crate::CreateReply {
             content,
             embeds,
-            attachments,
+            mut attachments,
             components,
             ephemeral: _, // can't edit ephemerality in retrospect
             allowed_mentions,
             reply: _,
+            reply_to: _,
+            attachment_edit_mode,
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
             __non_exhaustive: (),
         } = self;
 
@@ -172,6 +515,53 @@ impl CreateReply {
         if let Some(allowed_mentions) = allowed_mentions {
             builder = builder.allowed_mentions(allowed_mentions);
         }
+        match attachment_edit_mode {
+            AttachmentEditMode::Replace => builder = builder.clear_attachments(),
+            AttachmentEditMode::Append => {}
+            AttachmentEditMode::Keep => attachments.clear(),
+        }
+        for attachment in attachments {
+            builder = builder.new_attachment(attachment);
+        }
+
+        builder.embeds(embeds)
+    }
+
+    /// Serialize this response builder to a [`serenity::EditWebhookMessage`], for editing
+    /// followup responses
+    pub fn to_slash_followup_response_edit(
+        self,
+        mut builder: serenity::EditWebhookMessage,
+    ) -> serenity::EditWebhookMessage {
+        let crate::CreateReply {
+            content,
+            embeds,
+            mut attachments,
+            components,
+            ephemeral: _, // can't edit ephemerality in retrospect
+            allowed_mentions,
+            reply: _,
+            reply_to: _,
+            attachment_edit_mode,
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
+            __non_exhaustive: (),
+        } = self;
+
+        if let Some(content) = content {
+            builder = builder.content(content);
+        }
+        if let Some(components) = components {
+            builder = builder.components(components);
+        }
+        if let Some(allowed_mentions) = allowed_mentions {
+            builder = builder.allowed_mentions(allowed_mentions);
+        }
+        match attachment_edit_mode {
+            AttachmentEditMode::Replace => builder = builder.clear_attachments(),
+            AttachmentEditMode::Append => {}
+            AttachmentEditMode::Keep => attachments.clear(),
+        }
         for attachment in attachments {
             builder = builder.new_attachment(attachment);
         }
@@ -184,17 +574,25 @@ impl CreateReply {
         let crate::CreateReply {
             content,
             embeds,
-            attachments,
+            mut attachments,
             components,
             ephemeral: _, // not supported in prefix
             allowed_mentions,
             reply: _, // can't edit reference message afterwards
+            reply_to: _,
+            attachment_edit_mode,
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
             __non_exhaustive: (),
         } = self;
 
-        let mut attachments_builder = serenity::EditAttachments::new();
+        match attachment_edit_mode {
+            AttachmentEditMode::Replace => builder = builder.remove_all_attachments(),
+            AttachmentEditMode::Append => {}
+            AttachmentEditMode::Keep => attachments.clear(),
+        }
         for attachment in attachments {
-            attachments_builder = attachments_builder.add(attachment);
+            builder = builder.new_attachment(attachment);
         }
 
         if let Some(content) = content {
@@ -207,7 +605,7 @@ impl CreateReply {
             builder = builder.components(components);
         }
 
-        builder.embeds(embeds).attachments(attachments_builder)
+        builder.embeds(embeds)
     }
 
     /// Serialize this response builder to a [`serenity::CreateMessage`]
@@ -223,6 +621,10 @@ impl CreateReply {
             ephemeral: _, // not supported in prefix
             allowed_mentions,
             reply,
+            reply_to,
+            attachment_edit_mode: _, // new message
+            component_handlers: _,
+            lazy_attachments: _, // generated in resolve_lazy_attachments
             __non_exhaustive: (),
         } = self;
 
@@ -237,7 +639,11 @@ impl CreateReply {
             builder = builder.components(components);
         }
         if reply {
-            builder = builder.reference_message(invocation_message);
+            let mut reference = invocation_message;
+            if let Some(message_id) = reply_to {
+                reference.message_id = Some(message_id);
+            }
+            builder = builder.reference_message(reference);
         }
 
         for attachment in attachments {
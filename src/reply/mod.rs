@@ -6,6 +6,12 @@ pub use builder::*;
 mod send_reply;
 pub use send_reply::*;
 
+mod queue;
+pub use queue::*;
+
+mod split;
+pub use split::*;
+
 use crate::serenity_prelude as serenity;
 use std::borrow::Cow;
 
@@ -89,28 +95,73 @@ impl ReplyHandle<'_> {
         }
     }
 
+    /// Returns the ID of the sent message if it's known without an HTTP request, i.e. for prefix
+    /// command replies and followup responses. The ID of an initial interaction response isn't
+    /// returned by Discord, use [`Self::message`] to fetch it.
+    ///
+    /// Returns None in autocomplete context
+    pub fn message_id(&self) -> Option<serenity::MessageId> {
+        use ReplyHandleInner::*;
+        match &self.0 {
+            Prefix(msg)
+            | Application {
+                followup: Some(msg),
+                ..
+            } => Some(msg.id),
+            Application { followup: None, .. } | Autocomplete => None,
+        }
+    }
+
+    /// Sends another message related to this reply: a followup response to the same interaction
+    /// for application commands, or a new message for prefix commands. Returns a handle to the new
+    /// message, whose [`Self::message_id`] is known.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let handle = ctx.say("Generating report...").await?;
+    /// let report = handle
+    ///     .followup(ctx, poise::CreateReply::default().content("Report: all good"))
+    ///     .await?;
+    /// println!("report sent as message {:?}", report.message_id());
+    /// # Ok(()) }
+    /// ```
+    pub async fn followup<'b, U, E>(
+        &self,
+        ctx: crate::Context<'b, U, E>,
+        builder: CreateReply,
+    ) -> Result<ReplyHandle<'b>, serenity::Error> {
+        if let ReplyHandleInner::Autocomplete = self.0 {
+            panic!("reply is a no-op in autocomplete context");
+        }
+        // This reply has been sent, so in application commands the interaction is responded to
+        // already and the message becomes a followup
+        send_reply_inner(ctx, builder, false).await
+    }
+
     /// Edits the message that this [`ReplyHandle`] points to
     // TODO: return the edited Message object?
     // TODO: should I eliminate the ctx parameter by storing it in self instead? Would infect
     //  ReplyHandle with <U, E> type parameters
-    pub async fn edit<'att, U, E>(
+    pub async fn edit<U, E>(
         &self,
         ctx: crate::Context<'_, U, E>,
         builder: CreateReply,
     ) -> Result<(), serenity::Error> {
-        let reply = ctx.reply_builder(builder);
+        let reply = ctx.reply_builder(builder).resolve_lazy_attachments().await;
 
         match &self.0 {
             ReplyHandleInner::Prefix(msg) => {
+                // Start from an empty builder so that adding embeds or attachments won't add on
+                // top of the pre-edit items but replace them (which is apparently the more
+                // intuitive behavior). Notably, setting the builder to default doesn't mean the
+                // entire message is reset to empty: Discord only updates parts of the message
+                // that have had a modification specified
+                let mut builder = serenity::EditMessage::new();
+                for attachment_id in reply.attachments_to_keep(msg) {
+                    builder = builder.keep_existing_attachment(attachment_id);
+                }
                 msg.clone()
-                    .edit(ctx.serenity_context(), {
-                        // Clear builder so that adding embeds or attachments won't add on top of
-                        // the pre-edit items but replace them (which is apparently the more
-                        // intuitive behavior). Notably, setting the builder to default doesn't
-                        // mean the entire message is reset to empty: Discord only updates parts
-                        // of the message that have had a modification specified
-                        reply.to_prefix_edit(serenity::EditMessage::new())
-                    })
+                    .edit(ctx.serenity_context(), reply.to_prefix_edit(builder))
                     .await?;
             }
             ReplyHandleInner::Application {
@@ -118,8 +169,14 @@ impl ReplyHandle<'_> {
                 interaction,
                 followup: None,
             } => {
-                let builder =
-                    reply.to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
+                let mut builder = serenity::EditInteractionResponse::new();
+                if reply.attachment_edit_mode == crate::AttachmentEditMode::Append {
+                    let msg = interaction.get_response(http).await?;
+                    for attachment_id in reply.attachments_to_keep(&msg) {
+                        builder = builder.keep_existing_attachment(attachment_id);
+                    }
+                }
+                let builder = reply.to_slash_initial_response_edit(builder);
 
                 interaction.edit_response(http, builder).await?;
             }
@@ -128,16 +185,166 @@ impl ReplyHandle<'_> {
                 interaction,
                 followup: Some(msg),
             } => {
-                let builder = reply
-                    .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
+                let mut builder = serenity::EditWebhookMessage::new();
+                for attachment_id in reply.attachments_to_keep(msg) {
+                    builder = builder.keep_existing_attachment(attachment_id);
+                }
+                let builder = reply.to_slash_followup_response_edit(builder);
 
-                interaction.edit_followup(http, msg.id, builder).await?;
+                // Followups are messages of the application's interaction webhook
+                let webhook_id = serenity::WebhookId::new(interaction.application_id.get());
+                let context = (webhook_id, interaction.token.as_str(), msg.id);
+                serenity::Builder::execute(builder, *http, context).await?;
             }
             ReplyHandleInner::Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
         Ok(())
     }
 
+    /// Edits the message that this [`ReplyHandle`] points to, based on its current state.
+    ///
+    /// The closure receives a [`CreateReply`] with the current content and embeds of the message,
+    /// which it can modify incrementally, for example to append a line. Components are left
+    /// untouched unless set by the closure, and existing attachments are kept unless the closure
+    /// changes [`CreateReply::attachment_edit_mode`].
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let handle = ctx.say("Step 1 done").await?;
+    /// handle
+    ///     .edit_with(ctx, |reply| {
+    ///         let content = format!("{}\nStep 2 done", reply.content.as_deref().unwrap_or_default());
+    ///         reply.content(content)
+    ///     })
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn edit_with<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        edit: impl FnOnce(CreateReply) -> CreateReply,
+    ) -> Result<(), serenity::Error> {
+        let existing = {
+            let msg = self.message().await?;
+            CreateReply {
+                content: Some(msg.content.clone()),
+                embeds: msg.embeds.iter().cloned().map(Into::into).collect(),
+                attachment_edit_mode: AttachmentEditMode::Keep,
+                ..Default::default()
+            }
+        };
+        self.edit(ctx, edit(existing)).await
+    }
+
+    /// Returns a component interaction collector that only collects interactions with the
+    /// components of this message, for further configuration.
+    ///
+    /// See [`Self::await_component_interaction`] for the common case.
+    pub async fn component_interaction_collector<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Result<serenity::ComponentInteractionCollector, serenity::Error> {
+        let message_id = match self.message_id() {
+            Some(message_id) => message_id,
+            None => self.message().await?.id,
+        };
+        Ok(
+            serenity::ComponentInteractionCollector::new(ctx.serenity_context())
+                .message_id(message_id),
+        )
+    }
+
+    /// Waits for the invoking user to interact with a component (e.g. click a button) of this
+    /// message. Returns None if the timeout elapses first.
+    ///
+    /// The interaction must still be responded to, e.g. with
+    /// [`serenity::CreateInteractionResponse::Acknowledge`].
+    pub async fn await_component_interaction<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        timeout: std::time::Duration,
+    ) -> Result<Option<serenity::ComponentInteraction>, serenity::Error> {
+        let collector = self.component_interaction_collector(ctx).await?;
+        Ok(collector
+            .author_id(ctx.author().id)
+            .timeout(timeout)
+            .next()
+            .await)
+    }
+
+    /// Like [`Self::await_component_interaction`], but collects all interactions of the invoking
+    /// user with the components of this message until the timeout elapses.
+    pub async fn await_component_interactions<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        timeout: std::time::Duration,
+    ) -> Result<impl futures_util::Stream<Item = serenity::ComponentInteraction>, serenity::Error>
+    {
+        let collector = self.component_interaction_collector(ctx).await?;
+        Ok(collector
+            .author_id(ctx.author().id)
+            .timeout(timeout)
+            .stream())
+    }
+
+    /// Like [`Self::await_component_interactions`], but only yields the interactions for which
+    /// `filter` returns Some, together with the returned value. Useful for parsing custom IDs into
+    /// a typed action up front.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>, handle: poise::ReplyHandle<'_>) -> Result<(), poise::serenity_prelude::Error> {
+    /// use poise::futures_util::StreamExt;
+    ///
+    /// enum Vote { Up, Down }
+    /// let votes = handle
+    ///     .await_component_interactions_with(
+    ///         ctx,
+    ///         |press| match press.data.custom_id.as_str() {
+    ///             "upvote" => Some(Vote::Up),
+    ///             "downvote" => Some(Vote::Down),
+    ///             _ => None,
+    ///         },
+    ///         std::time::Duration::from_secs(60),
+    ///     )
+    ///     .await?;
+    /// let mut votes = std::pin::pin!(votes);
+    /// while let Some((vote, press)) = votes.next().await {
+    ///     // ...
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn await_component_interactions_with<U, E, T>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        filter: impl Fn(&serenity::ComponentInteraction) -> Option<T>,
+        timeout: std::time::Duration,
+    ) -> Result<
+        impl futures_util::Stream<Item = (T, serenity::ComponentInteraction)>,
+        serenity::Error,
+    > {
+        let interactions = self.await_component_interactions(ctx, timeout).await?;
+        Ok(futures_util::StreamExt::filter_map(
+            interactions,
+            move |interaction| {
+                let item = filter(&interaction).map(|value| (value, interaction));
+                std::future::ready(item)
+            },
+        ))
+    }
+
+    /// Publishes this message to the channels following the announcement channel it was sent in,
+    /// and returns the published message.
+    ///
+    /// Fails if the message wasn't sent in an announcement channel, was already published, or is
+    /// ephemeral.
+    pub async fn crosspost<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        let msg = self.message().await?;
+        msg.crosspost(ctx.serenity_context()).await
+    }
+
     /// Deletes this message
     pub async fn delete<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
         match &self.0 {
@@ -0,0 +1,104 @@
+//! Pacing outgoing replies per channel, see [`ReplyQueue`]
+
+use crate::serenity_prelude as serenity;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Serializes and paces the replies sent via [`crate::send_reply`] per channel, so that commands
+/// sending many messages (long reports, pagination fallbacks...) don't run into Discord's channel
+/// rate limits.
+///
+/// Replies in a channel are sent one after another, in the order they were requested. If
+/// [`Self::messages_per_window`] replies were already sent in the channel within the last
+/// [`Self::window`], the next reply waits. Initial interaction responses aren't paced, because
+/// they must be sent within three seconds and don't count towards the channel rate limit.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     reply_queue: Some(poise::ReplyQueue::new(5, std::time::Duration::from_secs(5))),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug)]
+pub struct ReplyQueue {
+    /// How many replies may be sent per channel within [`Self::window`]
+    messages_per_window: u32,
+    /// Length of the rate limit window
+    window: Duration,
+    /// Send timestamps within the current window, per channel. The async mutex serializes sends
+    channels:
+        std::sync::Mutex<HashMap<serenity::ChannelId, Arc<tokio::sync::Mutex<VecDeque<Instant>>>>>,
+}
+
+/// Permission to send a reply in a channel, see [`ReplyQueue::acquire`]. The next reply in the
+/// channel waits until this is dropped.
+#[derive(Debug)]
+pub struct ReplyQueuePermit(#[allow(dead_code)] tokio::sync::OwnedMutexGuard<VecDeque<Instant>>);
+
+impl Default for ReplyQueue {
+    /// Five replies per five seconds, in line with Discord's usual channel rate limit
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(5))
+    }
+}
+
+impl ReplyQueue {
+    /// Creates a queue which sends at most `messages_per_window` replies per channel within
+    /// `window`
+    pub fn new(messages_per_window: u32, window: Duration) -> Self {
+        Self {
+            messages_per_window: messages_per_window.max(1),
+            window,
+            channels: Default::default(),
+        }
+    }
+
+    /// How many replies may be sent per channel within [`Self::window`]
+    pub fn messages_per_window(&self) -> u32 {
+        self.messages_per_window
+    }
+
+    /// Length of the rate limit window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Waits until a reply may be sent in the given channel. Hold the returned permit until the
+    /// reply has been sent, so that replies in the channel are sent in order.
+    ///
+    /// Called automatically by [`crate::send_reply`].
+    pub async fn acquire(&self, channel_id: serenity::ChannelId) -> ReplyQueuePermit {
+        let channel = {
+            let mut channels = self.channels.lock().unwrap();
+            // Forget channels nobody has sent to within the window
+            channels.retain(|_, channel| {
+                Arc::strong_count(channel) > 1
+                    || channel.try_lock().map_or(true, |sent| {
+                        sent.back()
+                            .is_some_and(|sent_at| sent_at.elapsed() < self.window)
+                    })
+            });
+            channels.entry(channel_id).or_default().clone()
+        };
+
+        let mut sent = channel.lock_owned().await;
+        while sent
+            .front()
+            .is_some_and(|sent_at| sent_at.elapsed() >= self.window)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= self.messages_per_window as usize {
+            if let Some(oldest) = sent.pop_front() {
+                tokio::time::sleep(
+                    (oldest + self.window).saturating_duration_since(Instant::now()),
+                )
+                .await;
+            }
+        }
+        sent.push_back(Instant::now());
+        ReplyQueuePermit(sent)
+    }
+}
@@ -27,14 +27,120 @@ pub async fn send_reply<U, E>(
     ctx: crate::Context<'_, U, E>,
     builder: crate::CreateReply,
 ) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+    send_reply_inner(ctx, builder, true).await
+}
+
+/// Implementation of [`send_reply`]. If `primary` is false, the reply is sent as an additional
+/// message in prefix commands, without reusing or tracking the command's response
+pub(super) async fn send_reply_inner<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    builder: crate::CreateReply,
+    primary: bool,
+) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+    #[cfg(feature = "testing")]
+    if let Some(store) = crate::testing::reply_store(ctx.serenity_context()).await {
+        return Ok(capture_reply(ctx, builder, &store).await);
+    }
+
     Ok(match ctx {
         crate::Context::Prefix(ctx) => super::ReplyHandle(super::ReplyHandleInner::Prefix(
-            crate::send_prefix_reply(ctx, builder).await?,
+            send_prefix_reply_inner(ctx, builder, primary).await?,
         )),
         crate::Context::Application(ctx) => crate::send_application_reply(ctx, builder).await?,
     })
 }
 
+/// Sends a reply and runs its [`crate::CreateReply::component_handlers`] for each interaction of
+/// the invoking user with the message's components, until the timeout elapses or a handler
+/// returns [`std::ops::ControlFlow::Break`]. Afterwards, the components are removed from the
+/// message.
+///
+/// Interactions with components without a handler are ignored. Errors from a handler stop the
+/// loop and are returned. See [`crate::CreateReply::on_component`] for an example.
+pub async fn send_interactive_reply<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    mut builder: crate::CreateReply,
+    timeout: std::time::Duration,
+) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+    let handlers = std::mem::take(&mut builder.component_handlers);
+    let handle = send_reply(ctx, builder).await?;
+
+    let find_handler = |press: &serenity::ComponentInteraction| {
+        handlers
+            .iter()
+            .find(|(custom_id, _)| *custom_id == press.data.custom_id)
+            .map(|(_, handler)| handler.clone())
+    };
+    let presses = handle
+        .await_component_interactions_with(ctx, find_handler, timeout)
+        .await?;
+    futures_util::pin_mut!(presses);
+    while let Some((handler, press)) = futures_util::StreamExt::next(&mut presses).await {
+        if handler(ctx.serenity_context(), press).await?.is_break() {
+            break;
+        }
+    }
+
+    let reply = crate::CreateReply::default().components(Vec::new());
+    handle.edit(ctx, reply).await?;
+    Ok(handle)
+}
+
+/// Sends a reply whose content may exceed Discord's message length limit, split into multiple
+/// messages according to the [`crate::SplitPolicy`], see [`crate::CreateReply::split_long_content`].
+///
+/// In application commands, the first message is the interaction response and the rest are
+/// followups. In prefix commands with [`crate::Command::reuse_response`], the first message
+/// edits the existing response and the rest are sent as new messages.
+pub async fn send_chunked_reply<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    builder: crate::CreateReply,
+    policy: crate::SplitPolicy,
+) -> Result<Vec<crate::ReplyHandle<'_>>, serenity::Error> {
+    let mut handles = Vec::new();
+    for (i, reply) in builder.split_long_content(policy).into_iter().enumerate() {
+        handles.push(send_reply_inner(ctx, reply, i == 0).await?);
+    }
+    Ok(handles)
+}
+
+/// Stores the reply in a [`crate::testing::MockContext`]'s reply store instead of sending it, and
+/// returns a handle to a fake message
+#[cfg(feature = "testing")]
+async fn capture_reply<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    builder: crate::CreateReply,
+    store: &std::sync::Mutex<Vec<crate::CreateReply>>,
+) -> crate::ReplyHandle<'a> {
+    let builder = ctx.reply_builder(builder).resolve_lazy_attachments().await;
+    let content = builder.content.clone().unwrap_or_default();
+    store.lock().unwrap().push(builder);
+
+    match ctx {
+        crate::Context::Prefix(ctx) => {
+            let mut author = serenity::User::default();
+            author.id = ctx.framework.bot_id;
+            author.bot = true;
+
+            let mut msg = serenity::CustomMessage::new();
+            msg.channel_id(ctx.msg.channel_id)
+                .author(author)
+                .content(content)
+                .timestamp(serenity::Timestamp::now());
+            super::ReplyHandle(super::ReplyHandleInner::Prefix(Box::new(msg.build())))
+        }
+        crate::Context::Application(ctx) => {
+            ctx.has_sent_initial_response
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            super::ReplyHandle(super::ReplyHandleInner::Application {
+                http: &ctx.serenity_context.http,
+                interaction: ctx.interaction,
+                followup: None,
+            })
+        }
+    }
+}
+
 /// Shorthand of [`send_reply`] for text-only messages
 ///
 /// Note: panics when called in an autocomplete context!
@@ -60,12 +166,19 @@ pub async fn send_application_reply<U, E>(
     if ctx.interaction_type == crate::CommandInteractionType::Autocomplete {
         return Ok(super::ReplyHandle(super::ReplyHandleInner::Autocomplete));
     }
+    let builder = builder.resolve_lazy_attachments().await;
 
+    // Set the flag before sending, so that an automatic deferral doesn't race the initial
+    // response while it's in flight
     let has_sent_initial_response = ctx
         .has_sent_initial_response
-        .load(std::sync::atomic::Ordering::SeqCst);
+        .swap(true, std::sync::atomic::Ordering::SeqCst);
 
     let followup = if has_sent_initial_response {
+        let _permit = match &ctx.framework.options.reply_queue {
+            Some(queue) => Some(queue.acquire(ctx.interaction.channel_id).await),
+            None => None,
+        };
         Some(Box::new({
             let builder = builder
                 .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
@@ -78,14 +191,18 @@ pub async fn send_application_reply<U, E>(
         let builder =
             builder.to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
 
-        ctx.interaction
+        let response = ctx
+            .interaction
             .create_response(
                 ctx.serenity_context,
                 serenity::CreateInteractionResponse::Message(builder),
             )
-            .await?;
-        ctx.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
+            .await;
+        if let Err(error) = response {
+            ctx.has_sent_initial_response
+                .store(false, std::sync::atomic::Ordering::SeqCst);
+            return Err(error);
+        }
 
         None
     };
@@ -102,11 +219,33 @@ pub async fn send_prefix_reply<U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
     builder: crate::CreateReply,
 ) -> Result<Box<serenity::Message>, serenity::Error> {
-    let builder = ctx.reply_builder(builder);
+    send_prefix_reply_inner(ctx, builder, true).await
+}
+
+/// Implementation of [`send_prefix_reply`], see [`send_reply_inner`] for `primary`
+async fn send_prefix_reply_inner<U, E>(
+    ctx: crate::PrefixContext<'_, U, E>,
+    builder: crate::CreateReply,
+    primary: bool,
+) -> Result<Box<serenity::Message>, serenity::Error> {
+    let mut builder = ctx.reply_builder(builder).resolve_lazy_attachments().await;
+    // Synthetic invocations have no real message to reply to or to track edits of
+    let is_synthetic = matches!(
+        ctx.trigger,
+        crate::MessageDispatchTrigger::Scheduled
+            | crate::MessageDispatchTrigger::Event
+            | crate::MessageDispatchTrigger::Programmatic
+    );
+    if is_synthetic {
+        builder.reply = false;
+    }
 
     // This must only return None when we _actually_ want to reuse the existing response! There are
     // no checks later
     let lock_edit_tracker = || {
+        if is_synthetic || !primary {
+            return None;
+        }
         if let Some(edit_tracker) = &ctx.framework.options().prefix_options.edit_tracker {
             return Some(edit_tracker.write().unwrap());
         }
@@ -117,23 +256,38 @@ pub async fn send_prefix_reply<U, E>(
         lock_edit_tracker()
             .as_mut()
             .and_then(|t| t.find_bot_response(ctx.msg.id))
-            .cloned()
     } else {
         None
     };
 
     Ok(Box::new(if let Some(mut response) = existing_response {
+        if ctx
+            .framework
+            .options()
+            .prefix_options
+            .keep_previous_response_media
+        {
+            if builder.embeds.is_empty() {
+                builder.embeds = response.embeds.iter().cloned().map(Into::into).collect();
+            }
+            if builder.attachments.is_empty() {
+                builder.attachment_edit_mode = crate::AttachmentEditMode::Append;
+            }
+        }
+
         response
             .edit(ctx.serenity_context, {
                 // Reset the message. We don't want leftovers of the previous message (e.g. user
                 // sends a message with `.content("abc")` in a track_edits command, and the edited
                 // message happens to contain embeds, we don't want to keep those embeds)
                 // (*f = Default::default() won't do)
-                let b = serenity::EditMessage::new()
+                let mut b = serenity::EditMessage::new()
                     .content("")
                     .embeds(Vec::new())
-                    .components(Vec::new())
-                    .remove_all_attachments();
+                    .components(Vec::new());
+                for attachment_id in builder.attachments_to_keep(&response) {
+                    b = b.keep_existing_attachment(attachment_id);
+                }
 
                 builder.to_prefix_edit(b)
             })
@@ -147,6 +301,10 @@ pub async fn send_prefix_reply<U, E>(
 
         response
     } else {
+        let _permit = match &ctx.framework.options.reply_queue {
+            Some(queue) => Some(queue.acquire(ctx.msg.channel_id).await),
+            None => None,
+        };
         let new_response = ctx
             .msg
             .channel_id
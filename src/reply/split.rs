@@ -0,0 +1,194 @@
+//! Splitting long replies into multiple messages, see [`crate::CreateReply::split_long_content`]
+
+/// Opening and closing code fence
+const FENCE: &str = "```";
+/// Maximum length of a reopened code fence including the language, longer ones are truncated
+const MAX_FENCE_LENGTH: usize = 24;
+
+/// How [`crate::CreateReply::split_long_content`] splits the content of a reply
+#[derive(Clone, Debug)]
+pub struct SplitPolicy {
+    /// Maximum number of characters per message. 2000 by default, which is Discord's limit
+    pub max_length: usize,
+    /// Whether code blocks that are split across messages are closed at the end of one message
+    /// and reopened, with the same language, at the start of the next. True by default.
+    ///
+    /// Ignored if [`Self::max_length`] is 30 characters or less, which leaves no room for the
+    /// reopened code fence.
+    pub preserve_code_blocks: bool,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for SplitPolicy {
+    fn default() -> Self {
+        Self {
+            max_length: 2000,
+            preserve_code_blocks: true,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Number of characters in the string, which is what Discord's length limit counts
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Splits a line that doesn't fit into a single message into pieces of at most `max_length`
+/// characters, preferably at whitespace
+fn split_line(line: &str, max_length: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while char_len(rest) > max_length {
+        let limit = rest
+            .char_indices()
+            .nth(max_length)
+            .map_or(rest.len(), |(i, _)| i);
+        let split_at = match rest[..limit].rfind(char::is_whitespace) {
+            Some(i) if i > 0 => i + rest[i..].chars().next().map_or(0, char::len_utf8),
+            _ => limit,
+        };
+        let (piece, remainder) = rest.split_at(split_at);
+        pieces.push(piece);
+        rest = remainder;
+    }
+    pieces.push(rest);
+    pieces
+}
+
+/// Splits text into chunks of at most [`SplitPolicy::max_length`] characters, preferably at line
+/// breaks
+pub(crate) fn split_text(text: &str, policy: &SplitPolicy) -> Vec<String> {
+    // Leave room for reopening and closing a code block, including line breaks, unless the
+    // messages are too short for that
+    let max_length = policy.max_length.max(1);
+    let preserve_code_blocks =
+        policy.preserve_code_blocks && max_length > MAX_FENCE_LENGTH + FENCE.len() + 2;
+    let fence_budget = if preserve_code_blocks {
+        MAX_FENCE_LENGTH + FENCE.len() + 2
+    } else {
+        0
+    };
+    let line_budget = max_length - fence_budget;
+
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    // The line that opened the code block we're in, if any
+    let mut open_fence: Option<String> = None;
+
+    for line in text.split_inclusive('\n') {
+        for piece in split_line(line, line_budget) {
+            let closing_len = if open_fence.is_some() {
+                FENCE.len() + 1
+            } else {
+                0
+            };
+            if !chunk.is_empty() && char_len(&chunk) + char_len(piece) + closing_len > max_length {
+                if let Some(fence) = &open_fence {
+                    if !chunk.ends_with('\n') {
+                        chunk.push('\n');
+                    }
+                    chunk.push_str(FENCE);
+                    chunks.push(std::mem::take(&mut chunk));
+                    chunk.push_str(fence);
+                    chunk.push('\n');
+                } else {
+                    chunks.push(std::mem::take(&mut chunk));
+                }
+            }
+            chunk.push_str(piece);
+
+            if preserve_code_blocks && piece.matches(FENCE).count() % 2 == 1 {
+                open_fence = match open_fence {
+                    Some(_) => None,
+                    None => {
+                        // Keep the language of the code block, if the fence starts the line
+                        let trimmed = piece.trim();
+                        Some(match trimmed.strip_prefix(FENCE) {
+                            Some(language) if !language.contains(char::is_whitespace) => {
+                                trimmed.chars().take(MAX_FENCE_LENGTH).collect()
+                            }
+                            _ => FENCE.to_owned(),
+                        })
+                    }
+                };
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+impl crate::CreateReply {
+    /// Splits this reply into multiple replies whose content fits into a single message each,
+    /// preferably at line breaks. See [`crate::send_chunked_reply`] to send them.
+    ///
+    /// Code blocks that span multiple messages are closed and reopened according to the
+    /// [`SplitPolicy`]. The first reply keeps the [inline reply](Self::reply) settings, and the
+    /// last one gets the embeds, attachments and components. Returns the reply unchanged if it
+    /// doesn't need splitting.
+    ///
+    /// ```rust
+    /// let text = format!("```rust\n{}```", "let x = 1;\n".repeat(500));
+    /// let replies = poise::CreateReply::default()
+    ///     .content(text)
+    ///     .split_long_content(poise::SplitPolicy::default());
+    /// assert_eq!(replies.len(), 3);
+    /// for reply in &replies {
+    ///     let content = reply.content.as_deref().unwrap();
+    ///     assert!(content.chars().count() <= 2000);
+    ///     assert!(content.starts_with("```rust\n") && content.ends_with("```"));
+    /// }
+    /// ```
+    pub fn split_long_content(self, policy: SplitPolicy) -> Vec<Self> {
+        let chunks = match &self.content {
+            Some(content) if char_len(content) > policy.max_length => split_text(content, &policy),
+            _ => return vec![self],
+        };
+
+        let rest = Self {
+            ephemeral: self.ephemeral,
+            allowed_mentions: self.allowed_mentions.clone(),
+            ..Default::default()
+        };
+        let first = Self {
+            embeds: Vec::new(),
+            attachments: Vec::new(),
+            lazy_attachments: Vec::new(),
+            components: None,
+            component_handlers: Vec::new(),
+            ..self.clone()
+        };
+        let mut replies = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| if i == 0 { &first } else { &rest }.clone().content(chunk))
+            .collect::<Vec<_>>();
+
+        if let Some(last) = replies.last_mut() {
+            last.embeds = self.embeds;
+            last.attachments = self.attachments;
+            last.lazy_attachments = self.lazy_attachments;
+            last.components = self.components;
+            last.component_handlers = self.component_handlers;
+        }
+        replies
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_text_short_max_length() {
+    let policy = SplitPolicy {
+        max_length: 10,
+        ..Default::default()
+    };
+    let text = "```\nsome code that is longer than ten characters\n```";
+    let chunks = split_text(text, &policy);
+    assert!(chunks.len() > 1);
+    assert!(chunks.iter().all(|chunk| char_len(chunk) <= 10));
+    assert_eq!(chunks.concat(), text);
+}
@@ -0,0 +1,148 @@
+//! Running commands on a recurring schedule, without a user invoking them
+
+use crate::serenity_prelude as serenity;
+
+/// Determines when a [`ScheduledCommand`] runs next.
+///
+/// Implemented for [`std::time::Duration`] (fixed interval) and, with the `cron` feature, for
+/// [`cron::Schedule`].
+pub trait Schedule: Send + Sync {
+    /// Returns how long to wait from now until the next run, or None if the schedule has no more
+    /// upcoming runs.
+    ///
+    /// Called when a run starts, so the delay is measured from the start of the previous run
+    fn next_delay(&self) -> Option<std::time::Duration>;
+}
+
+impl Schedule for std::time::Duration {
+    fn next_delay(&self) -> Option<std::time::Duration> {
+        Some(*self)
+    }
+}
+
+#[cfg(feature = "cron")]
+impl Schedule for cron::Schedule {
+    fn next_delay(&self) -> Option<std::time::Duration> {
+        let now = serenity::Timestamp::now();
+        let next = self.after(&*now).next()?;
+        Some((next - *now).to_std().unwrap_or_default())
+    }
+}
+
+/// A command invocation that is executed on a [`Schedule`] instead of being triggered by a user.
+///
+/// Scheduled commands are dispatched through the prefix code path with a synthetic invocation
+/// message, so the invoked command must have `prefix_command` enabled. The synthetic message is
+/// authored by the bot itself and posted "in" [`Self::channel_id`]; responses sent via
+/// [`crate::Context::say`] or [`crate::Context::send`] go into that channel. The trigger of such
+/// invocations is [`crate::MessageDispatchTrigger::Scheduled`].
+///
+/// Each scheduled command runs as a background task (see
+/// [`crate::FrameworkOptions::spawn_scheduled_command`]), so it stops when
+/// [`crate::FrameworkOptions::shutdown_token`] is cancelled.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// let announcement = poise::ScheduledCommand::new(
+///     "announce Don't forget to drink water!",
+///     serenity::ChannelId::new(123456789),
+///     std::time::Duration::from_secs(60 * 60),
+/// );
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     scheduled_commands: vec![announcement],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ScheduledCommand {
+    /// Command name and arguments, as if typed after the prefix, e.g. `"purge 100"`.
+    ///
+    /// Subcommands are resolved like in a normal prefix invocation.
+    pub invocation: String,
+    /// Channel in which the command is executed and where its responses are sent
+    pub channel_id: serenity::ChannelId,
+    /// When to run the command
+    #[derivative(Debug = "ignore")]
+    pub schedule: Box<dyn Schedule>,
+    /// Whether to run permission checks, command checks and cooldown checks before each run.
+    ///
+    /// Checks see the bot itself as the command author. False by default.
+    pub run_checks: bool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl ScheduledCommand {
+    /// Creates a new scheduled command with checks disabled
+    pub fn new(
+        invocation: impl Into<String>,
+        channel_id: serenity::ChannelId,
+        schedule: impl Schedule + 'static,
+    ) -> Self {
+        Self {
+            invocation: invocation.into(),
+            channel_id,
+            schedule: Box::new(schedule),
+            run_checks: false,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl<U: Send + Sync + 'static, E: Send + Sync + 'static> crate::FrameworkOptions<U, E> {
+    /// Runs the command on its schedule as a background task, like
+    /// [`Self::spawn_periodic`]. It stops once [`Self::shutdown_token`] is cancelled.
+    ///
+    /// [`crate::Framework`] calls this for every command in [`Self::scheduled_commands`] when
+    /// it's created. If you're doing dispatch manually, call this yourself and run
+    /// [`crate::run_background_tasks`].
+    pub fn spawn_scheduled_command(&self, job: ScheduledCommand) {
+        let ScheduledCommand {
+            invocation,
+            channel_id,
+            schedule,
+            run_checks,
+            __non_exhaustive: (),
+        } = job;
+        self.spawn_with_schedule(schedule, move |ctx| {
+            let invocation = invocation.clone();
+            Box::pin(async move {
+                crate::dispatch::run_synthetic_invocation(
+                    ctx.framework,
+                    ctx.serenity_context,
+                    &invocation,
+                    channel_id,
+                    None,
+                    crate::MessageDispatchTrigger::Scheduled,
+                    run_checks,
+                )
+                .await;
+                // Errors were forwarded to the error handlers already
+                Ok(())
+            })
+        });
+    }
+}
+
+/// Executes a single run of a [`ScheduledCommand`] immediately, regardless of its schedule.
+///
+/// Errors are forwarded to the framework's error handlers like normal command errors.
+pub async fn run_scheduled_command<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    job: &ScheduledCommand,
+) {
+    crate::dispatch::run_synthetic_invocation(
+        framework,
+        ctx,
+        &job.invocation,
+        job.channel_id,
+        None,
+        crate::MessageDispatchTrigger::Scheduled,
+        job.run_checks,
+    )
+    .await;
+}
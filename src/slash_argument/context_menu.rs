@@ -35,3 +35,43 @@ impl<U, E> ContextMenuParameter<U, E> for serenity::Message {
         crate::ContextMenuCommandAction::Message(action)
     }
 }
+
+/// Parameter type for context menu commands that appear on both users and messages. Registers a
+/// user and a message context menu entry with the same name, which share checks and cooldowns.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// #[poise::command(context_menu_command = "Report")]
+/// async fn report(
+///     ctx: poise::ApplicationContext<'_, (), serenity::Error>,
+///     target: poise::ContextMenuTarget,
+/// ) -> Result<(), serenity::Error> {
+///     let user = match &target {
+///         poise::ContextMenuTarget::User(user) => user,
+///         poise::ContextMenuTarget::Message(message) => &message.author,
+///     };
+///     ctx.say(format!("Reported {}", user.name)).await?;
+///     Ok(())
+/// }
+///
+/// assert_eq!(report().create_as_context_menu_commands().len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)] // users are small compared to messages, which are boxed
+pub enum ContextMenuTarget {
+    /// The command was invoked on a user
+    User(serenity::User),
+    /// The command was invoked on a message
+    Message(Box<serenity::Message>),
+}
+
+impl<U, E> ContextMenuParameter<U, E> for ContextMenuTarget {
+    fn to_action(
+        action: fn(
+            crate::ApplicationContext<'_, U, E>,
+            Self,
+        ) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>>,
+    ) -> crate::ContextMenuCommandAction<U, E> {
+        crate::ContextMenuCommandAction::UserAndMessage(action)
+    }
+}
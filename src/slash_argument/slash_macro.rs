@@ -57,16 +57,19 @@ impl SlashArgError {
                 ctx: ctx.into(),
                 error,
                 input: Some(input),
+                parameter: None,
             },
             Self::Invalid(description) => crate::FrameworkError::ArgumentParse {
                 ctx: ctx.into(),
                 error: description.into(),
                 input: None,
+                parameter: None,
             },
             Self::Http(error) => crate::FrameworkError::ArgumentParse {
                 ctx: ctx.into(),
                 error: error.into(),
                 input: None,
+                parameter: None,
             },
             Self::__NonExhaustive => unreachable!(),
         }
@@ -152,6 +155,15 @@ macro_rules! _parse_slash {
             .unwrap_or(false)
     };
 
+    // Extract #[custom_parser = "..."] T
+    ($ctx:ident, $interaction:ident, $args:ident => $name:literal: CUSTOM $parser:ty => $type:ty) => {
+        <$parser as $crate::CustomParameterParser<$type>>::extract(
+            $ctx,
+            $interaction,
+            $args.iter().find(|arg| arg.name == $name).map(|arg| &arg.value),
+        ).await?
+    };
+
     // Extract T
     ($ctx:ident, $interaction:ident, $args:ident => $name:literal: $($type:tt)*) => {
         $crate::_parse_slash!($ctx, $interaction, $args => $name: Option<$($type)*>)
@@ -183,6 +195,24 @@ let (arg1, arg2) = poise::parse_slash_args!(
 */
 #[macro_export]
 macro_rules! parse_slash_args {
+    ($ctx:expr, $interaction:expr, $args:expr => $(
+        ( $name:literal: $($type:tt)* )
+    ),* $(,)? ) => {
+        async /* not move! */ {
+            $crate::_parse_slash_args_named!(
+                $ctx, $interaction, $args => $( ( $name: $($type)* ), )*
+            )
+            .await
+            .map_err(|(error, _)| error)
+        }
+    };
+}
+
+/// Like [`parse_slash_args`], but the error additionally contains the name of the parameter that
+/// failed to parse. Used by the [`crate::command`] macro
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _parse_slash_args_named {
     ($ctx:expr, $interaction:expr, $args:expr => $(
         ( $name:literal: $($type:tt)* )
     ),* $(,)? ) => {
@@ -192,8 +222,14 @@ macro_rules! parse_slash_args {
             // ctx here is a serenity::Context, so it doesn't already contain interaction!
             let (ctx, interaction, args) = ($ctx, $interaction, $args);
 
-            Ok::<_, $crate::SlashArgError>(( $(
-                $crate::_parse_slash!( ctx, interaction, args => $name: $($type)* ),
+            Ok::<_, ($crate::SlashArgError, &'static str)>(( $(
+                async {
+                    Ok::<_, $crate::SlashArgError>(
+                        $crate::_parse_slash!( ctx, interaction, args => $name: $($type)* )
+                    )
+                }
+                .await
+                .map_err(|error| (error, $name))?,
             )* ))
         }
     };
@@ -0,0 +1,224 @@
+//! Command usage statistics, see [`UsageTracker`]
+
+use crate::serenity_prelude as serenity;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// Invocation counts within one time bucket of a [`UsageTracker`]
+#[derive(Debug, Default)]
+struct UsageBucket {
+    /// Index of the time bucket, i.e. its start time divided by the bucket size
+    index: u64,
+    /// Invocations per qualified command name
+    commands: HashMap<String, u64>,
+    /// Invocations per user
+    users: HashMap<serenity::UserId, u64>,
+    /// Invocations per guild
+    guilds: HashMap<serenity::GuildId, u64>,
+    /// Invocations in total, including those in DMs
+    total: u64,
+}
+
+/// Counts command invocations per command, user and guild, for `~stats`-style usage statistics.
+/// See [`crate::builtins::usage_stats`] for a command that displays them.
+///
+/// The tracker is an [`crate::InvocationObserver`]. Register it in
+/// [`crate::FrameworkOptions::invocation_observers`], usually in an [`std::sync::Arc`] so that
+/// you can keep a handle for querying it, and every command invocation that passes its checks is
+/// counted automatically, regardless of whether the command succeeds. This includes synthetic
+/// invocations like [scheduled commands](crate::ScheduledCommand).
+///
+/// Invocations are counted in time buckets of [`Self::bucket_size`], which determines the
+/// granularity of the time windows that can be queried. Buckets older than [`Self::retention`]
+/// are discarded. The statistics are kept in memory only.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// use std::time::Duration;
+///
+/// let tracker = poise::stats::UsageTracker::new(
+///     Duration::from_secs(60 * 60),
+///     Duration::from_secs(60 * 60 * 24 * 7),
+/// );
+/// let user_id = serenity::UserId::new(1);
+/// tracker.record("ping", user_id, None);
+/// tracker.record("ping", user_id, None);
+/// tracker.record("config set", user_id, Some(serenity::GuildId::new(2)));
+///
+/// assert_eq!(tracker.total(None), 3);
+/// assert_eq!(
+///     tracker.commands(Some(Duration::from_secs(60))),
+///     [("ping".to_owned(), 2), ("config set".to_owned(), 1)],
+/// );
+/// assert_eq!(tracker.users(None), [(user_id, 3)]);
+/// assert_eq!(tracker.guilds(None), [(serenity::GuildId::new(2), 1)]);
+/// ```
+#[derive(Debug)]
+pub struct UsageTracker {
+    /// Length of each time bucket
+    bucket_size: Duration,
+    /// How long buckets are kept
+    retention: Duration,
+    /// Time buckets, oldest first
+    buckets: parking_lot::Mutex<VecDeque<UsageBucket>>,
+}
+
+impl Default for UsageTracker {
+    /// Hourly buckets, kept for 30 days
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(60 * 60),
+            Duration::from_secs(60 * 60 * 24 * 30),
+        )
+    }
+}
+
+impl UsageTracker {
+    /// Creates a tracker counting invocations in buckets of `bucket_size`, which are kept for
+    /// `retention`. A bucket size under one second is treated as one second
+    pub fn new(bucket_size: Duration, retention: Duration) -> Self {
+        Self {
+            bucket_size: bucket_size.max(Duration::from_secs(1)),
+            retention,
+            buckets: parking_lot::Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Length of each time bucket
+    pub fn bucket_size(&self) -> Duration {
+        self.bucket_size
+    }
+
+    /// How long invocation counts are kept
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Returns the index of the time bucket that the given time falls into
+    fn bucket_index(&self, time: SystemTime) -> u64 {
+        let since_epoch = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        since_epoch.as_secs() / self.bucket_size.as_secs()
+    }
+
+    /// Returns the index of the oldest bucket within the given duration before now
+    fn first_bucket_index(&self, window: Duration) -> u64 {
+        self.bucket_index(
+            SystemTime::now()
+                .checked_sub(window)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        )
+    }
+
+    /// Counts an invocation of the given command. Called automatically during dispatch if the
+    /// tracker is registered in [`crate::FrameworkOptions::invocation_observers`]
+    pub fn record(
+        &self,
+        command: &str,
+        user_id: serenity::UserId,
+        guild_id: Option<serenity::GuildId>,
+    ) {
+        let index = self.bucket_index(SystemTime::now());
+        let first_kept = self.first_bucket_index(self.retention);
+
+        let mut buckets = self.buckets.lock();
+        while buckets.front().is_some_and(|b| b.index < first_kept) {
+            buckets.pop_front();
+        }
+        if buckets.back().map_or(true, |b| b.index < index) {
+            buckets.push_back(UsageBucket {
+                index,
+                ..Default::default()
+            });
+        }
+        let bucket = buckets.back_mut().expect("just pushed a bucket");
+
+        *bucket.commands.entry(command.to_owned()).or_default() += 1;
+        *bucket.users.entry(user_id).or_default() += 1;
+        if let Some(guild_id) = guild_id {
+            *bucket.guilds.entry(guild_id).or_default() += 1;
+        }
+        bucket.total += 1;
+    }
+
+    /// Sums up the counts of the buckets within the window, using `counts` to pick the counts
+    /// from each bucket. Returns them sorted by count, highest first
+    fn sum<K: Clone + Eq + std::hash::Hash + Ord>(
+        &self,
+        window: Option<Duration>,
+        counts: impl Fn(&UsageBucket) -> &HashMap<K, u64>,
+    ) -> Vec<(K, u64)> {
+        let first = window.map_or(0, |window| self.first_bucket_index(window));
+        let mut sums = HashMap::<K, u64>::new();
+        for bucket in self.buckets.lock().iter().filter(|b| b.index >= first) {
+            for (key, count) in counts(bucket) {
+                *sums.entry(key.clone()).or_default() += count;
+            }
+        }
+
+        let mut sums = sums.into_iter().collect::<Vec<_>>();
+        sums.sort_by(|(a_key, a_count), (b_key, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+        });
+        sums
+    }
+
+    /// Returns the number of invocations per command within the given time window before now,
+    /// most used first. None means all retained invocations.
+    ///
+    /// The window is rounded up to whole [buckets](Self::bucket_size).
+    pub fn commands(&self, window: Option<Duration>) -> Vec<(String, u64)> {
+        self.sum(window, |bucket| &bucket.commands)
+    }
+
+    /// Returns the number of invocations per user within the given time window, most active
+    /// first. See [`Self::commands`]
+    pub fn users(&self, window: Option<Duration>) -> Vec<(serenity::UserId, u64)> {
+        self.sum(window, |bucket| &bucket.users)
+    }
+
+    /// Returns the number of invocations per guild within the given time window, most active
+    /// first. Invocations in DMs aren't included. See [`Self::commands`]
+    pub fn guilds(&self, window: Option<Duration>) -> Vec<(serenity::GuildId, u64)> {
+        self.sum(window, |bucket| &bucket.guilds)
+    }
+
+    /// Returns the total number of invocations within the given time window. See
+    /// [`Self::commands`]
+    pub fn total(&self, window: Option<Duration>) -> u64 {
+        let first = window.map_or(0, |window| self.first_bucket_index(window));
+        self.buckets
+            .lock()
+            .iter()
+            .filter(|b| b.index >= first)
+            .map(|b| b.total)
+            .sum()
+    }
+
+    /// Removes all statistics
+    pub fn clear(&self) {
+        self.buckets.lock().clear();
+    }
+}
+
+impl<U, E> crate::InvocationObserver<U, E> for UsageTracker {
+    /// Counts the invocation, unless it was denied
+    fn on_completed(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        _: Duration,
+        result: &Result<(), crate::FrameworkError<'_, U, E>>,
+    ) {
+        if let Err(error) = result {
+            if error.denial_reason().is_some() {
+                return;
+            }
+        }
+        self.record(
+            &ctx.command().qualified_name,
+            ctx.author().id,
+            ctx.guild_id(),
+        );
+    }
+}
@@ -26,12 +26,40 @@ pub struct Command<U, E> {
     ///
     /// The enum variant shows which Discord item this context menu command works on
     pub context_menu_action: Option<crate::ContextMenuCommandAction<U, E>>,
+    /// Callback to execute when this command is invoked as the primary entry point of an app
+    /// with Activities, i.e. when the user launches the app from the App Launcher
+    ///
+    /// Only one top-level command of an app can be its primary entry point. Responding to the
+    /// interaction is up to the callback; without a response, Discord shows an error.
+    #[derivative(Debug = "ignore")]
+    pub entry_point_action: Option<
+        for<'a> fn(
+            crate::ApplicationContext<'a, U, E>,
+        ) -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>,
+    >,
 
     // ============= Command type agnostic data
     /// Subcommands of this command, if any
     pub subcommands: Vec<Command<U, E>>,
     /// Require a subcommand to be invoked
     pub subcommand_required: bool,
+    /// Whether this subcommand is a subcommand group, i.e. the middle level of a
+    /// `/config logging enable` style command tree.
+    ///
+    /// Groups are registered as [`serenity::CommandOptionType::SubCommandGroup`] and may only
+    /// contain plain subcommands, as Discord doesn't allow deeper nesting. The help builtins list
+    /// groups separately from plain subcommands. Has no effect on top-level commands.
+    pub subcommand_group: bool,
+    /// If true, the permission requirements, restrictions, checks and cooldowns of parent commands
+    /// don't apply when this command is invoked as a subcommand. Useful for subcommands that should
+    /// be more permissive than their parent, like a `/config view` open to everyone under an
+    /// admin-only `/config`
+    pub skip_parent_checks: bool,
+    /// If false, the [`Self::checks`] of parent commands don't run when this command is invoked as
+    /// a subcommand. Unlike [`Self::skip_parent_checks`], the restrictions, permission
+    /// requirements and cooldowns of parent commands still apply. True by default
+    #[derivative(Default(value = "true"))]
+    pub inherit_checks: bool,
     /// Main name of the command. Aliases (prefix-only) can be set in [`Self::aliases`].
     pub name: String,
     /// Localized names with locale string as the key (slash-only)
@@ -58,24 +86,50 @@ pub struct Command<U, E> {
     /// Multiline description with detailed usage instructions. Displayed in the command specific
     /// help: `~help command_name`
     pub help_text: Option<String>,
+    /// Localized help texts with locale string as the key. Used by the help builtins in place of
+    /// [`Self::help_text`] if the invocation locale has an entry
+    pub help_text_localizations: std::collections::HashMap<String, String>,
+    /// Example invocations, like `~add 1 2`. Displayed by the help builtins and by
+    /// [`crate::builtins::on_error`] when arguments fail to parse
+    pub examples: Vec<String>,
     /// Handles command cooldowns. Mainly for framework internal use
     pub cooldowns: std::sync::Mutex<crate::CooldownTracker>,
     /// Configuration for the [`crate::CooldownTracker`]
     pub cooldown_config: std::sync::RwLock<crate::CooldownConfig>,
+    /// Name of the [`crate::CooldownBucket`] in [`crate::FrameworkOptions::buckets`] that limits
+    /// this command, if any
+    pub bucket: Option<String>,
+    /// Maximum number of invocations of this command that may run at the same time. Further
+    /// invocations fail with [`crate::FrameworkError::TooManyConcurrentInvocations`] until one of
+    /// the running invocations finishes. Unlimited if None
+    pub max_concurrent_invocations: Option<usize>,
+    /// Number of invocations of this command that are currently running. Mainly for framework
+    /// internal use
+    pub active_invocations: std::sync::atomic::AtomicUsize,
     /// After the first response, whether to post subsequent responses as edits to the initial
     /// message
     ///
     /// Note: in prefix commands, this only has an effect if
-    /// `crate::PrefixFrameworkOptions::edit_tracker` is set.
+    /// `crate::PrefixFrameworkOptions::edit_tracker` is set. To keep embeds and attachments of the
+    /// previous response that the new response doesn't set, see
+    /// [`crate::PrefixFrameworkOptions::keep_previous_response_media`].
     pub reuse_response: bool,
     /// Permissions which users must have to invoke this command. Used by Discord to set who can
-    /// invoke this as a slash command. Not used on prefix commands or checked internally.
+    /// invoke this as a slash command. Not used on prefix commands.
+    ///
+    /// Discord ignores this field on subcommands, so poise checks it internally when a slash
+    /// subcommand is invoked. The top-level command is registered with the permissions that all
+    /// of its subcommands have in common.
     ///
     /// Set to [`serenity::Permissions::empty()`] by default
     pub default_member_permissions: serenity::Permissions,
     /// Permissions which users must have to invoke this command. This is checked internally and
     /// works for both prefix commands and slash commands.
     ///
+    /// Permissions are taken from the interaction if possible, otherwise from cache or HTTP. If
+    /// they can't be determined, the invocation is denied with
+    /// [`crate::FrameworkError::PermissionsUnverifiable`].
+    ///
     /// Set to [`serenity::Permissions::empty()`] by default
     pub required_permissions: serenity::Permissions,
     /// Permissions without which command execution will fail. You can set this to fail early and
@@ -93,12 +147,44 @@ pub struct Command<U, E> {
     pub dm_only: bool,
     /// If true, the command may only run in NSFW channels
     pub nsfw_only: bool,
+    /// Installation contexts in which this application command is available: apps installed to a
+    /// guild, apps installed to a user (user-installable apps), or both. Only applies to top-level
+    /// commands. If None, Discord's default is used
+    pub install_context: Option<Vec<serenity::InstallationContext>>,
+    /// Interaction contexts in which this application command can be used: guilds, DMs with the
+    /// bot, and other DMs and group DMs (only for user-installed apps). Only applies to top-level
+    /// commands. If None, Discord's default is used
+    pub interaction_context: Option<Vec<serenity::InteractionContext>>,
     /// Command-specific override for [`crate::FrameworkOptions::on_error`]
     #[derivative(Debug = "ignore")]
     pub on_error: Option<fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>>,
+    /// Called with a summary of every finished invocation of this command, instead of the logger
+    /// of any [`crate::InvocationLog`] observer
+    #[derivative(Debug = "ignore")]
+    pub invocation_logger: Option<crate::InvocationLogger>,
     /// If any of these functions returns false, this command will not be executed.
+    ///
+    /// Checks run after the restrictions and permission requirements of the invoked command and
+    /// its parents, in this order: [`crate::FrameworkOptions::command_check`], then the checks of
+    /// each parent command from the outermost one down (see [`Self::inherit_checks`]), then the
+    /// checks of the invoked command itself. Within a command, [`Self::final_checks`] run after
+    /// these checks.
+    ///
+    /// A check function that passed once isn't run again in the same invocation. This is
+    /// best-effort, because functions are told apart by address: the compiler may give one
+    /// function several addresses, so it runs again, or merge identical functions into one, so
+    /// only one of them runs. Checks should therefore be idempotent.
     #[derivative(Debug = "ignore")]
     pub checks: Vec<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Like [`Self::checks`], but if all of these pass, the checks of subcommands below this
+    /// command are skipped. Useful for e.g. a moderator check on a parent command which should
+    /// override any stricter checks of its subcommands
+    #[derivative(Debug = "ignore")]
+    pub final_checks: Vec<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Middleware wrapping the execution of this command, run after the global
+    /// [`crate::FrameworkOptions::middleware`]. See [`crate::CommandMiddleware`].
+    #[derivative(Debug = "ignore")]
+    pub middleware: Vec<Box<dyn crate::CommandMiddleware<U, E>>>,
     /// List of parameters for this command
     ///
     /// Used for registering and parsing slash commands. Can also be used in help commands
@@ -122,7 +208,19 @@ pub struct Command<U, E> {
     pub context_menu_name: Option<String>,
     /// Whether responses to this command should be ephemeral by default (application-only)
     pub ephemeral: bool,
+    /// Whether to defer the response automatically if the command hasn't responded after
+    /// [`crate::FrameworkOptions::auto_defer_after`], or two seconds if that's unset. Keeps slow
+    /// commands from running into Discord's three second response deadline (application-only)
+    pub auto_defer: bool,
+    /// Applied to the builder at the end of [`Self::create_as_slash_command`]. Allows setting
+    /// registration fields that poise doesn't model (yet), like newly released Discord features
+    #[derivative(Debug = "ignore")]
+    pub on_create_slash: Option<fn(serenity::CreateCommand) -> serenity::CreateCommand>,
 
+    /// The original command and mapping functions of commands created by [`Self::adapt`]
+    #[doc(hidden)]
+    #[derivative(Debug = "ignore")]
+    pub __adapter: Option<Box<dyn std::any::Any + Send + Sync>>,
     // Like #[non_exhaustive], but #[poise::command] still needs to be able to create an instance
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -136,15 +234,21 @@ impl<U, E> PartialEq for Command<U, E> {
 impl<U, E> Eq for Command<U, E> {}
 
 impl<U, E> Command<U, E> {
+    /// Starts building a command with the given name at runtime, see [`crate::CommandBuilder`]
+    pub fn builder(name: impl Into<String>) -> crate::CommandBuilder<U, E> {
+        crate::CommandBuilder::new(name)
+    }
+
     /// Serializes this Command into an application command option, which is the form which Discord
     /// requires subcommands to be in
     fn create_as_subcommand(&self) -> Option<serenity::CreateCommandOption> {
         self.slash_action?;
 
-        let kind = if self.subcommands.is_empty() {
-            serenity::CommandOptionType::SubCommand
-        } else {
+        let is_group = self.subcommand_group || !self.subcommands.is_empty();
+        let kind = if is_group {
             serenity::CommandOptionType::SubCommandGroup
+        } else {
+            serenity::CommandOptionType::SubCommand
         };
 
         let description = self.description.as_deref().unwrap_or("A slash command");
@@ -157,7 +261,7 @@ impl<U, E> Command<U, E> {
             builder = builder.description_localized(locale, description);
         }
 
-        if self.subcommands.is_empty() {
+        if !is_group {
             for param in &self.parameters {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
                 // just ignore them but have to abort the creation process entirely
@@ -165,6 +269,13 @@ impl<U, E> Command<U, E> {
             }
         } else {
             for subcommand in &self.subcommands {
+                if subcommand.subcommand_group || !subcommand.subcommands.is_empty() {
+                    tracing::warn!(
+                        "not registering `{}`: subcommand groups can't be nested",
+                        subcommand.qualified_name
+                    );
+                    continue;
+                }
                 if let Some(subcommand) = subcommand.create_as_subcommand() {
                     builder = builder.add_sub_option(subcommand);
                 }
@@ -191,13 +302,12 @@ impl<U, E> Command<U, E> {
 
         // This is_empty check is needed because Discord special cases empty
         // default_member_permissions to mean "admin-only" (yes it's stupid)
-        if !self.default_member_permissions.is_empty() {
-            builder = builder.default_member_permissions(self.default_member_permissions);
+        let default_member_permissions = self.aggregated_default_member_permissions();
+        if !default_member_permissions.is_empty() {
+            builder = builder.default_member_permissions(default_member_permissions);
         }
 
-        if self.guild_only {
-            builder = builder.dm_permission(false);
-        }
+        builder = self.apply_contexts(builder);
 
         if self.subcommands.is_empty() {
             for param in &self.parameters {
@@ -213,26 +323,283 @@ impl<U, E> Command<U, E> {
             }
         }
 
+        if let Some(on_create_slash) = self.on_create_slash {
+            builder = on_create_slash(builder);
+        }
         Some(builder)
     }
 
     /// Generates a context menu command builder from this [`Command`] instance. This can be used
     /// to register this command on Discord's servers
+    ///
+    /// For commands on both users and messages ([`crate::ContextMenuTarget`]), this only returns
+    /// the user entry. Use [`Self::create_as_context_menu_commands`] to get both.
     pub fn create_as_context_menu_command(&self) -> Option<serenity::CreateCommand> {
-        let context_menu_action = self.context_menu_action?;
+        self.create_as_context_menu_commands().into_iter().next()
+    }
+
+    /// Generates the context menu command builders of this [`Command`] instance: one for user
+    /// and message commands, and two for commands on both users and messages
+    pub fn create_as_context_menu_commands(&self) -> Vec<serenity::CreateCommand> {
+        let kinds: &[serenity::CommandType] = match self.context_menu_action {
+            Some(crate::ContextMenuCommandAction::User(_)) => &[serenity::CommandType::User],
+            Some(crate::ContextMenuCommandAction::Message(_)) => &[serenity::CommandType::Message],
+            Some(crate::ContextMenuCommandAction::UserAndMessage(_)) => {
+                &[serenity::CommandType::User, serenity::CommandType::Message]
+            }
+            Some(crate::ContextMenuCommandAction::__NonExhaustive) => unreachable!(),
+            None => &[],
+        };
 
         // TODO: localization?
         let name = self.context_menu_name.as_deref().unwrap_or(&self.name);
-        let mut builder = serenity::CreateCommand::new(name).kind(match context_menu_action {
-            crate::ContextMenuCommandAction::User(_) => serenity::CommandType::User,
-            crate::ContextMenuCommandAction::Message(_) => serenity::CommandType::Message,
-            crate::ContextMenuCommandAction::__NonExhaustive => unreachable!(),
-        });
+        kinds
+            .iter()
+            .map(|&kind| self.apply_contexts(serenity::CreateCommand::new(name).kind(kind)))
+            .collect()
+    }
 
-        if self.guild_only {
-            builder = builder.dm_permission(false);
+    /// Generates a primary entry point command builder from this [`Command`] instance, if it has
+    /// an [`Self::entry_point_action`]. This can be used to register this command on Discord's
+    /// servers
+    ///
+    /// serenity can't set the `handler` field of entry point commands yet, so Discord's default
+    /// handler applies.
+    pub fn create_as_entry_point_command(&self) -> Option<serenity::CreateCommand> {
+        self.entry_point_action?;
+
+        let kind = crate::ApplicationCommandType::PrimaryEntryPoint;
+        let mut builder = serenity::CreateCommand::new(self.name.clone())
+            .description(self.description.as_deref().unwrap_or("Launch the activity"))
+            .kind(kind.into());
+        for (locale, name) in &self.name_localizations {
+            builder = builder.name_localized(locale, name);
+        }
+        for (locale, description) in &self.description_localizations {
+            builder = builder.description_localized(locale, description);
         }
+        Some(self.apply_contexts(builder))
+    }
 
-        Some(builder)
+    /// Returns the kinds of application commands this command is registered as, not including
+    /// its subcommands
+    pub fn command_types(&self) -> Vec<crate::ApplicationCommandType> {
+        let mut kinds = Vec::new();
+        if self.slash_action.is_some() {
+            kinds.push(crate::ApplicationCommandType::ChatInput);
+        }
+        match self.context_menu_action {
+            Some(crate::ContextMenuCommandAction::User(_)) => {
+                kinds.push(crate::ApplicationCommandType::User)
+            }
+            Some(crate::ContextMenuCommandAction::Message(_)) => {
+                kinds.push(crate::ApplicationCommandType::Message)
+            }
+            Some(crate::ContextMenuCommandAction::UserAndMessage(_)) => kinds.extend([
+                crate::ApplicationCommandType::User,
+                crate::ApplicationCommandType::Message,
+            ]),
+            Some(crate::ContextMenuCommandAction::__NonExhaustive) => unreachable!(),
+            None => {}
+        }
+        if self.entry_point_action.is_some() {
+            kinds.push(crate::ApplicationCommandType::PrimaryEntryPoint);
+        }
+        kinds
+    }
+
+    /// Returns the [`Self::default_member_permissions`] to register for this command, including
+    /// the permissions that all of its slash subcommands require.
+    ///
+    /// Discord only honors the permissions of top-level commands. Users see the command if they
+    /// can use at least one subcommand, so the common permissions of the subcommands are added.
+    /// Dispatch enforces the permissions of the individual subcommands.
+    fn aggregated_default_member_permissions(&self) -> serenity::Permissions {
+        let subcommands = self
+            .subcommands
+            .iter()
+            .filter(|subcommand| subcommand.slash_action.is_some())
+            .map(|subcommand| subcommand.aggregated_default_member_permissions())
+            .reduce(|a, b| a & b)
+            .unwrap_or_else(serenity::Permissions::empty);
+        self.default_member_permissions | subcommands
+    }
+
+    /// Sets [`Self::install_context`] and [`Self::interaction_context`] on the builder, if set.
+    /// Guild-only commands are restricted to guilds unless they specify interaction contexts
+    fn apply_contexts(&self, mut builder: serenity::CreateCommand) -> serenity::CreateCommand {
+        if let Some(install_context) = &self.install_context {
+            builder = builder.integration_types(install_context.clone());
+        }
+        let interaction_context = match &self.interaction_context {
+            Some(interaction_context) => Some(interaction_context.clone()),
+            None if self.guild_only => Some(vec![serenity::InteractionContext::Guild]),
+            None => None,
+        };
+        if let Some(interaction_context) = interaction_context {
+            builder = builder.contexts(interaction_context);
+        }
+        builder
+    }
+}
+
+/// Error returned by [`Command::merge`] if the two commands can't be combined into one
+#[derive(Debug, Clone)]
+pub struct IncompatibleCommands {
+    /// Name of the command that was being merged
+    pub name: String,
+    /// What keeps the commands from being merged
+    pub reason: &'static str,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for IncompatibleCommands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "can't merge commands `{}`: {}", self.name, self.reason)
+    }
+}
+impl std::error::Error for IncompatibleCommands {}
+
+/// Takes the value that is set, or fails if both are set to different values
+fn merge_option<T: PartialEq>(
+    a: Option<T>,
+    b: Option<T>,
+    reason: &'static str,
+) -> Result<Option<T>, &'static str> {
+    match (a, b) {
+        (Some(a), Some(b)) if a != b => Err(reason),
+        (a, b) => Ok(a.or(b)),
+    }
+}
+
+impl<U, E> Command<U, E> {
+    /// Combines a prefix-only and a slash-only implementation of the same command into one
+    /// command, for when the two need genuinely different signatures.
+    ///
+    /// The order of the arguments doesn't matter. Slash-specific data (like parameters,
+    /// localizations and context menu settings) is taken from the slash command, prefix-specific
+    /// data (like aliases) from the prefix command. Checks of both commands apply to both
+    /// invocation kinds.
+    ///
+    /// Fails if the commands don't share the same name, if not exactly one of them is a prefix
+    /// command and the other a slash command, or if their metadata is contradictory, for example
+    /// different descriptions or permission requirements.
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # type Context<'a> = poise::Context<'a, (), Error>;
+    /// /// Bans a user
+    /// #[poise::command(prefix_command, rename = "ban")]
+    /// async fn ban_prefix(ctx: Context<'_>, #[rest] users: String) -> Result<(), Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// /// Bans a user
+    /// #[poise::command(slash_command, rename = "ban")]
+    /// async fn ban_slash(ctx: Context<'_>, user: poise::serenity_prelude::User) -> Result<(), Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let ban = ban_prefix().merge(ban_slash())?;
+    /// assert!(ban.prefix_action.is_some() && ban.slash_action.is_some());
+    /// # Ok::<(), poise::IncompatibleCommands>(())
+    /// ```
+    pub fn merge(self, other: Self) -> Result<Self, IncompatibleCommands> {
+        let name = self.name.clone();
+        Self::merge_inner(self, other).map_err(|reason| IncompatibleCommands {
+            name,
+            reason,
+            __non_exhaustive: (),
+        })
+    }
+
+    /// Implementation of [`Self::merge`]
+    fn merge_inner(a: Self, b: Self) -> Result<Self, &'static str> {
+        if a.name != b.name {
+            return Err("the commands have different names");
+        }
+        let (prefix, mut slash) = match (a.prefix_action.is_some(), b.prefix_action.is_some()) {
+            (true, false) => (a, b),
+            (false, true) => (b, a),
+            _ => return Err("exactly one of the commands must be a prefix command"),
+        };
+        if prefix.slash_action.is_some() || slash.slash_action.is_none() {
+            return Err("exactly one of the commands must be a slash command");
+        }
+        if prefix.__adapter.is_some() && slash.__adapter.is_some() {
+            return Err("both commands were created by Command::adapt");
+        }
+        if prefix.owners_only != slash.owners_only
+            || prefix.guild_only != slash.guild_only
+            || prefix.dm_only != slash.dm_only
+            || prefix.nsfw_only != slash.nsfw_only
+            || prefix.required_permissions != slash.required_permissions
+            || prefix.required_bot_permissions != slash.required_bot_permissions
+        {
+            return Err("the commands have different access restrictions");
+        }
+        if *prefix.cooldown_config.read().unwrap() != *slash.cooldown_config.read().unwrap() {
+            return Err("the commands have different cooldowns");
+        }
+        if prefix.bucket != slash.bucket {
+            return Err("the commands have different rate limit buckets");
+        }
+        if prefix.max_concurrent_invocations != slash.max_concurrent_invocations {
+            return Err("the commands have different concurrency limits");
+        }
+        if !prefix.subcommands.is_empty() && !slash.subcommands.is_empty() {
+            return Err("both commands have subcommands");
+        }
+        if prefix.context_menu_action.is_some() {
+            return Err("context menu commands must be merged as part of the slash command");
+        }
+
+        slash.category = merge_option(prefix.category, slash.category, "different categories")?;
+        slash.description = merge_option(
+            prefix.description,
+            slash.description,
+            "different descriptions",
+        )?;
+        slash.help_text = merge_option(prefix.help_text, slash.help_text, "different help texts")?;
+        slash.examples = prefix.examples.into_iter().chain(slash.examples).collect();
+        if prefix.on_error.is_some() && slash.on_error.is_some() {
+            return Err("both commands have an error handler");
+        }
+        slash.on_error = slash.on_error.or(prefix.on_error);
+        if prefix.invocation_logger.is_some() && slash.invocation_logger.is_some() {
+            return Err("both commands have an invocation logger");
+        }
+        slash.invocation_logger = slash.invocation_logger.or(prefix.invocation_logger);
+        slash.install_context = merge_option(
+            prefix.install_context,
+            slash.install_context,
+            "different install contexts",
+        )?;
+        slash.interaction_context = merge_option(
+            prefix.interaction_context,
+            slash.interaction_context,
+            "different interaction contexts",
+        )?;
+        slash.entry_point_action = slash.entry_point_action.or(prefix.entry_point_action);
+        slash.__adapter = slash.__adapter.or(prefix.__adapter);
+
+        if slash.subcommands.is_empty() {
+            slash.subcommands = prefix.subcommands;
+            slash.subcommand_required = prefix.subcommand_required;
+            slash.subcommand_group = prefix.subcommand_group;
+        }
+        slash.hide_in_help &= prefix.hide_in_help;
+        slash.reuse_response |= prefix.reuse_response;
+        slash.checks.extend(prefix.checks);
+        slash.final_checks.extend(prefix.final_checks);
+        slash.inherit_checks &= prefix.inherit_checks;
+        slash.middleware.extend(prefix.middleware);
+
+        slash.prefix_action = prefix.prefix_action;
+        slash.aliases = prefix.aliases;
+        slash.invoke_on_edit = prefix.invoke_on_edit;
+        slash.track_deletion = prefix.track_deletion;
+        slash.broadcast_typing = prefix.broadcast_typing;
+        Ok(slash)
     }
 }
@@ -0,0 +1,736 @@
+//! Adapting commands to different user data and error types, see [`crate::Command::adapt`]
+//!
+//! Command callbacks are plain function pointers, so they can't capture the mapping functions or
+//! the original command. Instead, the adapted command stores both in [`Adapter`], and the
+//! callbacks of the adapted command are generic functions that fetch it from
+//! [`crate::Command::__adapter`] on every invocation.
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Everything an adapted command needs to run the original command
+struct Adapter<U: 'static, E: 'static, U2, E2> {
+    /// The command as it was before adapting
+    original: crate::Command<U, E>,
+    /// Stand-in framework options for the original command, which can't see the real ones
+    options: crate::FrameworkOptions<U, E>,
+    /// Stand-in command snapshot for the original command, which can't see the real commands
+    commands: crate::ActiveCommands<'static, U, E>,
+    /// Maps the bot's user data to the user data of the original command
+    map_data: fn(&U2) -> &U,
+    /// Maps errors of the original command to the bot's error type
+    map_err: fn(E) -> E2,
+}
+
+/// Trait bounds shared by all adapter functions
+trait AdapterTypes: Send + Sync + 'static {}
+impl<T: Send + Sync + 'static> AdapterTypes for T {}
+
+/// Returns the adapter of an adapted command, or None if the command wasn't created by
+/// [`crate::Command::adapt`] with these types
+fn adapter<U, E, U2, E2>(command: &crate::Command<U2, E2>) -> Option<&Adapter<U, E, U2, E2>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    command.__adapter.as_ref()?.downcast_ref()
+}
+
+/// Like [`adapter`], for the callbacks of adapted commands, which are never stored on other
+/// commands
+fn expect_adapter<U, E, U2, E2>(command: &crate::Command<U2, E2>) -> &Adapter<U, E, U2, E2>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    adapter(command).expect("adapted command callback invoked on a non-adapted command")
+}
+
+/// Maps the (adapted) parent commands of an invocation back to the original commands. Parent
+/// commands that weren't adapted are left out.
+fn parent_commands<'a, U, E, U2, E2>(
+    parent_commands: &[&'a crate::Command<U2, E2>],
+) -> Vec<&'a crate::Command<U, E>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    parent_commands
+        .iter()
+        .filter_map(|command| Some(&adapter::<U, E, U2, E2>(command)?.original))
+        .collect()
+}
+
+/// Placeholder action for contexts that are only used to run checks
+fn noop_action<U, E>(
+    _: crate::PrefixContext<'_, U, E>,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>> {
+    Box::pin(async { Ok(()) })
+}
+
+impl<U, E, U2, E2> Adapter<U, E, U2, E2>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    /// Framework context as seen by the original command
+    fn framework<'a>(
+        &'a self,
+        framework: crate::FrameworkContext<'a, U2, E2>,
+    ) -> crate::FrameworkContext<'a, U, E> {
+        crate::FrameworkContext {
+            bot_id: framework.bot_id,
+            options: &self.options,
+            commands: &self.commands,
+            user_data: (self.map_data)(framework.user_data),
+            shard_manager: framework.shard_manager,
+        }
+    }
+
+    /// Prefix context as seen by the original command
+    fn prefix_context<'a>(
+        &'a self,
+        ctx: crate::PrefixContext<'a, U2, E2>,
+        parent_commands: &'a [&'a crate::Command<U, E>],
+    ) -> crate::PrefixContext<'a, U, E> {
+        crate::PrefixContext {
+            serenity_context: ctx.serenity_context,
+            msg: ctx.msg,
+            prefix: ctx.prefix,
+            invoked_command_name: ctx.invoked_command_name,
+            args: ctx.args,
+            framework: self.framework(ctx.framework),
+            parent_commands,
+            command: &self.original,
+            data: (self.map_data)(ctx.data),
+            invocation_data: ctx.invocation_data,
+            trigger: ctx.trigger,
+            locale: ctx.locale,
+            action: self.original.prefix_action.unwrap_or(noop_action),
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Application context as seen by the original command
+    fn application_context<'a>(
+        &'a self,
+        ctx: crate::ApplicationContext<'a, U2, E2>,
+        parent_commands: &'a [&'a crate::Command<U, E>],
+    ) -> crate::ApplicationContext<'a, U, E> {
+        crate::ApplicationContext {
+            serenity_context: ctx.serenity_context,
+            interaction: ctx.interaction,
+            interaction_type: ctx.interaction_type,
+            args: ctx.args,
+            has_sent_initial_response: ctx.has_sent_initial_response,
+            framework: self.framework(ctx.framework),
+            parent_commands,
+            command: &self.original,
+            data: (self.map_data)(ctx.data),
+            invocation_data: ctx.invocation_data,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Converts an error of the original command into an error of the adapted command
+    fn map_framework_error<'a>(
+        &self,
+        error: crate::FrameworkError<'_, U, E>,
+        ctx: crate::Context<'a, U2, E2>,
+    ) -> crate::FrameworkError<'a, U2, E2> {
+        use crate::FrameworkError as Error;
+        match error {
+            Error::Command { error, .. } => Error::Command {
+                error: (self.map_err)(error),
+                ctx,
+            },
+            Error::SubcommandRequired { .. } => Error::SubcommandRequired { ctx },
+            Error::CommandPanic { payload, .. } => Error::CommandPanic { payload, ctx },
+            Error::ArgumentParse {
+                error,
+                input,
+                parameter,
+                ..
+            } => Error::ArgumentParse {
+                error,
+                input,
+                // The adapted command has the same parameters as the original command
+                parameter: parameter.and_then(|parameter| {
+                    ctx.command()
+                        .parameters
+                        .iter()
+                        .find(|p| p.name == parameter.name)
+                }),
+                ctx,
+            },
+            Error::InvalidChannelType { expected, got, .. } => {
+                Error::InvalidChannelType { expected, got, ctx }
+            }
+            Error::ParameterConstraintViolation {
+                parameter,
+                constraint,
+                ..
+            } => Error::ParameterConstraintViolation {
+                parameter,
+                constraint,
+                ctx,
+            },
+            Error::CommandStructureMismatch { description, .. } => match ctx {
+                crate::Context::Application(ctx) => {
+                    Error::CommandStructureMismatch { description, ctx }
+                }
+                crate::Context::Prefix(_) => Error::ArgumentParse {
+                    error: description.into(),
+                    input: None,
+                    parameter: None,
+                    ctx,
+                },
+            },
+            Error::CooldownHit {
+                remaining_cooldown, ..
+            } => Error::CooldownHit {
+                remaining_cooldown,
+                ctx,
+            },
+            Error::RatelimitDelayed { delay, .. } => Error::RatelimitDelayed { delay, ctx },
+            Error::TooManyConcurrentInvocations { limit, .. } => {
+                Error::TooManyConcurrentInvocations { limit, ctx }
+            }
+            Error::MissingBotPermissions {
+                missing_permissions,
+                ..
+            } => Error::MissingBotPermissions {
+                missing_permissions,
+                ctx,
+            },
+            Error::MissingUserPermissions {
+                missing_permissions,
+                ..
+            } => Error::MissingUserPermissions {
+                missing_permissions,
+                ctx,
+            },
+            Error::PermissionsUnverifiable { user_id, .. } => {
+                Error::PermissionsUnverifiable { user_id, ctx }
+            }
+            Error::NotAnOwner { .. } => Error::NotAnOwner { ctx },
+            Error::GuildOnly { .. } => Error::GuildOnly { ctx },
+            Error::DmOnly { .. } => Error::DmOnly { ctx },
+            Error::NsfwOnly { .. } => Error::NsfwOnly { ctx },
+            Error::CommandCheckFailed { error, .. } => Error::CommandCheckFailed {
+                error: error.map(self.map_err),
+                ctx,
+            },
+            // The remaining errors don't originate from command invocations
+            _ => Error::CommandPanic {
+                payload: Some(format!(
+                    "adapted command `{}` returned an unexpected framework error",
+                    self.original.qualified_name
+                )),
+                ctx,
+            },
+        }
+    }
+
+    /// Handles the result of running the original command: errors go to the original command's
+    /// error handler if it has one, and are converted and returned otherwise
+    async fn finish<'a>(
+        &self,
+        result: Result<(), crate::FrameworkError<'_, U, E>>,
+        ctx: crate::Context<'a, U2, E2>,
+    ) -> Result<(), crate::FrameworkError<'a, U2, E2>> {
+        let error = match result {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+        match self.original.on_error {
+            Some(on_error) => {
+                on_error(error).await;
+                Ok(())
+            }
+            None => Err(self.map_framework_error(error, ctx)),
+        }
+    }
+}
+
+/// The original command doesn't start cooldowns itself (see [`crate::Command::adapt`]), because
+/// the host framework checks them on the adapted command
+async fn start_cooldown<U, E>(ctx: crate::Context<'_, U, E>) {
+    if !ctx.framework().options().manual_cooldowns {
+        crate::start_cooldown(ctx).await;
+    }
+}
+
+/// Prefix action of adapted commands
+fn prefix_action<U, E, U2, E2>(
+    ctx: crate::PrefixContext<'_, U2, E2>,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(action) = adapter.original.prefix_action else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.prefix_context(ctx, &parent_commands);
+        let action = || action(original_ctx);
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// Slash action of adapted commands
+fn slash_action<U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'_, U2, E2>,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(action) = adapter.original.slash_action else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.application_context(ctx, &parent_commands);
+        let action = || action(original_ctx);
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// Entry point action of adapted commands
+fn entry_point_action<U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'_, U2, E2>,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(action) = adapter.original.entry_point_action else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.application_context(ctx, &parent_commands);
+        let action = || action(original_ctx);
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// User context menu action of adapted commands
+fn context_menu_user_action<U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'_, U2, E2>,
+    user: serenity::User,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(crate::ContextMenuCommandAction::User(action)) =
+            adapter.original.context_menu_action
+        else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.application_context(ctx, &parent_commands);
+        let action = || action(original_ctx, user.clone());
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// Message context menu action of adapted commands
+fn context_menu_message_action<U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'_, U2, E2>,
+    message: serenity::Message,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(crate::ContextMenuCommandAction::Message(action)) =
+            adapter.original.context_menu_action
+        else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.application_context(ctx, &parent_commands);
+        let action = || action(original_ctx, message.clone());
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// Context menu action on users and messages of adapted commands
+fn context_menu_user_and_message_action<U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'_, U2, E2>,
+    target: crate::ContextMenuTarget,
+) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U2, E2>>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let Some(crate::ContextMenuCommandAction::UserAndMessage(action)) =
+            adapter.original.context_menu_action
+        else {
+            return Ok(());
+        };
+        start_cooldown(ctx.into()).await;
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        let original_ctx = adapter.application_context(ctx, &parent_commands);
+        let action = || action(original_ctx, target.clone());
+        let result = crate::middleware::run_with_middleware(original_ctx.into(), &action).await;
+        adapter.finish(result, ctx.into()).await
+    })
+}
+
+/// Runs all [`crate::Command::checks`] of the original command
+fn check<U, E, U2, E2>(ctx: crate::Context<'_, U2, E2>) -> BoxFuture<'_, Result<bool, E2>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(run_checks::<U, E, U2, E2>(ctx, |command| &command.checks))
+}
+
+/// Runs all [`crate::Command::final_checks`] of the original command
+fn final_check<U, E, U2, E2>(ctx: crate::Context<'_, U2, E2>) -> BoxFuture<'_, Result<bool, E2>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(run_checks::<U, E, U2, E2>(ctx, |command| {
+        &command.final_checks
+    }))
+}
+
+/// Runs the given list of check functions of the original command
+async fn run_checks<U, E, U2, E2>(
+    ctx: crate::Context<'_, U2, E2>,
+    checks: fn(
+        &crate::Command<U, E>,
+    ) -> &Vec<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+) -> Result<bool, E2>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    let adapter = expect_adapter::<U, E, U2, E2>(ctx.command());
+    let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands());
+    let inner_ctx = match ctx {
+        crate::Context::Prefix(ctx) => {
+            crate::Context::Prefix(adapter.prefix_context(ctx, &parent_commands))
+        }
+        crate::Context::Application(ctx) => {
+            crate::Context::Application(adapter.application_context(ctx, &parent_commands))
+        }
+    };
+    for check in checks(&adapter.original) {
+        if !check(inner_ctx).await.map_err(adapter.map_err)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Runs the autocomplete callback of the focused parameter of the original command
+fn autocomplete<'a, U, E, U2, E2>(
+    ctx: crate::ApplicationContext<'a, U2, E2>,
+    partial: &'a str,
+) -> BoxFuture<'a, Result<serenity::CreateAutocompleteResponse, crate::SlashArgError>>
+where
+    U: AdapterTypes,
+    E: AdapterTypes,
+    U2: AdapterTypes,
+    E2: AdapterTypes,
+{
+    Box::pin(async move {
+        let adapter = expect_adapter::<U, E, U2, E2>(ctx.command);
+        let focused_option_name = ctx.args.iter().find_map(|o| match &o.value {
+            serenity::ResolvedValue::Autocomplete { .. } => Some(o.name),
+            _ => None,
+        });
+        let callback = adapter
+            .original
+            .parameters
+            .iter()
+            .find(|p| Some(&*p.name) == focused_option_name)
+            .and_then(|p| p.autocomplete_callback);
+        let Some(callback) = callback else {
+            return Ok(serenity::CreateAutocompleteResponse::new());
+        };
+
+        let parent_commands = parent_commands::<U, E, U2, E2>(ctx.parent_commands);
+        callback(adapter.application_context(ctx, &parent_commands), partial).await
+    })
+}
+
+impl<U, E> crate::Command<U, E>
+where
+    U: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    /// Converts this command into a command for bots with a different user data type, for example
+    /// to use a command from a library with its own data type. `map_data` extracts the data this
+    /// command needs from the bot's data.
+    ///
+    /// See [`Self::adapt`] for details.
+    pub fn map_data<U2>(self, map_data: fn(&U2) -> &U) -> crate::Command<U2, E>
+    where
+        U2: Send + Sync + 'static,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        self.adapt(map_data, |error| error)
+    }
+
+    /// Converts this command into a command for bots with a different error type. `map_err`
+    /// converts errors returned by this command into the bot's error type.
+    ///
+    /// See [`Self::adapt`] for details.
+    pub fn map_err<E2>(self, map_err: fn(E) -> E2) -> crate::Command<U, E2>
+    where
+        E2: Send + Sync + 'static,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        self.adapt(|data| data, map_err)
+    }
+
+    /// Converts this command into a command for bots with a different error type, converting
+    /// errors with [`Into`]. Useful for merging commands from crates with different error enums
+    /// into one framework:
+    ///
+    /// ```rust
+    /// # #[derive(Debug)] struct ModerationError;
+    /// # impl std::fmt::Display for ModerationError {
+    /// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { Ok(()) }
+    /// # }
+    /// # impl std::error::Error for ModerationError {}
+    /// type Error = Box<dyn std::error::Error + Send + Sync>;
+    ///
+    /// # let moderation_command = poise::Command::<(), ModerationError>::default();
+    /// let command: poise::Command<(), Error> = moderation_command.err_into();
+    /// ```
+    ///
+    /// See [`Self::adapt`] for details.
+    pub fn err_into<E2>(self) -> crate::Command<U, E2>
+    where
+        E2: Send + Sync + 'static,
+        E: Into<E2> + std::fmt::Display + std::fmt::Debug,
+    {
+        self.map_err(Into::into)
+    }
+
+    /// Converts this command and its subcommands into commands for bots with different user data
+    /// and error types.
+    ///
+    /// ```rust
+    /// # type LibError = Box<dyn std::error::Error + Send + Sync>;
+    /// # #[derive(Debug)] struct BotError;
+    /// # impl From<LibError> for BotError { fn from(_: LibError) -> Self { Self } }
+    /// struct LibData {}
+    /// struct BotData {
+    ///     lib: LibData,
+    /// }
+    ///
+    /// # let lib_command = poise::Command::<LibData, LibError>::default();
+    /// let command: poise::Command<BotData, BotError> =
+    ///     lib_command.adapt(|data: &BotData| &data.lib, BotError::from);
+    /// ```
+    ///
+    /// The adapted command has the same metadata (names, parameters, checks, cooldowns...) and
+    /// runs the original command's callbacks. Errors returned by the original command are
+    /// converted and handled by the bot's error handlers, unless the original command has its own
+    /// [`Self::on_error`].
+    ///
+    /// Inside the original command, some things behave differently than in a regular command:
+    /// - [`crate::Context::framework`] has default [`crate::FrameworkOptions`] instead of the
+    ///   bot's, so e.g. the command list and the edit tracker aren't available
+    /// - [`crate::Context::command`] is the original command, whose subcommands were moved to
+    ///   the adapted command. Its [`Self::custom_data`] is preserved
+    /// - Cooldowns are started before argument parsing, so invocations with invalid arguments
+    ///   also trigger them
+    pub fn adapt<U2, E2>(
+        mut self,
+        map_data: fn(&U2) -> &U,
+        map_err: fn(E) -> E2,
+    ) -> crate::Command<U2, E2>
+    where
+        U2: Send + Sync + 'static,
+        E2: Send + Sync + 'static,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let subcommands = std::mem::take(&mut self.subcommands)
+            .into_iter()
+            .map(|subcommand| subcommand.adapt(map_data, map_err))
+            .collect();
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| crate::CommandParameter {
+                name: parameter.name.clone(),
+                name_localizations: parameter.name_localizations.clone(),
+                description: parameter.description.clone(),
+                description_localizations: parameter.description_localizations.clone(),
+                required: parameter.required,
+                channel_types: parameter.channel_types.clone(),
+                choices: parameter.choices.clone(),
+                min: parameter.min,
+                max: parameter.max,
+                min_length: parameter.min_length,
+                max_length: parameter.max_length,
+                type_setter: parameter.type_setter,
+                autocomplete_callback: parameter
+                    .autocomplete_callback
+                    .map(|_| autocomplete::<U, E, U2, E2> as _),
+                option_modifier: parameter.option_modifier,
+                __non_exhaustive: (),
+            })
+            .collect();
+        let context_menu_action = self.context_menu_action.map(|action| match action {
+            crate::ContextMenuCommandAction::User(_) => {
+                crate::ContextMenuCommandAction::User(context_menu_user_action::<U, E, U2, E2>)
+            }
+            crate::ContextMenuCommandAction::Message(_) => {
+                crate::ContextMenuCommandAction::Message(
+                    context_menu_message_action::<U, E, U2, E2>,
+                )
+            }
+            crate::ContextMenuCommandAction::UserAndMessage(_) => {
+                crate::ContextMenuCommandAction::UserAndMessage(
+                    context_menu_user_and_message_action::<U, E, U2, E2>,
+                )
+            }
+            crate::ContextMenuCommandAction::__NonExhaustive => {
+                crate::ContextMenuCommandAction::__NonExhaustive
+            }
+        });
+
+        let mut adapted = crate::Command {
+            prefix_action: self
+                .prefix_action
+                .map(|_| prefix_action::<U, E, U2, E2> as _),
+            slash_action: self.slash_action.map(|_| slash_action::<U, E, U2, E2> as _),
+            context_menu_action,
+            entry_point_action: self
+                .entry_point_action
+                .map(|_| entry_point_action::<U, E, U2, E2> as _),
+            subcommands,
+            subcommand_required: self.subcommand_required,
+            subcommand_group: self.subcommand_group,
+            skip_parent_checks: self.skip_parent_checks,
+            inherit_checks: self.inherit_checks,
+            name: self.name.clone(),
+            name_localizations: self.name_localizations.clone(),
+            qualified_name: self.qualified_name.clone(),
+            identifying_name: self.identifying_name.clone(),
+            source_code_name: self.source_code_name.clone(),
+            category: self.category.clone(),
+            hide_in_help: self.hide_in_help,
+            description: self.description.clone(),
+            description_localizations: self.description_localizations.clone(),
+            help_text: self.help_text.clone(),
+            help_text_localizations: self.help_text_localizations.clone(),
+            examples: self.examples.clone(),
+            cooldowns: std::sync::Mutex::new(crate::CooldownTracker::new()),
+            cooldown_config: std::sync::RwLock::new(self.cooldown_config.read().unwrap().clone()),
+            bucket: self.bucket.clone(),
+            max_concurrent_invocations: self.max_concurrent_invocations,
+            active_invocations: std::sync::atomic::AtomicUsize::new(0),
+            reuse_response: self.reuse_response,
+            default_member_permissions: self.default_member_permissions,
+            required_permissions: self.required_permissions,
+            required_bot_permissions: self.required_bot_permissions,
+            owners_only: self.owners_only,
+            guild_only: self.guild_only,
+            dm_only: self.dm_only,
+            nsfw_only: self.nsfw_only,
+            install_context: self.install_context.clone(),
+            interaction_context: self.interaction_context.clone(),
+            on_error: None,
+            invocation_logger: self.invocation_logger,
+            checks: match self.checks.is_empty() {
+                true => Vec::new(),
+                false => vec![check::<U, E, U2, E2>],
+            },
+            final_checks: match self.final_checks.is_empty() {
+                true => Vec::new(),
+                false => vec![final_check::<U, E, U2, E2>],
+            },
+            // The original command's middleware is run by the adapted actions
+            middleware: Vec::new(),
+            parameters,
+            custom_data: Box::new(()),
+            aliases: self.aliases.clone(),
+            invoke_on_edit: self.invoke_on_edit,
+            track_deletion: self.track_deletion,
+            broadcast_typing: self.broadcast_typing,
+            context_menu_name: self.context_menu_name.clone(),
+            ephemeral: self.ephemeral,
+            auto_defer: self.auto_defer,
+            on_create_slash: self.on_create_slash,
+            __adapter: None,
+            __non_exhaustive: (),
+        };
+
+        adapted.__adapter = Some(Box::new(Adapter {
+            original: self,
+            options: crate::FrameworkOptions {
+                manual_cooldowns: true,
+                ..Default::default()
+            },
+            commands: crate::ActiveCommands::empty(),
+            map_data,
+            map_err,
+        }));
+        adapted
+    }
+}
@@ -0,0 +1,275 @@
+//! A builder for constructing [`crate::Command`]s at runtime, see [`crate::Command::builder`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Prefix action type of [`crate::Command::prefix_action`]
+type PrefixAction<U, E> = for<'a> fn(
+    crate::PrefixContext<'a, U, E>,
+) -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>;
+
+/// Slash action type of [`crate::Command::slash_action`]
+type SlashAction<U, E> = for<'a> fn(
+    crate::ApplicationContext<'a, U, E>,
+) -> BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>;
+
+/// Builder for a [`crate::Command`], for commands that are constructed at runtime instead of with
+/// the [`crate::command`] macro. Combine with [`crate::CommandParameterBuilder`] for the
+/// parameters.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// let command = poise::Command::<(), Error>::builder("ban")
+///     .description("Bans a user")
+///     .parameter(
+///         poise::CommandParameter::builder("mode")
+///             .description("How to ban")
+///             .choice("Soft", "soft")
+///             .choice("Hard", "hard")
+///             .build(),
+///     )
+///     .slash_action(|ctx| {
+///         Box::pin(async move {
+///             let mode = ctx.args.iter().find(|arg| arg.name == "mode");
+///             // ...
+///             Ok(())
+///         })
+///     })
+///     .guild_only(true)
+///     .build();
+///
+/// assert!(command.create_as_slash_command().is_some());
+/// ```
+#[must_use]
+pub struct CommandBuilder<U, E> {
+    /// The command being built
+    command: crate::Command<U, E>,
+}
+
+impl<U, E> CommandBuilder<U, E> {
+    /// Starts building a command with the given name and no actions
+    pub(crate) fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            command: crate::Command {
+                qualified_name: name.clone(),
+                identifying_name: name.clone(),
+                source_code_name: name.clone(),
+                name,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Sets the description. Shown in the slash command list and in help menus
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.command.description = Some(description.into());
+        self
+    }
+
+    /// Adds a localized name for the given locale
+    pub fn name_localized(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.command
+            .name_localizations
+            .insert(locale.into(), name.into());
+        self
+    }
+
+    /// Adds a localized description for the given locale
+    pub fn description_localized(
+        mut self,
+        locale: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.command
+            .description_localizations
+            .insert(locale.into(), description.into());
+        self
+    }
+
+    /// Sets the multiline help text shown in the help command
+    pub fn help_text(mut self, help_text: impl Into<String>) -> Self {
+        self.command.help_text = Some(help_text.into());
+        self
+    }
+
+    /// Sets the category, used for grouping commands in the help command
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.command.category = Some(category.into());
+        self
+    }
+
+    /// Adds an alternative name for prefix invocations
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.command.aliases.push(alias.into());
+        self
+    }
+
+    /// Appends a parameter, see [`crate::CommandParameter::builder`]
+    pub fn parameter(mut self, parameter: crate::CommandParameter<U, E>) -> Self {
+        self.command.parameters.push(parameter);
+        self
+    }
+
+    /// Appends a subcommand
+    pub fn subcommand(mut self, subcommand: crate::Command<U, E>) -> Self {
+        self.command.subcommands.push(subcommand);
+        self
+    }
+
+    /// Sets whether a subcommand must be given, see [`crate::Command::subcommand_required`]
+    pub fn subcommand_required(mut self, subcommand_required: bool) -> Self {
+        self.command.subcommand_required = subcommand_required;
+        self
+    }
+
+    /// Sets the callback for prefix invocations, which makes this a prefix command
+    pub fn prefix_action(mut self, action: PrefixAction<U, E>) -> Self {
+        self.command.prefix_action = Some(action);
+        self
+    }
+
+    /// Sets the callback for slash invocations, which makes this a slash command
+    pub fn slash_action(mut self, action: SlashAction<U, E>) -> Self {
+        self.command.slash_action = Some(action);
+        self
+    }
+
+    /// Sets the callback for context menu invocations, which makes this a context menu command
+    /// with the given name
+    pub fn context_menu_action(
+        mut self,
+        name: impl Into<String>,
+        action: crate::ContextMenuCommandAction<U, E>,
+    ) -> Self {
+        self.command.context_menu_name = Some(name.into());
+        self.command.context_menu_action = Some(action);
+        self
+    }
+
+    /// Sets the callback for primary entry point invocations, which makes this the app's primary
+    /// entry point command, see [`crate::Command::entry_point_action`]
+    pub fn entry_point_action(mut self, action: SlashAction<U, E>) -> Self {
+        self.command.entry_point_action = Some(action);
+        self
+    }
+
+    /// Adds a check, see [`crate::Command::checks`]
+    pub fn check(
+        mut self,
+        check: fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>,
+    ) -> Self {
+        self.command.checks.push(check);
+        self
+    }
+
+    /// Adds a final check, see [`crate::Command::final_checks`]
+    pub fn final_check(
+        mut self,
+        check: fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>,
+    ) -> Self {
+        self.command.final_checks.push(check);
+        self
+    }
+
+    /// Sets whether the checks of parent commands run, see [`crate::Command::inherit_checks`]
+    pub fn inherit_checks(mut self, inherit_checks: bool) -> Self {
+        self.command.inherit_checks = inherit_checks;
+        self
+    }
+
+    /// Sets the error handler, see [`crate::Command::on_error`]
+    pub fn on_error(
+        mut self,
+        on_error: fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>,
+    ) -> Self {
+        self.command.on_error = Some(on_error);
+        self
+    }
+
+    /// Sets the invocation logger, see [`crate::Command::invocation_logger`]
+    pub fn invocation_logger(mut self, invocation_logger: crate::InvocationLogger) -> Self {
+        self.command.invocation_logger = Some(invocation_logger);
+        self
+    }
+
+    /// Sets the permissions members need by default to see and invoke this slash command, see
+    /// [`crate::Command::default_member_permissions`]
+    pub fn default_member_permissions(mut self, permissions: serenity::Permissions) -> Self {
+        self.command.default_member_permissions = permissions;
+        self
+    }
+
+    /// Sets the permissions the invoking user needs, see [`crate::Command::required_permissions`]
+    pub fn required_permissions(mut self, permissions: serenity::Permissions) -> Self {
+        self.command.required_permissions = permissions;
+        self
+    }
+
+    /// Sets the permissions the bot needs, see [`crate::Command::required_bot_permissions`]
+    pub fn required_bot_permissions(mut self, permissions: serenity::Permissions) -> Self {
+        self.command.required_bot_permissions = permissions;
+        self
+    }
+
+    /// Limits how many invocations of this command may run at the same time, see
+    /// [`crate::Command::max_concurrent_invocations`]
+    pub fn max_concurrent_invocations(mut self, limit: usize) -> Self {
+        self.command.max_concurrent_invocations = Some(limit);
+        self
+    }
+
+    /// Sets whether only bot owners can invoke this command
+    pub fn owners_only(mut self, owners_only: bool) -> Self {
+        self.command.owners_only = owners_only;
+        self
+    }
+
+    /// Sets whether this command can only be invoked in guilds
+    pub fn guild_only(mut self, guild_only: bool) -> Self {
+        self.command.guild_only = guild_only;
+        self
+    }
+
+    /// Sets whether this command can only be invoked in DMs
+    pub fn dm_only(mut self, dm_only: bool) -> Self {
+        self.command.dm_only = dm_only;
+        self
+    }
+
+    /// Sets whether this command can only be invoked in NSFW channels
+    pub fn nsfw_only(mut self, nsfw_only: bool) -> Self {
+        self.command.nsfw_only = nsfw_only;
+        self
+    }
+
+    /// Sets whether this command is hidden from the help command
+    pub fn hide_in_help(mut self, hide_in_help: bool) -> Self {
+        self.command.hide_in_help = hide_in_help;
+        self
+    }
+
+    /// Sets whether responses to slash invocations are ephemeral by default
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.command.ephemeral = ephemeral;
+        self
+    }
+
+    /// Sets whether slow slash invocations are deferred automatically, see
+    /// [`crate::Command::auto_defer`]
+    pub fn auto_defer(mut self, auto_defer: bool) -> Self {
+        self.command.auto_defer = auto_defer;
+        self
+    }
+
+    /// Sets arbitrary data, see [`crate::Command::custom_data`]
+    pub fn custom_data(mut self, custom_data: impl std::any::Any + Send + Sync) -> Self {
+        self.command.custom_data = Box::new(custom_data);
+        self
+    }
+
+    /// Finishes building the command
+    pub fn build(self) -> crate::Command<U, E> {
+        self.command
+    }
+}
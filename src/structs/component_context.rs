@@ -0,0 +1,297 @@
+//! Types for routing component and modal interactions to handlers, see
+//! [`crate::FrameworkOptions::component_handlers`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Handles the component and modal interactions whose custom ID matches a [`CustomIdPattern`],
+/// see [`crate::FrameworkOptions::component_handlers`]
+pub type ComponentHandler<U, E> =
+    for<'a> fn(ComponentContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>;
+
+/// Which custom IDs a [`ComponentHandler`] handles.
+///
+/// Can be created from a string: `"*"` matches any custom ID, a trailing `*` like in `"vote:*"`
+/// matches all custom IDs with that prefix, and anything else matches the custom ID exactly.
+///
+/// If multiple patterns match a custom ID, an exact match is preferred over a prefix match,
+/// longer prefixes are preferred over shorter ones and [`Self::Any`] is used last.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CustomIdPattern {
+    /// Matches exactly this custom ID
+    Exact(String),
+    /// Matches all custom IDs starting with this prefix
+    Prefix(String),
+    /// Matches any custom ID
+    Any,
+}
+
+impl CustomIdPattern {
+    /// If the custom ID matches this pattern, returns the rest of the custom ID after the matched
+    /// part, see [`ComponentContext::custom_id_args`]
+    pub fn matches<'b>(&self, custom_id: &'b str) -> Option<&'b str> {
+        match self {
+            Self::Exact(id) => (id == custom_id).then_some(""),
+            Self::Prefix(prefix) => custom_id.strip_prefix(prefix.as_str()),
+            Self::Any => Some(custom_id),
+        }
+    }
+
+    /// How specific this pattern is, for choosing between multiple matching patterns
+    pub(crate) fn specificity(&self) -> (bool, usize) {
+        match self {
+            Self::Exact(id) => (true, id.len()),
+            Self::Prefix(prefix) => (false, prefix.len() + 1),
+            Self::Any => (false, 0),
+        }
+    }
+}
+
+impl From<&str> for CustomIdPattern {
+    fn from(pattern: &str) -> Self {
+        match pattern.strip_suffix('*') {
+            Some("") => Self::Any,
+            Some(prefix) => Self::Prefix(prefix.to_owned()),
+            None => Self::Exact(pattern.to_owned()),
+        }
+    }
+}
+
+impl From<String> for CustomIdPattern {
+    fn from(pattern: String) -> Self {
+        Self::from(pattern.as_str())
+    }
+}
+
+/// The interaction that a [`ComponentHandler`] was invoked for
+#[derive(Clone, Copy, Debug)]
+pub enum RoutedInteraction<'a> {
+    /// A message component like a button or select menu was used
+    Component(&'a serenity::ComponentInteraction),
+    /// A modal was submitted
+    Modal(&'a serenity::ModalInteraction),
+}
+
+/// Context passed to [`ComponentHandler`]s, with access to the interaction, the framework and
+/// the user data
+pub struct ComponentContext<'a, U, E> {
+    /// Serenity's context, for example to send messages
+    pub serenity_context: &'a serenity::Context,
+    /// Framework data like options and user data
+    pub framework: crate::FrameworkContext<'a, U, E>,
+    /// The interaction that is being handled
+    pub interaction: RoutedInteraction<'a>,
+    /// The pattern that matched the custom ID of the interaction
+    pub pattern: &'a CustomIdPattern,
+    /// The rest of the custom ID after the part matched by [`Self::pattern`], for example `"42"`
+    /// for a custom ID of `"vote:42"` matched by `"vote:*"`. Useful for persistent components
+    /// that carry their state in the custom ID
+    pub custom_id_args: &'a str,
+    /// Whether the interaction has been responded to already
+    pub has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl<U, E> Copy for ComponentContext<'_, U, E> {}
+impl<U, E> Clone for ComponentContext<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, U, E> ComponentContext<'a, U, E> {
+    /// Returns the user data
+    pub fn data(&self) -> &'a U {
+        self.framework.user_data
+    }
+
+    /// Returns the framework data like options
+    pub fn framework(&self) -> crate::FrameworkContext<'a, U, E> {
+        self.framework
+    }
+
+    /// Returns serenity's context
+    pub fn serenity_context(&self) -> &'a serenity::Context {
+        self.serenity_context
+    }
+
+    /// Returns the full custom ID of the component or modal
+    pub fn custom_id(&self) -> &'a str {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => &interaction.data.custom_id,
+            RoutedInteraction::Modal(interaction) => &interaction.data.custom_id,
+        }
+    }
+
+    /// Returns the user who used the component or submitted the modal
+    pub fn author(&self) -> &'a serenity::User {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => &interaction.user,
+            RoutedInteraction::Modal(interaction) => &interaction.user,
+        }
+    }
+
+    /// Returns the ID of the channel the interaction happened in
+    pub fn channel_id(&self) -> serenity::ChannelId {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => interaction.channel_id,
+            RoutedInteraction::Modal(interaction) => interaction.channel_id,
+        }
+    }
+
+    /// Returns the ID of the guild the interaction happened in, if any
+    pub fn guild_id(&self) -> Option<serenity::GuildId> {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => interaction.guild_id,
+            RoutedInteraction::Modal(interaction) => interaction.guild_id,
+        }
+    }
+
+    /// Returns the message the component belongs to. For modals, only present if the modal was
+    /// opened from a message component
+    pub fn message(&self) -> Option<&'a serenity::Message> {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => Some(&interaction.message),
+            RoutedInteraction::Modal(interaction) => interaction.message.as_deref(),
+        }
+    }
+
+    /// Parses the submitted modal. Returns None if the interaction isn't a modal submission
+    pub fn parse_modal<M: crate::Modal>(&self) -> Option<Result<M, &'static str>> {
+        match self.interaction {
+            RoutedInteraction::Component(_) => None,
+            RoutedInteraction::Modal(interaction) => Some(M::parse(interaction.data.clone())),
+        }
+    }
+
+    /// Applies the framework's [`crate::FrameworkOptions::allowed_mentions`] to the builder
+    fn reply_builder(&self, mut builder: crate::CreateReply) -> crate::CreateReply {
+        builder.allowed_mentions = builder
+            .allowed_mentions
+            .or_else(|| self.framework.options.allowed_mentions.clone());
+        builder
+    }
+
+    /// Sends the interaction response, and marks the interaction as responded to
+    async fn create_response(
+        &self,
+        response: serenity::CreateInteractionResponse,
+    ) -> Result<(), serenity::Error> {
+        match self.interaction {
+            RoutedInteraction::Component(interaction) => {
+                interaction
+                    .create_response(self.serenity_context, response)
+                    .await?
+            }
+            RoutedInteraction::Modal(interaction) => {
+                interaction
+                    .create_response(self.serenity_context, response)
+                    .await?
+            }
+        }
+        self.has_sent_initial_response
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Sends a new message in response to the interaction, or a followup message if the
+    /// interaction was responded to already
+    pub async fn send(&self, builder: crate::CreateReply) -> Result<(), serenity::Error> {
+        let builder = self.reply_builder(builder).resolve_lazy_attachments().await;
+        if self
+            .has_sent_initial_response
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            let builder = builder
+                .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
+            match self.interaction {
+                RoutedInteraction::Component(interaction) => {
+                    interaction
+                        .create_followup(self.serenity_context, builder)
+                        .await?
+                }
+                RoutedInteraction::Modal(interaction) => {
+                    interaction
+                        .create_followup(self.serenity_context, builder)
+                        .await?
+                }
+            };
+            return Ok(());
+        }
+
+        let builder =
+            builder.to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+        self.create_response(serenity::CreateInteractionResponse::Message(builder))
+            .await
+    }
+
+    /// Shorthand of [`Self::send`] for text-only messages
+    pub async fn say(&self, text: impl Into<String>) -> Result<(), serenity::Error> {
+        self.send(crate::CreateReply::default().content(text)).await
+    }
+
+    /// Edits the message the component belongs to. If the interaction was responded to already,
+    /// e.g. with [`Self::defer`], the original response is edited instead
+    pub async fn update(&self, builder: crate::CreateReply) -> Result<(), serenity::Error> {
+        let builder = self.reply_builder(builder).resolve_lazy_attachments().await;
+        if self
+            .has_sent_initial_response
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            let builder =
+                builder.to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
+            match self.interaction {
+                RoutedInteraction::Component(interaction) => {
+                    interaction
+                        .edit_response(self.serenity_context, builder)
+                        .await?
+                }
+                RoutedInteraction::Modal(interaction) => {
+                    interaction
+                        .edit_response(self.serenity_context, builder)
+                        .await?
+                }
+            };
+            return Ok(());
+        }
+
+        let builder =
+            builder.to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+        self.create_response(serenity::CreateInteractionResponse::UpdateMessage(builder))
+            .await
+    }
+
+    /// Acknowledges the interaction without sending or editing a message, for handlers that need
+    /// more than three seconds. No-op if the interaction was responded to already
+    pub async fn defer(&self) -> Result<(), serenity::Error> {
+        if self
+            .has_sent_initial_response
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Ok(());
+        }
+        self.create_response(serenity::CreateInteractionResponse::Acknowledge)
+            .await
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_custom_id_pattern() {
+    let any = CustomIdPattern::from("*");
+    let vote = CustomIdPattern::from("vote:*");
+    let exact = CustomIdPattern::from("vote:up");
+    assert_eq!(any, CustomIdPattern::Any);
+    assert_eq!(vote, CustomIdPattern::Prefix("vote:".into()));
+    assert_eq!(exact, CustomIdPattern::Exact("vote:up".into()));
+
+    assert_eq!(any.matches("vote:42"), Some("vote:42"));
+    assert_eq!(vote.matches("vote:42"), Some("42"));
+    assert_eq!(vote.matches("poll:42"), None);
+    assert_eq!(exact.matches("vote:up"), Some(""));
+    assert_eq!(exact.matches("vote:down"), None);
+
+    assert!(exact.specificity() > vote.specificity());
+    assert!(vote.specificity() > any.specificity());
+    assert!(CustomIdPattern::from("vote:up*").specificity() > vote.specificity());
+}
@@ -40,6 +40,26 @@ impl<'a, U, E> From<crate::PrefixContext<'a, U, E>> for Context<'a, U, E> {
         Self::Prefix(x)
     }
 }
+
+/// New arguments for [`Context::rerun_with`]. Fields that don't apply to the invocation's kind are
+/// ignored.
+#[derive(Default, Debug, Clone)]
+pub struct RerunArgs<'b> {
+    /// For prefix invocations: argument string that replaces everything after the command name.
+    /// If None, the original arguments are reused
+    pub prefix_args: Option<&'b str>,
+    /// For application invocations: option values by name, replacing the original options of the
+    /// same name.
+    ///
+    /// Options that weren't given in the original invocation are added. Due to serenity's option
+    /// types not being constructible, that only works if the original invocation had at least one
+    /// option
+    pub slash_options: Vec<(&'b str, serenity::ResolvedValue<'b>)>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
 /// Macro to generate Context methods and also PrefixContext and ApplicationContext methods that
 /// delegate to Context
 macro_rules! context_methods {
@@ -58,14 +78,14 @@ macro_rules! context_methods {
         impl<'a, U, E> crate::PrefixContext<'a, U, E> { $(
             $( #[$($attrs)*] )*
             $($sig)* {
-                $crate::Context::Prefix($self).$fn_name($($arg)*) $(.$await)?
+                $crate::Context::Prefix($self).$fn_name($($arg),*) $(.$await)?
             }
         )* }
 
         impl<'a, U, E> crate::ApplicationContext<'a, U, E> { $(
             $( #[$($attrs)*] )*
             $($sig)* {
-                $crate::Context::Application($self).$fn_name($($arg)*) $(.$await)?
+                $crate::Context::Application($self).$fn_name($($arg),*) $(.$await)?
             }
         )* }
     };
@@ -132,6 +152,36 @@ context_methods! {
         crate::say_reply(self, text).await
     }
 
+    /// Like [`Self::say`], but splits text that exceeds Discord's message length limit into
+    /// multiple messages, see [`crate::send_chunked_reply`]
+    ///
+    /// Note: panics when called in an autocomplete context!
+    await (say_chunked self text)
+    (pub async fn say_chunked(
+        self,
+        text: impl Into<String>,
+    ) -> Result<Vec<crate::ReplyHandle<'a>>, serenity::Error>) {
+        let reply = crate::CreateReply::default().content(text);
+        crate::send_chunked_reply(self, reply, crate::SplitPolicy::default()).await
+    }
+
+    /// Like [`Self::say`], but with the given allowed mentions instead of
+    /// [`crate::FrameworkOptions::allowed_mentions`], for example to make sure a command that
+    /// echoes user input can't ping anyone.
+    ///
+    /// Note: panics when called in an autocomplete context!
+    await (say_with_mentions self text allowed_mentions)
+    (pub async fn say_with_mentions(
+        self,
+        text: impl Into<String>,
+        allowed_mentions: serenity::CreateAllowedMentions,
+    ) -> Result<crate::ReplyHandle<'a>, serenity::Error>) {
+        let reply = crate::CreateReply::default()
+            .content(text)
+            .allowed_mentions(allowed_mentions);
+        self.send(reply).await
+    }
+
     /// Like [`Self::say`], but formats the message as a reply to the user's command
     /// message.
     ///
@@ -175,7 +225,7 @@ context_methods! {
         crate::CooldownContext {
             user_id: self.author().id,
             channel_id: self.channel_id(),
-            guild_id: self.guild_id()
+            guild_id: self.guild_id(),
         }
     }
 
@@ -223,6 +273,24 @@ context_methods! {
         }
     }
 
+    /// Returns where this command was invoked from: a guild, a DM with the bot, or another DM or
+    /// group DM, which is only possible if the app is user-installed. See
+    /// [`crate::Command::interaction_context`].
+    ///
+    /// For prefix commands, this is [`serenity::InteractionContext::Guild`] or
+    /// [`serenity::InteractionContext::BotDm`]. For application commands, this is None if Discord
+    /// didn't send the context.
+    (interaction_context self)
+    (pub fn interaction_context(self) -> Option<serenity::InteractionContext>) {
+        match self {
+            Self::Application(ctx) => ctx.interaction.context,
+            Self::Prefix(ctx) => Some(match ctx.msg.guild_id {
+                Some(_) => serenity::InteractionContext::Guild,
+                None => serenity::InteractionContext::BotDm,
+            }),
+        }
+    }
+
     /// Return the guild channel of this context, if we are inside a guild.
     #[cfg(feature = "cache")]
     await (guild_channel self)
@@ -386,6 +454,45 @@ context_methods! {
         }
     }
 
+    /// Re-runs this entire command invocation with modified arguments, for example to implement
+    /// "retry with fix" buttons. See [`RerunArgs`] for how arguments are replaced.
+    ///
+    /// Like [`Self::rerun`], permission checks are omitted. Unlike [`Self::rerun`], argument
+    /// parsing may fail with the new arguments; such errors are passed to the error handler and
+    /// `Ok(())` is returned. Only errors returned by the command code itself are returned
+    await (rerun_with self args)
+    (pub async fn rerun_with(self, args: RerunArgs<'_>) -> Result<(), E>) {
+        match self {
+            Self::Prefix(ctx) => {
+                let ctx = crate::PrefixContext {
+                    args: args.prefix_args.unwrap_or(ctx.args),
+                    ..ctx
+                };
+                crate::Context::Prefix(ctx).rerun_handling_errors().await
+            }
+            Self::Application(ctx) => {
+                let mut options = ctx.args.to_vec();
+                for (name, value) in args.slash_options {
+                    if let Some(option) = options.iter_mut().find(|o| o.name == name) {
+                        option.value = value;
+                    } else if let Some(template) = ctx.args.first() {
+                        let mut option = template.clone();
+                        option.name = name;
+                        option.value = value;
+                        options.push(option);
+                    } else {
+                        tracing::warn!("can't add option `{}` to an invocation without options", name);
+                    }
+                }
+                let ctx = crate::ApplicationContext {
+                    args: &options,
+                    ..ctx
+                };
+                crate::Context::Application(ctx).rerun_handling_errors().await
+            }
+        }
+    }
+
     /// Returns the string with which this command was invoked.
     ///
     /// For example `"/slash_command subcommand arg1:value1 arg2:value2"`.
@@ -449,38 +556,91 @@ context_methods! {
         }
     }
 
-    /// Stores the given value as the data for this command invocation
+    /// Stores the given value as data for this command invocation
     ///
     /// This data is carried across the `pre_command` hook, checks, main command execution, and
     /// `post_command`. It may be useful to cache data or pass information to later phases of command
     /// execution.
+    ///
+    /// Values are keyed by type (see [`crate::InvocationData`]): storing a value replaces the
+    /// previously stored value of the same type, but leaves values of other types untouched.
     await (set_invocation_data self data)
     (pub async fn set_invocation_data<T: 'static + Send + Sync>(self, data: T)) {
-        *self.invocation_data_raw().lock().await = Box::new(data);
+        self.invocation_data_raw().lock().await.insert(data);
     }
 
     /// Attempts to get the invocation data with the requested type
     ///
-    /// If the stored invocation data has a different type than requested, None is returned
+    /// If no invocation data of the requested type is stored, None is returned
     await (invocation_data self)
     (pub async fn invocation_data<T: 'static>(
         self,
     ) -> Option<impl std::ops::DerefMut<Target = T> + 'a>) {
-        tokio::sync::MutexGuard::try_map(self.invocation_data_raw().lock().await, |any| {
-            any.downcast_mut()
+        tokio::sync::MutexGuard::try_map(self.invocation_data_raw().lock().await, |data| {
+            data.get_mut()
         })
         .ok()
     }
 
+    /// Removes and returns the invocation data with the requested type, if any
+    await (remove_invocation_data self)
+    (pub async fn remove_invocation_data<T: 'static>(self) -> Option<T>) {
+        self.invocation_data_raw().lock().await.remove()
+    }
+
+    /// Locks and returns all invocation data, for accessing values of multiple types at once
+    await (invocation_data_map self)
+    (pub async fn invocation_data_map(
+        self,
+    ) -> tokio::sync::MutexGuard<'a, crate::InvocationData>) {
+        self.invocation_data_raw().lock().await
+    }
+
+    /// Returns the cancellation token of this invocation. Long-running commands can watch it to
+    /// abort cooperatively, for example by racing a database query against
+    /// [`crate::CancellationToken::cancelled`].
+    ///
+    /// The token is cancelled when [`crate::FrameworkOptions::shutdown_token`] is cancelled, for
+    /// example on framework shutdown, or when [`crate::FrameworkOptions::command_timeout`]
+    /// elapses. It's stored in the invocation data (see [`Self::invocation_data`]).
+    await (cancellation_token self)
+    (pub async fn cancellation_token(self) -> crate::CancellationToken) {
+        let token = self
+            .invocation_data_raw()
+            .lock()
+            .await
+            .get::<crate::CancellationToken>()
+            .cloned();
+        // Contexts created outside of poise's dispatch don't carry a token
+        token.unwrap_or_else(|| self.framework().options.shutdown_token.child_token())
+    }
+
     /// If available, returns the locale (selected language) of the invoking user
+    ///
+    /// For prefix commands, this is determined by [`crate::FrameworkOptions::locale_provider`].
     (locale self)
     (pub fn locale(self) -> Option<&'a str>) {
         match self {
             Context::Application(ctx) => Some(&ctx.interaction.locale),
-            Context::Prefix(_) => None,
+            Context::Prefix(ctx) => ctx.locale,
         }
     }
 
+    /// Picks the localization matching the invoking user's locale out of the given localizations,
+    /// following [`crate::FrameworkOptions::locale_fallbacks`].
+    ///
+    /// Returns None if the user's locale is unknown or nothing in the fallback chain is localized.
+    (localized self localizations)
+    (pub fn localized<'b>(
+        self,
+        localizations: &'b std::collections::HashMap<String, String>,
+    ) -> Option<&'b str>) {
+        let fallbacks = &self.framework().options().locale_fallbacks;
+        fallbacks
+            .resolve(self.locale()?, localizations)
+            .map(|s| s.as_str())
+    }
+
     /// Builds a [`crate::CreateReply`] by combining the builder closure with the defaults that were
     /// pre-configured in poise.
     ///
@@ -551,6 +711,17 @@ impl<'a, U, E> Context<'a, U, E> {
                     };
                 }
 
+                // Check entry point command
+                if crate::ApplicationCommandType::from_serenity(ctx.interaction.data.kind)
+                    == Some(crate::ApplicationCommandType::PrimaryEntryPoint)
+                {
+                    return if let Some(action) = ctx.command.entry_point_action {
+                        action(ctx).await
+                    } else {
+                        Ok(())
+                    };
+                }
+
                 // Check context menu command
                 if let (Some(action), Some(target)) = (
                     ctx.command.context_menu_action,
@@ -571,6 +742,18 @@ impl<'a, U, E> Context<'a, U, E> {
                                 Ok(())
                             }
                         }
+                        crate::ContextMenuCommandAction::UserAndMessage(action) => {
+                            let target = match target {
+                                serenity::ResolvedTarget::User(user, _) => {
+                                    crate::ContextMenuTarget::User((*user).clone())
+                                }
+                                serenity::ResolvedTarget::Message(message) => {
+                                    crate::ContextMenuTarget::Message(Box::new((*message).clone()))
+                                }
+                                _ => return Ok(()),
+                            };
+                            action(ctx, target).await
+                        }
                         crate::ContextMenuCommandAction::__NonExhaustive => unreachable!(),
                     };
                 }
@@ -588,8 +771,21 @@ impl<'a, U, E> Context<'a, U, E> {
         Ok(())
     }
 
+    /// Re-runs the invocation, returning command errors and forwarding all other errors to the
+    /// error handler. See [`Self::rerun_with`]
+    async fn rerun_handling_errors(self) -> Result<(), E> {
+        match self.rerun_inner().await {
+            Ok(()) => Ok(()),
+            Err(crate::FrameworkError::Command { error, ctx: _ }) => Err(error),
+            Err(other) => {
+                other.handle(self.framework().options).await;
+                Ok(())
+            }
+        }
+    }
+
     /// Returns the raw type erased invocation data
-    fn invocation_data_raw(self) -> &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>> {
+    fn invocation_data_raw(self) -> &'a tokio::sync::Mutex<crate::InvocationData> {
         match self {
             Context::Application(ctx) => ctx.invocation_data,
             Context::Prefix(ctx) => ctx.invocation_data,
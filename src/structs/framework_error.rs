@@ -36,6 +36,28 @@ pub enum FrameworkError<'a, U, E> {
         #[derivative(Debug = "ignore")]
         framework: crate::FrameworkContext<'a, U, E>,
     },
+    /// User code threw an error in a background task, see
+    /// [`crate::FrameworkOptions::spawn_periodic`]
+    #[non_exhaustive]
+    BackgroundTask {
+        /// Error which was thrown in the task
+        error: E,
+        /// The serenity context passed to the task
+        ctx: &'a serenity::Context,
+        /// The Framework passed to the task
+        #[derivative(Debug = "ignore")]
+        framework: crate::FrameworkContext<'a, U, E>,
+    },
+    /// User code threw an error in a component handler, see
+    /// [`crate::FrameworkOptions::component_handlers`]
+    #[non_exhaustive]
+    ComponentHandler {
+        /// Error which was thrown in the component handler
+        error: E,
+        /// The context passed to the component handler
+        #[derivative(Debug = "ignore")]
+        ctx: crate::ComponentContext<'a, U, E>,
+    },
     /// Error occurred during command execution
     #[non_exhaustive]
     Command {
@@ -74,6 +96,30 @@ pub enum FrameworkError<'a, U, E> {
         error: Box<dyn std::error::Error + Send + Sync>,
         /// If applicable, the input on which parsing failed
         input: Option<String>,
+        /// The parameter that failed to parse, if known
+        parameter: Option<&'a crate::CommandParameter<U, E>>,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// A channel argument was of a channel type that its parameter doesn't accept, see
+    /// [`crate::CommandParameter::channel_types`]
+    #[non_exhaustive]
+    InvalidChannelType {
+        /// Channel types the parameter accepts
+        expected: Vec<serenity::ChannelType>,
+        /// Channel type of the given channel
+        got: serenity::ChannelType,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// An argument violated a min/max or length constraint of its parameter, like
+    /// [`crate::CommandParameter::min`]
+    #[non_exhaustive]
+    ParameterConstraintViolation {
+        /// Name of the parameter
+        parameter: String,
+        /// The constraint that was violated
+        constraint: ParameterConstraint,
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
@@ -97,6 +143,27 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
+    /// The invocation hit the limit of its [`crate::CooldownBucket`] and waits until the limit
+    /// allows it to run, see [`crate::CooldownBucket::await_ratelimits`].
+    ///
+    /// This error doesn't abort the invocation, it's passed to the error handler so that the user
+    /// can be notified about the delay.
+    #[non_exhaustive]
+    RatelimitDelayed {
+        /// How long the invocation waits before it runs
+        delay: std::time::Duration,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked while [`crate::Command::max_concurrent_invocations`] invocations of it
+    /// were already running
+    #[non_exhaustive]
+    TooManyConcurrentInvocations {
+        /// The configured limit of concurrently running invocations
+        limit: usize,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
     /// Command was invoked but the bot is lacking the permissions specified in
     /// [`crate::Command::required_bot_permissions`]
     #[non_exhaustive]
@@ -116,6 +183,16 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
+    /// Command was invoked but the permissions of the user or the bot couldn't be determined to
+    /// evaluate [`crate::Command::required_permissions`] or
+    /// [`crate::Command::required_bot_permissions`], e.g. because the HTTP fallback failed
+    #[non_exhaustive]
+    PermissionsUnverifiable {
+        /// The user whose permissions couldn't be determined: either the author or the bot
+        user_id: serenity::UserId,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
     /// A non-owner tried to invoke an owners-only command
     #[non_exhaustive]
     NotAnOwner {
@@ -180,7 +257,7 @@ pub enum FrameworkError<'a, U, E> {
         framework: crate::FrameworkContext<'a, U, E>,
         /// See [`crate::Context::invocation_data`]
         #[derivative(Debug = "ignore")]
-        invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+        invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
         /// Which event triggered the message parsing routine
         trigger: crate::MessageDispatchTrigger,
     },
@@ -221,14 +298,21 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
         match *self {
             Self::Setup { ctx, .. } => ctx,
             Self::EventHandler { ctx, .. } => ctx,
+            Self::BackgroundTask { ctx, .. } => ctx,
+            Self::ComponentHandler { ctx, .. } => ctx.serenity_context,
             Self::Command { ctx, .. } => ctx.serenity_context(),
             Self::SubcommandRequired { ctx } => ctx.serenity_context(),
             Self::CommandPanic { ctx, .. } => ctx.serenity_context(),
             Self::ArgumentParse { ctx, .. } => ctx.serenity_context(),
+            Self::InvalidChannelType { ctx, .. } => ctx.serenity_context(),
+            Self::ParameterConstraintViolation { ctx, .. } => ctx.serenity_context(),
             Self::CommandStructureMismatch { ctx, .. } => ctx.serenity_context,
             Self::CooldownHit { ctx, .. } => ctx.serenity_context(),
+            Self::RatelimitDelayed { ctx, .. } => ctx.serenity_context(),
+            Self::TooManyConcurrentInvocations { ctx, .. } => ctx.serenity_context(),
             Self::MissingBotPermissions { ctx, .. } => ctx.serenity_context(),
             Self::MissingUserPermissions { ctx, .. } => ctx.serenity_context(),
+            Self::PermissionsUnverifiable { ctx, .. } => ctx.serenity_context(),
             Self::NotAnOwner { ctx, .. } => ctx.serenity_context(),
             Self::GuildOnly { ctx, .. } => ctx.serenity_context(),
             Self::DmOnly { ctx, .. } => ctx.serenity_context(),
@@ -249,10 +333,15 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::SubcommandRequired { ctx } => ctx,
             Self::CommandPanic { ctx, .. } => ctx,
             Self::ArgumentParse { ctx, .. } => ctx,
+            Self::InvalidChannelType { ctx, .. } => ctx,
+            Self::ParameterConstraintViolation { ctx, .. } => ctx,
             Self::CommandStructureMismatch { ctx, .. } => crate::Context::Application(ctx),
             Self::CooldownHit { ctx, .. } => ctx,
+            Self::RatelimitDelayed { ctx, .. } => ctx,
+            Self::TooManyConcurrentInvocations { ctx, .. } => ctx,
             Self::MissingBotPermissions { ctx, .. } => ctx,
             Self::MissingUserPermissions { ctx, .. } => ctx,
+            Self::PermissionsUnverifiable { ctx, .. } => ctx,
             Self::NotAnOwner { ctx, .. } => ctx,
             Self::GuildOnly { ctx, .. } => ctx,
             Self::DmOnly { ctx, .. } => ctx,
@@ -260,6 +349,8 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::CommandCheckFailed { ctx, .. } => ctx,
             Self::Setup { .. }
             | Self::EventHandler { .. }
+            | Self::BackgroundTask { .. }
+            | Self::ComponentHandler { .. }
             | Self::UnknownCommand { .. }
             | Self::UnknownInteraction { .. }
             | Self::NonCommandMessage { .. }
@@ -268,6 +359,92 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
         })
     }
 
+    /// If this error means that the invocation was denied by a check (command checks,
+    /// permissions, cooldowns, `owners_only`, `guild_only`...), returns the reason
+    pub fn denial_reason(&self) -> Option<InvocationDenial> {
+        Some(match *self {
+            Self::CommandCheckFailed { ref error, .. } => InvocationDenial::CommandCheckFailed {
+                errored: error.is_some(),
+            },
+            Self::CooldownHit {
+                remaining_cooldown, ..
+            } => InvocationDenial::CooldownHit { remaining_cooldown },
+            Self::TooManyConcurrentInvocations { limit, .. } => {
+                InvocationDenial::TooManyConcurrentInvocations { limit }
+            }
+            Self::MissingBotPermissions {
+                missing_permissions,
+                ..
+            } => InvocationDenial::MissingBotPermissions {
+                missing_permissions,
+            },
+            Self::MissingUserPermissions {
+                missing_permissions,
+                ..
+            } => InvocationDenial::MissingUserPermissions {
+                missing_permissions,
+            },
+            Self::PermissionsUnverifiable { user_id, .. } => {
+                InvocationDenial::PermissionsUnverifiable { user_id }
+            }
+            Self::NotAnOwner { .. } => InvocationDenial::NotAnOwner,
+            Self::GuildOnly { .. } => InvocationDenial::GuildOnly,
+            Self::DmOnly { .. } => InvocationDenial::DmOnly,
+            Self::NsfwOnly { .. } => InvocationDenial::NsfwOnly,
+            _ => return None,
+        })
+    }
+
+    /// Returns a stable identifier of the kind of this error, like `"command"` or
+    /// `"cooldown_hit"`, for example for metrics or to look up localized error messages
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Setup { .. } => "setup",
+            Self::EventHandler { .. } => "event_handler",
+            Self::BackgroundTask { .. } => "background_task",
+            Self::ComponentHandler { .. } => "component_handler",
+            Self::Command { .. } => "command",
+            Self::SubcommandRequired { .. } => "subcommand_required",
+            Self::CommandPanic { .. } => "command_panic",
+            Self::ArgumentParse { .. } => "argument_parse",
+            Self::InvalidChannelType { .. } => "invalid_channel_type",
+            Self::ParameterConstraintViolation { .. } => "parameter_constraint_violation",
+            Self::CommandStructureMismatch { .. } => "command_structure_mismatch",
+            Self::CooldownHit { .. } => "cooldown_hit",
+            Self::RatelimitDelayed { .. } => "ratelimit_delayed",
+            Self::TooManyConcurrentInvocations { .. } => "too_many_concurrent_invocations",
+            Self::MissingBotPermissions { .. } => "missing_bot_permissions",
+            Self::MissingUserPermissions { .. } => "missing_user_permissions",
+            Self::PermissionsUnverifiable { .. } => "permissions_unverifiable",
+            Self::NotAnOwner { .. } => "not_an_owner",
+            Self::GuildOnly { .. } => "guild_only",
+            Self::DmOnly { .. } => "dm_only",
+            Self::NsfwOnly { .. } => "nsfw_only",
+            Self::CommandCheckFailed { .. } => "command_check_failed",
+            Self::DynamicPrefix { .. } => "dynamic_prefix",
+            Self::UnknownCommand { .. } => "unknown_command",
+            Self::UnknownInteraction { .. } => "unknown_interaction",
+            Self::NonCommandMessage { .. } => "non_command_message",
+            Self::__NonExhaustive(unreachable) => match *unreachable {},
+        }
+    }
+
+    /// For command errors and command check errors, returns what
+    /// [`crate::FrameworkOptions::error_formatter`] makes of the user error. None for other
+    /// errors or if no error formatter is set
+    pub fn user_facing_error(&self) -> Option<UserFacingError> {
+        let (ctx, error) = match self {
+            Self::Command { ctx, error } => (ctx, error),
+            Self::CommandCheckFailed {
+                ctx,
+                error: Some(error),
+            } => (ctx, error),
+            _ => return None,
+        };
+        let error_formatter = ctx.framework().options.error_formatter?;
+        Some(error_formatter(error))
+    }
+
     /// Calls the appropriate `on_error` function (command-specific or global) with this error
     pub async fn handle(self, framework_options: &crate::FrameworkOptions<U, E>) {
         let on_error = self
@@ -278,6 +455,158 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
     }
 }
 
+/// How severe an error is, which decides the level it is logged at by
+/// [`crate::builtins::on_error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorSeverity {
+    /// Expected errors that are only interesting when debugging, logged at debug level
+    Debug,
+    /// Expected errors, like invalid user input, logged at info level
+    Info,
+    /// Errors that may need attention, logged at warn level
+    Warning,
+    /// Unexpected errors, logged at error level
+    Error,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// How to present a user error to users, as returned by
+/// [`crate::FrameworkOptions::error_formatter`]
+///
+/// ```rust
+/// #[derive(Debug)]
+/// enum Error {
+///     NotFound(String),
+///     Database(String),
+/// }
+/// # impl std::fmt::Display for Error {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { todo!() }
+/// # }
+///
+/// let options = poise::FrameworkOptions::<(), Error> {
+///     error_formatter: Some(|error| match error {
+///         Error::NotFound(what) => poise::UserFacingError::new(format!("{} not found", what)),
+///         // Don't show database internals to users
+///         Error::Database(_) => poise::UserFacingError::internal(),
+///     }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct UserFacingError {
+    /// Message shown to the user. If None, a generic message is shown instead
+    pub message: Option<String>,
+    /// How severe the error is, which decides the level the full error is logged at
+    pub severity: ErrorSeverity,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl UserFacingError {
+    /// An expected error whose message is shown to the user, logged at info level
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            severity: ErrorSeverity::Info,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// An unexpected error whose details aren't shown to the user, logged at error level
+    pub fn internal() -> Self {
+        Self {
+            message: None,
+            severity: ErrorSeverity::Error,
+            __non_exhaustive: (),
+        }
+    }
+
+    /// Sets the severity of this error
+    pub fn severity(mut self, severity: ErrorSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+/// A constraint of a [`crate::CommandParameter`], see
+/// [`FrameworkError::ParameterConstraintViolation`]
+///
+/// The [`std::fmt::Display`] implementation describes the constraint, e.g. "must be at most 10"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterConstraint {
+    /// [`crate::CommandParameter::min`]
+    Min(f64),
+    /// [`crate::CommandParameter::max`]
+    Max(f64),
+    /// [`crate::CommandParameter::min_length`]
+    MinLength(u16),
+    /// [`crate::CommandParameter::max_length`]
+    MaxLength(u16),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for ParameterConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Min(min) => write!(f, "must be at least {}", min),
+            Self::Max(max) => write!(f, "must be at most {}", max),
+            Self::MinLength(min) => write!(f, "must be at least {} characters long", min),
+            Self::MaxLength(max) => write!(f, "must be at most {} characters long", max),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+/// Why a command invocation was denied, see [`crate::FrameworkOptions::on_invocation_denied`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationDenial {
+    /// A command check or [`crate::FrameworkOptions::command_check`] returned false or errored.
+    /// The check error itself is passed to the error handler
+    CommandCheckFailed {
+        /// Whether the check errored instead of returning false
+        errored: bool,
+    },
+    /// The command was invoked before its cooldown expired
+    CooldownHit {
+        /// Time until the command may be invoked for the next time in the given context
+        remaining_cooldown: std::time::Duration,
+    },
+    /// [`crate::Command::max_concurrent_invocations`] invocations of the command were already
+    /// running
+    TooManyConcurrentInvocations {
+        /// The configured limit of concurrently running invocations
+        limit: usize,
+    },
+    /// The bot is lacking the permissions specified in [`crate::Command::required_bot_permissions`]
+    MissingBotPermissions {
+        /// Which permissions in particular the bot is lacking for this command
+        missing_permissions: serenity::Permissions,
+    },
+    /// The user is lacking the permissions specified in [`crate::Command::required_permissions`]
+    MissingUserPermissions {
+        /// List of permissions that the user is lacking. May be None if retrieving the user's
+        /// permissions failed
+        missing_permissions: Option<serenity::Permissions>,
+    },
+    /// The permissions of the user or the bot couldn't be determined
+    PermissionsUnverifiable {
+        /// The user whose permissions couldn't be determined: either the author or the bot
+        user_id: serenity::UserId,
+    },
+    /// A non-owner tried to invoke an owners-only command
+    NotAnOwner,
+    /// A guild-only command was invoked in DMs
+    GuildOnly,
+    /// A DM-only command was invoked in a guild
+    DmOnly,
+    /// A NSFW-only command was invoked in a non-NSFW channel
+    NsfwOnly,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
 /// Support functions for the macro, which can't create these #[non_exhaustive] enum variants
 #[doc(hidden)]
 impl<'a, U, E> FrameworkError<'a, U, E> {
@@ -290,7 +619,20 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
         input: Option<String>,
         error: Box<dyn std::error::Error + Send + Sync>,
     ) -> Self {
-        Self::ArgumentParse { error, input, ctx }
+        Self::ArgumentParse {
+            error,
+            input,
+            parameter: None,
+            ctx,
+        }
+    }
+
+    /// Sets the parameter of an [`Self::ArgumentParse`] error to the parameter with the given name
+    pub fn with_parameter_name(mut self, name: &str) -> Self {
+        if let Self::ArgumentParse { parameter, ctx, .. } = &mut self {
+            *parameter = ctx.command().parameters.iter().find(|p| p.name == name);
+        }
+        self
     }
 
     pub fn new_command_structure_mismatch(
@@ -322,6 +664,12 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                 "error in {} event event handler",
                 event.snake_case_name()
             ),
+            Self::BackgroundTask { .. } => write!(f, "error in background task"),
+            Self::ComponentHandler { ctx, .. } => write!(
+                f,
+                "error in component handler for custom ID `{}`",
+                ctx.custom_id()
+            ),
             Self::Command { error: _, ctx } => {
                 write!(f, "error in command `{}`", full_command_name!(ctx))
             }
@@ -338,12 +686,37 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
             Self::ArgumentParse {
                 error: _,
                 input,
+                parameter,
+                ctx,
+            } => {
+                write!(f, "failed to parse argument")?;
+                if let Some(parameter) = parameter {
+                    write!(f, " `{}`", parameter.name)?;
+                }
+                write!(
+                    f,
+                    " in command `{}` on input {:?}",
+                    full_command_name!(ctx),
+                    input
+                )
+            }
+            Self::InvalidChannelType { expected, got, ctx } => write!(
+                f,
+                "channel of type {:?} given to command `{}`, expected one of {:?}",
+                got,
+                full_command_name!(ctx),
+                expected
+            ),
+            Self::ParameterConstraintViolation {
+                parameter,
+                constraint,
                 ctx,
             } => write!(
                 f,
-                "failed to parse argument in command `{}` on input {:?}",
+                "argument `{}` of command `{}` {}",
+                parameter,
                 full_command_name!(ctx),
-                input
+                constraint
             ),
             Self::CommandStructureMismatch { description, ctx } => write!(
                 f,
@@ -360,6 +733,18 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                 full_command_name!(ctx),
                 remaining_cooldown
             ),
+            Self::RatelimitDelayed { delay, ctx } => write!(
+                f,
+                "invocation of command `{}` delayed by {:?} due to rate limit",
+                full_command_name!(ctx),
+                delay
+            ),
+            Self::TooManyConcurrentInvocations { limit, ctx } => write!(
+                f,
+                "command `{}` is already running {} times",
+                full_command_name!(ctx),
+                limit
+            ),
             Self::MissingBotPermissions {
                 missing_permissions,
                 ctx,
@@ -378,6 +763,12 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                 missing_permissions,
                 full_command_name!(ctx),
             ),
+            Self::PermissionsUnverifiable { user_id, ctx } => write!(
+                f,
+                "could not verify permissions of user {} to execute command `{}`",
+                user_id,
+                full_command_name!(ctx),
+            ),
             Self::NotAnOwner { ctx } => write!(
                 f,
                 "owner-only command `{}` cannot be run by non-owners",
@@ -439,14 +830,21 @@ impl<'a, U: std::fmt::Debug, E: std::error::Error + 'static> std::error::Error
         match self {
             Self::Setup { error, .. } => Some(error),
             Self::EventHandler { error, .. } => Some(error),
+            Self::BackgroundTask { error, .. } => Some(error),
+            Self::ComponentHandler { error, .. } => Some(error),
             Self::Command { error, .. } => Some(error),
             Self::SubcommandRequired { .. } => None,
             Self::CommandPanic { .. } => None,
             Self::ArgumentParse { error, .. } => Some(&**error),
+            Self::InvalidChannelType { .. } => None,
+            Self::ParameterConstraintViolation { .. } => None,
             Self::CommandStructureMismatch { .. } => None,
             Self::CooldownHit { .. } => None,
+            Self::RatelimitDelayed { .. } => None,
+            Self::TooManyConcurrentInvocations { .. } => None,
             Self::MissingBotPermissions { .. } => None,
             Self::MissingUserPermissions { .. } => None,
+            Self::PermissionsUnverifiable { .. } => None,
             Self::NotAnOwner { .. } => None,
             Self::GuildOnly { .. } => None,
             Self::DmOnly { .. } => None,
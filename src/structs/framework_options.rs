@@ -7,7 +7,18 @@ use crate::{serenity_prelude as serenity, BoxFuture};
 #[derivative(Debug(bound = ""))]
 pub struct FrameworkOptions<U, E> {
     /// List of commands in the framework
+    ///
+    /// Commands can also be added and removed while the bot is running, see
+    /// [`Self::add_command`] and [`Self::active_commands`].
     pub commands: Vec<crate::Command<U, E>>,
+    /// Commands added and removed at runtime. Leave this at its default and use
+    /// [`Self::add_command`], [`Self::remove_command`] and [`Self::replace_commands`]
+    #[derivative(Debug = "ignore")]
+    pub runtime_commands: crate::RuntimeCommands<U, E>,
+    /// Background tasks that were spawned but aren't running yet. Leave this at its default and
+    /// use [`Self::spawn_periodic`] and [`Self::spawn_at`]
+    #[derivative(Debug = "ignore")]
+    pub background_tasks: crate::BackgroundTasks<U, E>,
     /// Provide a callback to be invoked when any user code yields an error.
     #[derivative(Debug = "ignore")]
     pub on_error: fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>,
@@ -17,6 +28,14 @@ pub struct FrameworkOptions<U, E> {
     /// Called after every command if it was successful (returned Ok)
     #[derivative(Debug = "ignore")]
     pub post_command: fn(crate::Context<'_, U, E>) -> BoxFuture<'_, ()>,
+    /// Called whenever an invocation is denied by a check, missing permissions, a cooldown,
+    /// `owners_only`, `guild_only` etc. Useful to log attempted misuse in one place.
+    ///
+    /// Called before the error handler, which still receives the corresponding
+    /// [`crate::FrameworkError`].
+    #[derivative(Debug = "ignore")]
+    pub on_invocation_denied:
+        fn(crate::Context<'_, U, E>, crate::InvocationDenial) -> BoxFuture<'_, ()>,
     /// Provide a callback to be invoked before every command. The command will only be executed
     /// if the callback returns true.
     ///
@@ -40,6 +59,20 @@ pub struct FrameworkOptions<U, E> {
     /// Useful for implementing custom cooldown behavior. See [`crate::Command::cooldowns`] and
     /// the methods on [`crate::Cooldowns`] for how to do that.
     pub manual_cooldowns: bool,
+    /// If Some, command cooldowns are kept in this store instead of in
+    /// [`crate::Command::cooldowns`], for example to persist them across restarts. Commands with
+    /// a [`crate::CooldownConfig::wait_threshold`] additionally track their cooldowns and waiting
+    /// invocations in memory, so that concurrent invocations of this process don't pass together.
+    ///
+    /// None by default, which keeps cooldowns in memory.
+    #[derivative(Debug = "ignore")]
+    pub cooldown_store: Option<std::sync::Arc<dyn crate::CooldownStore>>,
+    /// Rate limit buckets by name, which commands can opt into via [`crate::Command::bucket`].
+    /// Buckets are checked after cooldowns, unless [`Self::manual_cooldowns`] is set. Invocations
+    /// denied by a check, cooldown or bucket of any of the invoked commands don't use up a bucket.
+    ///
+    /// Empty by default.
+    pub buckets: std::collections::HashMap<String, crate::CooldownBucket>,
     /// If `true`, changes behavior of guild_only command check to abort execution if the guild is
     /// not in cache.
     ///
@@ -61,6 +94,9 @@ pub struct FrameworkOptions<U, E> {
     /// Prefix command specific options.
     pub prefix_options: crate::PrefixFrameworkOptions<U, E>,
     /// User IDs which are allowed to use owners_only commands
+    ///
+    /// If [`Self::initialize_owners`] is set, the owners fetched on startup are added to the ones
+    /// given here, so this can serve as a static list of additional owners.
     pub owners: std::collections::HashSet<serenity::UserId>,
     /// If true, [`Self::owners`] is automatically initialized with the results of
     /// [`serenity::Http::get_current_application_info()`].
@@ -74,6 +110,123 @@ pub struct FrameworkOptions<U, E> {
     ///
     /// None by default.
     pub initialized_team_roles: Option<Vec<serenity::TeamMemberRole>>,
+    /// If [`Self::initialize_owners`] is `true`, whether the owner of the bot application (as
+    /// opposed to its team members) is initialized as an owner.
+    ///
+    /// True by default.
+    pub initialize_application_owner: bool,
+    /// If set, the commands are registered at this target on startup, before the setup callback
+    /// runs. Nothing is sent if the registered commands are already up to date, see
+    /// [`crate::builtins::RegisterTarget::register`]. Registration errors are logged.
+    ///
+    /// None by default. See [`crate::FrameworkBuilder::auto_register`]
+    pub auto_register: Option<crate::builtins::RegisterTarget>,
+    /// If set, [`crate::builtins::on_error`] posts a detailed report of command errors, command
+    /// panics, event handler errors, background task errors and component handler errors here, see
+    /// [`crate::builtins::error_report`].
+    ///
+    /// None by default.
+    pub error_sink: Option<crate::builtins::ErrorSink>,
+    /// If set, decides which part of command errors and command check errors is shown to users,
+    /// and how severe they are. [`crate::builtins::on_error`] then shows the user-facing message
+    /// and logs the full error at the returned severity, see [`crate::UserFacingError`].
+    ///
+    /// None by default, in which case command errors are shown to users as-is.
+    #[derivative(Debug = "ignore")]
+    pub error_formatter: Option<fn(&E) -> crate::UserFacingError>,
+    /// Commands to run periodically without a user invoking them.
+    ///
+    /// [`crate::Framework`] moves them into [`Self::background_tasks`] when it's created, see
+    /// [`Self::spawn_scheduled_command`]. See [`crate::ScheduledCommand`] for details.
+    pub scheduled_commands: Vec<crate::ScheduledCommand>,
+    /// Callbacks that map gateway events onto command invocations, for command logic that should
+    /// also run automatically, e.g. a `welcome` command on GuildMemberAddition.
+    ///
+    /// Called on every event, before [`Self::event_handler`]. See [`crate::EventInvocation`].
+    pub event_commands:
+        Vec<fn(&serenity::Context, &serenity::FullEvent) -> Option<crate::EventInvocation>>,
+    /// Determines which localizations are used when there's none for the user's exact locale, for
+    /// example in help output.
+    pub locale_fallbacks: crate::localization::LocaleFallbacks,
+    /// Called on every prefix command invocation to determine its locale, because unlike slash
+    /// command invocations, prefix invocations don't carry the user's locale. Useful for example
+    /// for a guild-configured bot language.
+    ///
+    /// The result is returned from [`crate::Context::locale`]. It should be one of
+    /// [`crate::localization::DISCORD_LOCALES`]; other values are ignored.
+    #[derivative(Debug = "ignore")]
+    pub locale_provider:
+        Option<fn(crate::PartialContext<'_, U, E>) -> BoxFuture<'_, Option<String>>>,
+    /// Parent of the cancellation tokens of all command invocations, see
+    /// [`crate::Context::cancellation_token`].
+    ///
+    /// Cancelled when [`crate::Framework`] is dropped. For a graceful shutdown, cancel it yourself
+    /// before shutting down the shards, so that running commands can wrap up. Also stops the
+    /// background tasks, see [`Self::spawn_periodic`].
+    pub shutdown_token: crate::CancellationToken,
+    /// If set, the cancellation token of a command invocation is cancelled once the command has
+    /// been running for this long. See [`crate::Context::cancellation_token`].
+    ///
+    /// None by default.
+    pub command_timeout: Option<std::time::Duration>,
+    /// If set, the response to every application command invocation is deferred automatically
+    /// once the command has been running for this long without responding. Also the delay used
+    /// by commands with [`crate::Command::auto_defer`].
+    ///
+    /// None by default.
+    pub auto_defer_after: Option<std::time::Duration>,
+    /// Whether command invocations run concurrently with the rest of the event handling.
+    ///
+    /// Serenity already handles each gateway event in its own task, so invocations triggered by
+    /// different events always run in parallel. Within a single event however, the invocation
+    /// normally runs to completion before [`Self::event_commands`] and [`Self::event_handler`]
+    /// get to see the event, so a slow command delays them. If true, the invocation, the event
+    /// commands and the event handler are all driven concurrently instead.
+    ///
+    /// They're polled concurrently within the event's task, not spawned as separate tasks, so
+    /// they don't run in parallel: an invocation that blocks the thread still blocks the rest.
+    ///
+    /// To keep heavy commands from piling up, see [`crate::Command::max_concurrent_invocations`].
+    ///
+    /// False by default.
+    pub concurrent_event_handling: bool,
+    /// If set, replies sent via [`crate::send_reply`] are paced per channel to stay within
+    /// Discord's channel rate limits. See [`crate::ReplyQueue`].
+    ///
+    /// None by default.
+    pub reply_queue: Option<crate::ReplyQueue>,
+    /// Observers notified at each stage of every command invocation, for example for analytics,
+    /// metrics ([`crate::MetricsObserver`]), usage statistics ([`crate::stats::UsageTracker`]) or
+    /// audit logs ([`crate::InvocationLog`]). See [`crate::InvocationObserver`].
+    #[derivative(Debug = "ignore")]
+    pub invocation_observers: Vec<Box<dyn crate::InvocationObserver<U, E>>>,
+    /// Middleware wrapping the execution of every command, in order. Runs before the
+    /// command-specific [`crate::Command::middleware`]. See [`crate::CommandMiddleware`].
+    #[derivative(Debug = "ignore")]
+    pub middleware: Vec<Box<dyn crate::CommandMiddleware<U, E>>>,
+    /// Handlers for component and modal interactions, keyed by the custom IDs they handle. Unlike
+    /// collectors, these keep working for messages sent before a restart, so they're suited for
+    /// persistent components. See [`crate::CustomIdPattern`] for the matching rules.
+    ///
+    /// Interactions without a matching handler are ignored, so make sure the patterns don't
+    /// overlap with custom IDs awaited by collectors, e.g. in [`crate::send_interactive_reply`].
+    /// Errors are forwarded to [`Self::on_error`] as [`crate::FrameworkError::ComponentHandler`].
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// let mut options = poise::FrameworkOptions::<(), Error>::default();
+    /// options.component_handlers.insert("vote:*".into(), |ctx| {
+    ///     Box::pin(async move {
+    ///         ctx.say(format!("You voted for {}", ctx.custom_id_args)).await?;
+    ///         Ok(())
+    ///     })
+    /// });
+    /// ```
+    ///
+    /// Empty by default.
+    #[derivative(Debug = "ignore")]
+    pub component_handlers:
+        std::collections::HashMap<crate::CustomIdPattern, crate::ComponentHandler<U, E>>,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -101,6 +254,8 @@ where
         #[allow(deprecated)] // we need to set the listener field
         Self {
             commands: Vec::new(),
+            runtime_commands: Default::default(),
+            background_tasks: Default::default(),
             on_error: |error| {
                 Box::pin(async move {
                     if let Err(e) = crate::builtins::on_error(error).await {
@@ -112,6 +267,7 @@ where
             listener: (),
             pre_command: |_| Box::pin(async {}),
             post_command: |_| Box::pin(async {}),
+            on_invocation_denied: |_, _| Box::pin(async {}),
             command_check: None,
             skip_checks_for_owners: false,
             allowed_mentions: Some(
@@ -123,11 +279,29 @@ where
             ),
             reply_callback: None,
             manual_cooldowns: false,
+            cooldown_store: None,
+            buckets: std::collections::HashMap::new(),
             require_cache_for_guild_check: false,
             prefix_options: Default::default(),
             owners: Default::default(),
             initialize_owners: true,
             initialized_team_roles: None,
+            initialize_application_owner: true,
+            auto_register: None,
+            error_sink: None,
+            error_formatter: None,
+            scheduled_commands: Vec::new(),
+            event_commands: Vec::new(),
+            locale_fallbacks: Default::default(),
+            locale_provider: None,
+            shutdown_token: Default::default(),
+            command_timeout: None,
+            auto_defer_after: None,
+            concurrent_event_handling: false,
+            reply_queue: None,
+            invocation_observers: Vec::new(),
+            middleware: Vec::new(),
+            component_handlers: std::collections::HashMap::new(),
             __non_exhaustive: (),
         }
     }
@@ -0,0 +1,58 @@
+//! Custom data carried across a single command invocation, see [`InvocationData`]
+
+/// Custom data carried across a single command invocation, keyed by type.
+///
+/// Stores at most one value per type, so different layers (checks, hooks, the command body) can
+/// attach their own data without overwriting each other, as long as they use distinct types. Use
+/// a newtype to store multiple values of the same underlying type.
+///
+/// Usually accessed via [`crate::Context::set_invocation_data`] and
+/// [`crate::Context::invocation_data`].
+#[derive(Default, Debug)]
+pub struct InvocationData {
+    /// The stored values, keyed by their type
+    values: std::collections::HashMap<std::any::TypeId, Box<dyn std::any::Any + Send + Sync>>,
+}
+
+impl InvocationData {
+    /// Creates an empty instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the given value, returning the previously stored value of this type, if any
+    pub fn insert<T: 'static + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(std::any::TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    /// Returns a reference to the stored value of this type, if any
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&std::any::TypeId::of::<T>())?
+            .downcast_ref()
+    }
+
+    /// Returns a mutable reference to the stored value of this type, if any
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values
+            .get_mut(&std::any::TypeId::of::<T>())?
+            .downcast_mut()
+    }
+
+    /// Removes and returns the stored value of this type, if any
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&std::any::TypeId::of::<T>())?
+            .downcast()
+            .ok()
+            .map(|value| *value)
+    }
+
+    /// Returns whether a value of this type is stored
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&std::any::TypeId::of::<T>())
+    }
+}
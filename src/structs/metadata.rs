@@ -0,0 +1,229 @@
+//! Serializable snapshots of the command tree, for rendering command lists outside of Discord,
+//! see [`crate::Command::to_metadata`]
+
+use crate::serenity_prelude as serenity;
+
+/// Serializable description of a [`crate::Command`] and its subcommands, as returned by
+/// [`crate::Command::to_metadata`].
+///
+/// Callbacks like checks can't be serialized, so only their number is included.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CommandMetadata {
+    /// See [`crate::Command::name`]
+    pub name: String,
+    /// See [`crate::Command::qualified_name`]
+    pub qualified_name: String,
+    /// See [`crate::Command::name_localizations`]
+    pub name_localizations: std::collections::HashMap<String, String>,
+    /// See [`crate::Command::description`]
+    pub description: Option<String>,
+    /// See [`crate::Command::description_localizations`]
+    pub description_localizations: std::collections::HashMap<String, String>,
+    /// See [`crate::Command::help_text`]
+    pub help_text: Option<String>,
+    /// See [`crate::Command::category`]
+    pub category: Option<String>,
+    /// See [`crate::Command::examples`]
+    pub examples: Vec<String>,
+    /// See [`crate::Command::aliases`]
+    pub aliases: Vec<String>,
+    /// See [`crate::Command::hide_in_help`]
+    pub hide_in_help: bool,
+    /// Whether this command can be invoked as a prefix command
+    pub prefix_command: bool,
+    /// Whether this command can be invoked as a slash command
+    pub slash_command: bool,
+    /// Name of the context menu entry, if this command can be invoked as a context menu command
+    pub context_menu_command: Option<String>,
+    /// Whether this command is the app's primary entry point, see
+    /// [`crate::Command::entry_point_action`]
+    pub entry_point_command: bool,
+    /// See [`crate::Command::parameters`]
+    pub parameters: Vec<ParameterMetadata>,
+    /// See [`crate::Command::subcommands`]
+    pub subcommands: Vec<CommandMetadata>,
+    /// See [`crate::Command::subcommand_required`]
+    pub subcommand_required: bool,
+    /// See [`crate::Command::subcommand_group`]
+    pub subcommand_group: bool,
+    /// Names of the permissions in [`crate::Command::default_member_permissions`]
+    pub default_member_permissions: Vec<String>,
+    /// Names of the permissions in [`crate::Command::required_permissions`]
+    pub required_permissions: Vec<String>,
+    /// Names of the permissions in [`crate::Command::required_bot_permissions`]
+    pub required_bot_permissions: Vec<String>,
+    /// See [`crate::Command::owners_only`]
+    pub owners_only: bool,
+    /// See [`crate::Command::guild_only`]
+    pub guild_only: bool,
+    /// See [`crate::Command::dm_only`]
+    pub dm_only: bool,
+    /// See [`crate::Command::nsfw_only`]
+    pub nsfw_only: bool,
+    /// Number of [`crate::Command::checks`]
+    pub checks: usize,
+    /// Number of [`crate::Command::final_checks`]
+    pub final_checks: usize,
+    /// See [`crate::Command::inherit_checks`]
+    pub inherit_checks: bool,
+    /// See [`crate::Command::cooldown_config`]
+    pub cooldowns: CooldownMetadata,
+    /// See [`crate::Command::bucket`]
+    pub bucket: Option<String>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub __non_exhaustive: (),
+}
+
+/// Serializable description of a [`crate::CommandParameter`]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ParameterMetadata {
+    /// See [`crate::CommandParameter::name`]
+    pub name: String,
+    /// See [`crate::CommandParameter::name_localizations`]
+    pub name_localizations: std::collections::HashMap<String, String>,
+    /// See [`crate::CommandParameter::description`]
+    pub description: Option<String>,
+    /// See [`crate::CommandParameter::description_localizations`]
+    pub description_localizations: std::collections::HashMap<String, String>,
+    /// See [`crate::CommandParameter::required`]
+    pub required: bool,
+    /// Slash command option type like `"String"` or `"User"`, or None for parameters that don't
+    /// support slash commands
+    pub kind: Option<String>,
+    /// See [`crate::CommandParameter::choices`]
+    pub choices: Vec<crate::CommandParameterChoice>,
+    /// Whether this parameter has an autocomplete callback
+    pub autocomplete: bool,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub __non_exhaustive: (),
+}
+
+/// Serializable description of a [`crate::CooldownConfig`], in seconds
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct CooldownMetadata {
+    /// See [`crate::CooldownConfig::global`]
+    pub global: Option<f64>,
+    /// See [`crate::CooldownConfig::user`]
+    pub user: Option<f64>,
+    /// See [`crate::CooldownConfig::guild`]
+    pub guild: Option<f64>,
+    /// See [`crate::CooldownConfig::channel`]
+    pub channel: Option<f64>,
+    /// See [`crate::CooldownConfig::member`]
+    pub member: Option<f64>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub __non_exhaustive: (),
+}
+
+/// Returns the names of the given permissions
+fn permission_names(permissions: serenity::Permissions) -> Vec<String> {
+    permissions
+        .get_permission_names()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+impl<U, E> crate::CommandParameter<U, E> {
+    /// Returns a serializable description of this parameter
+    pub fn to_metadata(&self) -> ParameterMetadata {
+        // The option type is only available by applying the type setter to an option builder
+        let kind = self
+            .create_as_slash_command_option()
+            .and_then(|option| serenity::json::to_value(option).ok())
+            .and_then(|option| option.get("type")?.as_u64())
+            .map(|kind| format!("{:?}", serenity::CommandOptionType::from(kind as u8)));
+
+        ParameterMetadata {
+            name: self.name.clone(),
+            name_localizations: self.name_localizations.clone(),
+            description: self.description.clone(),
+            description_localizations: self.description_localizations.clone(),
+            required: self.required,
+            kind,
+            choices: self.choices.clone(),
+            autocomplete: self.autocomplete_callback.is_some(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl<U, E> crate::Command<U, E> {
+    /// Returns a serializable description of this command and its subcommands, for example to
+    /// render a command list on a website.
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// #[poise::command(slash_command, required_permissions = "BAN_MEMBERS")]
+    /// async fn ban(
+    ///     ctx: poise::Context<'_, (), ()>,
+    ///     #[description = "Who to ban"] user: serenity::User,
+    /// ) -> Result<(), ()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let metadata = ban().to_metadata();
+    /// assert_eq!(metadata.required_permissions, ["Ban Members"]);
+    /// assert_eq!(metadata.parameters[0].kind.as_deref(), Some("User"));
+    /// let json = serenity::json::to_string(&metadata).unwrap();
+    /// ```
+    pub fn to_metadata(&self) -> CommandMetadata {
+        let cooldown_config = self.cooldown_config.read().unwrap();
+        let secs = |duration: Option<std::time::Duration>| duration.map(|d| d.as_secs_f64());
+
+        CommandMetadata {
+            name: self.name.clone(),
+            qualified_name: self.qualified_name.clone(),
+            name_localizations: self.name_localizations.clone(),
+            description: self.description.clone(),
+            description_localizations: self.description_localizations.clone(),
+            help_text: self.help_text.clone(),
+            category: self.category.clone(),
+            examples: self.examples.clone(),
+            aliases: self.aliases.clone(),
+            hide_in_help: self.hide_in_help,
+            prefix_command: self.prefix_action.is_some(),
+            slash_command: self.slash_action.is_some(),
+            context_menu_command: self
+                .context_menu_action
+                .map(|_| self.context_menu_name.clone().unwrap_or(self.name.clone())),
+            entry_point_command: self.entry_point_action.is_some(),
+            parameters: self.parameters.iter().map(|p| p.to_metadata()).collect(),
+            subcommands: self.subcommands.iter().map(|c| c.to_metadata()).collect(),
+            subcommand_required: self.subcommand_required,
+            subcommand_group: self.subcommand_group,
+            default_member_permissions: permission_names(self.default_member_permissions),
+            required_permissions: permission_names(self.required_permissions),
+            required_bot_permissions: permission_names(self.required_bot_permissions),
+            owners_only: self.owners_only,
+            guild_only: self.guild_only,
+            dm_only: self.dm_only,
+            nsfw_only: self.nsfw_only,
+            checks: self.checks.len(),
+            final_checks: self.final_checks.len(),
+            inherit_checks: self.inherit_checks,
+            cooldowns: CooldownMetadata {
+                global: secs(cooldown_config.global),
+                user: secs(cooldown_config.user),
+                guild: secs(cooldown_config.guild),
+                channel: secs(cooldown_config.channel),
+                member: secs(cooldown_config.member),
+                __non_exhaustive: (),
+            },
+            bucket: self.bucket.clone(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl<U, E> crate::FrameworkOptions<U, E> {
+    /// Returns serializable descriptions of all [active commands](Self::active_commands), see
+    /// [`crate::Command::to_metadata`]
+    pub fn export_metadata(&self) -> Vec<CommandMetadata> {
+        let commands = self.active_commands();
+        commands.into_iter().map(|c| c.to_metadata()).collect()
+    }
+}
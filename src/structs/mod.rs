@@ -9,6 +9,26 @@ pub use framework_options::*;
 mod command;
 pub use command::*;
 
+mod component_context;
+pub use component_context::*;
+
+mod command_adapter;
+
+mod command_builder;
+pub use command_builder::*;
+
+mod parameter_builder;
+pub use parameter_builder::*;
+
+mod runtime_commands;
+pub use runtime_commands::*;
+
+mod metadata;
+pub use metadata::*;
+
+mod invocation_data;
+pub use invocation_data::*;
+
 mod prefix;
 pub use prefix::*;
 
@@ -0,0 +1,249 @@
+//! A builder for constructing [`crate::CommandParameter`]s at runtime, see
+//! [`crate::CommandParameter::builder`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Autocomplete callback type of [`crate::CommandParameter::autocomplete_callback`]
+type AutocompleteCallback<U, E> =
+    for<'a> fn(
+        crate::ApplicationContext<'a, U, E>,
+        &'a str,
+    )
+        -> BoxFuture<'a, Result<serenity::CreateAutocompleteResponse, crate::SlashArgError>>;
+
+/// Builder for a [`crate::CommandParameter`], for commands that are constructed at runtime
+/// instead of with the [`crate::command`] macro.
+///
+/// The parameter is a required string parameter unless configured otherwise.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// let parameter = poise::CommandParameter::<(), ()>::builder("target")
+///     .description("Who to ban")
+///     .user()
+///     .build();
+/// assert_eq!(parameter.name, "target");
+/// assert!(parameter.required);
+///
+/// let parameter = poise::CommandParameter::<(), ()>::builder("mode")
+///     .description("How to ban")
+///     .choices(["Soft", "Hard"])
+///     .required(false)
+///     .build();
+/// assert_eq!(parameter.choices.len(), 2);
+/// ```
+#[must_use]
+pub struct CommandParameterBuilder<U, E> {
+    /// The parameter being built
+    parameter: crate::CommandParameter<U, E>,
+}
+
+/// Generates a method that sets the slash command option type
+macro_rules! kind_setters {
+    ($( $(#[$attr:meta])* $name:ident => $kind:ident, )*) => { $(
+        $(#[$attr])*
+        pub fn $name(mut self) -> Self {
+            self.parameter.type_setter =
+                Some(|b| b.kind(serenity::CommandOptionType::$kind));
+            self
+        }
+    )* };
+}
+
+impl<U, E> CommandParameterBuilder<U, E> {
+    /// Starts building a required string parameter with the given name
+    pub(crate) fn new(name: impl Into<String>) -> Self {
+        Self {
+            parameter: crate::CommandParameter {
+                name: name.into(),
+                name_localizations: Default::default(),
+                description: None,
+                description_localizations: Default::default(),
+                required: true,
+                channel_types: None,
+                choices: Vec::new(),
+                min: None,
+                max: None,
+                min_length: None,
+                max_length: None,
+                type_setter: Some(|b| b.kind(serenity::CommandOptionType::String)),
+                autocomplete_callback: None,
+                option_modifier: None,
+                __non_exhaustive: (),
+            },
+        }
+    }
+
+    /// Sets the description. Required for slash commands
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.parameter.description = Some(description.into());
+        self
+    }
+
+    /// Adds a localized name for the given locale
+    pub fn name_localized(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.parameter
+            .name_localizations
+            .insert(locale.into(), name.into());
+        self
+    }
+
+    /// Adds a localized description for the given locale
+    pub fn description_localized(
+        mut self,
+        locale: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.parameter
+            .description_localizations
+            .insert(locale.into(), description.into());
+        self
+    }
+
+    /// Sets whether users must fill in this parameter. `true` by default
+    pub fn required(mut self, required: bool) -> Self {
+        self.parameter.required = required;
+        self
+    }
+
+    kind_setters! {
+        /// Makes this a string parameter. This is the default
+        string => String,
+        /// Makes this an integer parameter
+        integer => Integer,
+        /// Makes this a floating point number parameter
+        number => Number,
+        /// Makes this a boolean parameter
+        boolean => Boolean,
+        /// Makes this a user parameter
+        user => User,
+        /// Makes this a role parameter
+        role => Role,
+        /// Makes this a user or role parameter
+        mentionable => Mentionable,
+        /// Makes this an attachment parameter
+        attachment => Attachment,
+    }
+
+    /// Sets the slash command option type. Equivalent to calling the respective method like
+    /// [`Self::integer`]. Types that can't be parameters, like subcommands, are ignored
+    pub fn kind(self, kind: serenity::CommandOptionType) -> Self {
+        match kind {
+            serenity::CommandOptionType::String => self.string(),
+            serenity::CommandOptionType::Integer => self.integer(),
+            serenity::CommandOptionType::Number => self.number(),
+            serenity::CommandOptionType::Boolean => self.boolean(),
+            serenity::CommandOptionType::User => self.user(),
+            serenity::CommandOptionType::Role => self.role(),
+            serenity::CommandOptionType::Mentionable => self.mentionable(),
+            serenity::CommandOptionType::Attachment => self.attachment(),
+            serenity::CommandOptionType::Channel => self.channel(None),
+            _ => {
+                tracing::warn!(
+                    "ignoring invalid option type {:?} for parameter `{}`",
+                    kind,
+                    self.parameter.name
+                );
+                self
+            }
+        }
+    }
+
+    /// Sets the minimum value of a number parameter, see [`crate::CommandParameter::min`]
+    pub fn min(mut self, min: f64) -> Self {
+        self.parameter.min = Some(min);
+        self
+    }
+
+    /// Sets the maximum value of a number parameter, see [`crate::CommandParameter::max`]
+    pub fn max(mut self, max: f64) -> Self {
+        self.parameter.max = Some(max);
+        self
+    }
+
+    /// Sets the minimum length of a string parameter, see [`crate::CommandParameter::min_length`]
+    pub fn min_length(mut self, min_length: u16) -> Self {
+        self.parameter.min_length = Some(min_length);
+        self
+    }
+
+    /// Sets the maximum length of a string parameter, see [`crate::CommandParameter::max_length`]
+    pub fn max_length(mut self, max_length: u16) -> Self {
+        self.parameter.max_length = Some(max_length);
+        self
+    }
+
+    /// Makes this a channel parameter. If `channel_types` is given, users can only choose
+    /// channels of these types
+    pub fn channel(mut self, channel_types: Option<Vec<serenity::ChannelType>>) -> Self {
+        self.parameter.type_setter = Some(|b| b.kind(serenity::CommandOptionType::Channel));
+        self.parameter.channel_types = channel_types;
+        self
+    }
+
+    /// Makes this a choice parameter with the given choice labels.
+    ///
+    /// Like choice parameters generated by the macro, the slash command option is an integer
+    /// holding the index of the selected choice.
+    pub fn choices(mut self, choices: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.parameter.type_setter = Some(|b| b.kind(serenity::CommandOptionType::Integer));
+        self.parameter.choices = choices
+            .into_iter()
+            .map(|name| crate::CommandParameterChoice {
+                name: name.into(),
+                localizations: Default::default(),
+                value: None,
+                __non_exhaustive: (),
+            })
+            .collect();
+        self
+    }
+
+    /// Appends a choice with an explicit value that Discord sends when it's selected, see
+    /// [`crate::CommandParameterChoice::value`]. Makes this an integer or string parameter,
+    /// depending on the value.
+    ///
+    /// ```rust
+    /// let parameter = poise::CommandParameter::<(), ()>::builder("size")
+    ///     .description("Pizza size")
+    ///     .choice("Small", 26)
+    ///     .choice("Large", 32)
+    ///     .build();
+    /// assert_eq!(parameter.choices[1].value, Some(poise::ChoiceValue::Integer(32)));
+    /// ```
+    pub fn choice(mut self, name: impl Into<String>, value: impl Into<crate::ChoiceValue>) -> Self {
+        let value = value.into();
+        self.parameter.type_setter = match value {
+            crate::ChoiceValue::String(_) => Some(|b| b.kind(serenity::CommandOptionType::String)),
+            _ => Some(|b| b.kind(serenity::CommandOptionType::Integer)),
+        };
+        self.parameter.choices.push(crate::CommandParameterChoice {
+            name: name.into(),
+            localizations: Default::default(),
+            value: Some(value),
+            __non_exhaustive: (),
+        });
+        self
+    }
+
+    /// Sets the autocomplete callback, see [`crate::CommandParameter::autocomplete_callback`]
+    pub fn autocomplete(mut self, callback: AutocompleteCallback<U, E>) -> Self {
+        self.parameter.autocomplete_callback = Some(callback);
+        self
+    }
+
+    /// Sets a function that's applied to the option builder on registration, see
+    /// [`crate::CommandParameter::option_modifier`]
+    pub fn option_modifier(
+        mut self,
+        option_modifier: fn(serenity::CreateCommandOption) -> serenity::CreateCommandOption,
+    ) -> Self {
+        self.parameter.option_modifier = Some(option_modifier);
+        self
+    }
+
+    /// Finishes building the parameter
+    pub fn build(self) -> crate::CommandParameter<U, E> {
+        self.parameter
+    }
+}
@@ -12,6 +12,15 @@ pub enum MessageDispatchTrigger {
     /// The message was edited, and was not a valid invocation pre-edit (i.e. user typoed the
     /// command, then fixed it)
     MessageEditFromInvalid,
+    /// The command was run by the scheduler; the invocation message is synthetic and was never
+    /// actually sent. See [`crate::ScheduledCommand`]
+    Scheduled,
+    /// The command was run in response to a gateway event; the invocation message is synthetic and
+    /// was never actually sent. See [`crate::EventInvocation`]
+    Event,
+    /// The command was run programmatically via [`crate::FrameworkContext::invoke_command`]; the
+    /// invocation message is synthetic and was never actually sent
+    Programmatic,
     #[doc(hidden)]
     __NonExhaustive,
 }
@@ -47,9 +56,11 @@ pub struct PrefixContext<'a, U, E> {
     #[derivative(Debug = "ignore")]
     pub data: &'a U,
     /// Custom user data carried across a single command invocation
-    pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    pub invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     /// How this command invocation was triggered
     pub trigger: MessageDispatchTrigger,
+    /// Locale of this invocation, as determined by [`crate::FrameworkOptions::locale_provider`]
+    pub locale: Option<&'static str>,
     /// The function that is called to execute the actual command
     #[derivative(Debug = "ignore")]
     pub action: fn(
@@ -67,6 +78,16 @@ impl<U, E> Clone for PrefixContext<'_, U, E> {
     }
 }
 impl<U, E> Copy for PrefixContext<'_, U, E> {}
+
+impl<'a, U, E> PrefixContext<'a, U, E> {
+    /// Returns the unparsed remainder of the invocation after the command name, with leading
+    /// whitespace trimmed. Useful for commands that parse their arguments themselves
+    ///
+    /// Same as [`Self::args`].
+    pub fn args_raw(&self) -> &'a str {
+        self.args
+    }
+}
 impl<U, E> crate::_GetGenerics for PrefixContext<'_, U, E> {
     type U = U;
     type E = E;
@@ -83,6 +104,174 @@ pub enum Prefix {
     __NonExhaustive,
 }
 
+impl Prefix {
+    /// Compiles a regex prefix. The regex must match at the start of the message, so there's no
+    /// need to anchor it with `^`
+    ///
+    /// ```rust
+    /// let prefix = poise::Prefix::regex(r"[Hh]ey bot,?")?;
+    /// assert_eq!(prefix.strip("Hey bot, ping"), Some(("Hey bot,", " ping")));
+    /// assert_eq!(prefix.strip("ping, hey bot"), None);
+    /// # Ok::<(), regex::Error>(())
+    /// ```
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self::Regex)
+    }
+
+    /// If the content starts with this prefix, returns the matched prefix and the rest of the
+    /// content
+    pub fn strip<'a>(&self, content: &'a str) -> Option<(&'a str, &'a str)> {
+        match self {
+            &Self::Literal(prefix) => Some((prefix, content.strip_prefix(prefix)?)),
+            Self::Regex(prefix) => {
+                let regex_match = prefix.find(content)?;
+                if regex_match.start() == 0 {
+                    Some(content.split_at(regex_match.end()))
+                } else {
+                    None
+                }
+            }
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+/// A kind of prefix that is tried when stripping the prefix off a message, see [`PrefixResolver`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PrefixSource {
+    /// The prefixes returned by [`PrefixFrameworkOptions::dynamic_prefix`] and
+    /// [`PrefixFrameworkOptions::dynamic_prefixes`], for example per-guild prefixes
+    Dynamic,
+    /// [`PrefixFrameworkOptions::prefix`]
+    Static,
+    /// [`PrefixFrameworkOptions::additional_prefixes`], literal and regex prefixes in list order
+    Additional,
+    /// [`PrefixFrameworkOptions::stripped_dynamic_prefix`]
+    StrippedDynamic,
+    /// A mention of the bot, if [`PrefixFrameworkOptions::mention_as_prefix`] is set
+    Mention,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+/// Determines which kinds of prefixes are tried, and in which order, when stripping the prefix off
+/// a message. The first matching prefix is used. See [`PrefixFrameworkOptions::prefix_resolver`].
+///
+/// By default, all sources are tried in this order:
+/// 1. [`PrefixSource::Dynamic`], e.g. the prefixes configured for the guild
+/// 2. [`PrefixSource::Static`], the global prefix
+/// 3. [`PrefixSource::Additional`], further literal and regex prefixes
+/// 4. [`PrefixSource::StrippedDynamic`], custom stripping logic
+/// 5. [`PrefixSource::Mention`]
+///
+/// For example, to only fall back to the global prefix if no guild prefix matches, and never
+/// accept mentions:
+/// ```rust
+/// use poise::PrefixSource;
+/// let resolver = poise::PrefixResolver::new([PrefixSource::Dynamic, PrefixSource::Static]);
+/// assert_eq!(resolver.sources(), &[PrefixSource::Dynamic, PrefixSource::Static]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefixResolver {
+    /// The prefix sources, in the order they are tried
+    sources: Vec<PrefixSource>,
+}
+
+impl PrefixResolver {
+    /// Creates a resolver trying the given sources in order. Sources that aren't listed are
+    /// never tried
+    pub fn new(sources: impl IntoIterator<Item = PrefixSource>) -> Self {
+        Self {
+            sources: sources.into_iter().collect(),
+        }
+    }
+
+    /// Returns the prefix sources, in the order they are tried
+    pub fn sources(&self) -> &[PrefixSource] {
+        &self.sources
+    }
+}
+
+impl Default for PrefixResolver {
+    fn default() -> Self {
+        Self::new([
+            PrefixSource::Dynamic,
+            PrefixSource::Static,
+            PrefixSource::Additional,
+            PrefixSource::StrippedDynamic,
+            PrefixSource::Mention,
+        ])
+    }
+}
+
+/// Caches dynamic prefixes per guild, see [`PrefixFrameworkOptions::prefix_cache`].
+///
+/// Cached prefixes are resolved again once they are older than the configured time to live.
+/// Prefixes in DMs aren't cached, since they may depend on the user.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// let cache = poise::PrefixCache::new(std::time::Duration::from_secs(60 * 10));
+/// let guild_id = serenity::GuildId::new(1);
+/// cache.insert(guild_id, vec!["!".into(), "?".into()]);
+/// assert_eq!(cache.get(guild_id), Some(vec!["!".into(), "?".into()]));
+///
+/// cache.invalidate(guild_id);
+/// assert_eq!(cache.get(guild_id), None);
+/// ```
+#[derive(Debug)]
+pub struct PrefixCache {
+    /// How long resolved prefixes stay valid
+    ttl: std::time::Duration,
+    /// Resolved prefixes per guild, with the time they were resolved
+    entries: parking_lot::Mutex<
+        std::collections::HashMap<serenity::GuildId, (std::time::Instant, Vec<String>)>,
+    >,
+}
+
+impl PrefixCache {
+    /// Creates an empty cache whose entries are valid for the given duration
+    pub fn new(ttl: std::time::Duration) -> Self {
+        Self {
+            ttl,
+            entries: parking_lot::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Returns the cached prefixes of the given guild, if they haven't expired yet
+    pub fn get(&self, guild_id: serenity::GuildId) -> Option<Vec<String>> {
+        let mut entries = self.entries.lock();
+        match entries.get(&guild_id) {
+            Some((resolved_at, prefixes)) if resolved_at.elapsed() < self.ttl => {
+                Some(prefixes.clone())
+            }
+            Some(_) => {
+                entries.remove(&guild_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores the resolved prefixes of the given guild
+    pub fn insert(&self, guild_id: serenity::GuildId, prefixes: Vec<String>) {
+        self.entries
+            .lock()
+            .insert(guild_id, (std::time::Instant::now(), prefixes));
+    }
+
+    /// Removes the cached prefixes of the given guild, so they are resolved again on the next
+    /// message
+    pub fn invalidate(&self, guild_id: serenity::GuildId) {
+        self.entries.lock().remove(&guild_id);
+    }
+
+    /// Removes all cached prefixes
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
 /// Prefix-specific framework configuration
 #[derive(derivative::Derivative)]
 #[derivative(Debug(bound = ""))]
@@ -102,6 +291,19 @@ pub struct PrefixFrameworkOptions<U, E> {
     #[derivative(Debug = "ignore")]
     pub dynamic_prefix:
         Option<fn(crate::PartialContext<'_, U, E>) -> BoxFuture<'_, Result<Option<String>, E>>>,
+    /// Like [`Self::dynamic_prefix`], but returns any number of prefixes, for example when each
+    /// guild can configure multiple custom prefixes. The first matching prefix is used.
+    ///
+    /// If both callbacks are set, the prefix returned by [`Self::dynamic_prefix`] is tried first.
+    #[derivative(Debug = "ignore")]
+    pub dynamic_prefixes:
+        Option<fn(crate::PartialContext<'_, U, E>) -> BoxFuture<'_, Result<Vec<String>, E>>>,
+    /// If Some, the prefixes returned by [`Self::dynamic_prefix`] and [`Self::dynamic_prefixes`]
+    /// are cached per guild, so the callbacks don't run for every message.
+    ///
+    /// After changing a guild's prefix, call [`crate::Framework::invalidate_prefix`] so the change
+    /// takes effect immediately.
+    pub prefix_cache: Option<PrefixCache>,
     /// Callback invoked on every message to strip the prefix off an incoming message.
     ///
     /// Override this field for advanced dynamic prefixes which change depending on guild or user.
@@ -126,6 +328,10 @@ pub struct PrefixFrameworkOptions<U, E> {
     >,
     /// Treat a bot mention (a ping) like a prefix
     pub mention_as_prefix: bool,
+    /// Which kinds of prefixes are tried, and in which order. By default, dynamic prefixes are
+    /// tried first, then [`Self::prefix`], [`Self::additional_prefixes`],
+    /// [`Self::stripped_dynamic_prefix`] and finally mentions.
+    pub prefix_resolver: PrefixResolver,
     /// If Some, the framework will react to message edits by editing the corresponding bot response
     /// with the new result.
     pub edit_tracker: Option<std::sync::Arc<std::sync::RwLock<crate::EditTracker>>>,
@@ -143,6 +349,10 @@ pub struct PrefixFrameworkOptions<U, E> {
     /// This is the case if the message edit happens before a command has sent a response, or if the
     /// command does not send a response at all.
     pub ignore_edits_if_not_yet_responded: bool,
+    /// When a [`crate::Command::reuse_response`] command reruns after an edit, whether the
+    /// embeds and attachments of the previous response are kept if the new response doesn't set
+    /// any. By default, the previous response is reset entirely. Default `false`
+    pub keep_previous_response_media: bool,
 
     /// Whether commands in messages emitted by this bot itself should be executed as well.
     pub execute_self_messages: bool,
@@ -152,6 +362,10 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub ignore_thread_creation: bool,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
+    /// Whether invocations wrapped entirely in a code block, like
+    /// ````` ```rust\n?eval 1 + 1\n``` `````, are parsed as if the code block wasn't there.
+    /// The language tag is skipped. Default `false`
+    pub unwrap_code_blocks: bool,
     /// Callback for all non-command messages. Useful if you want to run code on any message that
     /// is not a command
     pub non_command_message: Option<
@@ -181,8 +395,11 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             prefix: None,
             additional_prefixes: Vec::new(),
             dynamic_prefix: None,
+            dynamic_prefixes: None,
+            prefix_cache: None,
             stripped_dynamic_prefix: None,
             mention_as_prefix: true,
+            prefix_resolver: PrefixResolver::default(),
             edit_tracker: None,
             execute_untracked_edits: true,
             ignore_edits_if_not_yet_responded: false,
@@ -190,6 +407,8 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             ignore_bots: true,
             ignore_thread_creation: true,
             case_insensitive_commands: true,
+            unwrap_code_blocks: false,
+            keep_previous_response_media: false,
             non_command_message: None,
             // help_when_mentioned: true,
             // help_commmand: None,
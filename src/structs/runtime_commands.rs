@@ -0,0 +1,216 @@
+//! Adding and removing commands while the bot is running, see
+//! [`crate::FrameworkOptions::add_command`]
+
+use crate::serenity_prelude as serenity;
+
+/// Commands added and removed at runtime. Never modified in place; every modification stores a
+/// modified copy, so that snapshots stay valid while they're used
+struct RuntimeState<U, E> {
+    /// Commands added at runtime that are still active
+    added: Vec<std::sync::Arc<crate::Command<U, E>>>,
+    /// Names of the commands in [`crate::FrameworkOptions::commands`] that were removed
+    removed_static: std::collections::HashSet<String>,
+}
+
+// Manual impls, because derive would require U: Clone and E: Clone
+impl<U, E> Clone for RuntimeState<U, E> {
+    fn clone(&self) -> Self {
+        Self {
+            added: self.added.clone(),
+            removed_static: self.removed_static.clone(),
+        }
+    }
+}
+impl<U, E> Default for RuntimeState<U, E> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            removed_static: std::collections::HashSet::new(),
+        }
+    }
+}
+
+/// Commands added and removed at runtime, stored in [`crate::FrameworkOptions::runtime_commands`].
+///
+/// You don't need to interact with this type directly; use the methods on
+/// [`crate::FrameworkOptions`] instead.
+pub struct RuntimeCommands<U, E> {
+    /// Current state. Readers clone the [`std::sync::Arc`], writers replace it
+    state: parking_lot::RwLock<std::sync::Arc<RuntimeState<U, E>>>,
+}
+
+impl<U, E> Default for RuntimeCommands<U, E> {
+    fn default() -> Self {
+        Self {
+            state: parking_lot::RwLock::new(std::sync::Arc::default()),
+        }
+    }
+}
+
+/// Snapshot of the commands that were active at some point in time, see
+/// [`crate::FrameworkOptions::active_commands`].
+///
+/// Adding and removing commands doesn't affect existing snapshots. Removed commands are freed once
+/// no snapshot references them anymore.
+pub struct ActiveCommands<'a, U, E> {
+    /// [`crate::FrameworkOptions::commands`], including the removed ones
+    static_commands: &'a [crate::Command<U, E>],
+    /// Runtime modifications at the time of the snapshot
+    state: std::sync::Arc<RuntimeState<U, E>>,
+}
+
+impl<U, E> ActiveCommands<'static, U, E> {
+    /// Snapshot without any commands
+    pub(crate) fn empty() -> Self {
+        Self {
+            static_commands: &[],
+            state: std::sync::Arc::default(),
+        }
+    }
+}
+
+impl<U, E> ActiveCommands<'_, U, E> {
+    /// Iterates the commands in [`crate::FrameworkOptions::commands`] that weren't removed,
+    /// followed by the commands added at runtime
+    pub fn iter(&self) -> ActiveCommandsIter<'_, U, E> {
+        ActiveCommandsIter {
+            static_commands: self.static_commands.iter(),
+            removed_static: &self.state.removed_static,
+            runtime_commands: self.state.added.iter(),
+        }
+    }
+}
+
+impl<'b, U, E> IntoIterator for &'b ActiveCommands<'_, U, E> {
+    type Item = &'b crate::Command<U, E>;
+    type IntoIter = ActiveCommandsIter<'b, U, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the commands of an [`ActiveCommands`] snapshot
+pub struct ActiveCommandsIter<'b, U, E> {
+    /// Remaining commands of [`crate::FrameworkOptions::commands`], including removed ones
+    static_commands: std::slice::Iter<'b, crate::Command<U, E>>,
+    /// Names of the removed commands of [`crate::FrameworkOptions::commands`]
+    removed_static: &'b std::collections::HashSet<String>,
+    /// Remaining commands added at runtime
+    runtime_commands: std::slice::Iter<'b, std::sync::Arc<crate::Command<U, E>>>,
+}
+
+impl<'b, U, E> Iterator for ActiveCommandsIter<'b, U, E> {
+    type Item = &'b crate::Command<U, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let removed_static = self.removed_static;
+        self.static_commands
+            .find(|command| !removed_static.contains(&command.name))
+            .or_else(|| self.runtime_commands.next().map(|command| &**command))
+    }
+}
+
+impl<U, E> crate::FrameworkOptions<U, E> {
+    /// Returns a snapshot of all commands that are currently active: the commands in
+    /// [`Self::commands`] that weren't removed at runtime, followed by the commands added at
+    /// runtime.
+    ///
+    /// Dispatch works with the snapshot in [`crate::FrameworkContext::commands`], taken when the
+    /// event came in.
+    pub fn active_commands(&self) -> ActiveCommands<'_, U, E> {
+        ActiveCommands {
+            static_commands: &self.commands,
+            state: self.runtime_commands.state.read().clone(),
+        }
+    }
+
+    /// Applies a modification to the runtime commands. Modifications are serialized by the write
+    /// lock
+    fn modify_runtime_commands<T>(&self, f: impl FnOnce(&mut RuntimeState<U, E>) -> T) -> T {
+        let mut state = self.runtime_commands.state.write();
+        let mut new_state = RuntimeState::clone(&state);
+        let result = f(&mut new_state);
+        *state = std::sync::Arc::new(new_state);
+        result
+    }
+
+    /// Adds a command while the bot is running, replacing any active command with the same name.
+    /// Dispatch picks it up with the next invocation.
+    ///
+    /// Application commands must be registered with Discord before users can invoke them, see
+    /// [`Self::reregister_commands`].
+    ///
+    /// ```rust
+    /// #[poise::command(prefix_command)]
+    /// async fn ping(ctx: poise::Context<'_, (), String>) -> Result<(), String> { Ok(()) }
+    ///
+    /// let options = poise::FrameworkOptions::<(), String>::default();
+    /// options.add_command(ping());
+    /// assert_eq!(options.active_commands().iter().count(), 1);
+    /// assert!(options.remove_command("ping"));
+    /// assert_eq!(options.active_commands().iter().count(), 0);
+    /// ```
+    pub fn add_command(&self, mut command: crate::Command<U, E>) {
+        crate::set_qualified_names(std::slice::from_mut(&mut command));
+        self.modify_runtime_commands(|state| {
+            self.remove_command_from(state, &command.name);
+            state.added.push(std::sync::Arc::new(command));
+        });
+    }
+
+    /// Removes the active top-level command with the given name while the bot is running. Returns
+    /// false if there's no such command.
+    ///
+    /// Invocations of the command that are already running aren't affected.
+    pub fn remove_command(&self, name: &str) -> bool {
+        self.modify_runtime_commands(|state| self.remove_command_from(state, name))
+    }
+
+    /// Replaces all active commands with the given ones while the bot is running
+    pub fn replace_commands(&self, commands: Vec<crate::Command<U, E>>) {
+        self.modify_runtime_commands(|state| {
+            state.removed_static = self
+                .commands
+                .iter()
+                .map(|command| command.name.clone())
+                .collect();
+            state.added = commands
+                .into_iter()
+                .map(|mut command| {
+                    crate::set_qualified_names(std::slice::from_mut(&mut command));
+                    std::sync::Arc::new(command)
+                })
+                .collect();
+        });
+    }
+
+    /// Implementation of [`Self::remove_command`] on a copy of the runtime state
+    fn remove_command_from(&self, state: &mut RuntimeState<U, E>, name: &str) -> bool {
+        let len_before = state.added.len();
+        state.added.retain(|command| command.name != name);
+        let mut found = state.added.len() != len_before;
+        if self.commands.iter().any(|command| command.name == name) {
+            found |= state.removed_static.insert(name.to_owned());
+        }
+        found
+    }
+
+    /// Registers the [active commands](Self::active_commands) with Discord, replacing the
+    /// previously registered ones. Nothing is sent if they're already up to date. Call this after
+    /// adding or removing application commands at runtime.
+    ///
+    /// Registers at [`Self::auto_register`] if set, and globally otherwise.
+    pub async fn reregister_commands(
+        &self,
+        http: impl AsRef<serenity::Http>,
+    ) -> Result<(), serenity::Error> {
+        let builders =
+            crate::builtins::create_selected_application_commands(&self.active_commands());
+        self.auto_register
+            .as_ref()
+            .unwrap_or(&crate::builtins::RegisterTarget::Global)
+            .set_commands(http, builders)
+            .await
+    }
+}
@@ -11,6 +11,53 @@ pub enum CommandInteractionType {
     Autocomplete,
 }
 
+/// The kinds of application commands that a [`crate::Command`] can be registered as, see
+/// [`crate::Command::command_types`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum ApplicationCommandType {
+    /// Slash command, see [`crate::Command::slash_action`]
+    ChatInput,
+    /// Context menu command on users, see [`crate::Command::context_menu_action`]
+    User,
+    /// Context menu command on messages, see [`crate::Command::context_menu_action`]
+    Message,
+    /// The primary entry point of an app with Activities, see
+    /// [`crate::Command::entry_point_action`]
+    PrimaryEntryPoint,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl ApplicationCommandType {
+    /// Discord's value for primary entry point commands, which serenity doesn't know yet
+    const PRIMARY_ENTRY_POINT: u8 = 4;
+
+    /// Converts from serenity's type. Returns None for command types unknown to poise
+    pub fn from_serenity(kind: serenity::CommandType) -> Option<Self> {
+        Some(match kind {
+            serenity::CommandType::ChatInput => Self::ChatInput,
+            serenity::CommandType::User => Self::User,
+            serenity::CommandType::Message => Self::Message,
+            serenity::CommandType::Unknown(Self::PRIMARY_ENTRY_POINT) => Self::PrimaryEntryPoint,
+            _ => return None,
+        })
+    }
+}
+
+impl From<ApplicationCommandType> for serenity::CommandType {
+    fn from(kind: ApplicationCommandType) -> Self {
+        match kind {
+            ApplicationCommandType::ChatInput => Self::ChatInput,
+            ApplicationCommandType::User => Self::User,
+            ApplicationCommandType::Message => Self::Message,
+            ApplicationCommandType::PrimaryEntryPoint => {
+                Self::Unknown(ApplicationCommandType::PRIMARY_ENTRY_POINT)
+            }
+            ApplicationCommandType::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
 /// Application command specific context passed to command invocations.
 #[derive(derivative::Derivative)]
 #[derivative(Debug(bound = ""))]
@@ -28,7 +75,8 @@ pub struct ApplicationContext<'a, U, E> {
     /// top-level command arguments, whereas [`Self::args`] is the options of the actual
     /// subcommand, if any.
     pub args: &'a [serenity::ResolvedOption<'a>],
-    /// Keeps track of whether an initial response has been sent.
+    /// Keeps track of whether an initial response has been sent. Set right before the initial
+    /// response is sent, so that concurrent responses become followups.
     ///
     /// Discord requires different HTTP endpoints for initial and additional responses.
     pub has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
@@ -46,7 +94,7 @@ pub struct ApplicationContext<'a, U, E> {
     #[derivative(Debug = "ignore")]
     pub data: &'a U,
     /// Custom user data carried across a single command invocation
-    pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    pub invocation_data: &'a tokio::sync::Mutex<crate::InvocationData>,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -65,20 +113,24 @@ impl<U, E> crate::_GetGenerics for ApplicationContext<'_, U, E> {
 impl<U, E> ApplicationContext<'_, U, E> {
     /// See [`crate::Context::defer()`]
     pub async fn defer_response(&self, ephemeral: bool) -> Result<(), serenity::Error> {
+        // Set the flag before sending, so that concurrent responses can't race the deferral
         if !self
             .has_sent_initial_response
-            .load(std::sync::atomic::Ordering::SeqCst)
+            .swap(true, std::sync::atomic::Ordering::SeqCst)
         {
             let response = serenity::CreateInteractionResponse::Defer(
                 serenity::CreateInteractionResponseMessage::new().ephemeral(ephemeral),
             );
 
-            self.interaction
+            let result = self
+                .interaction
                 .create_response(self.serenity_context, response)
-                .await?;
-
-            self.has_sent_initial_response
-                .store(true, std::sync::atomic::Ordering::SeqCst);
+                .await;
+            if result.is_err() {
+                self.has_sent_initial_response
+                    .store(false, std::sync::atomic::Ordering::SeqCst);
+            }
+            result?;
         }
         Ok(())
     }
@@ -104,6 +156,14 @@ pub enum ContextMenuCommandAction<U, E> {
             serenity::Message,
         ) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>>,
     ),
+    /// Context menu entries on both users and messages, see [`crate::ContextMenuTarget`]
+    UserAndMessage(
+        #[derivative(Debug = "ignore")]
+        fn(
+            ApplicationContext<'_, U, E>,
+            crate::ContextMenuTarget,
+        ) -> BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>>,
+    ),
     #[doc(hidden)]
     __NonExhaustive,
 }
@@ -114,14 +174,61 @@ impl<U, E> Clone for ContextMenuCommandAction<U, E> {
     }
 }
 
+/// The value Discord sends when a choice is selected, see [`CommandParameterChoice::value`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(untagged)]
+pub enum ChoiceValue {
+    /// Integer value, for integer choice parameters
+    Integer(i32),
+    /// String value, for string choice parameters
+    String(String),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl std::fmt::Display for ChoiceValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(value) => value.fmt(f),
+            Self::String(value) => value.fmt(f),
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl From<i32> for ChoiceValue {
+    fn from(value: i32) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<String> for ChoiceValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for ChoiceValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
 /// A single drop-down choice in a slash command choice parameter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct CommandParameterChoice {
     /// Label of this choice
     pub name: String,
     /// Localized labels with locale string as the key (slash-only)
     pub localizations: std::collections::HashMap<String, String>,
+    /// Value that Discord sends when this choice is selected. If None, the index of this choice in
+    /// the choice list is used as an integer value.
+    ///
+    /// Explicit values stay the same when choices are reordered, which matters when the values
+    /// are stored somewhere. All choices of a parameter must use the same kind of value.
+    pub value: Option<ChoiceValue>,
     #[doc(hidden)]
+    #[serde(skip)]
     pub __non_exhaustive: (),
 }
 
@@ -141,10 +248,23 @@ pub struct CommandParameter<U, E> {
     pub required: bool,
     /// If this parameter is a channel, users can only enter these channel types in a slash command
     ///
-    /// Prefix commands are currently unaffected by this
+    /// Arguments of other channel types are rejected with
+    /// [`crate::FrameworkError::InvalidChannelType`]. For prefix commands, this is only checked for
+    /// parameter types with a known channel type, like [`serenity::GuildChannel`]
     pub channel_types: Option<Vec<serenity::ChannelType>>,
     /// If this parameter is a choice parameter, this is the fixed list of options
     pub choices: Vec<CommandParameterChoice>,
+    /// Minimum value of a number parameter
+    ///
+    /// Slash commands show this in the Discord UI. Arguments below it are rejected with
+    /// [`crate::FrameworkError::ParameterConstraintViolation`] for prefix commands too
+    pub min: Option<f64>,
+    /// Maximum value of a number parameter, see [`Self::min`]
+    pub max: Option<f64>,
+    /// Minimum length of a string parameter in characters, see [`Self::min`]
+    pub min_length: Option<u16>,
+    /// Maximum length of a string parameter in characters, see [`Self::min`]
+    pub max_length: Option<u16>,
     /// Closure that sets this parameter's type and min/max value in the given builder
     ///
     /// For example a u32 [`CommandParameter`] would store this as the [`Self::type_setter`]:
@@ -169,11 +289,21 @@ pub struct CommandParameter<U, E> {
             Result<serenity::CreateAutocompleteResponse, crate::SlashArgError>,
         >,
     >,
+    /// Applied to the builder at the end of [`Self::create_as_slash_command_option`]. Allows
+    /// setting option fields that poise doesn't model (yet)
+    #[derivative(Debug = "ignore")]
+    pub option_modifier: Option<fn(serenity::CreateCommandOption) -> serenity::CreateCommandOption>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
 impl<U, E> CommandParameter<U, E> {
+    /// Starts building a parameter with the given name at runtime, see
+    /// [`crate::CommandParameterBuilder`]
+    pub fn builder(name: impl Into<String>) -> crate::CommandParameterBuilder<U, E> {
+        crate::CommandParameterBuilder::new(name)
+    }
+
     /// Generates a slash command parameter builder from this [`CommandParameter`] instance. This
     /// can be used to register the command on Discord's servers
     pub fn create_as_slash_command_option(&self) -> Option<serenity::CreateCommandOption> {
@@ -202,10 +332,35 @@ impl<U, E> CommandParameter<U, E> {
             builder = builder.channel_types(channel_types);
         }
         for (i, choice) in self.choices.iter().enumerate() {
-            builder =
-                builder.add_int_choice_localized(&choice.name, i as _, choice.localizations.iter());
+            let localizations = choice.localizations.iter();
+            builder = match &choice.value {
+                None => builder.add_int_choice_localized(&choice.name, i as _, localizations),
+                Some(ChoiceValue::Integer(value)) => {
+                    builder.add_int_choice_localized(&choice.name, *value, localizations)
+                }
+                Some(ChoiceValue::String(value)) => {
+                    builder.add_string_choice_localized(&choice.name, value, localizations)
+                }
+                Some(ChoiceValue::__NonExhaustive) => unreachable!(),
+            };
         }
 
-        Some((self.type_setter?)(builder))
+        builder = (self.type_setter?)(builder);
+        if let Some(min) = self.min {
+            builder = builder.min_number_value(min);
+        }
+        if let Some(max) = self.max {
+            builder = builder.max_number_value(max);
+        }
+        if let Some(min_length) = self.min_length {
+            builder = builder.min_length(min_length);
+        }
+        if let Some(max_length) = self.max_length {
+            builder = builder.max_length(max_length);
+        }
+        if let Some(option_modifier) = self.option_modifier {
+            builder = option_modifier(builder);
+        }
+        Some(builder)
     }
 }
@@ -0,0 +1,234 @@
+//! Background tasks that run alongside the bot, like reminders or cache purges, see
+//! [`crate::FrameworkOptions::spawn_periodic`] and [`crate::FrameworkOptions::spawn_at`]
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// Context passed to background tasks, with access to the framework and the user data
+pub struct TaskContext<'a, U, E> {
+    /// Serenity's context, for example to send messages
+    pub serenity_context: &'a serenity::Context,
+    /// Framework data like options and user data
+    pub framework: crate::FrameworkContext<'a, U, E>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl<U, E> Copy for TaskContext<'_, U, E> {}
+impl<U, E> Clone for TaskContext<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, U, E> TaskContext<'a, U, E> {
+    /// Returns the user data
+    pub fn data(&self) -> &'a U {
+        self.framework.user_data
+    }
+
+    /// Returns [`crate::FrameworkOptions::shutdown_token`]. Long-running tasks should stop when
+    /// it's cancelled
+    pub fn shutdown_token(&self) -> &'a crate::CancellationToken {
+        &self.framework.options.shutdown_token
+    }
+}
+
+/// Action of a background task
+type TaskAction<U, E> =
+    Box<dyn for<'a> Fn(TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>> + Send + Sync>;
+
+/// A background task waiting to be run by [`run_background_tasks`]
+struct BackgroundTask<U, E> {
+    /// When to run the task
+    schedule: Box<dyn crate::Schedule>,
+    /// Whether to stop after the first run
+    once: bool,
+    /// The code to run
+    action: TaskAction<U, E>,
+}
+
+/// [`crate::Schedule`] of a task that runs once at the given point in time
+struct At(serenity::Timestamp);
+
+impl crate::Schedule for At {
+    fn next_delay(&self) -> Option<std::time::Duration> {
+        let remaining = self.0.unix_timestamp() - serenity::Timestamp::now().unix_timestamp();
+        Some(std::time::Duration::from_secs(
+            remaining.try_into().unwrap_or(0),
+        ))
+    }
+}
+
+/// Background tasks that were spawned but aren't running yet, stored in
+/// [`crate::FrameworkOptions::background_tasks`].
+///
+/// You don't need to interact with this type directly; use the methods on
+/// [`crate::FrameworkOptions`] or [`crate::Framework`] instead.
+pub struct BackgroundTasks<U, E> {
+    /// Sends spawned tasks to [`run_background_tasks`]
+    sender: tokio::sync::mpsc::UnboundedSender<BackgroundTask<U, E>>,
+    /// Taken by [`run_background_tasks`]
+    receiver:
+        parking_lot::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<BackgroundTask<U, E>>>>,
+}
+
+impl<U, E> Default for BackgroundTasks<U, E> {
+    fn default() -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver: parking_lot::Mutex::new(Some(receiver)),
+        }
+    }
+}
+
+impl<U, E> crate::FrameworkOptions<U, E> {
+    /// Runs the given action every `interval`, starting one interval after the bot is ready (or
+    /// after this call, if the bot is already running).
+    ///
+    /// Errors are forwarded to [`Self::on_error`] as [`crate::FrameworkError::BackgroundTask`].
+    /// The task stops once [`Self::shutdown_token`] is cancelled, which happens when the
+    /// [`crate::Framework`] is dropped; a run that's in progress is completed first.
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// let options = poise::FrameworkOptions::<(), Error>::default();
+    /// options.spawn_periodic(std::time::Duration::from_secs(60 * 60), |ctx| {
+    ///     Box::pin(async move {
+    ///         println!("{} is still running", ctx.framework.bot_id);
+    ///         Ok(())
+    ///     })
+    /// });
+    /// ```
+    pub fn spawn_periodic<F>(&self, interval: std::time::Duration, action: F)
+    where
+        F: for<'a> Fn(TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.spawn_task(Box::new(interval), false, Box::new(action));
+    }
+
+    /// Runs the given action once at the given point in time, with second precision. Points in
+    /// the past run as soon as the bot is ready.
+    ///
+    /// Like [`Self::spawn_periodic`], errors are forwarded to [`Self::on_error`] and the task is
+    /// dropped without running if [`Self::shutdown_token`] is cancelled before.
+    pub fn spawn_at<F>(&self, when: impl Into<serenity::Timestamp>, action: F)
+    where
+        F: for<'a> Fn(TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.spawn_task(Box::new(At(when.into())), true, Box::new(action));
+    }
+
+    /// Runs the given action according to a [`crate::Schedule`], like [`Self::spawn_periodic`]
+    pub(crate) fn spawn_with_schedule<F>(&self, schedule: Box<dyn crate::Schedule>, action: F)
+    where
+        F: for<'a> Fn(TaskContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.spawn_task(schedule, false, Box::new(action));
+    }
+
+    /// Queues a task for [`run_background_tasks`]
+    fn spawn_task(&self, schedule: Box<dyn crate::Schedule>, once: bool, action: TaskAction<U, E>) {
+        let task = BackgroundTask {
+            schedule,
+            once,
+            action,
+        };
+        if self.background_tasks.sender.send(task).is_err() {
+            tracing::warn!("background task spawned after the task runner stopped");
+        }
+    }
+}
+
+/// Runs the tasks spawned via [`crate::FrameworkOptions::spawn_periodic`] and
+/// [`crate::FrameworkOptions::spawn_at`], including tasks spawned while this is running. The
+/// returned future completes once [`crate::FrameworkOptions::shutdown_token`] is cancelled and
+/// all task runs in progress have finished.
+///
+/// [`crate::Framework`] calls this automatically after the first Ready event. If you're doing
+/// dispatch manually, spawn or await this function yourself. Only the first call runs the tasks.
+pub async fn run_background_tasks<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+) {
+    let Some(mut receiver) = framework.options.background_tasks.receiver.lock().take() else {
+        tracing::warn!("background tasks are already being run");
+        return;
+    };
+
+    let cancelled = framework.options.shutdown_token.cancelled();
+    futures_util::pin_mut!(cancelled);
+    // Stops accepting new tasks on shutdown, while letting the running ones wind down
+    let tasks = futures_util::stream::poll_fn(|cx| {
+        if std::future::Future::poll(cancelled.as_mut(), cx).is_ready() {
+            return std::task::Poll::Ready(None);
+        }
+        receiver.poll_recv(cx)
+    });
+    futures_util::StreamExt::for_each_concurrent(tasks, None, |task| {
+        run_background_task(framework, ctx, task)
+    })
+    .await;
+}
+
+/// Runs a single task according to its schedule until it's exhausted or the framework shuts down
+async fn run_background_task<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    task: BackgroundTask<U, E>,
+) {
+    let Some(delay) = task.schedule.next_delay() else {
+        return;
+    };
+    let mut deadline = tokio::time::Instant::now() + delay;
+    loop {
+        let sleep = tokio::time::sleep_until(deadline);
+        let cancelled = framework.options.shutdown_token.cancelled();
+        futures_util::pin_mut!(sleep, cancelled);
+        if let futures_util::future::Either::Right(_) =
+            futures_util::future::select(sleep, cancelled).await
+        {
+            break;
+        }
+
+        // Measure the next delay from the start of this run, so that the time spent running the
+        // task doesn't accumulate as drift
+        let started = tokio::time::Instant::now();
+        let next_delay = task.schedule.next_delay();
+
+        // The framework context of the task runner lives forever, so take a fresh snapshot of the
+        // commands for every run
+        let commands = framework.options.active_commands();
+        let framework = crate::FrameworkContext {
+            commands: &commands,
+            ..framework
+        };
+        let task_ctx = TaskContext {
+            serenity_context: ctx,
+            framework,
+            __non_exhaustive: (),
+        };
+        if let Err(error) = (task.action)(task_ctx).await {
+            let error = crate::FrameworkError::BackgroundTask {
+                error,
+                ctx,
+                framework,
+            };
+            (framework.options.on_error)(error).await;
+        }
+
+        match next_delay {
+            Some(delay) if !task.once => deadline = started + delay,
+            _ => break,
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Unit-testing commands without connecting to Discord, see [`MockContext`]
+
+use crate::serenity_prelude as serenity;
+use std::sync::{Arc, Mutex};
+
+/// Replies captured from the invocations of a [`MockContext`]
+type ReplyStore = Arc<Mutex<Vec<crate::CreateReply>>>;
+
+/// Key under which a [`MockContext`] stores its [`ReplyStore`] in the serenity context's data, so
+/// that [`crate::send_reply`] can find it
+struct CapturedReplies;
+
+impl serenity::prelude::TypeMapKey for CapturedReplies {
+    type Value = ReplyStore;
+}
+
+/// Returns where to store replies instead of sending them, if `ctx` belongs to a [`MockContext`]
+pub(crate) async fn reply_store(ctx: &serenity::Context) -> Option<ReplyStore> {
+    ctx.data.read().await.get::<CapturedReplies>().cloned()
+}
+
+/// Records the errors of failed invocations, as their [`std::fmt::Display`] output
+struct ErrorRecorder(Arc<Mutex<Vec<String>>>);
+
+impl<U, E: std::fmt::Display> crate::InvocationObserver<U, E> for ErrorRecorder {
+    fn on_failed(&self, error: &crate::FrameworkError<'_, U, E>) {
+        self.0.lock().unwrap().push(error.to_string());
+    }
+
+    fn on_skipped_by_check(&self, error: &crate::FrameworkError<'_, U, E>) {
+        self.0.lock().unwrap().push(error.to_string());
+    }
+}
+
+/// What happened during an invocation dispatched via [`MockContext`]
+#[derive(Clone, Default)]
+pub struct MockResponse {
+    /// Replies sent via [`crate::send_reply`] and its shorthands like [`crate::Context::say`],
+    /// in the order they were sent. Includes replies sent by the error handler
+    pub replies: Vec<crate::CreateReply>,
+    /// Errors the invocation failed with, including check failures, as their
+    /// [`std::fmt::Display`] output. The errors were passed to the error handler as usual
+    pub errors: Vec<String>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl MockResponse {
+    /// Returns the text content of all [`Self::replies`], with replies without content skipped
+    pub fn contents(&self) -> Vec<&str> {
+        self.replies
+            .iter()
+            .filter_map(|reply| reply.content.as_deref())
+            .collect()
+    }
+}
+
+/// Dispatches prefix and slash command invocations to your commands without a Discord
+/// connection, for use in `#[tokio::test]`s.
+///
+/// Replies sent via [`crate::send_reply`] and its shorthands are captured into a
+/// [`MockResponse`] instead of being sent. Any other request to Discord, for example
+/// [`crate::Context::defer`] or editing a reply, fails with a connection error because the HTTP
+/// client points to a closed local port. Likewise, the cache stays empty, so guild data like
+/// members and roles isn't available.
+///
+/// Requires the `testing` feature, and a Tokio runtime with the `net` and `rt` features. Not
+/// compatible with serenity's `voice` feature.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// #[poise::command(prefix_command, slash_command)]
+/// async fn add(ctx: poise::Context<'_, (), Error>, a: i64, b: i64) -> Result<(), Error> {
+///     ctx.say((a + b).to_string()).await?;
+///     Ok(())
+/// }
+///
+/// # #[tokio::main] async fn main() -> Result<(), Error> {
+/// let options = poise::FrameworkOptions {
+///     commands: vec![add()],
+///     prefix_options: poise::PrefixFrameworkOptions {
+///         prefix: Some("~".into()),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// let mock = poise::testing::MockContext::new(options, ()).await?;
+///
+/// let response = mock.dispatch_message("~add 1 2").await;
+/// assert_eq!(response.contents(), ["3"]);
+///
+/// let response = mock
+///     .dispatch_slash("add", &[("a", 1.into()), ("b", 2.into())])
+///     .await;
+/// assert_eq!(response.contents(), ["3"]);
+///
+/// let response = mock.dispatch_message("~add 1 two").await;
+/// assert_eq!(response.errors.len(), 1);
+/// # Ok(()) }
+/// ```
+pub struct MockContext<U, E> {
+    /// Serenity context backed by the mock gateway connection and HTTP client
+    serenity_context: serenity::Context,
+    /// Shard manager that isn't managing any shards
+    shard_manager: Arc<serenity::ShardManager>,
+    /// Framework options, including the commands under test
+    options: crate::FrameworkOptions<U, E>,
+    /// User data passed to the commands
+    data: U,
+    /// User ID of the mock bot
+    bot_id: serenity::UserId,
+    /// Author of dispatched invocations
+    author: serenity::User,
+    /// Channel of dispatched invocations
+    channel_id: serenity::ChannelId,
+    /// Guild of dispatched invocations, None for DMs
+    guild_id: Option<serenity::GuildId>,
+    /// Locale of dispatched interactions
+    locale: String,
+    /// Replies captured since the last dispatch
+    replies: ReplyStore,
+    /// Errors recorded since the last dispatch
+    errors: Arc<Mutex<Vec<String>>>,
+    /// Source of message and interaction IDs
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+impl<U: Send + Sync, E: std::fmt::Display> MockContext<U, E> {
+    /// Creates a mock environment running the given framework options. Invocations are made by
+    /// user 2 in DM channel 3, and the bot has ID 1; use the setters to change this.
+    pub async fn new(
+        mut options: crate::FrameworkOptions<U, E>,
+        data: U,
+    ) -> Result<Self, serenity::Error> {
+        let replies = ReplyStore::default();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        options
+            .invocation_observers
+            .push(Box::new(ErrorRecorder(errors.clone())));
+        crate::set_qualified_names(&mut options.commands);
+
+        let mut type_map = serenity::prelude::TypeMap::new();
+        type_map.insert::<CapturedReplies>(replies.clone());
+        let type_map = Arc::new(serenity::prelude::RwLock::new(type_map));
+
+        // Requests to Discord are sent to a port that nobody listens on
+        let closed_port = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await?
+            .local_addr()?
+            .port();
+        let http = Arc::new(
+            serenity::HttpBuilder::new("mock")
+                .proxy(format!("http://127.0.0.1:{}", closed_port))
+                .ratelimiter_disabled(true)
+                .application_id(serenity::ApplicationId::new(1))
+                .build(),
+        );
+        #[cfg(feature = "cache")]
+        let cache = Arc::new(serenity::Cache::new());
+
+        let ws_url = Arc::new(serenity::prelude::Mutex::new(mock_gateway().await?));
+        let (shard_manager, _) = serenity::ShardManager::new(serenity::ShardManagerOptions {
+            data: type_map.clone(),
+            event_handlers: Vec::new(),
+            raw_event_handlers: Vec::new(),
+            framework: Arc::new(std::sync::OnceLock::new()),
+            shard_index: 0,
+            shard_init: 0,
+            shard_total: 1,
+            ws_url: ws_url.clone(),
+            #[cfg(feature = "cache")]
+            cache: cache.clone(),
+            http: http.clone(),
+            intents: serenity::GatewayIntents::empty(),
+            presence: None,
+        });
+        let shard = serenity::Shard::new(
+            ws_url,
+            "mock",
+            serenity::ShardInfo {
+                id: serenity::ShardId(0),
+                total: 1,
+            },
+            serenity::GatewayIntents::empty(),
+            None,
+        )
+        .await?;
+        // The runner is never started; it's only needed to create the shard messenger
+        let runner = serenity::ShardRunner::new(serenity::ShardRunnerOptions {
+            data: type_map.clone(),
+            event_handlers: Vec::new(),
+            raw_event_handlers: Vec::new(),
+            framework: None,
+            manager: shard_manager.clone(),
+            shard,
+            #[cfg(feature = "cache")]
+            cache: cache.clone(),
+            http: http.clone(),
+        });
+
+        let serenity_context = serenity::Context {
+            data: type_map,
+            shard: serenity::ShardMessenger::new(&runner),
+            shard_id: serenity::ShardId(0),
+            http,
+            #[cfg(feature = "cache")]
+            cache,
+        };
+
+        let mut author = serenity::User::default();
+        author.id = serenity::UserId::new(2);
+        author.name = "tester".into();
+
+        Ok(Self {
+            serenity_context,
+            shard_manager,
+            options,
+            data,
+            bot_id: serenity::UserId::new(1),
+            author,
+            channel_id: serenity::ChannelId::new(3),
+            guild_id: None,
+            locale: "en-US".into(),
+            replies,
+            errors,
+            next_id: std::sync::atomic::AtomicU64::new(1000),
+        })
+    }
+
+    /// Sets the author of dispatched invocations
+    pub fn author(mut self, author: serenity::User) -> Self {
+        self.author = author;
+        self
+    }
+
+    /// Sets the channel of dispatched invocations
+    pub fn channel_id(mut self, channel_id: serenity::ChannelId) -> Self {
+        self.channel_id = channel_id;
+        self
+    }
+
+    /// Sets the guild of dispatched invocations. None (the default) means DMs
+    pub fn guild_id(mut self, guild_id: Option<serenity::GuildId>) -> Self {
+        self.guild_id = guild_id;
+        self
+    }
+
+    /// Sets the locale of dispatched interactions. `en-US` by default
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    /// Returns the mock serenity context, for example to build your own events
+    pub fn serenity_context(&self) -> &serenity::Context {
+        &self.serenity_context
+    }
+
+    /// Returns the framework options of the mock environment, for example to add commands at
+    /// runtime
+    pub fn options(&self) -> &crate::FrameworkOptions<U, E> {
+        &self.options
+    }
+
+    /// Returns a framework context for the mock environment, for example to call
+    /// [`crate::dispatch_event`] directly. Take the command snapshot with
+    /// `mock.options().active_commands()`
+    pub fn framework<'a>(
+        &'a self,
+        commands: &'a crate::ActiveCommands<'a, U, E>,
+    ) -> crate::FrameworkContext<'a, U, E> {
+        crate::FrameworkContext::new(
+            self.bot_id,
+            &self.options,
+            commands,
+            &self.data,
+            &self.shard_manager,
+        )
+    }
+
+    /// Returns a fresh snowflake for messages and interactions
+    fn next_id(&self) -> u64 {
+        self.next_id
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Dispatches a message with the given content, which must include the prefix, and returns
+    /// the replies and errors of the resulting invocation
+    pub async fn dispatch_message(&self, content: &str) -> MockResponse {
+        let mut msg = serenity::CustomMessage::new();
+        msg.id(serenity::MessageId::new(self.next_id()))
+            .channel_id(self.channel_id)
+            .author(self.author.clone())
+            .content(content)
+            .timestamp(serenity::Timestamp::now());
+        if let Some(guild_id) = self.guild_id {
+            msg.guild_id(guild_id);
+        }
+        let new_message = msg.build();
+
+        self.dispatch_event(serenity::FullEvent::Message { new_message })
+            .await
+    }
+
+    /// Builds a slash command interaction. `command` is the full command name, including
+    /// subcommands (`"config set"`). Option values can be strings, integers, numbers and
+    /// booleans; for other option types, build the interaction yourself and use
+    /// [`Self::dispatch_interaction`].
+    pub fn slash_interaction(
+        &self,
+        command: &str,
+        options: &[(&str, serenity::json::Value)],
+    ) -> serenity::CommandInteraction {
+        use serenity::json::json;
+
+        let mut options = options
+            .iter()
+            .map(|(name, value)| {
+                let kind = match value {
+                    serenity::json::Value::Bool(_) => serenity::CommandOptionType::Boolean,
+                    serenity::json::Value::Number(n) if n.is_i64() || n.is_u64() => {
+                        serenity::CommandOptionType::Integer
+                    }
+                    serenity::json::Value::Number(_) => serenity::CommandOptionType::Number,
+                    _ => serenity::CommandOptionType::String,
+                };
+                json!({ "name": name, "type": u8::from(kind), "value": value })
+            })
+            .collect::<Vec<_>>();
+
+        // Nest the options into the subcommand and subcommand group options
+        let mut names = command.split_whitespace().collect::<Vec<_>>();
+        let name = if names.is_empty() {
+            ""
+        } else {
+            names.remove(0)
+        };
+        for (i, subcommand) in names.iter().enumerate().rev() {
+            let kind = match i == names.len() - 1 {
+                true => serenity::CommandOptionType::SubCommand,
+                false => serenity::CommandOptionType::SubCommandGroup,
+            };
+            options = vec![json!({
+                "name": subcommand,
+                "type": u8::from(kind),
+                "options": options,
+            })];
+        }
+
+        let mut user = serenity::json::to_value(&self.author).unwrap_or_default();
+        if let Some(user) = user.as_object_mut() {
+            // Serialized users lack the discriminator if it's unset, which doesn't round-trip
+            user.entry("discriminator").or_insert_with(|| json!("0"));
+        }
+        let interaction = json!({
+            "id": self.next_id().to_string(),
+            "application_id": "1",
+            "type": 2,
+            "data": {
+                "id": "1",
+                "name": name,
+                "type": 1,
+                "options": options,
+            },
+            "guild_id": self.guild_id.map(|id| id.to_string()),
+            "channel_id": self.channel_id.to_string(),
+            "user": user,
+            "token": "mock",
+            "version": 1,
+            "locale": self.locale,
+            "entitlements": [],
+            "attachment_size_limit": 10 * 1024 * 1024,
+        });
+        serenity::json::from_value(interaction)
+            .expect("mock interaction should be a valid command interaction")
+    }
+
+    /// Dispatches a slash command invocation, see [`Self::slash_interaction`], and returns the
+    /// replies and errors of the invocation
+    pub async fn dispatch_slash(
+        &self,
+        command: &str,
+        options: &[(&str, serenity::json::Value)],
+    ) -> MockResponse {
+        let interaction = self.slash_interaction(command, options);
+        self.dispatch_interaction(interaction).await
+    }
+
+    /// Dispatches the given command interaction and returns the replies and errors of the
+    /// invocation
+    pub async fn dispatch_interaction(
+        &self,
+        interaction: serenity::CommandInteraction,
+    ) -> MockResponse {
+        let interaction = serenity::Interaction::Command(interaction);
+        self.dispatch_event(serenity::FullEvent::InteractionCreate { interaction })
+            .await
+    }
+
+    /// Dispatches the given event and returns the replies and errors of the resulting
+    /// invocations
+    pub async fn dispatch_event(&self, event: serenity::FullEvent) -> MockResponse {
+        let commands = self.options.active_commands();
+        // Boxed, because the dispatch future is too large for the stack of test threads in debug
+        // builds
+        Box::pin(crate::dispatch_event(
+            self.framework(&commands),
+            &self.serenity_context,
+            event,
+        ))
+        .await;
+        MockResponse {
+            replies: std::mem::take(&mut *self.replies.lock().unwrap()),
+            errors: std::mem::take(&mut *self.errors.lock().unwrap()),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Starts a local WebSocket server that accepts one connection and ignores everything sent over
+/// it. Serenity can't create a [`serenity::ShardMessenger`] without a connected shard
+async fn mock_gateway() -> Result<String, serenity::Error> {
+    use futures_util::StreamExt as _;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let Ok((stream, _)) = listener.accept().await else {
+            return;
+        };
+        let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+            return;
+        };
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+    Ok(url)
+}
@@ -47,26 +47,103 @@ fn update_message(message: &mut serenity::Message, update: serenity::MessageUpda
     // }
 }
 
-/// A single cached command invocation
-#[derive(Debug)]
-struct CachedInvocation {
+/// A single tracked command invocation, as stored in an [`EditTrackerStore`]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TrackedInvocation {
     /// User message that triggered this command invocation
-    user_msg: serenity::Message,
+    pub user_msg: serenity::Message,
     /// Associated bot response of this command invocation
-    bot_response: Option<serenity::Message>,
+    pub bot_response: Option<serenity::Message>,
     /// Whether the bot response should be deleted when the user deletes their message
-    track_deletion: bool,
+    pub track_deletion: bool,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub __non_exhaustive: (),
+}
+
+impl TrackedInvocation {
+    /// Whether the user message was last sent or edited longer than `max_age` ago
+    pub fn is_expired(&self, max_age: std::time::Duration) -> bool {
+        let last_update = self
+            .user_msg
+            .edited_timestamp
+            .unwrap_or(self.user_msg.timestamp);
+        let age = serenity::Timestamp::now().unix_timestamp() - last_update.unix_timestamp();
+        age >= max_age.as_secs() as i64
+    }
+}
+
+/// Storage backend of an [`EditTracker`].
+///
+/// The default, [`InMemoryEditTrackerStore`], forgets all invocations when the bot restarts.
+/// Implement this trait on top of a database to keep edit tracking and deletion tracking working
+/// across restarts for recently invoked commands. [`TrackedInvocation`] implements
+/// [`serde::Serialize`] and [`serde::Deserialize`] for this purpose.
+///
+/// The methods are called while the edit tracker is locked, so they should be quick; use a local
+/// database like SQLite or a synchronous client with a short timeout.
+pub trait EditTrackerStore: Send + Sync {
+    /// Returns the invocation triggered by the given user message, if tracked
+    fn get(&self, user_msg_id: serenity::MessageId) -> Option<TrackedInvocation>;
+    /// Stores the invocation, replacing any invocation with the same user message ID
+    fn insert(&mut self, invocation: TrackedInvocation);
+    /// Removes and returns the invocation triggered by the given user message, if tracked
+    fn remove(&mut self, user_msg_id: serenity::MessageId) -> Option<TrackedInvocation>;
+    /// Removes all invocations that are expired, see [`TrackedInvocation::is_expired`]
+    fn purge(&mut self, max_age: std::time::Duration);
+}
+
+/// [`EditTrackerStore`] that keeps invocations in memory. Used by [`EditTracker::for_timespan`]
+#[derive(Debug, Default)]
+pub struct InMemoryEditTrackerStore {
+    /// Tracked invocations, in the order they were first tracked
+    // TODO: change to `OrderedMap<MessageId, TrackedInvocation>`?
+    invocations: Vec<TrackedInvocation>,
+}
+
+impl EditTrackerStore for InMemoryEditTrackerStore {
+    fn get(&self, user_msg_id: serenity::MessageId) -> Option<TrackedInvocation> {
+        self.invocations
+            .iter()
+            .find(|invocation| invocation.user_msg.id == user_msg_id)
+            .cloned()
+    }
+
+    fn insert(&mut self, invocation: TrackedInvocation) {
+        match self
+            .invocations
+            .iter_mut()
+            .find(|existing| existing.user_msg.id == invocation.user_msg.id)
+        {
+            Some(existing) => *existing = invocation,
+            None => self.invocations.push(invocation),
+        }
+    }
+
+    fn remove(&mut self, user_msg_id: serenity::MessageId) -> Option<TrackedInvocation> {
+        let index = self
+            .invocations
+            .iter()
+            .position(|invocation| invocation.user_msg.id == user_msg_id)?;
+        Some(self.invocations.remove(index))
+    }
+
+    fn purge(&mut self, max_age: std::time::Duration) {
+        self.invocations
+            .retain(|invocation| !invocation.is_expired(max_age));
+    }
 }
 
 /// Stores messages and the associated bot responses in order to implement poise's edit tracking
 /// feature.
-#[derive(Debug)]
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
 pub struct EditTracker {
     /// Duration after which cached messages can be purged
     max_duration: std::time::Duration,
-    /// Cache, which stores invocation messages, and the corresponding bot response message if any
-    // TODO: change to `OrderedMap<MessageId, (Message, Option<serenity::Message>)>`?
-    cache: Vec<CachedInvocation>,
+    /// Stores invocation messages, and the corresponding bot response message if any
+    #[derivative(Debug = "ignore")]
+    store: Box<dyn EditTrackerStore>,
 }
 
 impl EditTracker {
@@ -76,9 +153,18 @@ impl EditTracker {
     /// is called. If you supply the created [`EditTracker`] to [`crate::Framework`], the framework
     /// will take care of that by calling [`Self::purge`] periodically.
     pub fn for_timespan(duration: std::time::Duration) -> std::sync::RwLock<Self> {
+        Self::with_store(duration, InMemoryEditTrackerStore::default())
+    }
+
+    /// Like [`Self::for_timespan`], but stores the tracked invocations in the given
+    /// [`EditTrackerStore`], e.g. to keep tracking edits across bot restarts
+    pub fn with_store(
+        duration: std::time::Duration,
+        store: impl EditTrackerStore + 'static,
+    ) -> std::sync::RwLock<Self> {
         std::sync::RwLock::new(Self {
             max_duration: duration,
-            cache: Vec::new(),
+            store: Box::new(store),
         })
     }
 
@@ -91,12 +177,8 @@ impl EditTracker {
         user_msg_update: &serenity::MessageUpdateEvent,
         ignore_edits_if_not_yet_responded: bool,
     ) -> Option<(serenity::Message, bool)> {
-        match self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == user_msg_update.id)
-        {
-            Some(invocation) => {
+        match self.store.get(user_msg_update.id) {
+            Some(mut invocation) => {
                 if ignore_edits_if_not_yet_responded && invocation.bot_response.is_none() {
                     return None;
                 }
@@ -111,7 +193,9 @@ impl EditTracker {
                 }
 
                 update_message(&mut invocation.user_msg, user_msg_update.clone());
-                Some((invocation.user_msg.clone(), true))
+                let user_msg = invocation.user_msg.clone();
+                self.store.insert(invocation);
+                Some((user_msg, true))
             }
             None => {
                 if ignore_edits_if_not_yet_responded {
@@ -131,11 +215,7 @@ impl EditTracker {
         &mut self,
         deleted_message_id: serenity::MessageId,
     ) -> Option<serenity::Message> {
-        let invocation = self.cache.remove(
-            self.cache
-                .iter()
-                .position(|invocation| invocation.user_msg.id == deleted_message_id)?,
-        );
+        let invocation = self.store.remove(deleted_message_id)?;
         if invocation.track_deletion {
             invocation.bot_response
         } else {
@@ -145,27 +225,12 @@ impl EditTracker {
 
     /// Forget all of the messages that are older than the specified duration.
     pub fn purge(&mut self) {
-        let max_duration = self.max_duration;
-        self.cache.retain(|invocation| {
-            let last_update = invocation
-                .user_msg
-                .edited_timestamp
-                .unwrap_or(invocation.user_msg.timestamp);
-            let age = serenity::Timestamp::now().unix_timestamp() - last_update.unix_timestamp();
-            age < max_duration.as_secs() as i64
-        });
+        self.store.purge(self.max_duration);
     }
 
     /// Given a message by a user, find the corresponding bot response, if one exists and is cached.
-    pub fn find_bot_response(
-        &self,
-        user_msg_id: serenity::MessageId,
-    ) -> Option<&serenity::Message> {
-        let invocation = self
-            .cache
-            .iter()
-            .find(|invocation| invocation.user_msg.id == user_msg_id)?;
-        invocation.bot_response.as_ref()
+    pub fn find_bot_response(&self, user_msg_id: serenity::MessageId) -> Option<serenity::Message> {
+        self.store.get(user_msg_id)?.bot_response
     }
 
     /// Notify the [`EditTracker`] that the given user message should be associated with the given
@@ -176,34 +241,31 @@ impl EditTracker {
         bot_response: serenity::Message,
         track_deletion: bool,
     ) {
-        if let Some(invocation) = self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == user_msg.id)
-        {
-            invocation.bot_response = Some(bot_response);
-        } else {
-            self.cache.push(CachedInvocation {
+        let invocation = match self.store.get(user_msg.id) {
+            Some(invocation) => TrackedInvocation {
+                bot_response: Some(bot_response),
+                ..invocation
+            },
+            None => TrackedInvocation {
                 user_msg: user_msg.clone(),
                 bot_response: Some(bot_response),
                 track_deletion,
-            });
-        }
+                __non_exhaustive: (),
+            },
+        };
+        self.store.insert(invocation);
     }
 
     /// Store that this command is currently running; so that if the command is editing its own
     /// invocation message (e.g. removing embeds), we don't accidentally treat it as an
     /// `execute_untracked_edits` situation and start an infinite loop
     pub fn track_command(&mut self, user_msg: &serenity::Message, track_deletion: bool) {
-        if !self
-            .cache
-            .iter()
-            .any(|invocation| invocation.user_msg.id == user_msg.id)
-        {
-            self.cache.push(CachedInvocation {
+        if self.store.get(user_msg.id).is_none() {
+            self.store.insert(TrackedInvocation {
                 user_msg: user_msg.clone(),
                 bot_response: None,
                 track_deletion,
+                __non_exhaustive: (),
             });
         }
     }
@@ -0,0 +1,542 @@
+//! Parameter types for human-readable durations and points in time, see [`Duration`] and
+//! [`Timestamp`]
+
+use crate::serenity_prelude as serenity;
+
+/// Seconds per day, used to compute times of day in UTC
+const SECONDS_PER_DAY: i64 = 60 * 60 * 24;
+
+/// Units accepted in durations, with their length in seconds
+const DURATION_UNITS: &[(&[&str], u64)] = &[
+    (&["s", "sec", "secs", "second", "seconds"], 1),
+    (&["m", "min", "mins", "minute", "minutes"], 60),
+    (&["h", "hr", "hrs", "hour", "hours"], 60 * 60),
+    (&["d", "day", "days"], 60 * 60 * 24),
+    (&["w", "week", "weeks"], 60 * 60 * 24 * 7),
+];
+
+/// Durations suggested by [`autocomplete_duration`], as pairs of label and value
+const DURATION_SUGGESTIONS: &[(&str, &str)] = &[
+    ("1 minute", "1m"),
+    ("5 minutes", "5m"),
+    ("10 minutes", "10m"),
+    ("30 minutes", "30m"),
+    ("1 hour", "1h"),
+    ("6 hours", "6h"),
+    ("12 hours", "12h"),
+    ("1 day", "1d"),
+    ("3 days", "3d"),
+    ("1 week", "1w"),
+];
+
+/// Error thrown when parsing a malformed [`Duration`]
+#[derive(Debug, Clone)]
+pub struct DurationParseError {
+    /// What's wrong with the input
+    reason: String,
+}
+impl std::fmt::Display for DurationParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid duration: {} (try something like `10m`, `1h30m` or `2 days`)",
+            self.reason
+        )
+    }
+}
+impl std::error::Error for DurationParseError {}
+
+/// Error thrown when parsing a malformed [`Timestamp`]
+#[derive(Debug, Clone)]
+pub struct TimestampParseError {
+    /// What's wrong with the input
+    reason: String,
+}
+impl std::fmt::Display for TimestampParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid time: {} (try something like `in 2h`, `tomorrow 6pm` or a Discord timestamp)",
+            self.reason
+        )
+    }
+}
+impl std::error::Error for TimestampParseError {}
+
+/// A command parameter type for human-readable durations like `10m`, `1h30m`, `1.5 hours` or
+/// `2d 12h`.
+///
+/// Supported units are seconds (`s`), minutes (`m`), hours (`h`), days (`d`) and weeks (`w`), as
+/// well as their long forms. In prefix commands, the duration may span multiple words, as in
+/// `2 days 12 hours`.
+///
+/// For slash commands, the parameter is a string option. Add
+/// `#[autocomplete = "poise::types::autocomplete_duration"]` to suggest common durations.
+///
+/// ```rust
+/// let duration: poise::types::Duration = "1h 30m".parse().unwrap();
+/// assert_eq!(*duration, std::time::Duration::from_secs(90 * 60));
+/// assert_eq!(duration.to_string(), "1h30m");
+/// assert!("30".parse::<poise::types::Duration>().is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(pub std::time::Duration);
+
+impl std::ops::Deref for Duration {
+    type Target = std::time::Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl std::str::FromStr for Duration {
+    type Err = DurationParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| DurationParseError { reason };
+
+        let mut rest = input.trim();
+        if rest.is_empty() {
+            return Err(error("empty input".into()));
+        }
+
+        let mut seconds = 0.0;
+        while !rest.is_empty() {
+            let number_end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let (number, after_number) = rest.split_at(number_end);
+            let number = number
+                .parse::<f64>()
+                .map_err(|_| error(format!("expected a number at `{}`", rest)))?;
+
+            let after_number = after_number.trim_start();
+            let unit_end = after_number
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(after_number.len());
+            let (unit, after_unit) = after_number.split_at(unit_end);
+            if unit.is_empty() {
+                return Err(error(format!("missing unit after `{}`", number)));
+            }
+            let unit_seconds = DURATION_UNITS
+                .iter()
+                .find(|(names, _)| names.contains(&unit.to_lowercase().as_str()))
+                .map(|&(_, seconds)| seconds)
+                .ok_or_else(|| error(format!("unknown unit `{}`", unit)))?;
+
+            seconds += number * unit_seconds as f64;
+            rest = after_unit.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+
+        std::time::Duration::try_from_secs_f64(seconds)
+            .map(Self)
+            .map_err(|_| error("too long".into()))
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut seconds = self.0.as_secs();
+        if seconds == 0 {
+            return f.write_str("0s");
+        }
+        for &(names, unit_seconds) in DURATION_UNITS.iter().rev() {
+            if seconds >= unit_seconds {
+                write!(f, "{}{}", seconds / unit_seconds, names[0])?;
+                seconds %= unit_seconds;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A command parameter type for points in time like `in 2h`, `tomorrow 6pm`, `18:30`, `now`, Unix
+/// timestamps, RFC 3339 dates or Discord timestamp mentions (`<t:1700000000:R>`).
+///
+/// Durations like `2h` or `in 2h` are relative to now, and `2h ago` lies in the past. Times of day
+/// are interpreted in UTC. A time of day without `today` or `tomorrow` refers to its next
+/// occurrence.
+///
+/// For slash commands, the parameter is a string option. In prefix commands, multi-word inputs
+/// like `tomorrow 6pm` or `in 2 hours` don't need to be quoted.
+///
+/// ```rust
+/// let timestamp: poise::types::Timestamp = "<t:1700000000:R>".parse().unwrap();
+/// assert_eq!(timestamp.unix_timestamp(), 1700000000);
+/// assert_eq!(timestamp.to_string(), "<t:1700000000:F>");
+///
+/// let now = poise::serenity_prelude::Timestamp::now().unix_timestamp();
+/// let in_two_hours: poise::types::Timestamp = "in 2h".parse().unwrap();
+/// assert!((in_two_hours.unix_timestamp() - now - 2 * 60 * 60).abs() <= 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub serenity::Timestamp);
+
+impl std::ops::Deref for Timestamp {
+    type Target = serenity::Timestamp;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Timestamp> for serenity::Timestamp {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl From<serenity::Timestamp> for Timestamp {
+    fn from(timestamp: serenity::Timestamp) -> Self {
+        Self(timestamp)
+    }
+}
+
+/// Parses a time of day like `6pm`, `6:30pm`, `18:30`, `noon` or `midnight` into seconds since
+/// midnight
+fn parse_time_of_day(input: &str) -> Option<i64> {
+    match input {
+        "noon" => return Some(12 * 60 * 60),
+        "midnight" => return Some(0),
+        _ => {}
+    }
+
+    let (input, pm) = if let Some(input) = input.strip_suffix("pm") {
+        (input.trim_end(), Some(true))
+    } else if let Some(input) = input.strip_suffix("am") {
+        (input.trim_end(), Some(false))
+    } else {
+        (input, None)
+    };
+
+    let (hour, minute) = match input.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        // A bare number is only a time of day with am/pm
+        None => (input.parse().ok().filter(|_| pm.is_some())?, 0),
+    };
+    let hour: i64 = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None if hour > 23 => return None,
+        None => hour,
+    };
+    let minute: i64 = minute;
+    if minute > 59 {
+        return None;
+    }
+    Some(hour * 60 * 60 + minute * 60)
+}
+
+/// Parses a Discord timestamp mention like `<t:1700000000>` or `<t:1700000000:R>`
+fn parse_timestamp_mention(input: &str) -> Option<i64> {
+    let inner = input.strip_prefix("<t:")?.strip_suffix('>')?;
+    let seconds = match inner.split_once(':') {
+        Some((seconds, style)) if style.len() == 1 => seconds,
+        Some(_) => return None,
+        None => inner,
+    };
+    seconds.parse().ok()
+}
+
+/// Converts a Unix timestamp, or returns None if it's out of range
+///
+/// Unlike [`serenity::Timestamp::from_unix_timestamp`], this doesn't overflow on huge inputs
+fn unix_timestamp(seconds: i64) -> Option<serenity::Timestamp> {
+    serenity::Timestamp::from_millis(seconds.checked_mul(1000)?).ok()
+}
+
+/// Returns the timestamp a given number of seconds after `now`, or None if it's out of range
+fn offset_timestamp(now: i64, seconds: i64) -> Option<serenity::Timestamp> {
+    unix_timestamp(now.checked_add(seconds)?)
+}
+
+impl std::str::FromStr for Timestamp {
+    type Err = TimestampParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_timestamp(input, serenity::Timestamp::now().unix_timestamp())
+    }
+}
+
+/// Implementation of [`Timestamp::from_str`], with relative inputs relative to `now`, a Unix
+/// timestamp
+fn parse_timestamp(input: &str, now: i64) -> Result<Timestamp, TimestampParseError> {
+    let error = |reason: String| TimestampParseError { reason };
+    let out_of_range = || error("out of range".into());
+
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(error("empty input".into()));
+    }
+    if let Some(seconds) = parse_timestamp_mention(input) {
+        return unix_timestamp(seconds)
+            .map(Timestamp)
+            .ok_or_else(out_of_range);
+    }
+    if let Ok(seconds) = input.parse::<i64>() {
+        return unix_timestamp(seconds)
+            .map(Timestamp)
+            .ok_or_else(out_of_range);
+    }
+    if let Ok(timestamp) = serenity::Timestamp::parse(input) {
+        return Ok(Timestamp(timestamp));
+    }
+
+    let lowercase = input.to_lowercase();
+    let lowercase = lowercase.trim_start_matches("at ").trim();
+
+    let mut words = lowercase.splitn(2, char::is_whitespace);
+    let day_offset = match words.next() {
+        Some("now") if words.next().is_none() => {
+            return unix_timestamp(now).map(Timestamp).ok_or_else(out_of_range)
+        }
+        Some("today") => Some(0),
+        Some("tomorrow") => Some(1),
+        _ => None,
+    };
+    if let Some(day_offset) = day_offset {
+        let time = words.next().unwrap_or("").trim();
+        let time = time.strip_prefix("at ").unwrap_or(time).trim();
+        let seconds = if time.is_empty() {
+            day_offset * SECONDS_PER_DAY
+        } else {
+            let time = parse_time_of_day(time)
+                .ok_or_else(|| error(format!("`{}` is not a time of day", time)))?;
+            now.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY - now
+                + day_offset * SECONDS_PER_DAY
+                + time
+        };
+        return offset_timestamp(now, seconds)
+            .map(Timestamp)
+            .ok_or_else(out_of_range);
+    }
+
+    if let Some(time) = parse_time_of_day(lowercase) {
+        let today = now.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY + time;
+        let next = if today > now {
+            today
+        } else {
+            today + SECONDS_PER_DAY
+        };
+        return unix_timestamp(next).map(Timestamp).ok_or_else(out_of_range);
+    }
+
+    let (duration, sign) = if let Some(duration) = lowercase.strip_suffix("ago") {
+        (duration, -1)
+    } else {
+        (lowercase.strip_prefix("in ").unwrap_or(lowercase), 1)
+    };
+    let duration = duration.parse::<Duration>().map_err(|_| {
+        error(format!(
+            "`{}` is neither a date, a time of day nor a duration",
+            input
+        ))
+    })?;
+    let seconds = i64::try_from(duration.as_secs()).map_err(|_| out_of_range())?;
+    offset_timestamp(now, sign * seconds)
+        .map(Timestamp)
+        .ok_or_else(out_of_range)
+}
+
+impl std::fmt::Display for Timestamp {
+    /// Formats the timestamp as a Discord timestamp mention, which Discord renders in the user's
+    /// time zone
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<t:{}:F>", self.0.unix_timestamp())
+    }
+}
+
+/// Suggests common durations, for use as `#[autocomplete = "poise::types::autocomplete_duration"]`
+/// on [`Duration`] parameters.
+///
+/// If the user typed a bare number, suggests it with each unit instead.
+#[allow(clippy::unused_async)] // autocomplete callbacks are awaited by the command macro
+pub async fn autocomplete_duration<U, E>(
+    _: crate::Context<'_, U, E>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let partial = partial.trim();
+    if !partial.is_empty() && partial.chars().all(|c| c.is_ascii_digit()) {
+        return DURATION_UNITS
+            .iter()
+            .map(|(names, _)| {
+                let value = format!("{}{}", partial, names[0]);
+                let label = format!("{} {}", partial, names[names.len() - 1]);
+                serenity::AutocompleteChoice::new(label, value)
+            })
+            .collect();
+    }
+
+    DURATION_SUGGESTIONS
+        .iter()
+        .filter(|(label, value)| value.starts_with(partial) || label.starts_with(partial))
+        .map(|&(label, value)| serenity::AutocompleteChoice::new(label, value))
+        .collect()
+}
+
+/// Implements [`crate::SlashArgument`] and [`crate::PopArgument`] for types that are parsed from
+/// strings via [`std::str::FromStr`]. Prefix arguments span as many words as form a valid value,
+/// up to the given limit
+macro_rules! impl_parsed_argument {
+    ($type:ty, $max_words:literal) => {
+        #[async_trait::async_trait]
+        impl crate::SlashArgument for $type {
+            async fn extract(
+                _: &serenity::Context,
+                _: &serenity::CommandInteraction,
+                value: &serenity::ResolvedValue<'_>,
+            ) -> Result<Self, crate::SlashArgError> {
+                match *value {
+                    serenity::ResolvedValue::String(string) => {
+                        string.parse().map_err(|e| crate::SlashArgError::Parse {
+                            error: Box::new(e),
+                            input: string.into(),
+                        })
+                    }
+                    _ => Err(crate::SlashArgError::CommandStructureMismatch {
+                        description: "expected string",
+                    }),
+                }
+            }
+
+            fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+                builder.kind(serenity::CommandOptionType::String)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl<'a> crate::PopArgument<'a> for $type {
+            async fn pop_from(
+                args: &'a str,
+                attachment_index: usize,
+                ctx: &serenity::Context,
+                msg: &serenity::Message,
+            ) -> Result<
+                (&'a str, usize, Self),
+                (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+            > {
+                let (mut rest, _, input) =
+                    crate::pop_prefix_argument!(String, args, attachment_index, ctx, msg).await?;
+                let mut parsed = input.parse::<Self>().map(|value| (rest, value));
+
+                // Extend the input word by word, keeping the longest input that parses
+                let mut candidate = input.clone();
+                for _ in 1..$max_words {
+                    let Ok((next_rest, _, word)) =
+                        crate::pop_prefix_argument!(String, rest, attachment_index, ctx, msg).await
+                    else {
+                        break;
+                    };
+                    candidate = format!("{} {}", candidate, word);
+                    rest = next_rest;
+                    if let Ok(value) = candidate.parse::<Self>() {
+                        parsed = Ok((rest, value));
+                    }
+                }
+
+                match parsed {
+                    Ok((rest, value)) => Ok((rest, attachment_index, value)),
+                    Err(e) => Err((Box::new(e), Some(input))),
+                }
+            }
+        }
+    };
+}
+
+impl_parsed_argument!(Duration, 6);
+impl_parsed_argument!(Timestamp, 4);
+
+#[cfg(test)]
+#[test]
+fn test_parse_duration() {
+    let parse = |input: &str| input.parse::<Duration>().map(|duration| duration.as_secs());
+
+    assert_eq!(parse("1h30m").unwrap(), 90 * 60);
+    assert_eq!(parse("1.5 hours").unwrap(), 90 * 60);
+    assert_eq!(parse("2d, 12h").unwrap(), 60 * 60 * 60);
+
+    for malformed in ["", "1.2.3h", "5", "h", "1x", "5m 3"] {
+        assert!(
+            parse(malformed).is_err(),
+            "{:?} should be rejected",
+            malformed
+        );
+    }
+
+    assert_eq!(
+        parse("99999999999w").unwrap(),
+        99999999999 * 7 * 24 * 60 * 60
+    );
+    assert_eq!(
+        parse("99999999999999w").unwrap_err().to_string(),
+        "invalid duration: too long (try something like `10m`, `1h30m` or `2 days`)",
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_timestamp() {
+    // 2023-11-14 22:13:20 UTC
+    let now = 1700000000;
+    let day_start = 1699920000;
+    let parse =
+        |input: &str| parse_timestamp(input, now).map(|timestamp| timestamp.unix_timestamp());
+
+    assert_eq!(parse("now").unwrap(), now);
+    assert_eq!(parse("2h").unwrap(), now + 2 * 60 * 60);
+    assert_eq!(parse("in 2h").unwrap(), now + 2 * 60 * 60);
+    assert_eq!(parse("2h ago").unwrap(), now - 2 * 60 * 60);
+    assert_eq!(parse("tomorrow").unwrap(), now + SECONDS_PER_DAY);
+    assert_eq!(parse("1699000000").unwrap(), 1699000000);
+    assert_eq!(parse("<t:1699000000:R>").unwrap(), 1699000000);
+
+    // A time of day that already passed today rolls over to tomorrow
+    assert_eq!(parse("23:00").unwrap(), day_start + 23 * 60 * 60);
+    assert_eq!(
+        parse("10pm").unwrap(),
+        day_start + SECONDS_PER_DAY + 22 * 60 * 60
+    );
+    assert_eq!(parse("today 10pm").unwrap(), day_start + 22 * 60 * 60);
+    assert_eq!(
+        parse("tomorrow 6am").unwrap(),
+        day_start + SECONDS_PER_DAY + 6 * 60 * 60
+    );
+
+    for malformed in [
+        "",
+        "1x",
+        "in",
+        "ago",
+        "25:00",
+        "13pm",
+        "now 2h",
+        "99999999999999999",
+    ] {
+        assert!(
+            parse(malformed).is_err(),
+            "{:?} should be rejected",
+            malformed
+        );
+    }
+    for overflowing in ["in 99999999999w", "99999999999w ago", "99999999999999w"] {
+        assert!(
+            parse(overflowing).is_err(),
+            "{:?} should be rejected",
+            overflowing
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! Dispatches invocations through the framework with [`poise::testing::MockContext`], to cover
+//! the parts of the dispatch logic that depend on each other: checks, cooldowns, buckets and
+//! argument validation
+
+use poise::serenity_prelude as serenity;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+type Context<'a> = poise::Context<'a, Data, Error>;
+
+#[derive(Default)]
+struct Data {
+    /// Names of the checks that ran, in order
+    checks: Mutex<Vec<&'static str>>,
+    /// Number of times [`counting_check`] ran
+    counted_checks: AtomicUsize,
+    /// When [`queued`] ran
+    runs: Mutex<Vec<Instant>>,
+}
+
+impl Data {
+    fn take_checks(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.checks.lock().unwrap())
+    }
+}
+
+async fn mock(
+    commands: Vec<poise::Command<Data, Error>>,
+) -> poise::testing::MockContext<Data, Error> {
+    mock_with(poise::FrameworkOptions {
+        commands,
+        ..Default::default()
+    })
+    .await
+}
+
+async fn mock_with(
+    options: poise::FrameworkOptions<Data, Error>,
+) -> poise::testing::MockContext<Data, Error> {
+    let options = poise::FrameworkOptions {
+        prefix_options: poise::PrefixFrameworkOptions {
+            prefix: Some("~".into()),
+            ..Default::default()
+        },
+        command_check: Some(|ctx| Box::pin(global_check(ctx))),
+        ..options
+    };
+    poise::testing::MockContext::new(options, Data::default())
+        .await
+        .unwrap()
+}
+
+/// Returns the checks that ran since the last call
+fn ran_checks(mock: &poise::testing::MockContext<Data, Error>) -> Vec<&'static str> {
+    let commands = mock.options().active_commands();
+    mock.framework(&commands).user_data.take_checks()
+}
+
+/// Returns how often [`counting_check`] ran
+fn counted_checks(mock: &poise::testing::MockContext<Data, Error>) -> usize {
+    let commands = mock.options().active_commands();
+    let framework = mock.framework(&commands);
+    framework.user_data.counted_checks.load(Ordering::SeqCst)
+}
+
+fn record_check(ctx: Context<'_>, name: &'static str) {
+    ctx.data().checks.lock().unwrap().push(name);
+}
+
+async fn global_check(ctx: Context<'_>) -> Result<bool, Error> {
+    record_check(ctx, "global");
+    Ok(true)
+}
+
+async fn parent_check(ctx: Context<'_>) -> Result<bool, Error> {
+    record_check(ctx, "parent");
+    Ok(true)
+}
+
+async fn child_check(ctx: Context<'_>) -> Result<bool, Error> {
+    record_check(ctx, "child");
+    Ok(true)
+}
+
+async fn final_check(ctx: Context<'_>) -> Result<bool, Error> {
+    record_check(ctx, "final");
+    Ok(true)
+}
+
+async fn denying_check(ctx: Context<'_>) -> Result<bool, Error> {
+    record_check(ctx, "denying");
+    Ok(false)
+}
+
+async fn counting_check(ctx: Context<'_>) -> Result<bool, Error> {
+    ctx.data().counted_checks.fetch_add(1, Ordering::SeqCst);
+    Ok(true)
+}
+
+async fn reply_name(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say(ctx.command().qualified_name.clone()).await?;
+    Ok(())
+}
+
+#[poise::command(
+    prefix_command,
+    slash_command,
+    check = "parent_check",
+    subcommands("child", "uninherited", "skipping")
+)]
+async fn parent(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(prefix_command, slash_command, check = "child_check")]
+async fn child(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    slash_command,
+    inherit_checks = false,
+    check = "child_check"
+)]
+async fn uninherited(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    slash_command,
+    skip_parent_checks,
+    check = "child_check"
+)]
+async fn skipping(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    guild_only,
+    check = "parent_check",
+    subcommands("guarded_uninherited", "guarded_skipping")
+)]
+async fn guarded(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    rename = "uninherited",
+    inherit_checks = false,
+    check = "child_check"
+)]
+async fn guarded_uninherited(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    rename = "skipping",
+    skip_parent_checks,
+    check = "child_check"
+)]
+async fn guarded_skipping(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    check = "parent_check",
+    final_check = "final_check",
+    subcommands("strict")
+)]
+async fn moderated(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(prefix_command, check = "denying_check")]
+async fn strict(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[tokio::test]
+async fn test_check_order() {
+    let mock = mock(vec![parent()]).await;
+
+    let response = mock.dispatch_message("~parent child").await;
+    assert_eq!(response.contents(), ["parent child"]);
+    assert_eq!(ran_checks(&mock), ["global", "parent", "child"]);
+
+    let response = mock.dispatch_slash("parent child", &[]).await;
+    assert_eq!(response.contents(), ["parent child"]);
+    assert_eq!(ran_checks(&mock), ["global", "parent", "child"]);
+
+    let response = mock.dispatch_message("~parent").await;
+    assert_eq!(response.contents(), ["parent"]);
+    assert_eq!(ran_checks(&mock), ["global", "parent"]);
+}
+
+#[tokio::test]
+async fn test_final_checks() {
+    let mock = mock(vec![moderated()]).await;
+
+    // The final check of the parent passed, so the denying check of the subcommand is skipped
+    let response = mock.dispatch_message("~moderated strict").await;
+    assert_eq!(response.contents(), ["moderated strict"]);
+    assert_eq!(ran_checks(&mock), ["global", "parent", "final"]);
+}
+
+#[tokio::test]
+async fn test_inherit_checks() {
+    let mock = mock(vec![parent(), guarded()]).await;
+
+    let response = mock.dispatch_message("~parent uninherited").await;
+    assert_eq!(response.contents(), ["parent uninherited"]);
+    assert_eq!(ran_checks(&mock), ["global", "child"]);
+
+    // Restrictions of the parent still apply, and run before any check
+    let response = mock.dispatch_message("~guarded uninherited").await;
+    assert_eq!(
+        response.errors,
+        ["guild-only command `~guarded uninherited` cannot run in DMs"]
+    );
+    assert!(ran_checks(&mock).is_empty());
+}
+
+#[tokio::test]
+async fn test_skip_parent_checks() {
+    let mock = mock(vec![parent(), guarded()]).await;
+
+    let response = mock.dispatch_message("~parent skipping").await;
+    assert_eq!(response.contents(), ["parent skipping"]);
+    assert_eq!(ran_checks(&mock), ["global", "child"]);
+
+    // Unlike with inherit_checks = false, the guild_only restriction of the parent is skipped too
+    let response = mock.dispatch_message("~guarded skipping").await;
+    assert_eq!(response.contents(), ["guarded skipping"]);
+    assert!(response.errors.is_empty());
+    assert_eq!(ran_checks(&mock), ["global", "child"]);
+}
+
+#[poise::command(prefix_command, slash_command, user_cooldown = 1, cooldown_wait = 1)]
+async fn waiting(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(prefix_command, slash_command, user_cooldown = 60, cooldown_wait = 1)]
+async fn impatient(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[tokio::test]
+async fn test_cooldown_wait() {
+    let mock = mock(vec![waiting(), impatient()]).await;
+
+    assert_eq!(
+        mock.dispatch_message("~waiting").await.contents(),
+        ["waiting"]
+    );
+    let start = Instant::now();
+    let response = mock.dispatch_message("~waiting").await;
+    assert_eq!(response.contents(), ["waiting"]);
+    assert!(response.errors.is_empty());
+    assert!(start.elapsed() >= Duration::from_millis(500));
+
+    // A cooldown longer than the wait threshold is rejected right away
+    assert_eq!(
+        mock.dispatch_slash("impatient", &[]).await.contents(),
+        ["impatient"]
+    );
+    let response = mock.dispatch_slash("impatient", &[]).await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].starts_with("cooldown hit in command `/impatient`"));
+}
+
+#[poise::command(prefix_command)]
+async fn queued(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data().runs.lock().unwrap().push(Instant::now());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cooldown_waiters_run_one_per_period() {
+    let cooldown = Duration::from_millis(300);
+    let command = queued();
+    {
+        let mut config = command.cooldown_config.write().unwrap();
+        config.user = Some(cooldown);
+        config.wait_threshold = Some(cooldown);
+    }
+    let mock = mock_with(poise::FrameworkOptions {
+        commands: vec![command],
+        // Gives the other waiter a chance to run before the cooldown starts after parsing
+        pre_command: |_| Box::pin(tokio::task::yield_now()),
+        ..Default::default()
+    })
+    .await;
+
+    mock.dispatch_message("~queued").await;
+    let (first, second) = tokio::join!(
+        mock.dispatch_message("~queued"),
+        mock.dispatch_message("~queued"),
+    );
+    assert!(first.errors.is_empty() && second.errors.is_empty());
+
+    let commands = mock.options().active_commands();
+    let runs = mock
+        .framework(&commands)
+        .user_data
+        .runs
+        .lock()
+        .unwrap()
+        .clone();
+    assert_eq!(runs.len(), 3);
+    for pair in runs.windows(2) {
+        // Allow for timer imprecision
+        assert!(pair[1] - pair[0] >= cooldown - Duration::from_millis(50));
+    }
+}
+
+#[poise::command(prefix_command, slash_command, bucket = "limited")]
+async fn limited(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(
+    prefix_command,
+    slash_command,
+    bucket = "limited",
+    check = "denying_check"
+)]
+async fn denied(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(prefix_command, bucket = "delayed")]
+async fn delayed(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[tokio::test]
+async fn test_buckets() {
+    let mut options = poise::FrameworkOptions {
+        commands: vec![limited(), denied(), delayed()],
+        ..Default::default()
+    };
+    options.buckets.insert(
+        "limited".into(),
+        poise::CooldownBucket::new()
+            .limit(2)
+            .time_span(Duration::from_secs(60)),
+    );
+    options.buckets.insert(
+        "delayed".into(),
+        poise::CooldownBucket::new()
+            .delay(Duration::from_millis(500))
+            .await_ratelimits(1),
+    );
+    let mock = mock_with(options).await;
+
+    // Invocations failing a check don't use up the bucket
+    for _ in 0..3 {
+        assert_eq!(mock.dispatch_message("~denied").await.errors.len(), 1);
+    }
+    assert_eq!(
+        mock.dispatch_message("~limited").await.contents(),
+        ["limited"]
+    );
+    assert_eq!(
+        mock.dispatch_slash("limited", &[]).await.contents(),
+        ["limited"]
+    );
+    let response = mock.dispatch_message("~limited").await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].starts_with("cooldown hit in command `~limited`"));
+
+    assert_eq!(
+        mock.dispatch_message("~delayed").await.contents(),
+        ["delayed"]
+    );
+    let start = Instant::now();
+    let response = mock.dispatch_message("~delayed").await;
+    // The error handler announces the delay before the command runs
+    assert_eq!(response.contents().len(), 2);
+    assert_eq!(response.contents()[1], "delayed");
+    assert!(start.elapsed() >= Duration::from_millis(250));
+}
+
+#[poise::command(prefix_command, bucket = "metered", subcommands("cooled"))]
+async fn metered(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[poise::command(prefix_command, user_cooldown = 60)]
+async fn cooled(ctx: Context<'_>) -> Result<(), Error> {
+    reply_name(ctx).await
+}
+
+#[tokio::test]
+async fn test_denied_invocation_gives_back_bucket_use() {
+    let mut options = poise::FrameworkOptions {
+        commands: vec![metered()],
+        ..Default::default()
+    };
+    options.buckets.insert(
+        "metered".into(),
+        poise::CooldownBucket::new()
+            .limit(2)
+            .time_span(Duration::from_secs(60)),
+    );
+    let mock = mock_with(options).await;
+
+    let response = mock.dispatch_message("~metered cooled").await;
+    assert_eq!(response.contents(), ["metered cooled"]);
+    // The bucket of the parent passes, but the cooldown of the subcommand doesn't
+    let response = mock.dispatch_message("~metered cooled").await;
+    assert!(response.errors[0].starts_with("cooldown hit in command `~metered cooled`"));
+
+    let response = mock.dispatch_message("~metered").await;
+    assert_eq!(response.contents(), ["metered"]);
+    let response = mock.dispatch_message("~metered").await;
+    assert!(response.errors[0].starts_with("cooldown hit in command `~metered`"));
+}
+
+#[poise::command(prefix_command, slash_command, check = "counting_check")]
+async fn constrained(
+    ctx: Context<'_>,
+    #[min = 1]
+    #[max = 10]
+    number: i64,
+    #[max_length = 3] word: Option<String>,
+) -> Result<(), Error> {
+    ctx.say(format!("{} {}", number, word.unwrap_or_default()))
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_parameter_constraints() {
+    let mock = mock(vec![constrained()]).await;
+
+    let response = mock.dispatch_message("~constrained 10 abc").await;
+    assert_eq!(response.contents(), ["10 abc"]);
+    let response = mock
+        .dispatch_slash("constrained", &[("number", 1.into())])
+        .await;
+    assert_eq!(response.contents(), ["1 "]);
+    let checks_before = counted_checks(&mock);
+
+    for response in [
+        mock.dispatch_message("~constrained 11").await,
+        mock.dispatch_message("~constrained 5 abcd").await,
+        mock.dispatch_slash("constrained", &[("number", 0.into())])
+            .await,
+        mock.dispatch_slash(
+            "constrained",
+            &[("number", 5.into()), ("word", "abcd".into())],
+        )
+        .await,
+    ] {
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].starts_with("argument `"));
+    }
+    // Prefix arguments are validated after parsing, but slash arguments before the checks run
+    assert_eq!(counted_checks(&mock), checks_before + 2);
+}
+
+#[poise::command(slash_command, check = "counting_check")]
+async fn text_channel(
+    ctx: Context<'_>,
+    #[channel_types("Text")] channel: serenity::PartialChannel,
+) -> Result<(), Error> {
+    ctx.say(channel.name.unwrap_or_default()).await?;
+    Ok(())
+}
+
+/// Builds an invocation of [`text_channel`] with a resolved channel of the given type
+fn channel_interaction(
+    mock: &poise::testing::MockContext<Data, Error>,
+    kind: serenity::ChannelType,
+) -> serenity::CommandInteraction {
+    use serenity::json::json;
+
+    let interaction = mock.slash_interaction("text_channel", &[]);
+    let mut interaction = serenity::json::to_value(&interaction).unwrap();
+    interaction["data"]["options"] = json!([{ "name": "channel", "type": 7, "value": "5" }]);
+    interaction["data"]["resolved"] = json!({
+        "channels": {
+            "5": { "id": "5", "name": "general", "type": u8::from(kind), "permissions": "0" },
+        },
+    });
+    serenity::json::from_value(interaction).unwrap()
+}
+
+#[tokio::test]
+async fn test_channel_types() {
+    let mock = mock(vec![text_channel()]).await;
+
+    let interaction = channel_interaction(&mock, serenity::ChannelType::Text);
+    let response = mock.dispatch_interaction(interaction).await;
+    assert_eq!(response.contents(), ["general"]);
+    assert_eq!(counted_checks(&mock), 1);
+
+    let interaction = channel_interaction(&mock, serenity::ChannelType::Voice);
+    let response = mock.dispatch_interaction(interaction).await;
+    assert_eq!(
+        response.errors,
+        ["channel of type Voice given to command `/text_channel`, expected one of [Text]"]
+    );
+    // The channel type is validated before checks run
+    assert_eq!(counted_checks(&mock), 1);
+}
+
+#[poise::command(prefix_command, slash_command)]
+async fn ping(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("pong").await?;
+    Ok(())
+}
+
+#[poise::command(prefix_command, slash_command, rename = "ping")]
+async fn ping_v2(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("pong v2").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_runtime_commands() {
+    let mock = mock(vec![ping()]).await;
+
+    // Replacing a static command with a runtime command of the same name
+    mock.options().add_command(ping_v2());
+    assert_eq!(mock.dispatch_message("~ping").await.contents(), ["pong v2"]);
+    assert_eq!(
+        mock.dispatch_slash("ping", &[]).await.contents(),
+        ["pong v2"]
+    );
+
+    assert!(mock.options().remove_command("ping"));
+    assert!(!mock.options().remove_command("ping"));
+    assert!(mock.dispatch_message("~ping").await.contents().is_empty());
+    assert!(mock.dispatch_slash("ping", &[]).await.contents().is_empty());
+
+    mock.options().add_command(parent());
+    assert_eq!(
+        mock.dispatch_message("~parent child").await.contents(),
+        ["parent child"]
+    );
+}
+
+#[poise::command(prefix_command, slash_command)]
+async fn add(ctx: Context<'_>, a: i64, b: i64) -> Result<(), Error> {
+    ctx.say((a + b).to_string()).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+async fn remind(
+    ctx: Context<'_>,
+    text: String,
+    delay: poise::types::Duration,
+) -> Result<(), Error> {
+    ctx.say(format!("{} in {}", text, delay)).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_argument_parse_parameter() {
+    let mock = mock(vec![add(), remind()]).await;
+
+    let response = mock.dispatch_message("~add 1 two").await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].starts_with("failed to parse argument `b` in command `~add`"));
+
+    let response = mock.dispatch_message("~add one 2").await;
+    assert!(response.errors[0].starts_with("failed to parse argument `a` in command `~add`"));
+
+    let response = mock
+        .dispatch_slash(
+            "remind",
+            &[("text", "tea".into()), ("delay", "soon".into())],
+        )
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].starts_with("failed to parse argument `delay` in command `/remind`"));
+}
+
+type MaybeNumber = Option<i64>;
+
+#[poise::command(prefix_command, slash_command)]
+async fn optional(
+    ctx: Context<'_>,
+    #[required = false] number: MaybeNumber,
+    #[required = true] word: Option<String>,
+) -> Result<(), Error> {
+    ctx.say(format!("{:?} {:?}", number, word)).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_required_override() {
+    let command = optional();
+    let required = |name: &str| {
+        let parameter = command.parameters.iter().find(|p| p.name == name);
+        parameter.unwrap().required
+    };
+    assert!(!required("number"));
+    assert!(required("word"));
+
+    let mock = mock(vec![command]).await;
+    let response = mock
+        .dispatch_slash("optional", &[("word", "hi".into())])
+        .await;
+    assert_eq!(response.contents(), [r#"None Some("hi")"#]);
+    let response = mock.dispatch_message("~optional").await;
+    assert_eq!(response.contents(), ["None None"]);
+    let response = mock.dispatch_message("~optional 5").await;
+    assert_eq!(response.contents(), ["Some(5) None"]);
+}